@@ -1,12 +1,159 @@
 use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Формат консольного вывода логов
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Строки для чтения человеком
+    #[default]
+    Text,
+    /// Один JSON-объект на событие
+    Json,
+}
+
+/// Транспорт отправки логов во внешний сборщик
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogTransport {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+/// Формат сообщений для внешнего сборщика логов
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogProtocol {
+    /// JSON-строки для Logstash
+    #[default]
+    Json,
+    /// GELF 1.1 для Graylog; по UDP сообщения сжимаются и разбиваются на чанки
+    Gelf,
+}
+
+/// TLS-соединение со сборщиком логов (только для транспорта tcp)
+#[derive(Debug, Default, Deserialize)]
+pub struct LogstashTlsConfig {
+    /// PEM-файл с корневым сертификатом сборщика
+    pub ca_file: Option<String>,
+    /// Клиентский сертификат (PEM) для взаимной аутентификации
+    pub cert_file: Option<String>,
+    /// Ключ клиентского сертификата (PKCS#8 PEM)
+    pub key_file: Option<String>,
+    /// Имя сервера (SNI) и имя для проверки сертификата; по умолчанию `logstash_host`
+    pub server_name: Option<String>,
+}
+
+/// Экспорт в коллектор OpenTelemetry по OTLP/HTTP
+#[derive(Debug, Deserialize)]
+pub struct OtlpConfig {
+    /// Адрес коллектора, например `http://tempo:4318`;
+    /// путь сигнала (`/v1/traces`, `/v1/metrics`) добавляется сам
+    pub endpoint: String,
+    /// Дополнительные заголовки запросов, например для авторизации
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Имя сервиса в трассировках; по умолчанию `app_name`
+    pub service_name: Option<String>,
+}
+
+impl OtlpConfig {
+    /// Адрес приёма сигнала: к адресу коллектора добавляется путь, если его там нет
+    pub fn signal_endpoint(&self, path: &str) -> String {
+        let endpoint = self.endpoint.trim_end_matches('/');
+        if endpoint.ends_with(path) {
+            endpoint.to_string()
+        } else {
+            format!("{}{}", endpoint, path)
+        }
+    }
+
+    pub fn service_name<'a>(&'a self, app_name: Option<&'a str>) -> &'a str {
+        self.service_name.as_deref().or(app_name).unwrap_or("unknown_service")
+    }
+}
+
+/// Экспорт метрик: HTTP-эндпоинт Prometheus и/или отправка в коллектор по OTLP
+#[derive(Debug, Deserialize)]
+pub struct MetricsConfig {
+    pub prometheus: Option<PrometheusConfig>,
+    pub otlp: Option<OtlpConfig>,
+    /// Период отправки метрик по OTLP, секунд
+    #[serde(default = "MetricsConfig::default_otlp_interval_secs")]
+    pub otlp_interval_secs: u64,
+}
+
+impl MetricsConfig {
+    fn default_otlp_interval_secs() -> u64 {
+        60
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig {
+            prometheus: None,
+            otlp: None,
+            otlp_interval_secs: Self::default_otlp_interval_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrometheusConfig {
+    /// Адрес HTTP-эндпоинта со страницей метрик, например `0.0.0.0:9184`
+    pub listen: String,
+}
 
 #[derive(Debug, Deserialize)]
 pub struct LogConfig {
     pub log_level: String,
     pub use_color: bool,
+    #[serde(default)]
+    pub log_format: LogFormat,
+    /// Уровни логирования отдельных модулей, например `whois_rust: warn`
+    #[serde(default)]
+    pub log_levels: HashMap<String, String>,
+    /// Не больше стольких одинаковых сообщений (кроме ошибок) в минуту,
+    /// остальные сводятся в одно сообщение с их числом
+    pub rate_limit_per_minute: Option<u32>,
+    /// Значения полей, в имени которых есть одно из этих слов, заменяются на `***`
+    #[serde(default = "LogConfig::default_redact_fields")]
+    pub redact_fields: Vec<String>,
+    /// Формат меток времени (strftime), например `%Y-%m-%dT%H:%M:%S%.3fZ`
+    pub timestamp_format: Option<String>,
+    /// Часовой пояс меток времени: `local` (по умолчанию), `utc` или имя IANA
+    pub timezone: Option<String>,
     pub logstash_host: Option<String>,
     pub logstash_port: Option<u16>,
+    #[serde(default)]
+    pub logstash_transport: LogTransport,
+    #[serde(default)]
+    pub logstash_protocol: LogProtocol,
+    pub logstash_tls: Option<LogstashTlsConfig>,
     pub app_name: Option<String>,
+    pub otlp: Option<OtlpConfig>,
+}
+
+impl LogConfig {
+    fn default_redact_fields() -> Vec<String> {
+        ["password", "passwd", "secret", "token", "api_key", "apikey", "authorization"]
+            .map(String::from)
+            .to_vec()
+    }
+
+    /// Директивы EnvFilter: общий уровень и уровни модулей из `log_levels`
+    pub fn filter_directives(&self) -> String {
+        let mut modules: Vec<String> = self
+            .log_levels
+            .iter()
+            .map(|(module, level)| format!("{}={}", module, level))
+            .collect();
+        modules.sort();
+        modules.insert(0, self.log_level.clone());
+        modules.join(",")
+    }
 }
 
 impl Default for LogConfig {
@@ -14,9 +161,19 @@ impl Default for LogConfig {
         LogConfig {
             log_level: "info".to_string(),
             use_color: false,
+            log_format: LogFormat::default(),
+            log_levels: HashMap::new(),
+            rate_limit_per_minute: None,
+            redact_fields: Self::default_redact_fields(),
+            timestamp_format: None,
+            timezone: None,
             logstash_host: None,
             logstash_port: None,
+            logstash_transport: LogTransport::default(),
+            logstash_protocol: LogProtocol::default(),
+            logstash_tls: None,
             app_name: None,
+            otlp: None,
         }
     }
 }
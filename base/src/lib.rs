@@ -1,10 +1,13 @@
 pub mod config;
 pub mod logging;
+pub mod metrics;
 
 pub mod prelude {
     pub use anyhow;
     pub use config;
     pub use chrono;
+    pub use chrono_tz;
+    pub use metrics;
     pub use once_cell;
     pub use serde_json;
     pub use serde_yaml;
@@ -1,5 +1,6 @@
+use super::redact::Redactor;
 use super::span_fields_layer::SpanFields;
-use chrono::Local;
+use super::timestamp::TimestampFormat;
 use colored::*;
 use indexmap::IndexMap;
 use serde_json::{json, Value};
@@ -12,11 +13,15 @@ use tracing_subscriber::{
 
 pub struct ColorfulFormatter {
     pub use_color: bool,
+    pub timestamp: TimestampFormat,
+    pub redactor: Redactor,
 }
 
 impl ColorfulFormatter {
-    pub fn new(use_color: bool) -> Self {
-        Self { use_color }
+    const TIMESTAMP_FORMAT: &'static str = "%Y-%m-%d %H:%M:%S";
+
+    pub fn new(use_color: bool, timestamp: TimestampFormat, redactor: Redactor) -> Self {
+        Self { use_color, timestamp, redactor }
     }
 }
 
@@ -31,34 +36,10 @@ where
         mut writer: Writer<'_>,
         event: &Event<'_>,
     ) -> fmt::Result {
-        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+        let timestamp = self.timestamp.now(Self::TIMESTAMP_FORMAT);
         let level = event.metadata().level();
-
-        // Собираем поля из event
-        let mut fields = IndexMap::new();
-        let mut decl = "app".to_string();
-        let mut message = String::new();
-
-        let mut visitor = FieldVisitor {
-            fields: &mut fields,
-            decl: &mut decl,
-            message: &mut message,
-        };
-        event.record(&mut visitor);
-
-        // Собираем поля из span'ов
-        if let Some(scope) = ctx.event_scope() {
-            for span in scope.from_root() {
-                let extensions = span.extensions();
-                if let Some(span_fields) = extensions.get::<SpanFields>() {
-                    for (key, value) in &span_fields.fields {
-                        if !fields.contains_key(key) && key != "message" {
-                            fields.insert(key.clone(), value.clone());
-                        }
-                    }
-                }
-            }
-        }
+        let EventFields { decl, message, fields } =
+            EventFields::collect(ctx, event, &self.redactor);
 
         // Формируем строку
         let level_str = match *level {
@@ -93,6 +74,59 @@ where
     }
 }
 
+/// Поля события: `dcl`, сообщение и остальные поля события и его span'ов
+pub(super) struct EventFields {
+    pub decl: String,
+    pub message: String,
+    pub fields: IndexMap<String, Value>,
+}
+
+impl EventFields {
+    pub fn collect<S, N>(
+        ctx: &FmtContext<'_, S, N>,
+        event: &Event<'_>,
+        redactor: &Redactor,
+    ) -> Self
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+        N: for<'a> FormatFields<'a> + 'static,
+    {
+        // Собираем поля из event
+        let mut fields = IndexMap::new();
+        let mut decl = "app".to_string();
+        let mut message = String::new();
+
+        let mut visitor = FieldVisitor {
+            fields: &mut fields,
+            decl: &mut decl,
+            message: &mut message,
+        };
+        event.record(&mut visitor);
+
+        // Собираем поля из span'ов
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                let extensions = span.extensions();
+                if let Some(span_fields) = extensions.get::<SpanFields>() {
+                    for (key, value) in &span_fields.fields {
+                        if !fields.contains_key(key) && key != "message" {
+                            fields.insert(key.clone(), value.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        // Маскируем секреты
+        redactor.redact_text(&mut message);
+        for (key, value) in fields.iter_mut() {
+            redactor.redact_field(key, value);
+        }
+
+        Self { decl, message, fields }
+    }
+}
+
 struct FieldVisitor<'a> {
     fields: &'a mut IndexMap<String, Value>,
     decl: &'a mut String,
@@ -0,0 +1,43 @@
+use super::formatter::EventFields;
+use super::redact::Redactor;
+use super::timestamp::TimestampFormat;
+use serde_json::json;
+use std::fmt;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::{
+    fmt::{format::Writer, FmtContext, FormatEvent, FormatFields},
+    registry::LookupSpan,
+};
+
+/// Вывод событий по одному JSON-объекту на строку для сборщиков логов (Loki, ELK)
+pub struct JsonFormatter {
+    pub timestamp: TimestampFormat,
+    pub redactor: Redactor,
+}
+
+impl<S, N> FormatEvent<S, N> for JsonFormatter
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let EventFields { decl, message, fields } =
+            EventFields::collect(ctx, event, &self.redactor);
+
+        let entry = json!({
+            "timestamp": self.timestamp.now_rfc3339(),
+            "level": metadata.level().as_str(),
+            "dcl": decl,
+            "target": metadata.target(),
+            "message": message,
+            "fields": fields,
+        });
+        writeln!(writer, "{}", entry)
+    }
+}
@@ -0,0 +1,54 @@
+use once_cell::sync::OnceCell;
+use std::sync::Mutex;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+static FILTER: OnceCell<LevelHandle> = OnceCell::new();
+
+/// Фильтр уровней логирования, который можно заменить без перезапуска процесса
+struct LevelHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+    /// Директивы из конфигурации
+    initial: String,
+    current: Mutex<String>,
+}
+
+/// Слой фильтра с возможностью замены через [`set_log_level`]
+pub(super) fn reloadable(directives: String) -> reload::Layer<EnvFilter, Registry> {
+    let (layer, handle) = reload::Layer::new(EnvFilter::new(&directives));
+    let current = Mutex::new(directives.clone());
+    let _ = FILTER.set(LevelHandle { handle, initial: directives, current });
+    layer
+}
+
+/// Текущие директивы фильтра логирования
+pub fn log_level() -> Option<String> {
+    let filter = FILTER.get()?;
+    let current = filter.current.lock().unwrap_or_else(|e| e.into_inner());
+    Some(current.clone())
+}
+
+/// Директивы фильтра из конфигурации
+pub fn default_log_level() -> Option<String> {
+    FILTER.get().map(|filter| filter.initial.clone())
+}
+
+/// Заменяет фильтр логирования, например на `debug` или
+/// `info,checker=debug,ssl_checker_core=debug`
+pub fn set_log_level(directives: &str) -> anyhow::Result<()> {
+    let filter = EnvFilter::try_new(directives)?;
+    let Some(level) = FILTER.get() else {
+        anyhow::bail!("логгер не инициализирован");
+    };
+    level.handle.reload(filter)?;
+    *level.current.lock().unwrap_or_else(|e| e.into_inner()) = directives.to_string();
+    tracing::info!(dcl = "Logging", level = directives, "Изменён уровень логирования");
+    Ok(())
+}
+
+/// Возвращает фильтр логирования из конфигурации
+pub fn reset_log_level() -> anyhow::Result<()> {
+    let Some(initial) = default_log_level() else {
+        anyhow::bail!("логгер не инициализирован");
+    };
+    set_log_level(&initial)
+}
@@ -1,22 +1,159 @@
+use super::redact::Redactor;
+use crate::config::{LogProtocol, LogTransport, LogstashTlsConfig};
 use chrono::Utc;
+use flate2::{write::ZlibEncoder, Compression};
 use serde_json::{json, Map, Value};
+use std::io::Write;
 use std::net::SocketAddr;
-use tokio::io::AsyncWriteExt;
-use tokio::net::TcpStream;
-use tracing::{Event, Subscriber};
+use std::sync::Arc;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio_native_tls::TlsConnector;
+use tracing::{Event, Level, Subscriber};
 use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
 
+/// Максимальный размер UDP-датаграммы GELF, включая заголовок чанка
+const GELF_CHUNK_SIZE: usize = 8192;
+/// Заголовок чанка: магические байты, id сообщения, номер и число чанков
+const GELF_CHUNK_HEADER: usize = 12;
+const GELF_MAX_CHUNKS: usize = 128;
+
+enum Transport {
+    Tcp(SocketAddr, Option<Arc<Tls>>),
+    Udp(Arc<UdpSocket>),
+}
+
+/// Параметры TLS-соединения с сборщиком логов
+struct Tls {
+    connector: TlsConnector,
+    server_name: String,
+}
+
+impl Tls {
+    fn new(config: &LogstashTlsConfig, host: &str) -> anyhow::Result<Self> {
+        let mut builder = native_tls::TlsConnector::builder();
+        if let Some(path) = &config.ca_file {
+            let pem = std::fs::read(path)?;
+            builder.add_root_certificate(native_tls::Certificate::from_pem(&pem)?);
+        }
+        match (&config.cert_file, &config.key_file) {
+            (Some(cert), Some(key)) => {
+                let (cert, key) = (std::fs::read(cert)?, std::fs::read(key)?);
+                builder.identity(native_tls::Identity::from_pkcs8(&cert, &key)?);
+            }
+            (None, None) => {}
+            _ => anyhow::bail!("cert_file и key_file задаются только вместе"),
+        }
+        Ok(Self {
+            connector: TlsConnector::from(builder.build()?),
+            server_name: config.server_name.clone().unwrap_or_else(|| host.to_string()),
+        })
+    }
+}
+
 pub struct LogstashLayer {
-    addr: SocketAddr,
+    transport: Transport,
+    protocol: LogProtocol,
     app_name: String,
+    host: String,
+    redactor: Redactor,
 }
 
 impl LogstashLayer {
-    pub async fn new(host: &str, port: u16, app_name: &str) -> anyhow::Result<Self> {
+    pub async fn new(
+        host: &str,
+        port: u16,
+        app_name: &str,
+        transport: LogTransport,
+        protocol: LogProtocol,
+        tls: Option<&LogstashTlsConfig>,
+    ) -> anyhow::Result<Self> {
         let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
-        let _ = TcpStream::connect(addr).await?;
+        let transport = match transport {
+            LogTransport::Tcp => {
+                let tls = tls.map(|tls| Tls::new(tls, host)).transpose()?.map(Arc::new);
+                send_tcp(addr, tls.clone(), Vec::new()).await?;
+                Transport::Tcp(addr, tls)
+            }
+            LogTransport::Udp if tls.is_some() => {
+                anyhow::bail!("TLS поддерживается только для транспорта tcp")
+            }
+            LogTransport::Udp => {
+                let bind: SocketAddr = if addr.is_ipv4() {
+                    "0.0.0.0:0".parse()?
+                } else {
+                    "[::]:0".parse()?
+                };
+                let socket = UdpSocket::bind(bind).await?;
+                socket.connect(addr).await?;
+                Transport::Udp(Arc::new(socket))
+            }
+        };
         let app_name = app_name.to_string();
-        Ok(Self { addr, app_name })
+        let host = gethostname::gethostname().to_string_lossy().into_owned();
+        Ok(Self { transport, protocol, app_name, host, redactor: Redactor::default() })
+    }
+
+    /// Маскирование секретов в полях и сообщении перед отправкой
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    fn logstash_entry(&self, event: &Event<'_>, mut fields: Map<String, Value>) -> Value {
+        let metadata = event.metadata();
+        json!({
+            "@timestamp": Utc::now().to_rfc3339(),
+            "app": self.app_name,
+            "level": metadata.level().to_string(),
+            "target": metadata.target(),
+            "message": take_message(&mut fields),
+            "fields": fields,
+        })
+    }
+
+    /// Сообщение GELF 1.1; дополнительные поля передаются с префиксом `_`
+    fn gelf_entry(&self, event: &Event<'_>, mut fields: Map<String, Value>) -> Value {
+        let metadata = event.metadata();
+        let now = Utc::now();
+        let mut entry = Map::new();
+        entry.insert("version".to_string(), json!("1.1"));
+        entry.insert("host".to_string(), json!(self.host));
+        entry.insert("short_message".to_string(), json!(take_message(&mut fields)));
+        entry.insert(
+            "timestamp".to_string(),
+            json!(now.timestamp_millis() as f64 / 1000.0),
+        );
+        entry.insert("level".to_string(), json!(syslog_level(metadata.level())));
+        entry.insert("_app".to_string(), json!(self.app_name));
+        entry.insert("_target".to_string(), json!(metadata.target()));
+        for (key, value) in fields {
+            // `_id` зарезервировано в GELF
+            let key = match key.as_str() {
+                "id" => "_field_id".to_string(),
+                _ => format!("_{}", key),
+            };
+            entry.insert(key, value);
+        }
+        Value::Object(entry)
+    }
+
+    /// Кадры для отправки: строка JSON с `\n` или `\0` для TCP,
+    /// сжатые и при необходимости разбитые на чанки датаграммы GELF для UDP
+    fn frames(&self, entry: &Value) -> Vec<Vec<u8>> {
+        let mut payload = serde_json::to_vec(entry).unwrap_or_default();
+        match (&self.transport, self.protocol) {
+            (Transport::Tcp(..), LogProtocol::Json) => {
+                payload.push(b'\n');
+                vec![payload]
+            }
+            (Transport::Tcp(..), LogProtocol::Gelf) => {
+                payload.push(b'\0');
+                vec![payload]
+            }
+            (Transport::Udp(_), LogProtocol::Json) => vec![payload],
+            (Transport::Udp(_), LogProtocol::Gelf) => gelf_chunks(&compress(&payload)),
+        }
     }
 }
 
@@ -25,32 +162,118 @@ where
     S: Subscriber + for<'a> LookupSpan<'a>,
 {
     fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
-        let metadata = event.metadata();
-
         let mut fields = Map::new();
         let mut visitor = JsonVisitor(&mut fields);
         event.record(&mut visitor);
+        for (key, value) in fields.iter_mut() {
+            self.redactor.redact_field(key, value);
+        }
 
-        let log_entry = json!({
-            "@timestamp": Utc::now().to_rfc3339(),
-            "app": self.app_name,
-            "level": metadata.level().to_string(),
-            "target": metadata.target(),
-            "message": fields.remove("message").and_then(|v| v.as_str().map(|s| s.to_string())).unwrap_or_default(),
-            "fields": fields,
-        });
-
-        let addr = self.addr;
-        tokio::spawn(async move {
-            if let Ok(mut conn) = TcpStream::connect(addr).await {
-                let mut msg = serde_json::to_vec(&log_entry).unwrap_or_default();
-                msg.push(b'\n');
-                let _ = conn.write_all(&msg).await;
+        let entry = match self.protocol {
+            LogProtocol::Json => self.logstash_entry(event, fields),
+            LogProtocol::Gelf => self.gelf_entry(event, fields),
+        };
+        let frames = self.frames(&entry);
+
+        match &self.transport {
+            Transport::Tcp(addr, tls) => {
+                let addr = *addr;
+                let tls = tls.clone();
+                tokio::spawn(async move {
+                    let _ = send_tcp(addr, tls, frames).await;
+                });
             }
-        });
+            Transport::Udp(socket) => {
+                let socket = Arc::clone(socket);
+                tokio::spawn(async move {
+                    for frame in frames {
+                        let _ = socket.send(&frame).await;
+                    }
+                });
+            }
+        }
     }
 }
 
+/// Отправляет кадры по новому TCP-соединению, при необходимости поверх TLS
+async fn send_tcp(
+    addr: SocketAddr,
+    tls: Option<Arc<Tls>>,
+    frames: Vec<Vec<u8>>,
+) -> anyhow::Result<()> {
+    let conn = TcpStream::connect(addr).await?;
+    match tls {
+        Some(tls) => {
+            let conn = tls.connector.connect(&tls.server_name, conn).await?;
+            write_frames(conn, frames).await
+        }
+        None => write_frames(conn, frames).await,
+    }
+}
+
+async fn write_frames(
+    mut conn: impl AsyncWrite + Unpin,
+    frames: Vec<Vec<u8>>,
+) -> anyhow::Result<()> {
+    for frame in frames {
+        conn.write_all(&frame).await?;
+    }
+    conn.shutdown().await?;
+    Ok(())
+}
+
+fn take_message(fields: &mut Map<String, Value>) -> String {
+    fields
+        .remove("message")
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_default()
+}
+
+/// Уровни syslog, которые ожидает GELF
+fn syslog_level(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 3,
+        Level::WARN => 4,
+        Level::INFO => 6,
+        Level::DEBUG | Level::TRACE => 7,
+    }
+}
+
+fn compress(payload: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    let _ = encoder.write_all(payload);
+    encoder.finish().unwrap_or_default()
+}
+
+/// Разбивает сообщение на чанки GELF; слишком большое сообщение отбрасывается
+fn gelf_chunks(payload: &[u8]) -> Vec<Vec<u8>> {
+    if payload.len() <= GELF_CHUNK_SIZE {
+        return vec![payload.to_vec()];
+    }
+
+    let chunks: Vec<&[u8]> =
+        payload.chunks(GELF_CHUNK_SIZE - GELF_CHUNK_HEADER).collect();
+    if chunks.len() > GELF_MAX_CHUNKS {
+        return Vec::new();
+    }
+
+    let message_id = uuid::Uuid::now_v7().as_u64_pair().1.to_be_bytes();
+    let count = chunks.len() as u8;
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(seq, chunk)| {
+            let mut datagram = Vec::with_capacity(GELF_CHUNK_HEADER + chunk.len());
+            datagram.extend_from_slice(&[0x1e, 0x0f]);
+            datagram.extend_from_slice(&message_id);
+            datagram.push(seq as u8);
+            datagram.push(count);
+            datagram.extend_from_slice(chunk);
+            datagram
+        })
+        .collect()
+}
+
 struct JsonVisitor<'a>(&'a mut Map<String, Value>);
 
 impl<'a> tracing::field::Visit for JsonVisitor<'a> {
@@ -1,38 +1,118 @@
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing_subscriber::{
+    fmt::writer::BoxMakeWriter, layer::SubscriberExt, util::SubscriberInitExt, Layer,
+};
 
 mod formatter;
+mod json_formatter;
+mod level;
 mod logstash;
+mod otlp;
+mod rate_limit;
+mod redact;
 mod span_fields_layer;
+mod timestamp;
 
-use crate::config::LogConfig;
+use crate::config::{LogConfig, LogFormat};
 use colored::control;
 use formatter::ColorfulFormatter;
+use json_formatter::JsonFormatter;
 use logstash::LogstashLayer;
+use rate_limit::RateLimitLayer;
+use redact::Redactor;
 use span_fields_layer::SpanFieldsLayer;
 
+pub use level::{default_log_level, log_level, reset_log_level, set_log_level};
+pub use timestamp::TimestampFormat;
+
+
+/// Поток, в который пишет консольный слой логгера
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogOutput {
+    #[default]
+    Stdout,
+    Stderr,
+    /// Логи отбрасываются (терминал занят интерфейсом)
+    Sink,
+}
+
+/// Завершение работы логгера перед выходом из процесса: сводка по подавленным
+/// сообщениям и отправка накопленных span'ов
+pub fn shutdown() {
+    rate_limit::flush();
+    otlp::shutdown();
+}
+
 /// Инициализация глобального логгера
 pub async fn init_logging(config: &LogConfig) -> anyhow::Result<()> {
-    let span_fields = SpanFieldsLayer::default();
+    init_logging_to(config, LogOutput::Stdout).await
+}
+
+/// Инициализация глобального логгера с выбором потока вывода
+pub async fn init_logging_to(config: &LogConfig, output: LogOutput) -> anyhow::Result<()> {
+    let span_fields = SpanFieldsLayer;
     control::set_override(config.use_color);
 
-    let console = tracing_subscriber::fmt::layer()
-        .event_format(ColorfulFormatter::new(config.use_color))
-        .with_writer(std::io::stdout);
+    let writer = match output {
+        LogOutput::Stdout => BoxMakeWriter::new(std::io::stdout),
+        LogOutput::Stderr => BoxMakeWriter::new(std::io::stderr),
+        LogOutput::Sink => BoxMakeWriter::new(std::io::sink),
+    };
 
-    let env_filter = EnvFilter::new(&config.log_level);
+    let timestamp = TimestampFormat::new(
+        config.timestamp_format.as_deref(),
+        config.timezone.as_deref(),
+    )?;
+    let redactor = Redactor::new(&config.redact_fields);
+    let console = match config.log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer()
+            .event_format(ColorfulFormatter::new(
+                config.use_color,
+                timestamp,
+                redactor.clone(),
+            ))
+            .with_writer(writer)
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .event_format(JsonFormatter { timestamp, redactor: redactor.clone() })
+            .with_writer(writer)
+            .boxed(),
+    };
 
-    let subscriber =
-        tracing_subscriber::registry().with(env_filter).with(span_fields).with(console);
+    let env_filter = level::reloadable(config.filter_directives());
 
     // Добавляем Logstash если настроен
-    if let (Some(host), Some(port), Some(app_name)) =
-        (&config.logstash_host, config.logstash_port, &config.app_name)
-    {
-        let logstash = LogstashLayer::new(&host, port, app_name).await?;
-        subscriber.with(logstash).init();
-    } else {
-        subscriber.init();
-    }
+    let logstash = match (&config.logstash_host, config.logstash_port, &config.app_name) {
+        (Some(host), Some(port), Some(app_name)) => Some(
+            LogstashLayer::new(
+                host,
+                port,
+                app_name,
+                config.logstash_transport,
+                config.logstash_protocol,
+                config.logstash_tls.as_ref(),
+            )
+            .await?
+            .with_redactor(redactor),
+        ),
+        _ => None,
+    };
+
+    // Экспорт span'ов в OpenTelemetry если настроен
+    let otlp = match &config.otlp {
+        Some(otlp) => Some(otlp::layer(otlp, config.app_name.as_deref())?),
+        None => None,
+    };
+
+    let rate_limit = config.rate_limit_per_minute.map(RateLimitLayer::new);
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(rate_limit)
+        .with(span_fields)
+        .with(console)
+        .with(logstash)
+        .with(otlp)
+        .init();
 
     Ok(())
 }
@@ -0,0 +1,49 @@
+use crate::config::OtlpConfig;
+use once_cell::sync::OnceCell;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::{
+    trace::{SdkTracerProvider, Tracer},
+    Resource,
+};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+const TRACES_PATH: &str = "/v1/traces";
+
+static PROVIDER: OnceCell<SdkTracerProvider> = OnceCell::new();
+
+/// Слой экспорта span'ов в коллектор OpenTelemetry по OTLP/HTTP
+pub fn layer<S>(
+    config: &OtlpConfig,
+    app_name: Option<&str>,
+) -> anyhow::Result<OpenTelemetryLayer<S, Tracer>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(config.signal_endpoint(TRACES_PATH))
+        .with_headers(config.headers.clone())
+        .build()?;
+
+    let service_name = config.service_name(app_name).to_string();
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name).build())
+        .build();
+    let tracer = provider.tracer(env!("CARGO_PKG_NAME"));
+    let _ = PROVIDER.set(provider);
+
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Отправляет накопленные span'ы; вызывается перед завершением процесса
+pub fn shutdown() {
+    if let Some(provider) = PROVIDER.get()
+        && let Err(e) = provider.shutdown()
+    {
+        eprintln!("Не удалось отправить span'ы OpenTelemetry: {}", e);
+    }
+}
@@ -0,0 +1,165 @@
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::callsite::Identifier;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::{Layer, layer::Context};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+static STATE: OnceCell<Arc<RateLimitState>> = OnceCell::new();
+
+/// Ограничение числа одинаковых сообщений (с одного места в коде) до `limit` в минуту.
+/// Ошибки не ограничиваются. Раз в минуту по каждому месту, где сообщения были
+/// отброшены, выводится сводка с их числом
+pub struct RateLimitLayer {
+    state: Arc<RateLimitState>,
+}
+
+#[derive(Default)]
+struct RateLimitState {
+    limit: u32,
+    callsites: Mutex<HashMap<Identifier, CallsiteWindow>>,
+}
+
+struct CallsiteWindow {
+    started: Instant,
+    count: u32,
+    suppressed: u64,
+    level: Level,
+    dcl: String,
+    message: String,
+}
+
+impl RateLimitLayer {
+    pub fn new(limit: u32) -> Self {
+        let state = Arc::new(RateLimitState { limit, ..Default::default() });
+        let _ = STATE.set(state.clone());
+
+        let flushed = state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(WINDOW);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                flushed.flush();
+            }
+        });
+
+        Self { state }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RateLimitLayer {
+    fn event_enabled(&self, event: &Event<'_>, _ctx: Context<'_, S>) -> bool {
+        let metadata = event.metadata();
+        if *metadata.level() == Level::ERROR || metadata.target() == module_path!() {
+            return true;
+        }
+
+        let mut callsites =
+            self.state.callsites.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let window = callsites.entry(metadata.callsite()).or_insert_with(|| {
+            let mut visitor = SummaryVisitor::default();
+            event.record(&mut visitor);
+            CallsiteWindow {
+                started: now,
+                count: 0,
+                suppressed: 0,
+                level: *metadata.level(),
+                dcl: visitor.dcl.unwrap_or_else(|| "app".to_string()),
+                message: visitor.message,
+            }
+        });
+        if now.duration_since(window.started) >= WINDOW {
+            window.started = now;
+            window.count = 0;
+        }
+
+        if window.count < self.state.limit {
+            window.count += 1;
+            true
+        } else {
+            window.suppressed += 1;
+            false
+        }
+    }
+}
+
+impl RateLimitState {
+    /// Выводит сводку по отброшенным сообщениям и забывает устаревшие окна
+    fn flush(&self) {
+        let summaries: Vec<(Level, String, String, u64)> = {
+            let mut callsites = self.callsites.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let summaries = callsites
+                .values_mut()
+                .filter(|window| window.suppressed > 0)
+                .map(|window| {
+                    let suppressed = std::mem::take(&mut window.suppressed);
+                    (window.level, window.dcl.clone(), window.message.clone(), suppressed)
+                })
+                .collect();
+            callsites.retain(|_, window| now.duration_since(window.started) < WINDOW);
+            summaries
+        };
+
+        for (level, dcl, message, suppressed) in summaries {
+            macro_rules! summary {
+                ($level:expr) => {
+                    tracing::event!(
+                        $level,
+                        dcl = dcl,
+                        suppressed = suppressed,
+                        "Подавлено похожих сообщений: {}. Пример: {}",
+                        suppressed,
+                        message
+                    )
+                };
+            }
+            match level {
+                Level::WARN => summary!(Level::WARN),
+                Level::INFO => summary!(Level::INFO),
+                Level::DEBUG => summary!(Level::DEBUG),
+                _ => summary!(Level::TRACE),
+            }
+        }
+    }
+}
+
+/// Выводит сводку по сообщениям, отброшенным с последнего вывода
+pub(super) fn flush() {
+    if let Some(state) = STATE.get() {
+        state.flush();
+    }
+}
+
+#[derive(Default)]
+struct SummaryVisitor {
+    dcl: Option<String>,
+    message: String,
+}
+
+impl Visit for SummaryVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "dcl" | "decl" => self.dcl = Some(value.to_string()),
+            "message" => self.message = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "dcl" | "decl" => {
+                self.dcl = Some(format!("{:?}", value).trim_matches('"').to_string())
+            }
+            "message" => self.message = format!("{:?}", value),
+            _ => {}
+        }
+    }
+}
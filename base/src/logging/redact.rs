@@ -0,0 +1,114 @@
+use serde_json::Value;
+
+const MASK: &str = "***";
+
+/// Маскирование значений полей, имена которых содержат слова из списка
+/// (`password`, `token` и т.п.). Кроме значений самих полей маскируются значения
+/// вида `password: "..."` и `"token":"..."` внутри строк — так выглядят структуры,
+/// выведенные через `Debug` или сериализованные в JSON
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    names: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new(names: &[String]) -> Self {
+        let names = names
+            .iter()
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect();
+        Self { names }
+    }
+
+    fn is_sensitive(&self, field: &str) -> bool {
+        let field = field.to_lowercase();
+        self.names.iter().any(|name| field.contains(name.as_str()))
+    }
+
+    /// Маскирует значение поля `field` целиком или секреты внутри него
+    pub fn redact_field(&self, field: &str, value: &mut Value) {
+        if self.names.is_empty() {
+            return;
+        }
+        if self.is_sensitive(field) {
+            *value = Value::String(MASK.to_string());
+            return;
+        }
+        match value {
+            Value::String(text) => self.redact_text(text),
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    self.redact_field(key, value);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.redact_field("", item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Маскирует значения в кавычках после чувствительных имён внутри текста
+    pub fn redact_text(&self, text: &mut String) {
+        if self.names.is_empty() || !text.contains('"') {
+            return;
+        }
+
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text.as_str();
+        while let Some(pos) = rest.find('"') {
+            let (before, after) = rest.split_at(pos);
+            result.push_str(before);
+            result.push('"');
+            rest = &after[1..];
+
+            if !self.is_sensitive(Self::preceding_name(&result)) {
+                continue;
+            }
+            // Кавычки строки, вложенной в другую строку, экранированы: `\"`
+            let closing = if result.ends_with("\\\"") {
+                rest.find("\\\"")
+            } else {
+                Self::closing_quote(rest)
+            };
+            let Some(end) = closing else {
+                break;
+            };
+            result.push_str(MASK);
+            rest = &rest[end..];
+        }
+        result.push_str(rest);
+        *text = result;
+    }
+
+    /// Имя перед открывающей кавычкой значения: `name: "`, `"name":"` или `name="`
+    fn preceding_name(prefix: &str) -> &str {
+        let prefix = &prefix[..prefix.len() - 1];
+        let prefix = prefix.strip_suffix('\\').unwrap_or(prefix);
+        let Some(prefix) = prefix.trim_end().strip_suffix([':', '=']) else {
+            return "";
+        };
+        let prefix = prefix.trim_end().trim_end_matches(['"', '\\']);
+        let start = prefix
+            .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        &prefix[start..]
+    }
+
+    /// Позиция закрывающей кавычки с учётом экранирования
+    fn closing_quote(text: &str) -> Option<usize> {
+        let mut escaped = false;
+        for (i, c) in text.char_indices() {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => return Some(i),
+                _ => escaped = false,
+            }
+        }
+        None
+    }
+}
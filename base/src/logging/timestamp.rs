@@ -0,0 +1,77 @@
+use chrono::format::StrftimeItems;
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Часовой пояс меток времени в логах
+#[derive(Debug, Clone, Copy)]
+enum Zone {
+    Local,
+    Utc,
+    Named(Tz),
+}
+
+/// Формат и часовой пояс меток времени консольного вывода
+#[derive(Debug, Clone)]
+pub struct TimestampFormat {
+    format: Option<String>,
+    zone: Zone,
+}
+
+impl TimestampFormat {
+    /// `format` — строка формата strftime, `timezone` — `local`, `utc`
+    /// или имя из базы IANA (`Europe/Moscow`)
+    pub fn new(format: Option<&str>, timezone: Option<&str>) -> anyhow::Result<Self> {
+        if let Some(format) = format {
+            StrftimeItems::new(format)
+                .parse()
+                .map_err(|_| anyhow::anyhow!("некорректный формат времени {:?}", format))?;
+        }
+        let zone = match timezone.map(str::trim) {
+            None => Zone::Local,
+            Some(tz) if tz.eq_ignore_ascii_case("local") => Zone::Local,
+            Some(tz) if tz.eq_ignore_ascii_case("utc") => Zone::Utc,
+            Some(tz) => Zone::Named(
+                tz.parse()
+                    .map_err(|_| anyhow::anyhow!("неизвестный часовой пояс {:?}", tz))?,
+            ),
+        };
+        Ok(Self { format: format.map(str::to_string), zone })
+    }
+
+    /// Текущее время в заданном формате, а без него — в формате `default`
+    pub fn now(&self, default: &str) -> String {
+        let now = Utc::now();
+        match self.zone {
+            Zone::Local => self.render(now.with_timezone(&Local), default),
+            Zone::Utc => self.render(now, default),
+            Zone::Named(tz) => self.render(now.with_timezone(&tz), default),
+        }
+    }
+
+    /// Текущее время в заданном формате, а без него — в RFC 3339 с миллисекундами
+    pub fn now_rfc3339(&self) -> String {
+        let now = Utc::now();
+        match self.zone {
+            Zone::Local => self.render_rfc3339(now.with_timezone(&Local)),
+            Zone::Utc => self.render_rfc3339(now),
+            Zone::Named(tz) => self.render_rfc3339(now.with_timezone(&tz)),
+        }
+    }
+
+    fn render<Z: TimeZone>(&self, time: DateTime<Z>, default: &str) -> String
+    where
+        Z::Offset: std::fmt::Display,
+    {
+        time.format(self.format.as_deref().unwrap_or(default)).to_string()
+    }
+
+    fn render_rfc3339<Z: TimeZone>(&self, time: DateTime<Z>) -> String
+    where
+        Z::Offset: std::fmt::Display,
+    {
+        match &self.format {
+            Some(format) => time.format(format).to_string(),
+            None => time.to_rfc3339_opts(SecondsFormat::Millis, false),
+        }
+    }
+}
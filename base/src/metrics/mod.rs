@@ -0,0 +1,56 @@
+use crate::config::MetricsConfig;
+use metrics_exporter_prometheus::PrometheusBuilder;
+use metrics_util::layers::FanoutBuilder;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+mod otlp;
+
+pub use otlp::shutdown;
+
+/// Период обслуживания гистограмм Prometheus-экспортера
+const PROMETHEUS_UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Установка глобального приёмника метрик. Метрики записываются макросами
+/// `metrics::counter!`, `metrics::gauge!` и `metrics::histogram!`;
+/// без настроенных экспортеров они отбрасываются
+pub fn init_metrics(
+    config: &MetricsConfig,
+    app_name: Option<&str>,
+) -> anyhow::Result<()> {
+    let mut fanout = FanoutBuilder::default();
+    let mut exporters = 0;
+
+    if let Some(prometheus) = &config.prometheus {
+        let addr: SocketAddr = prometheus.listen.parse()?;
+        let (recorder, exporter) =
+            PrometheusBuilder::new().with_http_listener(addr).build()?;
+        let handle = recorder.handle();
+        tokio::spawn(async move {
+            if let Err(e) = exporter.await {
+                tracing::error!(dcl = "Metrics", e = ?e, "Ошибка HTTP-эндпоинта Prometheus");
+            }
+        });
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PROMETHEUS_UPKEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                handle.run_upkeep();
+            }
+        });
+        fanout = fanout.add_recorder(recorder);
+        exporters += 1;
+    }
+
+    if let Some(otlp) = &config.otlp {
+        let interval = Duration::from_secs(config.otlp_interval_secs);
+        fanout = fanout.add_recorder(otlp::OtlpRecorder::new(otlp, interval, app_name)?);
+        exporters += 1;
+    }
+
+    if exporters > 0 {
+        metrics::set_global_recorder(fanout.build())
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use crate::config::OtlpConfig;
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata,
+    Recorder, SharedString, Unit,
+};
+use once_cell::sync::OnceCell;
+use opentelemetry::{
+    KeyValue,
+    metrics::{Meter, MeterProvider as _},
+};
+use opentelemetry_otlp::{MetricExporter, WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::{
+    Resource,
+    metrics::{PeriodicReader, SdkMeterProvider},
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const METRICS_PATH: &str = "/v1/metrics";
+
+static PROVIDER: OnceCell<SdkMeterProvider> = OnceCell::new();
+
+/// Приёмник метрик, передающий их в инструменты OpenTelemetry
+/// с периодической отправкой в коллектор по OTLP/HTTP
+pub(super) struct OtlpRecorder {
+    meter: Meter,
+    descriptions: Mutex<HashMap<String, (Option<Unit>, SharedString)>>,
+    counters: Mutex<HashMap<Key, Arc<OtlpCounter>>>,
+    gauges: Mutex<HashMap<Key, Arc<OtlpGauge>>>,
+    histograms: Mutex<HashMap<Key, Arc<OtlpHistogram>>>,
+}
+
+impl OtlpRecorder {
+    pub fn new(
+        config: &OtlpConfig,
+        interval: Duration,
+        app_name: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let exporter = MetricExporter::builder()
+            .with_http()
+            .with_endpoint(config.signal_endpoint(METRICS_PATH))
+            .with_headers(config.headers.clone())
+            .build()?;
+        let reader = PeriodicReader::builder(exporter).with_interval(interval).build();
+        let resource = Resource::builder()
+            .with_service_name(config.service_name(app_name).to_string())
+            .build();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_resource(resource)
+            .build();
+        let meter = provider.meter(env!("CARGO_PKG_NAME"));
+        let _ = PROVIDER.set(provider);
+
+        Ok(Self {
+            meter,
+            descriptions: Mutex::default(),
+            counters: Mutex::default(),
+            gauges: Mutex::default(),
+            histograms: Mutex::default(),
+        })
+    }
+
+    fn describe(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        if let Ok(mut descriptions) = self.descriptions.lock() {
+            descriptions.insert(key.as_str().to_string(), (unit, description));
+        }
+    }
+
+    /// Описание и единица измерения, заданные через `describe_*`
+    fn description(&self, name: &str) -> (Option<&'static str>, String) {
+        let descriptions = self.descriptions.lock().ok();
+        match descriptions.as_ref().and_then(|d| d.get(name)) {
+            Some((unit, description)) => {
+                (unit.map(|u| u.as_canonical_label()), description.to_string())
+            }
+            None => (None, String::new()),
+        }
+    }
+
+    fn attributes(key: &Key) -> Vec<KeyValue> {
+        key.labels()
+            .map(|label| {
+                KeyValue::new(label.key().to_string(), label.value().to_string())
+            })
+            .collect()
+    }
+}
+
+/// Общий для всех вызовов `register_*` обработчик метрики с данным именем и метками
+fn cached<T>(
+    map: &Mutex<HashMap<Key, Arc<T>>>,
+    key: &Key,
+    create: impl FnOnce() -> T,
+) -> Arc<T> {
+    let mut map = map.lock().unwrap_or_else(|e| e.into_inner());
+    map.entry(key.clone()).or_insert_with(|| Arc::new(create())).clone()
+}
+
+impl Recorder for OtlpRecorder {
+    fn describe_counter(
+        &self,
+        key: KeyName,
+        unit: Option<Unit>,
+        description: SharedString,
+    ) {
+        self.describe(key, unit, description);
+    }
+
+    fn describe_gauge(
+        &self,
+        key: KeyName,
+        unit: Option<Unit>,
+        description: SharedString,
+    ) {
+        self.describe(key, unit, description);
+    }
+
+    fn describe_histogram(
+        &self,
+        key: KeyName,
+        unit: Option<Unit>,
+        description: SharedString,
+    ) {
+        self.describe(key, unit, description);
+    }
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        let handle = cached(&self.counters, key, || {
+            let (unit, description) = self.description(key.name());
+            let mut builder = self
+                .meter
+                .u64_counter(key.name().to_string())
+                .with_description(description);
+            if let Some(unit) = unit {
+                builder = builder.with_unit(unit);
+            }
+            OtlpCounter {
+                counter: builder.build(),
+                attributes: Self::attributes(key),
+                total: AtomicU64::new(0),
+            }
+        });
+        Counter::from_arc(handle)
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        let handle = cached(&self.gauges, key, || {
+            let (unit, description) = self.description(key.name());
+            let mut builder = self
+                .meter
+                .f64_gauge(key.name().to_string())
+                .with_description(description);
+            if let Some(unit) = unit {
+                builder = builder.with_unit(unit);
+            }
+            OtlpGauge {
+                gauge: builder.build(),
+                attributes: Self::attributes(key),
+                value: AtomicU64::new(0f64.to_bits()),
+            }
+        });
+        Gauge::from_arc(handle)
+    }
+
+    fn register_histogram(&self, key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        let handle = cached(&self.histograms, key, || {
+            let (unit, description) = self.description(key.name());
+            let mut builder = self
+                .meter
+                .f64_histogram(key.name().to_string())
+                .with_description(description);
+            if let Some(unit) = unit {
+                builder = builder.with_unit(unit);
+            }
+            OtlpHistogram {
+                histogram: builder.build(),
+                attributes: Self::attributes(key),
+            }
+        });
+        Histogram::from_arc(handle)
+    }
+}
+
+struct OtlpCounter {
+    counter: opentelemetry::metrics::Counter<u64>,
+    attributes: Vec<KeyValue>,
+    /// Накопленное значение, нужное для `absolute`
+    total: AtomicU64,
+}
+
+impl CounterFn for OtlpCounter {
+    fn increment(&self, value: u64) {
+        self.total.fetch_add(value, Ordering::Relaxed);
+        self.counter.add(value, &self.attributes);
+    }
+
+    fn absolute(&self, value: u64) {
+        let previous = self.total.fetch_max(value, Ordering::Relaxed);
+        if value > previous {
+            self.counter.add(value - previous, &self.attributes);
+        }
+    }
+}
+
+struct OtlpGauge {
+    gauge: opentelemetry::metrics::Gauge<f64>,
+    attributes: Vec<KeyValue>,
+    /// Текущее значение в виде битов f64
+    value: AtomicU64,
+}
+
+impl OtlpGauge {
+    fn update(&self, change: impl Fn(f64) -> f64) {
+        let mut current = self.value.load(Ordering::Relaxed);
+        loop {
+            let updated = change(f64::from_bits(current));
+            match self.value.compare_exchange_weak(
+                current,
+                updated.to_bits(),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    self.gauge.record(updated, &self.attributes);
+                    return;
+                }
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+impl GaugeFn for OtlpGauge {
+    fn increment(&self, value: f64) {
+        self.update(|current| current + value);
+    }
+
+    fn decrement(&self, value: f64) {
+        self.update(|current| current - value);
+    }
+
+    fn set(&self, value: f64) {
+        self.update(|_| value);
+    }
+}
+
+struct OtlpHistogram {
+    histogram: opentelemetry::metrics::Histogram<f64>,
+    attributes: Vec<KeyValue>,
+}
+
+impl HistogramFn for OtlpHistogram {
+    fn record(&self, value: f64) {
+        self.histogram.record(value, &self.attributes);
+    }
+}
+
+/// Отправляет накопленные метрики; вызывается перед завершением процесса
+pub fn shutdown() {
+    if let Some(provider) = PROVIDER.get()
+        && let Err(e) = provider.shutdown()
+    {
+        eprintln!("Не удалось отправить метрики OpenTelemetry: {}", e);
+    }
+}
@@ -0,0 +1,440 @@
+use crate::services::{
+    domain_checker::DomainCheckerService, report::ReportEntry, state::StateService,
+};
+use axum::{
+    Json, Router,
+    extract::{Query, Request, State},
+    http::{Method, StatusCode, header},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+};
+use base::logging;
+use base::prelude::{
+    anyhow::Result,
+    chrono::Duration,
+    serde_json::{Value, json},
+    tokio::net::TcpListener,
+    tracing,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+
+/// Состояние и проверка одного профиля или корневой конфигурации
+#[derive(Clone)]
+struct Target {
+    state: Arc<StateService>,
+    checker: Arc<DomainCheckerService>,
+    max_silence: Duration,
+}
+
+impl Target {
+    fn new(
+        state: Arc<StateService>,
+        checker: DomainCheckerService,
+        check_interval_hours: u64,
+    ) -> Self {
+        // Зависшим считается цикл, пропустивший два интервала проверки
+        let max_silence = Duration::hours(check_interval_hours as i64 * 2);
+        Self { state, checker: Arc::new(checker), max_silence }
+    }
+}
+
+#[derive(Clone)]
+struct AppState {
+    root: Target,
+    /// Профили конфигурации; если заданы, проверки выполняются только в них
+    profiles: BTreeMap<String, Target>,
+    /// Токен, без которого не принимаются запросы, меняющие работу демона
+    token: Option<Arc<str>>,
+}
+
+impl AppState {
+    /// Профиль из запроса или корневая конфигурация, если профилей нет
+    fn target(&self, query: &ProfileQuery) -> Result<&Target, (StatusCode, Json<Value>)> {
+        match &query.profile {
+            Some(name) => self.profiles.get(name).ok_or_else(|| {
+                (StatusCode::NOT_FOUND, Json(json!({ "error": "unknown profile" })))
+            }),
+            None if self.profiles.is_empty() => Ok(&self.root),
+            None => Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "profile is required",
+                    "profiles": self.profiles.keys().collect::<Vec<_>>(),
+                })),
+            )),
+        }
+    }
+
+    /// Профиль из запроса, все профили или корневая конфигурация
+    fn targets(
+        &self,
+        query: &ProfileQuery,
+    ) -> Result<Vec<&Target>, (StatusCode, Json<Value>)> {
+        if query.profile.is_none() && !self.profiles.is_empty() {
+            return Ok(self.profiles.values().collect());
+        }
+        self.target(query).map(|target| vec![target])
+    }
+}
+
+/// Параметр `?profile=<имя>`
+#[derive(Debug, Deserialize)]
+struct ProfileQuery {
+    profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRequest {
+    hostname: String,
+    /// Добавить в ответ цепочку сертификатов хоста в PEM
+    #[serde(default)]
+    dump_certs: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct LogLevelRequest {
+    level: String,
+}
+
+/// Встроенный HTTP-сервер демона
+pub struct ApiServer {
+    listen: String,
+    app: AppState,
+    dcl: &'static str,
+}
+
+impl ApiServer {
+    pub fn new(
+        listen: &str,
+        state: Arc<StateService>,
+        checker: DomainCheckerService,
+        check_interval_hours: u64,
+    ) -> Self {
+        Self {
+            listen: listen.to_string(),
+            app: AppState {
+                root: Target::new(state, checker, check_interval_hours),
+                profiles: BTreeMap::new(),
+                token: None,
+            },
+            dcl: "ApiServer",
+        }
+    }
+
+    /// Профиль конфигурации, доступный по `?profile=<имя>`
+    pub fn with_profile(
+        mut self,
+        name: &str,
+        state: Arc<StateService>,
+        checker: DomainCheckerService,
+        check_interval_hours: u64,
+    ) -> Self {
+        let target = Target::new(state, checker, check_interval_hours);
+        self.app.profiles.insert(name.to_string(), target);
+        self
+    }
+
+    /// Требовать `Authorization: Bearer <token>` в запросах POST, PUT и DELETE
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.app.token = Some(Arc::from(token));
+        self
+    }
+
+    fn router(&self) -> Router {
+        Router::new()
+            .route("/", get(dashboard))
+            .route("/healthz", get(healthz))
+            .route("/readyz", get(readyz))
+            .route("/check", post(check))
+            .route("/run", post(run))
+            .route("/results", get(results))
+            .route("/domains", get(domains))
+            .route(
+                "/log-level",
+                get(get_log_level).put(put_log_level).delete(reset_log_level),
+            )
+            .layer(middleware::from_fn_with_state(self.app.clone(), authorize))
+            .with_state(self.app.clone())
+    }
+
+    pub async fn serve(self) -> Result<()> {
+        let listener = TcpListener::bind(&self.listen).await?;
+        tracing::info!(dcl = self.dcl, listen = self.listen, "HTTP-сервер запущен");
+        if self.app.token.is_none() {
+            tracing::warn!(
+                dcl = self.dcl,
+                "Не задан http_server.token: запуск проверок и смена уровня \
+                 логирования доступны без авторизации"
+            );
+        }
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+/// Веб-интерфейс только для чтения поверх `/results` и `/healthz`
+async fn dashboard() -> Html<&'static str> {
+    Html(DASHBOARD_HTML)
+}
+
+/// Итоги запусков для `/healthz` и `/readyz`: без профилей — корневой
+/// конфигурации, иначе по каждому профилю
+fn run_status(
+    app: &AppState,
+    query: &ProfileQuery,
+    ok: bool,
+    status: &str,
+) -> Json<Value> {
+    match (&query.profile, app.profiles.is_empty()) {
+        (None, false) => {
+            let runs: BTreeMap<&String, _> = app
+                .profiles
+                .iter()
+                .map(|(name, target)| (name, target.state.run_state()))
+                .collect();
+            Json(json!({ "status": status, "ok": ok, "profiles": runs }))
+        }
+        _ => {
+            let run = app.target(query).map(|target| target.state.run_state()).ok();
+            Json(json!({ "status": status, "run": run }))
+        }
+    }
+}
+
+/// Liveness: 503, если цикл проверки давно не запускался или завис; с профилями —
+/// если это так хотя бы для одного профиля
+async fn healthz(
+    State(app): State<AppState>,
+    Query(query): Query<ProfileQuery>,
+) -> (StatusCode, Json<Value>) {
+    let targets = match app.targets(&query) {
+        Ok(targets) => targets,
+        Err(error) => return error,
+    };
+    let alive = targets.iter().all(|target| target.state.is_alive(target.max_silence));
+    let code = if alive { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    let status = if alive { "ok" } else { "stale" };
+    (code, run_status(&app, &query, alive, status))
+}
+
+/// Readiness: 503 до завершения первого запуска проверки; с профилями — во всех
+/// профилях
+async fn readyz(
+    State(app): State<AppState>,
+    Query(query): Query<ProfileQuery>,
+) -> (StatusCode, Json<Value>) {
+    let targets = match app.targets(&query) {
+        Ok(targets) => targets,
+        Err(error) => return error,
+    };
+    let ready = targets.iter().all(|target| target.state.is_ready());
+    let code = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    let status = if ready { "ready" } else { "starting" };
+    (code, run_status(&app, &query, ready, status))
+}
+
+/// Внеочередной запуск периодической проверки; без `profile` — во всех профилях
+async fn run(
+    State(app): State<AppState>,
+    Query(query): Query<ProfileQuery>,
+) -> (StatusCode, Json<Value>) {
+    match app.targets(&query) {
+        Ok(targets) => {
+            targets.iter().for_each(|target| target.state.request_run());
+            (StatusCode::ACCEPTED, Json(json!({ "status": "scheduled" })))
+        }
+        Err(error) => error,
+    }
+}
+
+/// Проверка одного хоста по запросу, без уведомлений
+async fn check(
+    State(app): State<AppState>,
+    Query(query): Query<ProfileQuery>,
+    Json(request): Json<CheckRequest>,
+) -> (StatusCode, Json<Value>) {
+    let hostname = request.hostname.trim();
+    if hostname.is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "hostname is empty" })));
+    }
+    // Профиль не обязателен: без него хост проверяется с корневыми настройками
+    let checker = match query.profile {
+        Some(_) => match app.target(&query) {
+            Ok(target) => &target.checker,
+            Err(error) => return error,
+        },
+        None => &app.root.checker,
+    };
+    let entries: Vec<ReportEntry> = checker.check_host(hostname).await;
+    let mut response = json!({ "hostname": hostname, "entries": entries });
+    if request.dump_certs {
+        response["chain"] = match checker.certificate_chain(hostname).await {
+            Ok(chain) => json!(chain),
+            Err(e) => json!({ "error": e.to_string() }),
+        };
+    }
+    (StatusCode::OK, Json(response))
+}
+
+/// Отчёт последнего запуска проверки
+async fn results(
+    State(app): State<AppState>,
+    Query(query): Query<ProfileQuery>,
+) -> (StatusCode, Json<Value>) {
+    let target = match app.target(&query) {
+        Ok(target) => target,
+        Err(error) => return error,
+    };
+    match target.state.last_report() {
+        Some(report) => (StatusCode::OK, Json(json!(report))),
+        None => {
+            (StatusCode::NOT_FOUND, Json(json!({ "error": "no completed runs yet" })))
+        }
+    }
+}
+
+/// Проверка токена у запросов, меняющих работу демона; чтение открыто
+async fn authorize(
+    State(app): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let read_only = matches!(*request.method(), Method::GET | Method::HEAD);
+    let Some(token) = app.token.as_deref().filter(|_| !read_only) else {
+        return next.run(request).await;
+    };
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()));
+    if authorized {
+        return next.run(request).await;
+    }
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        Json(json!({ "error": "missing or invalid token" })),
+    )
+        .into_response()
+}
+
+/// Сравнение без раннего выхода, чтобы время ответа не выдавало совпавшую часть
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Домены, загруженные из источников при последнем запуске
+async fn domains(
+    State(app): State<AppState>,
+    Query(query): Query<ProfileQuery>,
+) -> (StatusCode, Json<Value>) {
+    let target = match app.target(&query) {
+        Ok(target) => target,
+        Err(error) => return error,
+    };
+    match target.state.last_report() {
+        Some(report) => (StatusCode::OK, Json(json!(report.hostnames))),
+        None => {
+            (StatusCode::NOT_FOUND, Json(json!({ "error": "no completed runs yet" })))
+        }
+    }
+}
+
+/// Текущий уровень логирования и уровень из конфигурации
+async fn get_log_level() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::OK,
+        Json(json!({
+            "level": logging::log_level(),
+            "default": logging::default_log_level(),
+        })),
+    )
+}
+
+/// Замена уровня логирования без перезапуска, например
+/// `{"level": "info,checker=debug,ssl_checker_core=debug"}`
+async fn put_log_level(
+    Json(request): Json<LogLevelRequest>,
+) -> (StatusCode, Json<Value>) {
+    match logging::set_log_level(request.level.trim()) {
+        Ok(()) => get_log_level().await,
+        Err(e) => (StatusCode::BAD_REQUEST, Json(json!({ "error": e.to_string() }))),
+    }
+}
+
+/// Возврат уровня логирования из конфигурации
+async fn reset_log_level() -> (StatusCode, Json<Value>) {
+    match logging::reset_log_level() {
+        Ok(()) => get_log_level().await,
+        Err(e) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() })))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    fn router(token: Option<&str>) -> Router {
+        let checker = DomainCheckerService::new(Vec::new(), Vec::new(), 14, 30);
+        let state = Arc::new(StateService::new(None));
+        let server = ApiServer::new("127.0.0.1:0", state, checker, 6);
+        match token {
+            Some(token) => server.with_token(token),
+            None => server,
+        }
+        .router()
+    }
+
+    async fn status(
+        router: &Router,
+        method: Method,
+        uri: &str,
+        authorization: Option<&str>,
+    ) -> StatusCode {
+        let mut request = axum::http::Request::builder().method(method).uri(uri);
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        let request = request.body(Body::empty()).unwrap();
+        router.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn changing_requests_require_token() {
+        let router = router(Some("secret"));
+        let run = |authorization| status(&router, Method::POST, "/run", authorization);
+        assert_eq!(run(None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(run(Some("Bearer wrong")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(run(Some("secret")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(run(Some("Bearer secret")).await, StatusCode::ACCEPTED);
+        assert_eq!(
+            status(&router, Method::DELETE, "/log-level", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        // Чтение открыто: пробы Kubernetes работают без токена
+        assert_eq!(
+            status(&router, Method::GET, "/readyz", None).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_are_accepted_without_configured_token() {
+        let router = router(None);
+        assert_eq!(
+            status(&router, Method::POST, "/run", None).await,
+            StatusCode::ACCEPTED
+        );
+    }
+}
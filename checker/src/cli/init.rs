@@ -0,0 +1,122 @@
+use crate::config::template::{TemplateValues, render_config_template};
+use base::prelude::anyhow::{Result, bail};
+use clap::Args;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    /// Путь к создаваемому файлу (`-` — вывести в stdout)
+    #[arg(long, short, default_value = "config.yml")]
+    pub output: String,
+
+    /// Перезаписать существующий файл
+    #[arg(long)]
+    pub force: bool,
+
+    /// Запросить значения в терминале
+    #[arg(long, short)]
+    pub interactive: bool,
+
+    /// Число часов между проверками
+    #[arg(long)]
+    pub check_interval_hours: Option<u64>,
+
+    /// Порог уведомлений о сроке регистрации домена, дней
+    #[arg(long)]
+    pub alarm_days: Option<i64>,
+
+    /// Порог уведомлений о сроке действия сертификата, дней
+    #[arg(long)]
+    pub ssl_alarm_days: Option<i64>,
+
+    /// Файл со списком доменов
+    #[arg(long)]
+    pub hosts_file: Option<String>,
+
+    /// Токен Telegram-бота
+    #[arg(long)]
+    pub telegram_bot_token: Option<String>,
+
+    /// Идентификатор чата Telegram
+    #[arg(long)]
+    pub telegram_chat_id: Option<String>,
+}
+
+impl InitArgs {
+    /// Создаёт файл конфигурации по флагам и, при `--interactive`, ответам пользователя
+    pub fn run(&self) -> Result<()> {
+        let mut values = TemplateValues::default();
+        if let Some(v) = self.check_interval_hours {
+            values.check_interval_hours = v;
+        }
+        if let Some(v) = self.alarm_days {
+            values.alarm_days = v;
+        }
+        if let Some(v) = self.ssl_alarm_days {
+            values.ssl_alarm_days = v;
+        }
+        if let Some(v) = &self.hosts_file {
+            values.hosts_file = v.clone();
+        }
+        values.telegram_bot_token = self.telegram_bot_token.clone();
+        values.telegram_chat_id = self.telegram_chat_id.clone();
+
+        if self.interactive {
+            Self::prompt_values(&mut values)?;
+        }
+
+        let content = render_config_template(&values);
+        if self.output == "-" {
+            print!("{}", content);
+            return Ok(());
+        }
+
+        if Path::new(&self.output).exists() && !self.force {
+            bail!(
+                "Файл {} уже существует, используйте --force для перезаписи",
+                self.output
+            );
+        }
+        std::fs::write(&self.output, content)?;
+        eprintln!("Создан файл конфигурации: {}", self.output);
+        Ok(())
+    }
+
+    fn prompt_values(values: &mut TemplateValues) -> Result<()> {
+        values.check_interval_hours =
+            prompt("Интервал между проверками, часов", values.check_interval_hours)?;
+        values.alarm_days = prompt("Порог для доменов, дней", values.alarm_days)?;
+        values.ssl_alarm_days =
+            prompt("Порог для сертификатов, дней", values.ssl_alarm_days)?;
+        values.hosts_file = prompt("Файл со списком доменов", values.hosts_file.clone())?;
+
+        let token = prompt("Токен Telegram-бота (пусто — без Telegram)", String::new())?;
+        if !token.is_empty() {
+            values.telegram_bot_token = Some(token);
+            values.telegram_chat_id =
+                Some(prompt("Идентификатор чата Telegram", String::new())?);
+        }
+        Ok(())
+    }
+}
+
+/// Запрос значения в терминале; пустой ввод оставляет значение по умолчанию
+fn prompt<T: FromStr + ToString>(question: &str, default: T) -> Result<T> {
+    loop {
+        eprint!("{} [{}]: ", question, default.to_string());
+        io::stderr().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Ok(default);
+        }
+        match answer.parse() {
+            Ok(value) => return Ok(value),
+            Err(_) => eprintln!("Некорректное значение, попробуйте ещё раз"),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use super::Cli;
+use base::prelude::anyhow::Result;
+use clap::CommandFactory;
+use clap_mangen::Man;
+use roff::{Roff, bold, roman};
+use std::io::Write;
+use std::path::Path;
+
+/// Ключи файла конфигурации, описываемые в разделе CONFIGURATION
+const CONFIG_KEYS: &[(&str, &str)] = &[
+    ("check_interval_hours", "число часов между проверками в режиме демона"),
+    ("alarm_days", "порог уведомлений о сроке регистрации домена, дней (7)"),
+    ("ssl_alarm_days", "порог уведомлений о сроке действия сертификата, дней (7)"),
+    ("acme_alarm_days", "порог для сертификатов Let's Encrypt и ZeroSSL, дней (min(ssl_alarm_days, 20))"),
+    ("report_timezone", "часовой пояс дат истечения в уведомлениях, например Europe/Moscow"),
+    ("ssl_thresholds", "пороги важности сертификатов: список days и severity вместо ssl_alarm_days"),
+    ("business_days", "пороги оповещения в рабочих днях: weekend, holidays"),
+    ("check_ssl", "проверять SSL-сертификаты (true)"),
+    ("check_domains", "проверять сроки регистрации доменов через WHOIS (true)"),
+    ("skip_labels", "метки имён, сертификаты которых не проверяются (_dmarc, _domainkey, _acme-challenge, _spf)"),
+    ("skip_label_prefixes", "начала меток имён, сертификаты которых не проверяются, например autodiscover, _sip"),
+    ("wildcard_labels", "метки имён, которыми проверяется wildcard-запись *.example.com (test)"),
+    ("wildcard_sample", "сколько реальных поддоменов из источников проверять вместо wildcard_labels (0)"),
+    ("concurrency", "сколько проверок выполняется одновременно (100)"),
+    ("source_timeout_secs", "ограничение времени загрузки каждого источника, секунд (300)"),
+    ("max_run_duration_secs", "ограничение времени всего запуска, секунд"),
+    ("overlap_policy", "запуск, наступивший до завершения предыдущего: skip или queue (skip)"),
+    ("notify_chunk_size", "отправлять уведомления частями по N, не дожидаясь конца запуска"),
+    ("progress_log_every", "выводить ход проверки в лог через каждые N проверок"),
+    ("log_config.log_level", "уровень логирования: trace, debug, info, warn, error"),
+    ("log_config.log_levels", "уровни логирования отдельных модулей, например whois_rust: warn"),
+    ("log_config.use_color", "цветной вывод логов в терминал"),
+    ("log_config.log_format", "формат вывода логов: text или json"),
+    ("log_config.redact_fields", "слова в именах полей, значения которых маскируются в логах"),
+    ("log_config.timestamp_format", "формат меток времени в логах (strftime)"),
+    ("log_config.timezone", "часовой пояс меток времени: local, utc или имя IANA"),
+    ("log_config.rate_limit_per_minute", "не больше N одинаковых сообщений в минуту, кроме ошибок"),
+    ("log_config.logstash_host", "адрес Logstash для отправки логов"),
+    ("log_config.logstash_port", "порт Logstash"),
+    ("log_config.logstash_transport", "транспорт отправки логов: tcp или udp"),
+    ("log_config.logstash_protocol", "формат отправки логов: json (Logstash) или gelf (Graylog)"),
+    ("log_config.logstash_tls", "TLS для отправки логов по tcp: ca_file, cert_file, key_file, server_name"),
+    ("log_config.otlp.endpoint", "адрес коллектора OpenTelemetry для экспорта трассировки (OTLP/HTTP)"),
+    ("log_config.otlp.headers", "дополнительные заголовки запросов к коллектору"),
+    ("log_config.otlp.service_name", "имя сервиса в трассировках; по умолчанию app_name"),
+    ("metrics.prometheus.listen", "адрес HTTP-эндпоинта с метриками для Prometheus"),
+    ("metrics.otlp", "отправка метрик в коллектор OpenTelemetry: endpoint, headers, service_name"),
+    ("metrics.otlp_interval_secs", "период отправки метрик по OTLP, секунд (по умолчанию 60)"),
+    ("log_config.app_name", "имя приложения в записях Logstash"),
+    (
+        "http",
+        "общие настройки HTTP-клиентов: user_agent, verify_tls, ca_bundle, proxy, таймауты",
+    ),
+    (
+        "domains",
+        "настройки по шаблонам хостов: port, sni, alpn, alarm_days, ssl_alarm_days, tags, \
+         owner, skip_ssl, skip_whois, quic, ssh, ssh_port, ssh_alarm_days",
+    ),
+    (
+        "budgets",
+        "бюджеты групп хостов по тегам из domains: tags, whois_per_minute, \
+         whois_concurrency, ssl_per_minute, ssl_concurrency",
+    ),
+    ("http_server.listen", "адрес встроенного HTTP-сервера, например 0.0.0.0:8080"),
+    (
+        "http_server.token",
+        "токен Authorization: Bearer для запросов POST, PUT и DELETE; или token_file",
+    ),
+    ("state_path", "файл, в котором сохраняется состояние между перезапусками"),
+    ("results_dir", "каталог, в который каждый запуск сохраняет полный отчёт в JSON"),
+    ("profiles", "независимые профили (например, клиенты) с ключами, заменяющими ключи верхнего уровня: sources, notifiers, пороги и другие, кроме http_server, log_config, metrics и budgets; состояние каждого профиля хранится отдельно"),
+    ("compliance_report", "отчёты о своевременности продлений за завершившиеся периоды (нужен state_path): dir, period (month, quarter), min_days, formats (html, csv, json, table)"),
+    ("renewal_check.certbot_dir", "каталог certbot для сверки с продлёнными сертификатами"),
+    ("renewal_check.acme_sh_dir", "каталог acme.sh для сверки с продлёнными сертификатами"),
+    ("ct_monitor.domains", "домены, о новых сертификатах на которые уведомлять по журналам CT"),
+    ("ct_monitor.interval_secs", "период опроса crt.sh, секунд (по умолчанию 3600)"),
+    ("ct_monitor.auto_add", "добавлять хосты из новых сертификатов в проверку (false)"),
+    ("recheck_healthy_after_hours", "не перепроверять хосты вне окна оповещения, проверенные за N часов"),
+    ("notify_changes", "раздел «Что изменилось» по сравнению с предыдущим запуском (true)"),
+    ("notify_issuer_changes", "уведомление о смене издателя сертификата хоста (true)"),
+    ("ssl_error_after_runs", "сообщать о неожиданной ошибке SSL после N неудачных запусков подряд (1)"),
+    ("mail_check", "проверка сертификатов почтовых серверов доменов по MX и STARTTLS: resolver, port, timeout_secs, connect_timeout_secs, helo"),
+    ("revocation_check", "доступность OCSP и CRL издателей сертификатов в окне оповещения: timeout_secs, connect_timeout_secs, retries, retry_interval_secs"),
+    ("whois_rules", "правила разбора срока регистрации из WHOIS, проверяемые раньше встроенных: zones, patterns, formats, utc_offset_hours, expiry_published"),
+    ("maintenance_windows", "окна работ, в которые находки по хостам не уходят в уведомления: from, until, hosts, notifiers, reason"),
+    ("vault", "подключение к HashiCorp Vault: address, token, token_file, namespace, timeout_secs, connect_timeout_secs, retries, retry_interval_secs"),
+    ("credentials", "ключи API и токены со сроком действия: name, expires_at или vault (путь KV v2), vault_field, alarm_days, owner, tags"),
+    ("vault_pki", "издатели и выпущенные сертификаты движков PKI Vault: mounts, issued, issuer_alarm_days, tags"),
+    ("acm", "сертификаты AWS Certificate Manager: regions, role_arns, tags, timeout_secs, connect_timeout_secs, retries, retry_interval_secs"),
+    ("azure", "сертификаты Azure Key Vault со сверкой с сертификатами на хостах: vaults, tenant_id, client_id, client_secret или client_secret_file, identity_client_id, tags, timeout_secs, connect_timeout_secs, retries, retry_interval_secs"),
+    ("venafi", "сертификаты корпоративного УЦ Venafi TPP или TLS Protect Cloud: platform (tpp, cloud), url, token или token_file, folder, tags, timeout_secs, connect_timeout_secs, retries, retry_interval_secs"),
+    ("gcp", "сертификаты Google Cloud (Certificate Manager и SSL-сертификаты балансировщиков): projects, locations, credentials_file, tags, timeout_secs, connect_timeout_secs, retries, retry_interval_secs"),
+    ("certificate_files", "локальные файлы сертификатов PEM, DER и PKCS#12 (подпись кода, S/MIME): path, password, password_file, tags"),
+    ("sources.file.filename", "текстовый файл со списком доменов"),
+    (
+        "sources.selectel",
+        "DNS-зоны Selectel: account_id, password или password_file, project_name, user",
+    ),
+    (
+        "sources.url",
+        "список хостов по HTTP(S), строки или JSON: url, bearer_token или username \
+         и password (и их варианты *_file)",
+    ),
+    (
+        "sources.docker",
+        "хосты из меток Traefik и Caddy контейнеров Docker: endpoint, owner_label",
+    ),
+    (
+        "sources.haproxy",
+        "хосты сертификатов HAProxy из файла конфигурации (config) или runtime API \
+         (runtime_api); timeout_secs",
+    ),
+    (
+        "sources.f5",
+        "виртуальные серверы F5 BIG-IP: address, username, password или password_file, \
+         partition",
+    ),
+    (
+        "sources.netscaler",
+        "SSL-виртуальные серверы Citrix ADC: address, username, password или \
+         password_file",
+    ),
+    (
+        "timeout_secs, connect_timeout_secs, retries, retry_interval_secs",
+        "таймауты и повторы HTTP-запросов в секциях telegram, selectel, url, \
+         docker, f5, netscaler, acm, gcp, azure, venafi, ct_monitor и \
+         revocation_check",
+    ),
+    ("notifiers.console", "вывод уведомлений в консоль; labels"),
+    (
+        "notifiers.telegram",
+        "уведомления в Telegram: bot_token или bot_token_file, chat_id, retries, \
+         group_by_root, min_severity, labels, owners, mentions, host_mentions",
+    ),
+    (
+        "notifiers.*.labels",
+        "значки важности (icons: info, warning, high, critical) и заголовки разделов \
+         (headers: changes, ssl, mail, domains, credentials, errors) вместо встроенных",
+    ),
+];
+
+/// Коды завершения режима single_shot
+const EXIT_CODES: &[(&str, &str)] = &[
+    ("0", "всё в порядке"),
+    ("1", "есть домены или сертификаты в окне оповещения"),
+    ("2", "есть истёкшие или критические записи"),
+    ("3", "есть ошибки проверки или запуск завершился ошибкой"),
+];
+
+/// Печатает man-страницу в stdout или записывает страницы всех команд в каталог
+pub fn generate_man(output_dir: Option<&Path>) -> Result<()> {
+    let mut command = Cli::command().disable_help_subcommand(true);
+    command.build();
+
+    let Some(dir) = output_dir else {
+        render_main_page(&Man::new(command), &mut std::io::stdout())?;
+        return Ok(());
+    };
+
+    std::fs::create_dir_all(dir)?;
+    for subcommand in command.get_subcommands().cloned() {
+        Man::new(subcommand).generate_to(dir)?;
+    }
+    let man = Man::new(command);
+    let path = dir.join(man.get_filename());
+    let mut file = std::fs::File::create(&path)?;
+    render_main_page(&man, &mut file)?;
+    eprintln!("Man-страницы записаны в {}", dir.display());
+    Ok(())
+}
+
+/// Основная страница: разделы clap_mangen и описание конфигурации и кодов завершения
+fn render_main_page(man: &Man, out: &mut dyn Write) -> std::io::Result<()> {
+    man.render_title(out)?;
+    man.render_name_section(out)?;
+    man.render_synopsis_section(out)?;
+    man.render_description_section(out)?;
+    man.render_options_section(out)?;
+    man.render_subcommands_section(out)?;
+
+    let mut roff = Roff::default();
+    roff.control("SH", ["CONFIGURATION"]);
+    roff.text([roman(
+        "Конфигурация читается из файла config.yml в рабочем каталоге. \
+         Любой ключ можно переопределить переменной окружения с префиксом APP, \
+         например APP.check_interval_hours=12.",
+    )]);
+    definition_list(&mut roff, CONFIG_KEYS);
+
+    roff.control("SH", ["EXIT STATUS"]);
+    definition_list(&mut roff, EXIT_CODES);
+    roff.text([roman(
+        "При наличии записей нескольких категорий приоритет у 2, затем 1, затем 3.",
+    )]);
+    roff.to_writer(out)?;
+
+    man.render_version_section(out)
+}
+
+fn definition_list(roff: &mut Roff, items: &[(&str, &str)]) {
+    for (term, description) in items {
+        roff.control("TP", []);
+        roff.text([bold(*term)]);
+        roff.text([roman(*description)]);
+    }
+}
@@ -0,0 +1,152 @@
+mod init;
+mod man;
+
+use crate::injectors::RunOptions;
+use crate::output::{ComplianceFormat, OutputFormat};
+use crate::services::compliance::CompliancePeriod;
+use crate::services::forecast::ForecastPeriod;
+use clap::{CommandFactory, Parser, Subcommand};
+use base::prelude::chrono::NaiveDate;
+use clap_complete::Shell;
+pub use init::InitArgs;
+pub use man::generate_man;
+use std::path::PathBuf;
+
+/// Проверка срока действия SSL-сертификатов и доменов
+#[derive(Debug, Parser)]
+#[command(name = "checker", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Выполнить все проверки, но выводить уведомления в stdout
+    /// вместо настроенных модулей уведомлений
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Проверять только SSL-сертификаты
+    #[arg(long, global = true, conflicts_with = "only_domains")]
+    pub only_ssl: bool,
+
+    /// Проверять только сроки регистрации доменов (WHOIS)
+    #[arg(long, global = true)]
+    pub only_domains: bool,
+
+    /// Ограничить запуск указанным источником (можно повторять)
+    #[arg(long = "source", value_name = "NAME", global = true)]
+    pub sources: Vec<String>,
+
+    /// Ограничить запуск указанным модулем уведомлений (можно повторять)
+    #[arg(long = "notifier", value_name = "NAME", global = true)]
+    pub notifiers: Vec<String>,
+
+    /// Сохранить цепочку сертификатов каждого проверенного хоста в каталог
+    /// (`<хост>.pem`)
+    #[arg(long, value_name = "DIR", global = true)]
+    pub dump_certs: Option<PathBuf>,
+
+    /// Профиль конфигурации; без него демон и одноразовая проверка выполняют все
+    /// профили
+    #[arg(long, value_name = "NAME", global = true)]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Одноразовая проверка срока действия доменов
+    #[command(name = "single_shot")]
+    SingleShot {
+        /// Вывести полный набор результатов в stdout в указанном формате
+        #[arg(long, visible_alias = "output", value_enum)]
+        format: Option<OutputFormat>,
+    },
+    /// Интерактивный режим: ход проверки и таблица результатов в терминале
+    Tui,
+    /// Проверить сроки корневых и промежуточных сертификатов хранилища доверия
+    #[command(name = "trust_store")]
+    TrustStore {
+        /// PEM-набор сертификатов вместо системного
+        #[arg(long, value_name = "FILE")]
+        bundle: Option<PathBuf>,
+        /// Окно оповещения: сертификаты, истекающие в ближайшие N дней
+        #[arg(long, value_name = "N", default_value_t = 365)]
+        days: i64,
+        /// Формат вывода результатов в stdout
+        #[arg(long, visible_alias = "output", value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+    /// Прогноз истечений сертификатов по неделям или месяцам: число сертификатов
+    /// по издателям и тегам по последнему отчёту из файла состояния
+    Forecast {
+        /// Горизонт прогноза, месяцев
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 6,
+            value_parser = clap::value_parser!(u32).range(1..=120)
+        )]
+        months: u32,
+        /// Длина периода: week или month
+        #[arg(long, value_name = "PERIOD", default_value = "month")]
+        period: ForecastPeriod,
+        /// Отчёт в формате JSON (`single_shot --format json`) вместо файла состояния
+        #[arg(long, value_name = "FILE")]
+        report: Option<PathBuf>,
+        /// Формат вывода прогноза в stdout
+        #[arg(long, visible_alias = "output", value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+    /// Отчёт о соответствии: доля продлений не позже порога до истечения, истёкшие
+    /// сертификаты и среднее число дней до истечения при продлении
+    Compliance {
+        /// Период отчёта: month или quarter; по умолчанию последний завершившийся
+        /// период из секции compliance_report
+        #[arg(long, value_name = "PERIOD", conflicts_with = "from")]
+        period: Option<CompliancePeriod>,
+        /// Начало произвольного интервала вместо периода
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        from: Option<NaiveDate>,
+        /// Конец интервала, не включая; по умолчанию текущий момент
+        #[arg(long, value_name = "YYYY-MM-DD", requires = "from")]
+        until: Option<NaiveDate>,
+        /// Продление своевременно не позже чем за N дней до истечения
+        #[arg(long, value_name = "N")]
+        min_days: Option<i64>,
+        /// Формат вывода отчёта в stdout
+        #[arg(long, visible_alias = "output", value_enum, default_value = "table")]
+        format: ComplianceFormat,
+    },
+    /// Создать пример файла конфигурации со всеми параметрами
+    Init(InitArgs),
+    /// Сгенерировать man-страницу
+    Man {
+        /// Записать страницы всех команд в каталог вместо вывода в stdout
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+    },
+    /// Вывести скрипт автодополнения для командной оболочки
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+impl Cli {
+    pub fn run_options(&self) -> RunOptions {
+        RunOptions {
+            dry_run: self.dry_run,
+            only_ssl: self.only_ssl,
+            only_domains: self.only_domains,
+            sources: self.sources.clone(),
+            notifiers: self.notifiers.clone(),
+            dump_certs: self.dump_certs.clone(),
+        }
+    }
+
+    /// Печатает в stdout скрипт автодополнения для указанной оболочки
+    pub fn print_completions(shell: Shell) {
+        let mut command = Cli::command();
+        let name = command.get_name().to_string();
+        clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+    }
+}
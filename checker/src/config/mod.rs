@@ -1,70 +1,772 @@
-use base::config::LogConfig;
+mod profiles;
+mod secrets;
+pub mod template;
+mod validation;
+
+use base::config::{LogConfig, MetricsConfig};
+pub use ssl_checker_core::config::{
+    BusinessDaysConfig, CertificateFileConfig, CheckBudgetConfig, CredentialConfig,
+    DomainOverrideConfig, HttpConfig, MaintenanceWindowConfig, RenewalCheckConfig,
+    SeverityThreshold,
+};
+use crate::output::ComplianceFormat;
+use ssl_checker_core::compliance::CompliancePeriod;
+use ssl_checker_core::{NotifierLabels, Severity};
+use ssl_checker_core::DomainCheckerService;
+use ssl_checker_core::WhoisParseRule;
 use base::prelude::{
-    config::{Config, Environment, File},
-    once_cell::sync::Lazy,
-    anyhow::Result
+    config::{Config, Environment, File, Value, ValueKind},
+    once_cell::sync::OnceCell,
+    anyhow::Result,
+    serde_json,
 };
-use std::collections::HashMap;
-use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::net::{AddrParseError, IpAddr, SocketAddr};
+use serde::{de::Error as _, Deserialize, Deserializer};
 
-pub static CONFIG: Lazy<ServiceConfig> =
-    Lazy::new(|| ServiceConfig::load().expect("Failed to load config"));
+static CONFIG: OnceCell<ServiceConfig> = OnceCell::new();
 
+/// Источник доменов; тип задаётся полем `type`, а если оно не указано — именем секции
 #[derive(Debug, Deserialize)]
-#[serde(untagged)]
+#[serde(tag = "type", deny_unknown_fields)]
 pub enum SourceConfig {
+    #[serde(rename = "file", alias = "FileConfig")]
     FileConfig {
         filename: String,
     },
+    #[serde(rename = "selectel", alias = "SelectelConfig")]
     SelectelConfig {
         account_id: String,
+        #[serde(default)]
         password: String,
+        /// Файл, из которого при запуске читается `password`
+        password_file: Option<String>,
         project_name: String,
         user: String,
+        /// Таймаут HTTP-запроса к API, секунд
+        timeout_secs: Option<u64>,
+        /// Таймаут установки соединения, секунд
+        connect_timeout_secs: Option<u64>,
+        /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+        retries: Option<u32>,
+        /// Пауза между повторами, секунд
+        retry_interval_secs: Option<u64>,
+    },
+    #[serde(rename = "url", alias = "UrlConfig")]
+    UrlConfig {
+        /// Адрес списка: строки с хостами или JSON-массив
+        url: String,
+        /// Токен для заголовка `Authorization: Bearer`
+        #[serde(default)]
+        bearer_token: String,
+        /// Файл, из которого при запуске читается `bearer_token`
+        bearer_token_file: Option<String>,
+        /// Логин для заголовка `Authorization: Basic`
+        username: Option<String>,
+        #[serde(default)]
+        password: String,
+        /// Файл, из которого при запуске читается `password`
+        password_file: Option<String>,
+        /// Таймаут HTTP-запроса, секунд
+        timeout_secs: Option<u64>,
+        /// Таймаут установки соединения, секунд
+        connect_timeout_secs: Option<u64>,
+        /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+        retries: Option<u32>,
+        /// Пауза между повторами, секунд
+        retry_interval_secs: Option<u64>,
+    },
+    #[serde(rename = "docker", alias = "DockerConfig")]
+    DockerConfig {
+        /// Адрес демона: `unix:///var/run/docker.sock` (по умолчанию),
+        /// `tcp://host:2375` или `https://host:2376`
+        endpoint: Option<String>,
+        /// Метка контейнера с командой-владельцем его хостов
+        owner_label: Option<String>,
+        /// Таймаут HTTP-запроса, секунд
+        timeout_secs: Option<u64>,
+        /// Таймаут установки соединения, секунд
+        connect_timeout_secs: Option<u64>,
+        /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+        retries: Option<u32>,
+        /// Пауза между повторами, секунд
+        retry_interval_secs: Option<u64>,
+    },
+    #[serde(rename = "haproxy", alias = "HaproxyConfig")]
+    HaproxyConfig {
+        /// Файл конфигурации: сертификаты `crt`/`crt-list` и SNI из ACL
+        config: Option<String>,
+        /// Runtime API: `unix:///run/haproxy/admin.sock` или `tcp://host:9999`
+        runtime_api: Option<String>,
+        /// Таймаут ответа runtime API, секунд
+        timeout_secs: Option<u64>,
+    },
+    #[serde(rename = "f5", alias = "F5Config")]
+    F5Config {
+        /// Адрес интерфейса управления BIG-IP
+        address: String,
+        username: String,
+        #[serde(default)]
+        password: String,
+        /// Файл, из которого при запуске читается `password`
+        password_file: Option<String>,
+        /// Раздел, виртуальные серверы которого читаются; по умолчанию все
+        partition: Option<String>,
+        /// Таймаут HTTP-запроса, секунд
+        timeout_secs: Option<u64>,
+        /// Таймаут установки соединения, секунд
+        connect_timeout_secs: Option<u64>,
+        /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+        retries: Option<u32>,
+        /// Пауза между повторами, секунд
+        retry_interval_secs: Option<u64>,
+    },
+    #[serde(rename = "netscaler", alias = "NetScalerConfig")]
+    NetScalerConfig {
+        /// Адрес NITRO API (NSIP)
+        address: String,
+        username: String,
+        #[serde(default)]
+        password: String,
+        /// Файл, из которого при запуске читается `password`
+        password_file: Option<String>,
+        /// Таймаут HTTP-запроса, секунд
+        timeout_secs: Option<u64>,
+        /// Таймаут установки соединения, секунд
+        connect_timeout_secs: Option<u64>,
+        /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+        retries: Option<u32>,
+        /// Пауза между повторами, секунд
+        retry_interval_secs: Option<u64>,
     },
+    /// Тип, зарегистрированный в `ssl_checker_core::REGISTRY`
+    #[serde(skip)]
+    Plugin(PluginConfig),
 }
 
+/// Поведение периодической проверки, если запуск длится дольше `check_interval_hours`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlapPolicy {
+    /// Пропущенные запуски не выполняются, следующий — по расписанию
+    #[default]
+    Skip,
+    /// Пропущенный запуск выполняется сразу после затянувшегося, один раз
+    Queue,
+}
+
+/// Модуль уведомлений; тип задаётся полем `type`, а если оно не указано — именем секции
 #[derive(Debug, Deserialize)]
-#[serde(untagged)]
+#[serde(tag = "type", deny_unknown_fields)]
+// Секции разбираются один раз при загрузке, размер вариантов не важен
+#[allow(clippy::large_enum_variant)]
 pub enum NotifierConfig {
+    #[serde(rename = "telegram", alias = "Telegram")]
     Telegram {
+        #[serde(default)]
         bot_token: String,
+        /// Файл, из которого при запуске читается `bot_token`
+        bot_token_file: Option<String>,
         chat_id: String,
-        #[serde(default = "NotifierConfig::default_retries")]
-        retries: u32,
+        /// Таймаут HTTP-запроса к Bot API, секунд
+        timeout_secs: Option<u64>,
+        /// Таймаут установки соединения, секунд
+        connect_timeout_secs: Option<u64>,
+        /// Число повторов отправки сообщения
+        retries: Option<u32>,
+        /// Пауза между повторами, секунд
+        retry_interval_secs: Option<u64>,
+        /// Группировать сертификаты по корневому домену
+        #[serde(default)]
+        group_by_root: bool,
+        /// Отправлять только записи не ниже указанной важности
+        min_severity: Option<Severity>,
+        /// Значки важности и заголовки разделов
+        #[serde(default)]
+        labels: NotifierLabels,
+        /// Отправлять только записи хостов указанных команд-владельцев
+        #[serde(default)]
+        owners: Vec<String>,
+        /// Упоминания команд-владельцев в записях: команда → `@username`
+        #[serde(default)]
+        mentions: HashMap<String, String>,
+        /// Упоминания ответственных по шаблонам имён хостов: шаблон → `@username`
+        #[serde(default)]
+        host_mentions: HashMap<String, String>,
+    },
+    #[serde(rename = "console", alias = "Console")]
+    Console {
+        /// Метки важности и заголовки разделов
+        #[serde(default)]
+        labels: NotifierLabels,
     },
-    Console,
+    /// Тип, зарегистрированный в `ssl_checker_core::REGISTRY`
+    #[serde(skip)]
+    Plugin(PluginConfig),
+}
+
+/// Секция, тип которой не относится к встроенным модулям
+#[derive(Debug)]
+pub struct PluginConfig {
+    pub kind: String,
+    /// Поля секции, кроме `type` и `enabled`
+    pub settings: serde_json::Value,
+}
+
+/// Именованная секция `sources` или `notifiers`
+trait SectionConfig: for<'a> Deserialize<'a> {
+    /// Значения поля `type`, которые разбирает само перечисление
+    const BUILTIN_TYPES: &'static [&'static str];
+
+    fn plugin(plugin: PluginConfig) -> Self;
+}
+
+impl SectionConfig for SourceConfig {
+    const BUILTIN_TYPES: &'static [&'static str] =
+        &[
+            "file",
+            "FileConfig",
+            "selectel",
+            "SelectelConfig",
+            "url",
+            "UrlConfig",
+            "docker",
+            "DockerConfig",
+            "haproxy",
+            "HaproxyConfig",
+            "f5",
+            "F5Config",
+            "netscaler",
+            "NetScalerConfig",
+        ];
+
+    fn plugin(plugin: PluginConfig) -> Self {
+        SourceConfig::Plugin(plugin)
+    }
+}
+
+impl SectionConfig for NotifierConfig {
+    const BUILTIN_TYPES: &'static [&'static str] =
+        &["telegram", "Telegram", "console", "Console"];
+
+    fn plugin(plugin: PluginConfig) -> Self {
+        NotifierConfig::Plugin(plugin)
+    }
+}
+
+
+#[derive(Debug, Deserialize)]
+pub struct HttpServerConfig {
+    pub listen: String,
+    /// Токен `Authorization: Bearer` для запросов, меняющих работу демона
+    /// (POST, PUT, DELETE); без него такие запросы принимаются от любого клиента
+    #[serde(default)]
+    pub token: String,
+    /// Файл, из которого при запуске читается `token`
+    pub token_file: Option<String>,
+}
+
+/// Мониторинг журналов Certificate Transparency через crt.sh
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CtMonitorConfig {
+    /// Домены, на которые (вместе с поддоменами) отслеживается выпуск сертификатов
+    pub domains: Vec<String>,
+    /// Период опроса crt.sh, секунд
+    #[serde(default = "CtMonitorConfig::default_interval_secs")]
+    pub interval_secs: u64,
+    /// Добавлять найденные в журналах хосты в список проверяемых
+    #[serde(default)]
+    pub auto_add: bool,
+    /// Таймаут HTTP-запроса к crt.sh, секунд
+    pub timeout_secs: Option<u64>,
+    /// Таймаут установки соединения, секунд
+    pub connect_timeout_secs: Option<u64>,
+    /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+    pub retries: Option<u32>,
+    /// Пауза между повторами, секунд
+    pub retry_interval_secs: Option<u64>,
+}
+
+impl CtMonitorConfig {
+    fn default_interval_secs() -> u64 {
+        3600
+    }
+}
+
+/// Периодический отчёт о соответствии для аудита: доля своевременных продлений,
+/// истёкшие сертификаты и среднее число дней до истечения при продлении
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComplianceReportConfig {
+    /// Каталог, в который сохраняются отчёты за завершившиеся периоды
+    pub dir: String,
+    /// Период отчёта: month или quarter
+    #[serde(default)]
+    pub period: CompliancePeriod,
+    /// Продление своевременно, если выполнено не позже чем за столько дней до истечения
+    #[serde(default = "ComplianceReportConfig::default_min_days")]
+    pub min_days: i64,
+    /// Форматы файлов отчёта: html, csv, json, table (`.txt`)
+    #[serde(default = "ComplianceReportConfig::default_formats")]
+    pub formats: Vec<ComplianceFormat>,
+}
+
+impl ComplianceReportConfig {
+    pub(crate) fn default_min_days() -> i64 {
+        30
+    }
+
+    fn default_formats() -> Vec<ComplianceFormat> {
+        vec![ComplianceFormat::Html, ComplianceFormat::Csv]
+    }
+}
+
+/// Проверка доступности OCSP-респондеров и списков отзыва издателей сертификатов
+/// в окне оповещения
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RevocationCheckConfig {
+    /// Таймаут HTTP-запроса к OCSP или CRL, секунд
+    pub timeout_secs: Option<u64>,
+    /// Таймаут установки соединения, секунд
+    pub connect_timeout_secs: Option<u64>,
+    /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+    pub retries: Option<u32>,
+    /// Пауза между повторами, секунд
+    pub retry_interval_secs: Option<u64>,
+}
+
+/// Подключение к HashiCorp Vault
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VaultConfig {
+    /// Адрес сервера, например `https://vault.example.com:8200`
+    pub address: String,
+    /// Токен с правом чтения метаданных секретов
+    #[serde(default)]
+    pub token: String,
+    /// Файл, из которого при запуске читается `token`
+    pub token_file: Option<String>,
+    /// Пространство имён Vault Enterprise
+    pub namespace: Option<String>,
+    /// Таймаут HTTP-запроса, секунд
+    pub timeout_secs: Option<u64>,
+    /// Таймаут установки соединения, секунд
+    pub connect_timeout_secs: Option<u64>,
+    /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+    pub retries: Option<u32>,
+    /// Пауза между повторами, секунд
+    pub retry_interval_secs: Option<u64>,
+}
+
+/// Издатели и выпущенные сертификаты движков PKI Vault; подключение — из секции
+/// `vault`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VaultPkiConfig {
+    /// Пути движков PKI, например `pki` и `pki_int`
+    pub mounts: Vec<String>,
+    /// Проверять выпущенные сертификаты, а не только издателей
+    #[serde(default = "ServiceConfig::default_true")]
+    pub issued: bool,
+    /// Порог оповещения для издателей; по умолчанию `ssl_alarm_days`
+    pub issuer_alarm_days: Option<i64>,
+    /// Теги всех записей PKI
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Сертификаты AWS Certificate Manager. Учётные данные берутся из стандартной
+/// цепочки AWS: переменные окружения, профиль, роль экземпляра или задачи
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AcmConfig {
+    /// Регионы, например `eu-central-1`; по умолчанию регион из окружения AWS
+    #[serde(default)]
+    pub regions: Vec<String>,
+    /// Роли других учётных записей, принимаемые через STS; если заданы,
+    /// сертификаты своей учётной записи не читаются
+    #[serde(default)]
+    pub role_arns: Vec<String>,
+    /// Теги всех записей ACM
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Таймаут HTTP-запроса, секунд
+    pub timeout_secs: Option<u64>,
+    /// Таймаут установки соединения, секунд
+    pub connect_timeout_secs: Option<u64>,
+    /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+    pub retries: Option<u32>,
+    /// Пауза между повторами, секунд
+    pub retry_interval_secs: Option<u64>,
 }
 
-impl NotifierConfig {
-    fn default_retries() -> u32 { 5 }
+/// Сертификаты Google Cloud: Certificate Manager и классические SSL-сертификаты
+/// балансировщиков
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GcpConfig {
+    /// Идентификаторы проектов
+    pub projects: Vec<String>,
+    /// Расположения Certificate Manager; по умолчанию `global`
+    #[serde(default)]
+    pub locations: Vec<String>,
+    /// Ключ сервисного аккаунта (JSON); по умолчанию `GOOGLE_APPLICATION_CREDENTIALS`,
+    /// а без него — сервер метаданных
+    pub credentials_file: Option<String>,
+    /// Теги всех записей Google Cloud
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Таймаут HTTP-запроса, секунд
+    pub timeout_secs: Option<u64>,
+    /// Таймаут установки соединения, секунд
+    pub connect_timeout_secs: Option<u64>,
+    /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+    pub retries: Option<u32>,
+    /// Пауза между повторами, секунд
+    pub retry_interval_secs: Option<u64>,
 }
 
+/// Платформа Venafi
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VenafiPlatform {
+    /// Trust Protection Platform
+    #[default]
+    Tpp,
+    /// TLS Protect Cloud
+    Cloud,
+}
+
+/// Сертификаты корпоративного УЦ Venafi, в том числе ещё не развёрнутые
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VenafiConfig {
+    #[serde(default)]
+    pub platform: VenafiPlatform,
+    /// Адрес TPP (`https://tpp.example.com`); для Cloud по умолчанию
+    /// `https://api.venafi.cloud`
+    pub url: Option<String>,
+    /// Токен доступа TPP с областью `certificate` или ключ API Cloud
+    #[serde(default)]
+    pub token: String,
+    /// Файл, из которого при запуске читается `token`
+    pub token_file: Option<String>,
+    /// Папка политик TPP, сертификаты которой читаются рекурсивно;
+    /// по умолчанию `\VED\Policy`
+    pub folder: Option<String>,
+    /// Теги всех записей Venafi
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Таймаут HTTP-запроса, секунд
+    pub timeout_secs: Option<u64>,
+    /// Таймаут установки соединения, секунд
+    pub connect_timeout_secs: Option<u64>,
+    /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+    pub retries: Option<u32>,
+    /// Пауза между повторами, секунд
+    pub retry_interval_secs: Option<u64>,
+}
+
+/// Сертификаты Azure Key Vault. Без `client_id` токен выдаёт управляемое
+/// удостоверение
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AzureConfig {
+    /// Имена хранилищ (`kv-prod`) или их адреса (`https://kv-prod.vault.azure.net`)
+    pub vaults: Vec<String>,
+    /// Клиент (тенант) Microsoft Entra ID приложения
+    pub tenant_id: Option<String>,
+    /// Идентификатор приложения Entra ID
+    pub client_id: Option<String>,
+    /// Секрет приложения
+    #[serde(default)]
+    pub client_secret: String,
+    /// Файл, из которого при запуске читается `client_secret`
+    pub client_secret_file: Option<String>,
+    /// Идентификатор пользовательского управляемого удостоверения
+    pub identity_client_id: Option<String>,
+    /// Теги всех записей Key Vault
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Таймаут HTTP-запроса, секунд
+    pub timeout_secs: Option<u64>,
+    /// Таймаут установки соединения, секунд
+    pub connect_timeout_secs: Option<u64>,
+    /// Число повторов запроса при сетевых ошибках и ответах 429/5xx
+    pub retries: Option<u32>,
+    /// Пауза между повторами, секунд
+    pub retry_interval_secs: Option<u64>,
+}
+
+/// Проверка сертификатов почтовых серверов доменов через MX и STARTTLS
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MailCheckConfig {
+    /// DNS-сервер для запроса MX (`1.1.1.1` или `1.1.1.1:53`); по умолчанию
+    /// первый `nameserver` из `/etc/resolv.conf`
+    pub resolver: Option<String>,
+    /// Порт SMTP, по умолчанию 25
+    pub port: Option<u16>,
+    /// Таймаут SMTP-диалога и TLS-рукопожатия, секунд
+    pub timeout_secs: Option<u64>,
+    /// Таймаут установки соединения и запроса MX, секунд
+    pub connect_timeout_secs: Option<u64>,
+    /// Имя в команде EHLO
+    pub helo: Option<String>,
+}
+
+impl MailCheckConfig {
+    /// Адрес DNS-сервера; порт по умолчанию 53
+    pub fn resolver_addr(
+        &self,
+    ) -> Option<std::result::Result<SocketAddr, AddrParseError>> {
+        self.resolver.as_deref().map(|resolver| {
+            resolver
+                .parse::<SocketAddr>()
+                .or_else(|_| resolver.parse::<IpAddr>().map(|ip| SocketAddr::new(ip, 53)))
+        })
+    }
+}
 
 #[derive(Debug, Deserialize)]
 pub struct ServiceConfig {
     #[serde(default)]
     pub log_config: LogConfig,
+    /// Экспорт метрик в Prometheus и OpenTelemetry
+    #[serde(default)]
+    pub metrics: MetricsConfig,
     pub check_interval_hours: u64,
+    /// Модули уведомлений; с профилями могут задаваться только в профилях
+    #[serde(default, deserialize_with = "ServiceConfig::deserialize_notifiers")]
     pub notifiers: HashMap<String, NotifierConfig>,
+    /// Источники доменов; с профилями могут задаваться только в профилях
+    #[serde(default, deserialize_with = "ServiceConfig::deserialize_sources")]
     pub sources: HashMap<String, SourceConfig>,
     #[serde(default = "ServiceConfig::default_alarm_days")]
     pub alarm_days: i64,
     #[serde(default = "ServiceConfig::default_alarm_days")]
     pub ssl_alarm_days: i64,
+    /// Порог оповещения для сертификатов Let's Encrypt и ZeroSSL, дней
+    pub acme_alarm_days: Option<i64>,
+    /// Пороги важности сертификатов; заменяют `ssl_alarm_days`
+    pub ssl_thresholds: Option<Vec<SeverityThreshold>>,
+    /// Часовой пояс дат истечения в уведомлениях: имя из базы IANA или `UTC`
+    pub report_timezone: Option<String>,
+    /// Отсчитывать пороги оповещения в рабочих днях
+    pub business_days: Option<BusinessDaysConfig>,
+    #[serde(default = "ServiceConfig::default_true")]
+    pub check_ssl: bool,
+    #[serde(default = "ServiceConfig::default_true")]
+    pub check_domains: bool,
+    /// Метки имён, сертификаты которых не проверяются (служебные TXT-записи)
+    #[serde(default = "ServiceConfig::default_skip_labels")]
+    pub skip_labels: Vec<String>,
+    /// Начала меток имён, сертификаты которых не проверяются, например `autodiscover`
+    #[serde(default)]
+    pub skip_label_prefixes: Vec<String>,
+    /// Метки имён, которыми проверяется wildcard-запись `*.example.com`
+    #[serde(default = "ServiceConfig::default_wildcard_labels")]
+    pub wildcard_labels: Vec<String>,
+    /// Сколько реальных поддоменов из источников проверять вместо `wildcard_labels`
+    #[serde(default)]
+    pub wildcard_sample: usize,
+    /// Сколько проверок выполняется одновременно
+    #[serde(default = "ServiceConfig::default_concurrency")]
+    pub concurrency: usize,
+    /// Ограничение времени загрузки каждого источника, секунд
+    #[serde(default = "ServiceConfig::default_source_timeout_secs")]
+    pub source_timeout_secs: u64,
+    /// Ограничение времени всего запуска, секунд; должно быть меньше интервала проверок
+    pub max_run_duration_secs: Option<u64>,
+    /// Что делать с запуском, время которого наступило до завершения предыдущего
+    #[serde(default)]
+    pub overlap_policy: OverlapPolicy,
+    /// Через сколько уведомлений модули отправляют накопленное, не дожидаясь конца запуска
+    pub notify_chunk_size: Option<usize>,
+    /// Через сколько проверок выводить ход запуска в лог
+    pub progress_log_every: Option<usize>,
+    #[serde(default)]
+    pub http: HttpConfig,
+    /// Настройки проверки по шаблонам имён хостов
+    #[serde(default)]
+    pub domains: HashMap<String, DomainOverrideConfig>,
+    /// Бюджеты групп хостов по тегам из `domains`: частота и число одновременных
+    /// проверок WHOIS и сертификатов
+    #[serde(default)]
+    pub budgets: HashMap<String, CheckBudgetConfig>,
+    pub http_server: Option<HttpServerConfig>,
+    /// Путь к файлу состояния; без него состояние хранится только в памяти
+    pub state_path: Option<String>,
+    /// Каталог, в который каждый запуск сохраняет полный отчёт в JSON
+    pub results_dir: Option<String>,
+    /// Сверка сертификатов на серверах с продлёнными certbot и acme.sh
+    pub renewal_check: Option<RenewalCheckConfig>,
+    /// Уведомления о новых сертификатах на домены в журналах Certificate Transparency
+    pub ct_monitor: Option<CtMonitorConfig>,
+    /// Не перепроверять хосты, успешно проверенные не раньше указанного числа часов
+    /// назад и остающиеся вне окна оповещения
+    pub recheck_healthy_after_hours: Option<u64>,
+    /// Добавлять в уведомления раздел «Что изменилось» по сравнению с предыдущим запуском
+    #[serde(default = "ServiceConfig::default_true")]
+    pub notify_changes: bool,
+    /// Отдельное уведомление о смене издателя сертификата хоста
+    #[serde(default = "ServiceConfig::default_true")]
+    pub notify_issuer_changes: bool,
+    /// Сообщать о неожиданной ошибке SSL после указанного числа неудачных запусков подряд
+    pub ssl_error_after_runs: Option<u32>,
+    /// Проверка сертификатов почтовых серверов доменов
+    pub mail_check: Option<MailCheckConfig>,
+    /// Проверка доступности OCSP и CRL издателей сертификатов в окне оповещения
+    pub revocation_check: Option<RevocationCheckConfig>,
+    /// Правила разбора срока регистрации из ответов WHOIS, проверяемые раньше встроенных
+    #[serde(default)]
+    pub whois_rules: Vec<WhoisParseRule>,
+    /// Окна работ, в которые находки по хостам не уходят в уведомления
+    #[serde(default)]
+    pub maintenance_windows: Vec<MaintenanceWindowConfig>,
+    /// Локальные файлы сертификатов (PEM, DER, PKCS#12), проверяемые вместе с хостами
+    #[serde(default)]
+    pub certificate_files: Vec<CertificateFileConfig>,
+    /// Подключение к Vault для сроков учётных данных из метаданных секретов
+    pub vault: Option<VaultConfig>,
+    /// Ключи API, токены и пароли со сроком действия
+    #[serde(default)]
+    pub credentials: Vec<CredentialConfig>,
+    /// Издатели и сертификаты движков PKI Vault
+    pub vault_pki: Option<VaultPkiConfig>,
+    /// Сертификаты AWS Certificate Manager со сроками и состоянием продления
+    pub acm: Option<AcmConfig>,
+    /// Сертификаты Google Cloud со сроками и состоянием выпуска
+    pub gcp: Option<GcpConfig>,
+    /// Сертификаты Azure Key Vault со сверкой с сертификатами на хостах
+    pub azure: Option<AzureConfig>,
+    /// Сертификаты корпоративного УЦ Venafi со сверкой с сертификатами на хостах
+    pub venafi: Option<VenafiConfig>,
+    /// Отчёты о соответствии за завершившиеся месяцы или кварталы; нужен `state_path`
+    pub compliance_report: Option<ComplianceReportConfig>,
+    /// Независимые профили, например клиенты: ключи профиля заменяют одноимённые
+    /// ключи верхнего уровня, каждый профиль проверяется своим циклом
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Value>,
+    /// Разобранные и проверенные конфигурации профилей
+    #[serde(skip)]
+    pub profile_configs: BTreeMap<String, ServiceConfig>,
 }
 
 impl ServiceConfig {
+    /// Загружает и проверяет конфигурацию; вызывается один раз при запуске
+    pub fn init() -> Result<&'static Self> {
+        CONFIG.get_or_try_init(Self::load)
+    }
+
+    /// Конфигурация, загруженная [`ServiceConfig::init`]
+    pub fn get() -> &'static Self {
+        CONFIG.get().expect("Конфигурация не загружена")
+    }
+
     pub fn load() -> Result<Self> {
         let env_path = std::env::var("CONFIG_PATH").unwrap_or("config.yml".to_string());
 
-        let config: Self = Config::builder()
+        let built = Config::builder()
             .add_source(File::with_name(&env_path).required(false))
             .add_source(Environment::with_prefix("APP").separator("."))
-            .build()?
-            .try_deserialize()?;
+            .build()?;
+        Self::from_config(built)
+    }
+
+    /// Проверенная конфигурация с профилями из собранных источников
+    fn from_config(built: Config) -> Result<Self> {
+        let root: HashMap<String, Value> = built.clone().try_deserialize()?;
+        let mut config: Self = built.try_deserialize()?;
+        config.validate()?;
+        config.resolve_secrets()?;
+        config.profile_configs = config.load_profiles(&root)?;
 
         Ok(config)
     }
+
+    fn deserialize_sources<'de, D>(
+        deserializer: D,
+    ) -> std::result::Result<HashMap<String, SourceConfig>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Self::deserialize_sections(deserializer, "sources")
+    }
+
+    fn deserialize_notifiers<'de, D>(
+        deserializer: D,
+    ) -> std::result::Result<HashMap<String, NotifierConfig>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Self::deserialize_sections(deserializer, "notifiers")
+    }
+
+    /// Разбор именованных секций. Если в секции нет поля `type`, тип берётся из её имени,
+    /// как в конфигурациях прежних версий (`telegram:`, `file:`, `console: ~`).
+    /// Секции с `enabled: false` не попадают в конфигурацию, секции с типом,
+    /// неизвестным встроенным модулям, разбираются как [`PluginConfig`].
+    /// Ошибка разбора указывает путь к секции, например `notifiers.telegram`
+    fn deserialize_sections<'de, D, T>(
+        deserializer: D,
+        section: &str,
+    ) -> std::result::Result<HashMap<String, T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: SectionConfig,
+    {
+        let raw = HashMap::<String, Value>::deserialize(deserializer)?;
+        let mut sections = HashMap::new();
+        for (name, value) in raw {
+            let error = |e| D::Error::custom(format!("{}.{}: {}", section, name, e));
+            let mut table = match value.kind {
+                ValueKind::Nil => Default::default(),
+                _ => value.into_table().map_err(error)?,
+            };
+
+            // Отключённая секция пропускается целиком и не проверяется
+            let enabled = match table.remove("enabled") {
+                Some(enabled) => enabled.into_bool().map_err(error)?,
+                None => true,
+            };
+            if !enabled {
+                continue;
+            }
+
+            let kind = match table.remove("type") {
+                Some(kind) => kind.into_string().map_err(error)?,
+                None => name.clone(),
+            };
+            let conf = if T::BUILTIN_TYPES.contains(&kind.as_str()) {
+                table.insert("type".to_string(), Value::new(None, kind));
+                Value::new(None, ValueKind::Table(table))
+                    .try_deserialize()
+                    .map_err(error)?
+            } else {
+                let settings = Value::new(None, ValueKind::Table(table))
+                    .try_deserialize()
+                    .map_err(error)?;
+                T::plugin(PluginConfig { kind, settings })
+            };
+            sections.insert(name, conf);
+        }
+        Ok(sections)
+    }
+
     fn default_alarm_days() -> i64 {7}
+    fn default_true() -> bool { true }
+    fn default_concurrency() -> usize { DomainCheckerService::DEFAULT_CONCURRENCY }
+    fn default_wildcard_labels() -> Vec<String> {
+        vec![DomainCheckerService::DEFAULT_WILDCARD_LABEL.to_string()]
+    }
+    fn default_skip_labels() -> Vec<String> {
+        let labels = DomainCheckerService::DEFAULT_SKIPPED_LABELS;
+        labels.iter().map(|label| label.to_string()).collect()
+    }
+    fn default_source_timeout_secs() -> u64 {
+        DomainCheckerService::DEFAULT_SOURCE_TIMEOUT.as_secs()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,230 @@
+use super::ServiceConfig;
+use base::prelude::{
+    anyhow::{Context, Result, bail},
+    config::{Value, ValueKind},
+};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Ключи, общие для всего демона: в профилях не задаются. Бюджеты общие, чтобы
+/// ограничение регистратора не умножалось на число профилей
+const DAEMON_KEYS: [&str; 5] =
+    ["profiles", "http_server", "log_config", "metrics", "budgets"];
+
+impl ServiceConfig {
+    /// Конфигурации профилей: ключи верхнего уровня профиля заменяют одноимённые
+    /// ключи корневой конфигурации, остальные наследуются. Файл состояния, архив
+    /// отчётов и каталог отчётов о соответствии, если профиль не задаёт их сам,
+    /// получают имя профиля, чтобы профили не делили состояние
+    pub(super) fn load_profiles(
+        &self,
+        root: &HashMap<String, Value>,
+    ) -> Result<BTreeMap<String, ServiceConfig>> {
+        let mut profiles = BTreeMap::new();
+        let mut state_paths = HashMap::new();
+        for (name, value) in &self.profiles {
+            let path = format!("profiles.{}", name);
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                bail!(
+                    "{}: имя профиля может содержать только латиницу, цифры, _ и -",
+                    path
+                );
+            }
+            let table = match &value.kind {
+                ValueKind::Nil => Default::default(),
+                _ => value.clone().into_table().with_context(|| path.clone())?,
+            };
+            if let Some(key) = DAEMON_KEYS.iter().find(|key| table.contains_key(**key)) {
+                bail!("{}.{}: параметр общий для всего демона", path, key);
+            }
+
+            let mut merged: HashMap<String, Value> = root
+                .iter()
+                .filter(|(key, _)| key.as_str() != "profiles")
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            let own_state = table.contains_key("state_path");
+            let own_results = table.contains_key("results_dir");
+            let own_compliance = table.contains_key("compliance_report");
+            merged.extend(table);
+
+            let mut profile: ServiceConfig = Value::new(None, ValueKind::Table(merged))
+                .try_deserialize()
+                .with_context(|| path.clone())?;
+            if !own_state {
+                profile.state_path =
+                    profile.state_path.map(|p| namespaced_file(&p, name));
+            }
+            if !own_results {
+                profile.results_dir = profile.results_dir.map(|dir| join(&dir, name));
+            }
+            if !own_compliance && let Some(compliance) = &mut profile.compliance_report {
+                compliance.dir = join(&compliance.dir, name);
+            }
+            profile.validate().with_context(|| path.clone())?;
+            profile.resolve_secrets().with_context(|| path.clone())?;
+
+            if let Some(state_path) = &profile.state_path
+                && let Some(other) = state_paths.insert(state_path.clone(), name.clone())
+            {
+                bail!(
+                    "{}.state_path: {:?} уже использует профиль {}",
+                    path,
+                    state_path,
+                    other
+                );
+            }
+            profiles.insert(name.clone(), profile);
+        }
+        Ok(profiles)
+    }
+}
+
+/// `state.json` профиля `shop` — `state.shop.json`
+fn namespaced_file(path: &str, profile: &str) -> String {
+    let path = Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => {
+            format!("{}.{}.{}", stem, profile, extension.to_string_lossy())
+        }
+        None => format!("{}.{}", stem, profile),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+fn join(dir: &str, profile: &str) -> String {
+    Path::new(dir).join(profile).to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::prelude::config::{Config, File, FileFormat};
+
+    const ROOT: &str = r#"
+check_interval_hours: 6
+alarm_days: 14
+ssl_alarm_days: 7
+state_path: "/data/state.json"
+results_dir: "/data/results"
+compliance_report:
+  dir: "/data/compliance"
+sources:
+  file:
+    filename: "/data/domains.txt"
+notifiers:
+  console: ~
+"#;
+
+    fn load(profiles: &str) -> Result<ServiceConfig> {
+        let yaml = format!("{}profiles:\n{}", ROOT, profiles);
+        let built = Config::builder()
+            .add_source(File::from_str(&yaml, FileFormat::Yaml))
+            .build()?;
+        ServiceConfig::from_config(built)
+    }
+
+    fn error(profiles: &str) -> String {
+        format!("{:#}", load(profiles).expect_err("конфигурация принята"))
+    }
+
+    #[test]
+    fn profile_keys_replace_root_keys_and_others_are_inherited() {
+        let config = load(
+            r#"
+  shop:
+    ssl_alarm_days: 30
+    sources:
+      url:
+        url: "https://cmdb.example.com/hosts"
+  blog: ~
+"#,
+        )
+        .unwrap();
+        let shop = &config.profile_configs["shop"];
+        assert_eq!(shop.ssl_alarm_days, 30);
+        assert_eq!(shop.alarm_days, 14);
+        assert_eq!(shop.check_interval_hours, 6);
+        assert_eq!(shop.sources.keys().collect::<Vec<_>>(), ["url"]);
+        assert!(shop.notifiers.contains_key("console"));
+        assert!(shop.profiles.is_empty());
+
+        let blog = &config.profile_configs["blog"];
+        assert_eq!(blog.ssl_alarm_days, 7);
+        assert_eq!(blog.sources.keys().collect::<Vec<_>>(), ["file"]);
+    }
+
+    #[test]
+    fn inherited_paths_are_namespaced() {
+        let config = load(
+            r#"
+  shop: ~
+  blog:
+    state_path: "/data/blog.json"
+    results_dir: "/data/blog-results"
+    compliance_report:
+      dir: "/data/blog-compliance"
+"#,
+        )
+        .unwrap();
+        let shop = &config.profile_configs["shop"];
+        assert_eq!(shop.state_path.as_deref(), Some("/data/state.shop.json"));
+        assert_eq!(shop.results_dir.as_deref(), Some("/data/results/shop"));
+        assert_eq!(shop.compliance_report.as_ref().unwrap().dir, "/data/compliance/shop");
+
+        let blog = &config.profile_configs["blog"];
+        assert_eq!(blog.state_path.as_deref(), Some("/data/blog.json"));
+        assert_eq!(blog.results_dir.as_deref(), Some("/data/blog-results"));
+        assert_eq!(blog.compliance_report.as_ref().unwrap().dir, "/data/blog-compliance");
+    }
+
+    #[test]
+    fn daemon_keys_are_rejected_in_profiles() {
+        for key in DAEMON_KEYS {
+            let message = error(&format!("  shop:\n    {}: {{}}\n", key));
+            assert!(
+                message.contains(&format!("profiles.shop.{}: параметр общий", key)),
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn shared_state_path_is_rejected() {
+        let message = error(
+            r#"
+  blog:
+    state_path: "/data/shared.json"
+  shop:
+    state_path: "/data/shared.json"
+"#,
+        );
+        assert!(message.contains("profiles.shop.state_path"), "{}", message);
+        assert!(message.contains("уже использует профиль blog"), "{}", message);
+    }
+
+    #[test]
+    fn invalid_profile_name_is_rejected() {
+        let message = error("  \"shop a\": ~\n");
+        assert!(message.contains("имя профиля может содержать"), "{}", message);
+    }
+
+    #[test]
+    fn profile_is_validated_after_merge() {
+        let message = error("  shop:\n    ssl_alarm_days: 0\n");
+        assert!(message.starts_with("profiles.shop"), "{}", message);
+    }
+
+    #[test]
+    fn state_file_name_gets_profile_before_extension() {
+        assert_eq!(namespaced_file("/data/state.json", "shop"), "/data/state.shop.json");
+        assert_eq!(namespaced_file("state", "shop"), "state.shop");
+        assert_eq!(join("/data/results/", "shop"), "/data/results/shop");
+    }
+}
@@ -0,0 +1,71 @@
+use super::SecretRef;
+use base::prelude::{
+    anyhow::{anyhow, Context, Result},
+    serde_json,
+};
+
+/// Получение секретов из AWS Secrets Manager и SSM Parameter Store.
+/// Учётные данные и регион берутся из стандартной цепочки AWS
+/// (переменные окружения, профиль, роль ECS/EC2, web identity)
+pub(super) struct AwsSecretsProvider {
+    secrets_manager: aws_sdk_secretsmanager::Client,
+    ssm: aws_sdk_ssm::Client,
+}
+
+impl AwsSecretsProvider {
+    pub async fn new() -> Self {
+        let sdk_config = aws_config::load_from_env().await;
+        Self {
+            secrets_manager: aws_sdk_secretsmanager::Client::new(&sdk_config),
+            ssm: aws_sdk_ssm::Client::new(&sdk_config),
+        }
+    }
+
+    pub async fn resolve(&self, reference: &SecretRef) -> Result<String> {
+        match reference {
+            SecretRef::AwsSecretsManager { secret_id, json_key } => {
+                let secret = self
+                    .secrets_manager
+                    .get_secret_value()
+                    .secret_id(secret_id)
+                    .send()
+                    .await
+                    .with_context(|| format!("Secrets Manager: секрет {}", secret_id))?;
+                let value = secret.secret_string().ok_or_else(|| {
+                    anyhow!("Секрет {} не содержит строкового значения", secret_id)
+                })?;
+
+                match json_key {
+                    Some(key) => Self::json_field(value, key)
+                        .with_context(|| format!("Секрет {}, ключ {}", secret_id, key)),
+                    None => Ok(value.to_string()),
+                }
+            }
+            SecretRef::Ssm { name } => {
+                let parameter = self
+                    .ssm
+                    .get_parameter()
+                    .name(name)
+                    .with_decryption(true)
+                    .send()
+                    .await
+                    .with_context(|| format!("SSM: параметр {}", name))?;
+                parameter
+                    .parameter()
+                    .and_then(|p| p.value())
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow!("Параметр {} не содержит значения", name))
+            }
+        }
+    }
+
+    /// Значение поля секрета, хранящегося в Secrets Manager в виде JSON-объекта
+    fn json_field(secret: &str, key: &str) -> Result<String> {
+        let json: serde_json::Value = serde_json::from_str(secret)?;
+        match json.get(key) {
+            Some(serde_json::Value::String(value)) => Ok(value.clone()),
+            Some(value) => Ok(value.to_string()),
+            None => Err(anyhow!("Ключ отсутствует")),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+mod aws;
+
+use super::{NotifierConfig, ServiceConfig, SourceConfig};
+use aws::AwsSecretsProvider;
+use base::prelude::{
+    anyhow::{bail, Context, Result},
+    tokio,
+};
+
+/// Ссылка на секрет во внешнем хранилище, заданная вместо значения параметра
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum SecretRef {
+    /// `aws-sm:<secret-id>` или `aws-sm:<secret-id>#<json-key>` — AWS Secrets Manager
+    AwsSecretsManager { secret_id: String, json_key: Option<String> },
+    /// `ssm:<parameter-name>` — AWS SSM Parameter Store (с расшифровкой SecureString)
+    Ssm { name: String },
+}
+
+impl SecretRef {
+    fn parse(value: &str) -> Option<Self> {
+        if let Some(reference) = value.strip_prefix("aws-sm:") {
+            let (secret_id, json_key) = match reference.split_once('#') {
+                Some((id, key)) => (id, Some(key.to_string())),
+                None => (reference, None),
+            };
+            return Some(SecretRef::AwsSecretsManager {
+                secret_id: secret_id.to_string(),
+                json_key,
+            });
+        }
+        value.strip_prefix("ssm:").map(|name| SecretRef::Ssm { name: name.to_string() })
+    }
+}
+
+/// Параметр с учётными данными: путь секции, имя поля, значение и файл `<field>_file`
+struct Credential<'a> {
+    path: String,
+    field: &'static str,
+    value: &'a mut String,
+    file: Option<&'a str>,
+}
+
+impl ServiceConfig {
+    /// Подставляет значения учётных данных из файлов `*_file`
+    /// (например, смонтированных секретов Docker или Kubernetes)
+    /// и из внешних хранилищ по ссылкам вида `aws-sm:` и `ssm:`
+    pub(super) fn resolve_secrets(&mut self) -> Result<()> {
+        let mut credentials = self.credentials();
+        for credential in credentials.iter_mut() {
+            resolve_file(credential)?;
+        }
+
+        let references: Vec<(&mut Credential, SecretRef)> = credentials
+            .iter_mut()
+            .filter_map(|c| SecretRef::parse(c.value).map(|r| (c, r)))
+            .collect();
+        if references.is_empty() {
+            return Ok(());
+        }
+
+        // Конфигурация загружается синхронно, в том числе изнутри рантайма tokio,
+        // поэтому обращения к AWS выполняются в отдельном потоке со своим рантаймом
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()?
+                        .block_on(async {
+                            let provider = AwsSecretsProvider::new().await;
+                            for (credential, reference) in references {
+                                let Credential { path, field, value, .. } = credential;
+                                **value = provider
+                                    .resolve(&reference)
+                                    .await
+                                    .with_context(|| format!("{}.{}", path, field))?;
+                            }
+                            Ok(())
+                        })
+                })
+                .join()
+                .unwrap_or_else(|_| bail!("Поток получения секретов завершился аварийно"))
+        })
+    }
+
+    fn credentials(&mut self) -> Vec<Credential<'_>> {
+        let mut credentials = Vec::new();
+
+        for (name, source) in self.sources.iter_mut() {
+            match source {
+                SourceConfig::SelectelConfig { password, password_file, .. }
+                | SourceConfig::F5Config { password, password_file, .. }
+                | SourceConfig::NetScalerConfig { password, password_file, .. } => {
+                    credentials.push(Credential {
+                        path: format!("sources.{}", name),
+                        field: "password",
+                        value: password,
+                        file: password_file.as_deref(),
+                    });
+                }
+                // Авторизация списка необязательна: токен подставляется,
+                // только если задан, пароль — если задан логин
+                SourceConfig::UrlConfig {
+                    bearer_token,
+                    bearer_token_file,
+                    username,
+                    password,
+                    password_file,
+                    ..
+                } => {
+                    if !bearer_token.is_empty() || bearer_token_file.is_some() {
+                        credentials.push(Credential {
+                            path: format!("sources.{}", name),
+                            field: "bearer_token",
+                            value: bearer_token,
+                            file: bearer_token_file.as_deref(),
+                        });
+                    }
+                    if username.is_some() {
+                        credentials.push(Credential {
+                            path: format!("sources.{}", name),
+                            field: "password",
+                            value: password,
+                            file: password_file.as_deref(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (name, notifier) in self.notifiers.iter_mut() {
+            if let NotifierConfig::Telegram { bot_token, bot_token_file, .. } = notifier {
+                credentials.push(Credential {
+                    path: format!("notifiers.{}", name),
+                    field: "bot_token",
+                    value: bot_token,
+                    file: bot_token_file.as_deref(),
+                });
+            }
+        }
+
+        // Токен HTTP-сервера необязателен
+        if let Some(http_server) = &mut self.http_server
+            && (!http_server.token.is_empty() || http_server.token_file.is_some())
+        {
+            credentials.push(Credential {
+                path: "http_server".to_string(),
+                field: "token",
+                value: &mut http_server.token,
+                file: http_server.token_file.as_deref(),
+            });
+        }
+
+        if let Some(vault) = &mut self.vault {
+            credentials.push(Credential {
+                path: "vault".to_string(),
+                field: "token",
+                value: &mut vault.token,
+                file: vault.token_file.as_deref(),
+            });
+        }
+
+        // Без приложения токен выдаёт управляемое удостоверение
+        if let Some(azure) = &mut self.azure
+            && azure.client_id.is_some()
+        {
+            credentials.push(Credential {
+                path: "azure".to_string(),
+                field: "client_secret",
+                value: &mut azure.client_secret,
+                file: azure.client_secret_file.as_deref(),
+            });
+        }
+
+        if let Some(venafi) = &mut self.venafi {
+            credentials.push(Credential {
+                path: "venafi".to_string(),
+                field: "token",
+                value: &mut venafi.token,
+                file: venafi.token_file.as_deref(),
+            });
+        }
+
+        for (i, file) in self.certificate_files.iter_mut().enumerate() {
+            // Пароль нужен только зашифрованным PKCS#12
+            if !file.password.is_empty() || file.password_file.is_some() {
+                credentials.push(Credential {
+                    path: format!("certificate_files[{}]", i),
+                    field: "password",
+                    value: &mut file.password,
+                    file: file.password_file.as_deref(),
+                });
+            }
+        }
+
+        credentials
+    }
+}
+
+/// Читает секрет из файла, если он задан; ровно один из `<field>` и `<field>_file`
+/// должен быть указан
+fn resolve_file(credential: &mut Credential) -> Result<()> {
+    let Credential { path, field, value, file } = credential;
+    match file {
+        Some(_) if !value.is_empty() => {
+            bail!("{}: укажите либо {}, либо {}_file", path, field, field)
+        }
+        Some(file) => {
+            let content = std::fs::read_to_string(&file).with_context(|| {
+                format!("{}: не удалось прочитать {}_file {}", path, field, file)
+            })?;
+            **value = content.trim().to_string();
+            if value.is_empty() {
+                bail!("{}: файл {} пуст", path, file);
+            }
+        }
+        None if value.is_empty() => {
+            bail!("{}: не задан параметр {} или {}_file", path, field, field)
+        }
+        None => {}
+    }
+    Ok(())
+}
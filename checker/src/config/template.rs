@@ -0,0 +1,431 @@
+/// Значения, подставляемые в генерируемый шаблон конфигурации
+#[derive(Debug, Clone)]
+pub struct TemplateValues {
+    pub check_interval_hours: u64,
+    pub alarm_days: i64,
+    pub ssl_alarm_days: i64,
+    pub hosts_file: String,
+    pub telegram_bot_token: Option<String>,
+    pub telegram_chat_id: Option<String>,
+}
+
+impl Default for TemplateValues {
+    fn default() -> Self {
+        Self {
+            check_interval_hours: 24,
+            alarm_days: 7,
+            ssl_alarm_days: 7,
+            hosts_file: "hostnames.txt".to_string(),
+            telegram_bot_token: None,
+            telegram_chat_id: None,
+        }
+    }
+}
+
+/// Закомментировать блок, если он не заполнен пользователем
+fn commented(block: &str, enabled: bool) -> String {
+    if enabled {
+        return block.to_string();
+    }
+    block
+        .lines()
+        .map(|line| if line.is_empty() { String::new() } else { format!("# {}", line) })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Генерирует закомментированный пример `config.yml` со всеми вариантами
+/// источников, модулей уведомлений, порогов и настроек логирования
+pub fn render_config_template(values: &TemplateValues) -> String {
+    let telegram_enabled =
+        values.telegram_bot_token.is_some() && values.telegram_chat_id.is_some();
+    let telegram = commented(
+        &format!(
+            "  telegram:\n    bot_token: \"{}\"\n    chat_id: \"{}\"\n    \
+             timeout_secs: 3\n    connect_timeout_secs: 1\n    retries: 5\n    \
+             retry_interval_secs: 1\n    group_by_root: false\n    min_severity: info",
+            values.telegram_bot_token.as_deref().unwrap_or("1231231231:WASDwasd..."),
+            values.telegram_chat_id.as_deref().unwrap_or("-1231231231"),
+        ),
+        telegram_enabled,
+    );
+
+    format!(
+        r#"# Конфигурация SSL Checker
+# Любой ключ можно переопределить переменной окружения с префиксом APP,
+# например APP.check_interval_hours=12
+
+# Число часов между проверками в режиме демона
+check_interval_hours: {interval}
+
+# Число дней до истечения домена, начиная с которого отправляются уведомления
+alarm_days: {alarm_days}
+
+# Число дней до истечения сертификата, начиная с которого отправляются уведомления
+ssl_alarm_days: {ssl_alarm_days}
+# Порог для сертификатов Let's Encrypt и ZeroSSL: они продлеваются автоматически,
+# по умолчанию — меньшее из ssl_alarm_days и 20
+# acme_alarm_days: 10
+# Несколько порогов с разной важностью (info, warning, high, critical) вместо ssl_alarm_days
+# ssl_thresholds:
+#   - {{ days: 30, severity: info }}
+#   - {{ days: 14, severity: warning }}
+#   - {{ days: 7, severity: high }}
+#   - {{ days: 1, severity: critical }}
+# Часовой пояс дат истечения в уведомлениях (по умолчанию UTC)
+# report_timezone: "Europe/Moscow"
+# Пороги оповещения в рабочих днях: срок в понедельник при пороге 3 заметен уже в среду
+# business_days:
+#   weekend: [sat, sun]
+#   holidays: ["2025-01-01", "2025-01-02"]
+
+# Включение проверок сертификатов и сроков регистрации доменов (WHOIS)
+check_ssl: true
+check_domains: true
+# Не проверять сертификаты имён с этими метками (служебные TXT-записи) и с метками,
+# начинающимися с skip_label_prefixes (service discovery, которое не отдаёт HTTPS)
+skip_labels: ["_dmarc", "_domainkey", "_acme-challenge", "_spf"]
+# skip_label_prefixes: ["autodiscover", "_sip"]
+# Чем проверять wildcard-запись *.example.com: именами с этими метками (www.example.com)
+wildcard_labels: ["test"]
+# или N реальными поддоменами example.com из источников, если они есть
+# wildcard_sample: 3
+
+# Сколько проверок выполняется одновременно
+concurrency: 100
+# Сколько секунд ждать загрузки каждого источника; источники загружаются одновременно
+source_timeout_secs: 300
+# Ограничение времени всего запуска, секунд: по его истечении готовые результаты
+# отправляются, а непроверенные хосты попадают в отчёт с ошибкой
+# max_run_duration_secs: 3600
+# Если запуск длится дольше интервала: skip — пропустить наступившие запуски,
+# queue — выполнить один запуск сразу после затянувшегося
+overlap_policy: skip
+# Для больших списков: отправлять уведомления частями и выводить ход проверки в лог
+# notify_chunk_size: 50
+# progress_log_every: 1000
+
+log_config:
+  # trace, debug, info, warn, error
+  log_level: "info"
+  # Уровни отдельных модулей
+  # log_levels:
+  #   checker: "debug"
+  #   # Проверки и источники
+  #   ssl_checker_core: "debug"
+  #   whois_rust: "warn"
+  #   reqwest: "error"
+  use_color: true
+  # text - строки для человека, json - один JSON-объект на событие (Loki, ELK)
+  log_format: "text"
+  # Не больше N одинаковых сообщений в минуту (ошибки не ограничиваются),
+  # остальные сводятся в одно сообщение с их числом
+  # rate_limit_per_minute: 20
+  # Значения полей с этими словами в имени заменяются на *** (в том числе в Logstash)
+  # redact_fields: ["password", "passwd", "secret", "token", "api_key", "apikey", "authorization"]
+  # Формат меток времени (strftime) и часовой пояс: local, utc или имя IANA
+  # timestamp_format: "%Y-%m-%dT%H:%M:%S%.3fZ"
+  # timezone: "utc"
+  # Отправка логов в Logstash (все три параметра обязательны)
+  # logstash_host: "127.0.0.1"
+  # logstash_port: 5000
+  # tcp или udp; json - формат Logstash, gelf - Graylog без Logstash
+  # logstash_transport: "tcp"
+  # logstash_protocol: "json"
+  # TLS для транспорта tcp: корневой сертификат, клиентский сертификат и SNI
+  # logstash_tls:
+  #   ca_file: "/etc/ssl/logstash-ca.pem"
+  #   cert_file: "/etc/ssl/checker.pem"
+  #   key_file: "/etc/ssl/checker.key"
+  #   server_name: "logstash.local"
+  # Экспорт трассировки проверок в OpenTelemetry (Tempo, Jaeger) по OTLP/HTTP
+  # otlp:
+  #   endpoint: "http://127.0.0.1:4318"
+  #   headers:
+  #     Authorization: "Bearer <token>"
+  #   service_name: "ssl-checker"
+  # app_name: "ssl-checker"
+
+# Экспорт метрик: страница для Prometheus и/или отправка в OpenTelemetry
+# metrics:
+#   prometheus:
+#     listen: "0.0.0.0:9184"
+#   otlp:
+#     endpoint: "http://127.0.0.1:4318"
+#   otlp_interval_secs: 60
+
+# Общие настройки HTTP-клиентов источников и модулей уведомлений
+# http:
+#   user_agent: "ssl-checker"
+#   verify_tls: true
+#   ca_bundle: "/etc/ssl/corp-ca.pem"
+#   proxy: "http://proxy.local:3128"
+#   timeout_secs: 10
+#   connect_timeout_secs: 5
+#   retries: 3
+#   retry_interval_secs: 1
+
+# Настройки отдельных доменов; ключ — шаблон имени, * заменяет любые символы
+# domains:
+#   "api.example.com":
+#     port: 8443
+#     sni: "backend.example.com"
+#   "grpc.example.com":
+#     # Протоколы ALPN; gRPC-серверы без h2 могут обрывать рукопожатие
+#     alpn: ["h2"]
+#   "cdn.example.com":
+#     # Проверять и сертификат по QUIC (HTTP/3); checker собирается с --features quic
+#     quic: true
+#     ssl_alarm_days: 30
+#     tags: [prod, api]
+#     # Команда-владелец: записи уходят в её чат и упоминают её
+#     owner: payments
+#   "bastion*.example.com":
+#     # Проверять сертификат хоста OpenSSH, подписанный SSH-УЦ
+#     ssh: true
+#     ssh_port: 22
+#     ssh_alarm_days: 3
+#   "*.internal.example.com":
+#     skip_ssl: true
+#   "example.org":
+#     alarm_days: 60
+#     skip_whois: true
+
+# Бюджеты групп хостов с тегами из domains: сколько проверок WHOIS и сертификатов
+# выполняется в минуту и одновременно; группа без tags охватывает все хосты
+# budgets:
+#   client_a:
+#     tags: [client_a]
+#     whois_per_minute: 10
+#     whois_concurrency: 2
+#     ssl_per_minute: 120
+#     ssl_concurrency: 5
+
+# Встроенный HTTP-сервер: /healthz, /readyz, /results, веб-интерфейс
+# http_server:
+#   listen: "0.0.0.0:8080"
+#   # Токен для POST, PUT и DELETE (заголовок Authorization: Bearer)
+#   token_file: "/run/secrets/api_token"
+
+# Файл, в котором сохраняется состояние между перезапусками
+# state_path: "state.json"
+# Каталог, в который каждый запуск сохраняет полный отчёт в JSON
+# results_dir: "results"
+# Отчёты о соответствии для аудита за завершившиеся месяцы или кварталы: доля
+# своевременных продлений и истёкшие сертификаты; нужен state_path
+# compliance_report:
+#   dir: "compliance"
+#   period: month
+#   min_days: 30
+#   formats: [html, csv]
+# Сверка с сертификатами, продлёнными на этой машине: уведомление, если сервер
+# отдаёт сертификат старше продлённого
+# renewal_check:
+#   certbot_dir: "/etc/letsencrypt"
+#   acme_sh_dir: "/root/.acme.sh"
+# Уведомления о каждом новом сертификате на домены и их поддомены по журналам
+# Certificate Transparency (crt.sh); работает только в периодическом режиме
+# ct_monitor:
+#   domains: ["example.com"]
+#   interval_secs: 3600
+#   # Добавлять поддомены из новых сертификатов в список проверяемых хостов
+#   auto_add: false
+# Не перепроверять хосты, успешно проверенные за последние N часов и далёкие от окна оповещения
+# recheck_healthy_after_hours: 24
+# Раздел «Что изменилось» в уведомлениях: новые, продлённые и ставшие критическими записи
+notify_changes: true
+# Уведомление о смене издателя сертификата (например, DigiCert → Let's Encrypt)
+notify_issuer_changes: true
+# Сообщать о неожиданной ошибке SSL, только если хост не прошёл проверку N запусков подряд
+# ssl_error_after_runs: 3
+# Сертификаты почтовых серверов доменов: MX-записи корневых доменов и STARTTLS
+# mail_check:
+#   # DNS-сервер для запроса MX; по умолчанию из /etc/resolv.conf
+#   resolver: "1.1.1.1"
+#   port: 25
+#   timeout_secs: 10
+#   connect_timeout_secs: 5
+#   helo: "ssl-checker.example.com"
+# Проверять у сертификатов в окне оповещения доступность OCSP и CRL издателя:
+# при сбоях удостоверяющего центра продление часто не проходит
+# revocation_check:
+#   timeout_secs: 10
+#   connect_timeout_secs: 5
+#   retries: 1
+# Правила разбора срока регистрации из ответов WHOIS; проверяются раньше встроенных
+# (.ru, .рф, .jp, .br, .de и общего для остальных зон)
+# whois_rules:
+#   - zones: ["kz"]
+#     # Метки строки со сроком без учёта регистра; дата берётся после метки
+#     patterns: ["expiration date"]
+#     # Форматы даты chrono; RFC 3339 пробуется всегда
+#     formats: ["%Y-%m-%d %H:%M:%S"]
+#     # Часовой пояс дат без смещения, часов от UTC
+#     utc_offset_hours: 5
+#   # Реестр не публикует срок: ответ без него не считается ошибкой
+#   - zones: ["example"]
+#     expiry_published: false
+# Окна работ: находки по хостам пишутся в состояние и отчёт, но не уходят в уведомления,
+# например пока при переезде намеренно отдаётся старый сертификат
+# maintenance_windows:
+#   - from: "2025-06-01T22:00:00+03:00"
+#     until: "2025-06-02T06:00:00+03:00"
+#     # Шаблоны как в секции domains; по умолчанию все хосты
+#     hosts: ["*.shop.example.com"]
+#     # Секции notifiers; по умолчанию все модули
+#     notifiers: ["telegram"]
+#     reason: "Переезд магазина на новый балансировщик"
+
+# Ключи API и токены со сроком действия: срок задаётся в expires_at или хранится
+# в custom_metadata секрета KV версии 2 в Vault
+# vault:
+#   address: "https://vault.example.com:8200"
+#   token_file: "/run/secrets/vault_token"
+# credentials:
+#   - name: "Ключ API платёжного шлюза"
+#     expires_at: "2025-12-31"
+#     owner: "payments"
+#   - name: "Токен деплоя GitHub"
+#     vault: "secret/ci/github-deploy"
+#     alarm_days: 14
+
+# Издатели и выпущенные сертификаты движков PKI Vault (подключение — секция vault).
+# Истекающий издатель ломает выпуск, поэтому для издателей порог свой
+# vault_pki:
+#   mounts: ["pki", "pki_int"]
+#   issuer_alarm_days: 90
+
+# Сертификаты AWS Certificate Manager: срок и состояние управляемого продления.
+# Учётные данные — из стандартной цепочки AWS; role_arns читает другие учётные записи
+# acm:
+#   regions: ["eu-central-1", "us-east-1"]
+#   role_arns: ["arn:aws:iam::123456789012:role/ssl-checker"]
+
+# Сертификаты Google Cloud: Certificate Manager и SSL-сертификаты балансировщиков
+# со сроком и состоянием выпуска управляемых сертификатов
+# gcp:
+#   projects: ["shop-prod"]
+#   locations: ["global", "europe-west1"]
+#   credentials_file: "/run/secrets/gcp-ssl-checker.json"
+
+# Сертификаты Azure Key Vault; хосты, которые отдают сертификат старше хранящегося,
+# попадают в ошибки. Без client_id токен выдаёт управляемое удостоверение
+# azure:
+#   vaults: ["kv-shop-prod"]
+#   tenant_id: "00000000-0000-0000-0000-000000000000"
+#   client_id: "11111111-1111-1111-1111-111111111111"
+#   client_secret_file: "/run/secrets/azure_client_secret"
+
+# Сертификаты корпоративного УЦ Venafi (TPP или TLS Protect Cloud), в том числе
+# ещё не развёрнутые на хостах
+# venafi:
+#   platform: tpp
+#   url: "https://tpp.example.com"
+#   token_file: "/run/secrets/venafi_token"
+#   folder: "\\VED\\Policy\\Shop"
+
+# Локальные файлы сертификатов, которые не проверить подключением к хосту: подпись
+# кода, S/MIME, клиентские. PEM и DER читаются как есть, PKCS#12 — с паролем
+# certificate_files:
+#   - path: "/etc/ssl/codesign/release.p12"
+#     password_file: "/run/secrets/codesign_password"
+#     tags: ["release"]
+#   - path: "/etc/ssl/smime/security-team.pem"
+
+sources:
+  # Текстовый файл, по одному домену в строке; строка "host,owner" задаёт владельца
+  file:
+    filename: "{hosts_file}"
+  # DNS-зоны в Selectel
+  # selectel:
+  #   account_id: "12345"
+  #   password: "password"
+  #   # или файл с паролем, например секрет Docker/Kubernetes
+  #   # password_file: "/run/secrets/selectel_password"
+  #   project_name: "Project Name"
+  #   user: "user"
+  #   # Таймауты и повторы запросов к API (необязательно)
+  #   # timeout_secs: 30
+  #   # connect_timeout_secs: 10
+  #   # retries: 3
+  #   # retry_interval_secs: 1
+  # Список хостов по HTTP(S): строки "host" / "host,owner" или JSON-массив
+  # inventory:
+  #   type: url
+  #   url: "https://inventory.example.com/api/hosts.txt"
+  #   # Авторизация (необязательно): bearer_token или username и password,
+  #   # секреты можно читать из файлов *_file
+  #   # bearer_token_file: "/run/secrets/inventory_token"
+  #   # username: "ssl-checker"
+  #   # password: "password"
+  #   # timeout_secs: 30
+  # Хосты из меток Traefik и Caddy запущенных контейнеров Docker
+  # docker:
+  #   # Сокет демона (по умолчанию) или tcp://host:2375, https://host:2376
+  #   endpoint: "unix:///var/run/docker.sock"
+  #   # Метка контейнера с командой-владельцем его хостов (необязательно)
+  #   # owner_label: "team"
+  # Хосты сертификатов, которые обслуживает HAProxy: из файла конфигурации
+  # или через runtime API
+  # haproxy:
+  #   config: "/etc/haproxy/haproxy.cfg"
+  #   # runtime_api: "unix:///run/haproxy/admin.sock"
+  #   # timeout_secs: 10
+  # Виртуальные серверы F5 BIG-IP (iControl REST)
+  # f5:
+  #   address: "https://bigip.example.com"
+  #   username: "ssl-checker"
+  #   password_file: "/run/secrets/bigip_password"
+  #   # Только раздел Common (необязательно)
+  #   # partition: "Common"
+  # SSL-виртуальные серверы Citrix ADC / NetScaler (NITRO API)
+  # netscaler:
+  #   address: "https://adc.example.com"
+  #   username: "nsroot"
+  #   password_file: "/run/secrets/adc_password"
+
+notifiers:
+  # Вывод в консоль
+  console: ~
+  # Свои значки важности и заголовки разделов задаются в секции labels
+  # любого модуля уведомлений, например:
+  # console:
+  #   labels:
+  #     icons: {{ critical: "[!!]", high: "[!]" }}
+  #     headers: {{ ssl: "Expiring certificates" }}
+  # Telegram; вместо bot_token можно указать bot_token_file с путём к файлу.
+  # Любую секцию источника или модуля уведомлений можно отключить: enabled: false
+{telegram}
+  # Чат команды: только записи хостов её владельцев, с упоминанием дежурного
+  # payments_chat:
+  #   type: telegram
+  #   bot_token: "1231231231:WASDwasd..."
+  #   chat_id: "-1009876543210"
+  #   owners: [payments]
+  #   mentions: {{ payments: "@payments_oncall" }}
+  #   # Ответственные за отдельные хосты, шаблоны как в секции domains
+  #   host_mentions:
+  #     "*.pay.example.com": "@ivan"
+  #     "checkout.example.com": "@ivan @petr"
+
+# Профили, например клиенты: каждый со своими источниками, уведомлениями, порогами
+# и файлом состояния (state.<профиль>.json). Ключи профиля заменяют одноимённые
+# ключи верхнего уровня, остальные наследуются; с профилями проверяются только они
+# profiles:
+#   client_a:
+#     alarm_days: 14
+#     sources:
+#       file:
+#         filename: "client_a.txt"
+#     notifiers:
+#       telegram:
+#         bot_token_file: "/run/secrets/client_a_bot_token"
+#         chat_id: "-1001111111111"
+"#,
+        interval = values.check_interval_hours,
+        alarm_days = values.alarm_days,
+        ssl_alarm_days = values.ssl_alarm_days,
+        hosts_file = values.hosts_file,
+        telegram = telegram,
+    )
+}
@@ -0,0 +1,1056 @@
+use super::{
+    NotifierConfig, NotifierLabels, SectionConfig, ServiceConfig, SeverityThreshold,
+    SourceConfig, VenafiPlatform,
+};
+use crate::services::{
+    credentials,
+    http::HttpSettings,
+    quic,
+    registry::{PluginContext, REGISTRY},
+};
+use base::config::{LogTransport, OtlpConfig};
+use base::logging::TimestampFormat;
+use base::prelude::{
+    anyhow::{Result, bail},
+    chrono::format::{Item, StrftimeItems},
+    chrono_tz::Tz,
+    tracing_subscriber::EnvFilter,
+};
+use reqwest::{Proxy, Url};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+
+impl ServiceConfig {
+    /// Проверка значений после разбора; возвращает сразу все найденные проблемы
+    pub(super) fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        if self.check_interval_hours == 0 {
+            problems.push("check_interval_hours: должно быть больше 0".to_string());
+        }
+        if self.concurrency == 0 {
+            problems.push("concurrency: должно быть больше 0".to_string());
+        }
+        if self.source_timeout_secs == 0 {
+            problems.push("source_timeout_secs: должно быть больше 0".to_string());
+        }
+        let interval_secs = self.check_interval_hours.saturating_mul(3600);
+        match self.max_run_duration_secs {
+            Some(0) => {
+                problems.push("max_run_duration_secs: должно быть больше 0".to_string())
+            }
+            Some(secs) if interval_secs > 0 && secs >= interval_secs => {
+                problems.push(format!(
+                    "max_run_duration_secs: должно быть меньше интервала проверок \
+                     ({} с), указано {}",
+                    interval_secs, secs
+                ))
+            }
+            _ => {}
+        }
+        if self.notify_chunk_size == Some(0) {
+            problems.push("notify_chunk_size: должно быть больше 0".to_string());
+        }
+        if self.progress_log_every == Some(0) {
+            problems.push("progress_log_every: должно быть больше 0".to_string());
+        }
+        if self.ssl_error_after_runs == Some(0) {
+            problems.push("ssl_error_after_runs: должно быть больше 0".to_string());
+        }
+        if self.recheck_healthy_after_hours == Some(0) {
+            problems.push("recheck_healthy_after_hours: должно быть больше 0".to_string());
+        }
+        if self.alarm_days < 1 {
+            problems.push(format!(
+                "alarm_days: должно быть не меньше 1, указано {}",
+                self.alarm_days
+            ));
+        }
+        if self.ssl_alarm_days < 1 {
+            problems.push(format!(
+                "ssl_alarm_days: должно быть не меньше 1, указано {}",
+                self.ssl_alarm_days
+            ));
+        }
+
+        if let Some(dir) = &self.results_dir
+            && std::path::Path::new(dir).exists()
+            && !std::path::Path::new(dir).is_dir()
+        {
+            problems.push(format!("results_dir: {:?} не является каталогом", dir));
+        }
+
+        if let Some(days) = self.acme_alarm_days
+            && days < 1
+        {
+            problems.push(format!("acme_alarm_days: должно быть не меньше 1, указано {}", days));
+        }
+
+        if let Some(timezone) = &self.report_timezone
+            && timezone.parse::<Tz>().is_err()
+        {
+            problems
+                .push(format!("report_timezone: неизвестный часовой пояс {:?}", timezone));
+        }
+
+        if let Some(business_days) = &self.business_days {
+            let weekend: HashSet<_> = business_days.weekend.iter().collect();
+            if weekend.len() >= 7 {
+                problems.push("business_days.weekend: все дни недели выходные".to_string());
+            }
+        }
+
+        if let Some(renewal_check) = &self.renewal_check {
+            let dirs = [
+                ("renewal_check.certbot_dir", &renewal_check.certbot_dir),
+                ("renewal_check.acme_sh_dir", &renewal_check.acme_sh_dir),
+            ];
+            for (key, dir) in dirs {
+                if let Some(dir) = dir
+                    && !std::path::Path::new(dir).is_dir()
+                {
+                    problems.push(format!("{}: каталог {:?} не найден", key, dir));
+                }
+            }
+        }
+
+        if let Some(ct_monitor) = &self.ct_monitor {
+            if ct_monitor.domains.is_empty() {
+                problems.push("ct_monitor.domains: не указан ни один домен".to_string());
+            }
+            for domain in &ct_monitor.domains {
+                let invalid = |c: char| c.is_whitespace() || "*%/:?#".contains(c);
+                if domain.trim_matches('.').is_empty() || domain.contains(invalid) {
+                    problems.push(format!(
+                        "ct_monitor.domains: некорректное имя домена {:?}",
+                        domain
+                    ));
+                }
+            }
+            if ct_monitor.interval_secs < 60 {
+                problems.push(format!(
+                    "ct_monitor.interval_secs: должно быть не меньше 60, указано {}",
+                    ct_monitor.interval_secs
+                ));
+            }
+        }
+
+        if let Some(mail_check) = &self.mail_check {
+            if let Some(Err(_)) = mail_check.resolver_addr() {
+                problems.push(format!(
+                    "mail_check.resolver: ожидается адрес вида 1.1.1.1 или 1.1.1.1:53, \
+                     указано {:?}",
+                    mail_check.resolver.as_deref().unwrap_or_default()
+                ));
+            }
+            if mail_check.port == Some(0) {
+                problems.push("mail_check.port: должно быть больше 0".to_string());
+            }
+            if mail_check.helo.as_deref().is_some_and(|helo| helo.trim().is_empty()) {
+                problems.push("mail_check.helo: пустое значение".to_string());
+            }
+            validate_timeouts(
+                "mail_check",
+                mail_check.timeout_secs,
+                mail_check.connect_timeout_secs,
+                &mut problems,
+            );
+        }
+
+        if let Some(revocation_check) = &self.revocation_check {
+            validate_timeouts(
+                "revocation_check",
+                revocation_check.timeout_secs,
+                revocation_check.connect_timeout_secs,
+                &mut problems,
+            );
+        }
+
+        self.validate_whois_rules(&mut problems);
+        self.validate_maintenance_windows(&mut problems);
+        self.validate_certificate_files(&mut problems);
+        self.validate_credentials(&mut problems);
+        self.validate_vault_pki(&mut problems);
+        self.validate_acm(&mut problems);
+        self.validate_gcp(&mut problems);
+        self.validate_azure(&mut problems);
+        self.validate_venafi(&mut problems);
+        self.validate_compliance_report(&mut problems);
+        self.validate_budgets(&mut problems);
+        self.validate_labels(&mut problems);
+        self.validate_thresholds(&mut problems);
+        self.validate_logging(&mut problems);
+        self.validate_http(&mut problems);
+        self.validate_metrics(&mut problems);
+
+        if let Some(http_server) = &self.http_server
+            && http_server.listen.parse::<SocketAddr>().is_err()
+        {
+            problems.push(format!(
+                "http_server.listen: ожидается адрес вида 0.0.0.0:8080, указано {:?}",
+                http_server.listen
+            ));
+        }
+
+        for (pattern, settings) in &self.domains {
+            let path = format!("domains.{:?}", pattern);
+            if pattern.trim().is_empty() {
+                problems.push("domains: пустой шаблон имени".to_string());
+            }
+            if settings.port == Some(0) {
+                problems.push(format!("{}.port: должен быть от 1 до 65535", path));
+            }
+            if settings.ssh_port == Some(0) {
+                problems.push(format!("{}.ssh_port: должен быть от 1 до 65535", path));
+            }
+            if settings.owner.as_deref().is_some_and(|owner| owner.trim().is_empty()) {
+                problems.push(format!("{}.owner: пустое имя команды", path));
+            }
+            if settings.quic == Some(true) && !quic::SUPPORTED {
+                problems.push(format!(
+                    "{}.quic: checker собран без поддержки QUIC (--features quic)",
+                    path
+                ));
+            }
+            // Идентификатор протокола ALPN — от 1 до 255 байт
+            let invalid_alpn = settings.alpn.iter().flatten().find(|protocol| {
+                protocol.is_empty() || protocol.len() > 255
+            });
+            if let Some(protocol) = invalid_alpn {
+                problems.push(format!(
+                    "{}.alpn: некорректный протокол {:?}",
+                    path, protocol
+                ));
+            }
+            let thresholds = [
+                ("alarm_days", settings.alarm_days),
+                ("ssl_alarm_days", settings.ssl_alarm_days),
+                ("ssh_alarm_days", settings.ssh_alarm_days),
+            ];
+            for (field, days) in thresholds {
+                if let Some(days) = days
+                    && days < 1
+                {
+                    problems.push(format!("{}.{}: должно быть не меньше 1", path, field));
+                }
+            }
+        }
+
+        if self.sources.is_empty() && self.profiles.is_empty() {
+            problems.push("sources: не задано ни одного включённого источника доменов".to_string());
+        }
+        // Фабрикам плагинов нужен клиент, как и при запуске
+        let http_client = HttpSettings::shared_client(&self.http);
+        for (name, source) in &self.sources {
+            match source {
+                SourceConfig::FileConfig { filename } if filename.trim().is_empty() => {
+                    problems.push(format!("sources.{}.filename: пустой путь", name));
+                }
+                SourceConfig::SelectelConfig {
+                    account_id,
+                    project_name,
+                    user,
+                    timeout_secs,
+                    connect_timeout_secs,
+                    ..
+                } => {
+                    let path = format!("sources.{}", name);
+                    validate_timeouts(
+                        &path,
+                        *timeout_secs,
+                        *connect_timeout_secs,
+                        &mut problems,
+                    );
+                    let fields = [
+                        ("account_id", account_id),
+                        ("project_name", project_name),
+                        ("user", user),
+                    ];
+                    for (field, value) in fields {
+                        if value.trim().is_empty() {
+                            problems.push(format!(
+                                "sources.{}.{}: пустое значение",
+                                name, field
+                            ));
+                        }
+                    }
+                }
+                SourceConfig::UrlConfig {
+                    url,
+                    bearer_token,
+                    bearer_token_file,
+                    username,
+                    timeout_secs,
+                    connect_timeout_secs,
+                    ..
+                } => {
+                    let path = format!("sources.{}", name);
+                    validate_timeouts(
+                        &path,
+                        *timeout_secs,
+                        *connect_timeout_secs,
+                        &mut problems,
+                    );
+                    match Url::parse(url) {
+                        Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                        Ok(_) => problems.push(format!(
+                            "{}.url: поддерживаются только http и https: {:?}",
+                            path, url
+                        )),
+                        Err(e) => problems.push(format!(
+                            "{}.url: некорректный адрес {:?}: {}",
+                            path, url, e
+                        )),
+                    }
+                    let bearer = !bearer_token.is_empty() || bearer_token_file.is_some();
+                    if bearer && username.is_some() {
+                        problems.push(format!(
+                            "{}: укажите либо bearer_token, либо username и password",
+                            path
+                        ));
+                    }
+                    if username.as_deref().is_some_and(|u| u.trim().is_empty()) {
+                        problems.push(format!("{}.username: пустое значение", path));
+                    }
+                }
+                SourceConfig::DockerConfig {
+                    endpoint,
+                    owner_label,
+                    timeout_secs,
+                    connect_timeout_secs,
+                    ..
+                } => {
+                    let path = format!("sources.{}", name);
+                    validate_timeouts(
+                        &path,
+                        *timeout_secs,
+                        *connect_timeout_secs,
+                        &mut problems,
+                    );
+                    let supported = ["unix://", "tcp://", "http://", "https://"];
+                    if let Some(endpoint) = endpoint
+                        && !supported.iter().any(|scheme| {
+                            endpoint.len() > scheme.len() && endpoint.starts_with(scheme)
+                        })
+                    {
+                        problems.push(format!(
+                            "{}.endpoint: ожидается схема unix://, tcp://, http:// \
+                             или https://, указано {:?}",
+                            path, endpoint
+                        ));
+                    }
+                    if owner_label.as_deref().is_some_and(|l| l.trim().is_empty()) {
+                        problems.push(format!("{}.owner_label: пустое значение", path));
+                    }
+                }
+                SourceConfig::F5Config {
+                    address,
+                    username,
+                    timeout_secs,
+                    connect_timeout_secs,
+                    ..
+                }
+                | SourceConfig::NetScalerConfig {
+                    address,
+                    username,
+                    timeout_secs,
+                    connect_timeout_secs,
+                    ..
+                } => {
+                    let path = format!("sources.{}", name);
+                    validate_timeouts(
+                        &path,
+                        *timeout_secs,
+                        *connect_timeout_secs,
+                        &mut problems,
+                    );
+                    match Url::parse(address) {
+                        Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                        Ok(_) => problems.push(format!(
+                            "{}.address: поддерживаются только http и https: {:?}",
+                            path, address
+                        )),
+                        Err(e) => problems.push(format!(
+                            "{}.address: некорректный адрес {:?}: {}",
+                            path, address, e
+                        )),
+                    }
+                    if username.trim().is_empty() {
+                        problems.push(format!("{}.username: пустое значение", path));
+                    }
+                }
+                SourceConfig::HaproxyConfig { config, runtime_api, timeout_secs } => {
+                    let path = format!("sources.{}", name);
+                    validate_timeouts(&path, *timeout_secs, None, &mut problems);
+                    match (config, runtime_api) {
+                        (Some(value), None) | (None, Some(value)) => {
+                            if value.trim().is_empty() {
+                                let field = match config {
+                                    Some(_) => "config",
+                                    None => "runtime_api",
+                                };
+                                problems
+                                    .push(format!("{}.{}: пустое значение", path, field));
+                            }
+                        }
+                        _ => problems.push(format!(
+                            "{}: укажите либо config, либо runtime_api",
+                            path
+                        )),
+                    }
+                }
+                SourceConfig::Plugin(plugin) => {
+                    let path = format!("sources.{}", name);
+                    let context = PluginContext {
+                        name: &path,
+                        settings: &plugin.settings,
+                        http: &self.http,
+                        client: &http_client,
+                    };
+                    match REGISTRY.source(&plugin.kind, &context) {
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => problems.push(format!("{}: {:#}", path, e)),
+                        None => problems.push(format!(
+                            "{}.type: неизвестный тип {:?}, доступны: {}",
+                            path,
+                            plugin.kind,
+                            available_types(
+                                SourceConfig::BUILTIN_TYPES,
+                                REGISTRY.source_types()
+                            )
+                        )),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if self.notifiers.is_empty() && self.profiles.is_empty() {
+            problems
+                .push("notifiers: не задано ни одного включённого модуля уведомлений".to_string());
+        }
+        for (name, notifier) in &self.notifiers {
+            let path = format!("notifiers.{}", name);
+            match notifier {
+                NotifierConfig::Telegram {
+                    chat_id,
+                    timeout_secs,
+                    connect_timeout_secs,
+                    labels,
+                    owners,
+                    mentions,
+                    host_mentions,
+                    ..
+                } => {
+                    if chat_id.trim().is_empty() {
+                        problems.push(format!("{}.chat_id: пустое значение", path));
+                    }
+                    if owners.iter().any(|owner| owner.trim().is_empty()) {
+                        problems.push(format!("{}.owners: пустое имя команды", path));
+                    }
+                    for (owner, mention) in mentions {
+                        if mention.trim().is_empty() {
+                            problems.push(format!(
+                                "{}.mentions.{}: пустое упоминание",
+                                path, owner
+                            ));
+                        }
+                    }
+                    for (pattern, mention) in host_mentions {
+                        if pattern.trim().is_empty() {
+                            problems.push(format!(
+                                "{}.host_mentions: пустой шаблон имени",
+                                path
+                            ));
+                        }
+                        if mention.trim().is_empty() {
+                            problems.push(format!(
+                                "{}.host_mentions.{:?}: пустое упоминание",
+                                path, pattern
+                            ));
+                        }
+                    }
+                    validate_labels(&path, labels, &mut problems);
+                    validate_timeouts(
+                        &path,
+                        *timeout_secs,
+                        *connect_timeout_secs,
+                        &mut problems,
+                    );
+                }
+                NotifierConfig::Plugin(plugin) => {
+                    let context = PluginContext {
+                        name: &path,
+                        settings: &plugin.settings,
+                        http: &self.http,
+                        client: &http_client,
+                    };
+                    match REGISTRY.notifier(&plugin.kind, &context) {
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => problems.push(format!("{}: {:#}", path, e)),
+                        None => problems.push(format!(
+                            "{}.type: неизвестный тип {:?}, доступны: {}",
+                            path,
+                            plugin.kind,
+                            available_types(
+                                NotifierConfig::BUILTIN_TYPES,
+                                REGISTRY.notifier_types()
+                            )
+                        )),
+                    }
+                }
+                NotifierConfig::Console { labels } => {
+                    validate_labels(&path, labels, &mut problems);
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            return Ok(());
+        }
+        bail!("Ошибки в конфигурации:\n  - {}", problems.join("\n  - "))
+    }
+
+    fn validate_whois_rules(&self, problems: &mut Vec<String>) {
+        for (i, rule) in self.whois_rules.iter().enumerate() {
+            let path = format!("whois_rules[{}]", i);
+            if rule.zones.iter().any(|zone| zone.trim_matches('.').trim().is_empty()) {
+                problems.push(format!("{}.zones: пустое имя зоны", path));
+            }
+            if rule.expiry_published && rule.patterns.is_empty() {
+                problems.push(format!(
+                    "{}.patterns: нужна хотя бы одна метка строки со сроком \
+                     или expiry_published: false",
+                    path
+                ));
+            }
+            if rule.patterns.iter().any(|pattern| pattern.trim().is_empty()) {
+                problems.push(format!("{}.patterns: пустая метка", path));
+            }
+            for format in &rule.formats {
+                if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                    problems.push(format!(
+                        "{}.formats: некорректный формат даты {:?}",
+                        path, format
+                    ));
+                }
+            }
+            if !(-12..=14).contains(&rule.utc_offset_hours) {
+                problems.push(format!(
+                    "{}.utc_offset_hours: ожидается значение от -12 до 14, указано {}",
+                    path, rule.utc_offset_hours
+                ));
+            }
+        }
+    }
+
+    /// Метки и начала меток — одна метка имени без точек; wildcard-записи есть
+    /// чем проверить
+    fn validate_labels(&self, problems: &mut Vec<String>) {
+        if self.wildcard_labels.is_empty() && self.wildcard_sample == 0 {
+            problems.push(
+                "wildcard_labels: пустой список при wildcard_sample: 0 — \
+                 wildcard-записи не будут проверяться"
+                    .to_string(),
+            );
+        }
+        let lists = [
+            ("skip_labels", &self.skip_labels),
+            ("skip_label_prefixes", &self.skip_label_prefixes),
+            ("wildcard_labels", &self.wildcard_labels),
+        ];
+        for (key, labels) in lists {
+            for label in labels {
+                if label.trim().is_empty() || label.contains(['.', '*']) {
+                    problems.push(format!(
+                        "{}: ожидается одна метка имени без точек и `*`, указано {:?}",
+                        key, label
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Окна работ: окончание позже начала, шаблоны хостов не пустые, модули
+    /// уведомлений есть в секции `notifiers`
+    fn validate_certificate_files(&self, problems: &mut Vec<String>) {
+        for (i, file) in self.certificate_files.iter().enumerate() {
+            if file.path.trim().is_empty() {
+                problems.push(format!("certificate_files[{}].path: пустой путь", i));
+            }
+        }
+    }
+
+    fn validate_credentials(&self, problems: &mut Vec<String>) {
+        if let Some(vault) = &self.vault {
+            match Url::parse(&vault.address) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                _ => problems.push(format!(
+                    "vault.address: ожидается адрес http(s)://, указано {:?}",
+                    vault.address
+                )),
+            }
+            validate_timeouts(
+                "vault",
+                vault.timeout_secs,
+                vault.connect_timeout_secs,
+                problems,
+            );
+        }
+
+        let mut names = HashSet::new();
+        for (i, credential) in self.credentials.iter().enumerate() {
+            let path = format!("credentials[{}]", i);
+            if credential.name.trim().is_empty() {
+                problems.push(format!("{}.name: пустое имя", path));
+            } else if !names.insert(credential.name.as_str()) {
+                problems.push(format!(
+                    "{}.name: имя {:?} уже используется",
+                    path, credential.name
+                ));
+            }
+            match (&credential.expires_at, &credential.vault) {
+                (Some(expires_at), None) => {
+                    if let Err(e) = credentials::parse_expiry(expires_at) {
+                        problems.push(format!("{}.expires_at: {}", path, e));
+                    }
+                }
+                (None, Some(_)) if self.vault.is_none() => problems.push(format!(
+                    "{}.vault: не задана секция vault с адресом сервера",
+                    path
+                )),
+                (None, Some(_)) => {}
+                _ => problems
+                    .push(format!("{}: укажите либо expires_at, либо vault", path)),
+            }
+            if credential.vault_field.is_some() && credential.vault.is_none() {
+                problems.push(format!("{}.vault_field: задаётся только с vault", path));
+            }
+            if credential.alarm_days.is_some_and(|days| days < 1) {
+                problems.push(format!("{}.alarm_days: должно быть не меньше 1", path));
+            }
+        }
+    }
+
+    fn validate_acm(&self, problems: &mut Vec<String>) {
+        let Some(acm) = &self.acm else {
+            return;
+        };
+        for (i, region) in acm.regions.iter().enumerate() {
+            if region.trim().is_empty() {
+                problems.push(format!("acm.regions[{}]: пустой регион", i));
+            }
+        }
+        for (i, role_arn) in acm.role_arns.iter().enumerate() {
+            if !role_arn.starts_with("arn:aws") || !role_arn.contains(":role/") {
+                problems.push(format!(
+                    "acm.role_arns[{}]: ожидается ARN роли \
+                     arn:aws:iam::<account>:role/<name>, указано {:?}",
+                    i, role_arn
+                ));
+            }
+        }
+        validate_timeouts("acm", acm.timeout_secs, acm.connect_timeout_secs, problems);
+    }
+
+    fn validate_gcp(&self, problems: &mut Vec<String>) {
+        let Some(gcp) = &self.gcp else {
+            return;
+        };
+        if gcp.projects.is_empty() {
+            problems.push("gcp.projects: укажите хотя бы один проект".to_string());
+        }
+        for (i, project) in gcp.projects.iter().enumerate() {
+            if project.trim().is_empty() {
+                problems.push(format!("gcp.projects[{}]: пустой идентификатор", i));
+            }
+        }
+        for (i, location) in gcp.locations.iter().enumerate() {
+            if location.trim().is_empty() {
+                problems.push(format!("gcp.locations[{}]: пустое расположение", i));
+            }
+        }
+        if gcp.credentials_file.as_deref().is_some_and(|path| path.trim().is_empty()) {
+            problems.push("gcp.credentials_file: пустой путь".to_string());
+        }
+        validate_timeouts("gcp", gcp.timeout_secs, gcp.connect_timeout_secs, problems);
+    }
+
+    fn validate_vault_pki(&self, problems: &mut Vec<String>) {
+        let Some(pki) = &self.vault_pki else {
+            return;
+        };
+        if self.vault.is_none() {
+            problems.push("vault_pki: не задана секция vault с адресом сервера".to_string());
+        }
+        if pki.mounts.is_empty() {
+            problems.push("vault_pki.mounts: укажите хотя бы один движок PKI".to_string());
+        }
+        for (i, mount) in pki.mounts.iter().enumerate() {
+            if mount.trim_matches('/').trim().is_empty() {
+                problems.push(format!("vault_pki.mounts[{}]: пустой путь", i));
+            }
+        }
+        if let Some(days) = pki.issuer_alarm_days
+            && days < 1
+        {
+            problems.push(format!(
+                "vault_pki.issuer_alarm_days: ожидается число дней от 1, указано {}",
+                days
+            ));
+        }
+    }
+
+    fn validate_azure(&self, problems: &mut Vec<String>) {
+        let Some(azure) = &self.azure else {
+            return;
+        };
+        if azure.vaults.is_empty() {
+            problems.push("azure.vaults: укажите хотя бы одно хранилище".to_string());
+        }
+        for (i, vault) in azure.vaults.iter().enumerate() {
+            if vault.trim().is_empty() {
+                problems.push(format!("azure.vaults[{}]: пустое имя", i));
+            } else if vault.contains("://") {
+                match Url::parse(vault) {
+                    Ok(url) if url.scheme() == "https" => {}
+                    _ => problems.push(format!(
+                        "azure.vaults[{}]: ожидается адрес https://, указано {:?}",
+                        i, vault
+                    )),
+                }
+            }
+        }
+        match (&azure.client_id, &azure.tenant_id) {
+            (Some(_), None) => problems
+                .push("azure.tenant_id: обязателен вместе с client_id".to_string()),
+            (None, Some(_)) => problems
+                .push("azure.client_id: обязателен вместе с tenant_id".to_string()),
+            (Some(_), Some(_)) if azure.identity_client_id.is_some() => problems.push(
+                "azure: укажите либо client_id, либо identity_client_id".to_string(),
+            ),
+            _ => {}
+        }
+        if azure.client_id.is_none()
+            && (!azure.client_secret.is_empty() || azure.client_secret_file.is_some())
+        {
+            problems.push("azure.client_secret: задаётся только с client_id".to_string());
+        }
+        validate_timeouts("azure", azure.timeout_secs, azure.connect_timeout_secs, problems);
+    }
+
+    fn validate_venafi(&self, problems: &mut Vec<String>) {
+        let Some(venafi) = &self.venafi else {
+            return;
+        };
+        match (&venafi.url, venafi.platform) {
+            (None, VenafiPlatform::Tpp) => {
+                problems.push("venafi.url: обязателен для platform: tpp".to_string())
+            }
+            (None, VenafiPlatform::Cloud) => {}
+            (Some(url), _) => match Url::parse(url) {
+                Ok(parsed) if parsed.scheme() == "https" => {}
+                _ => problems.push(format!(
+                    "venafi.url: ожидается адрес https://, указано {:?}",
+                    url
+                )),
+            },
+        }
+        if venafi.token.is_empty() && venafi.token_file.is_none() {
+            problems.push("venafi.token: укажите token или token_file".to_string());
+        }
+        if venafi.folder.is_some() && venafi.platform == VenafiPlatform::Cloud {
+            problems.push("venafi.folder: задаётся только для platform: tpp".to_string());
+        }
+        validate_timeouts(
+            "venafi",
+            venafi.timeout_secs,
+            venafi.connect_timeout_secs,
+            problems,
+        );
+    }
+
+    fn validate_compliance_report(&self, problems: &mut Vec<String>) {
+        let Some(compliance) = &self.compliance_report else {
+            return;
+        };
+        if self.state_path.is_none() {
+            problems.push(
+                "compliance_report: нужен state_path для истории продлений".to_string(),
+            );
+        }
+        if compliance.dir.trim().is_empty() {
+            problems.push("compliance_report.dir: пустой путь".to_string());
+        } else if std::path::Path::new(&compliance.dir).exists()
+            && !std::path::Path::new(&compliance.dir).is_dir()
+        {
+            problems.push(format!(
+                "compliance_report.dir: {:?} не является каталогом",
+                compliance.dir
+            ));
+        }
+        if compliance.min_days < 0 {
+            problems.push(format!(
+                "compliance_report.min_days: должно быть не меньше 0, указано {}",
+                compliance.min_days
+            ));
+        }
+        if compliance.formats.is_empty() {
+            problems.push("compliance_report.formats: укажите хотя бы один формат".to_string());
+        }
+    }
+
+    fn validate_budgets(&self, problems: &mut Vec<String>) {
+        for (name, budget) in &self.budgets {
+            let path = format!("budgets.{}", name);
+            let limits = [
+                ("whois_per_minute", budget.whois_per_minute.map(|n| n as usize)),
+                ("whois_concurrency", budget.whois_concurrency),
+                ("ssl_per_minute", budget.ssl_per_minute.map(|n| n as usize)),
+                ("ssl_concurrency", budget.ssl_concurrency),
+            ];
+            if limits.iter().all(|(_, limit)| limit.is_none()) {
+                problems.push(format!(
+                    "{}: укажите хотя бы одно ограничение: whois_per_minute, \
+                     whois_concurrency, ssl_per_minute или ssl_concurrency",
+                    path
+                ));
+            }
+            for (key, _) in limits.iter().filter(|(_, limit)| *limit == Some(0)) {
+                problems.push(format!("{}.{}: должно быть больше 0", path, key));
+            }
+            if budget.tags.iter().any(|tag| tag.trim().is_empty()) {
+                problems.push(format!("{}.tags: пустой тег", path));
+            }
+        }
+    }
+
+    fn validate_maintenance_windows(&self, problems: &mut Vec<String>) {
+        for (i, window) in self.maintenance_windows.iter().enumerate() {
+            let path = format!("maintenance_windows[{}]", i);
+            if window.until <= window.from {
+                problems.push(format!("{}.until: должно быть позже from", path));
+            }
+            if window.hosts.iter().any(|pattern| pattern.trim().is_empty()) {
+                problems.push(format!("{}.hosts: пустой шаблон хоста", path));
+            }
+            for name in &window.notifiers {
+                if !self.notifiers.contains_key(name) {
+                    problems.push(format!(
+                        "{}.notifiers: модуль уведомлений {:?} не найден в конфигурации",
+                        path, name
+                    ));
+                }
+            }
+        }
+    }
+
+    fn validate_http(&self, problems: &mut Vec<String>) {
+        let http = &self.http;
+        validate_timeouts("http", http.timeout_secs, http.connect_timeout_secs, problems);
+        if let Some(path) = &http.ca_bundle
+            && let Err(e) = HttpSettings::load_ca_bundle(path)
+        {
+            problems.push(format!("http.ca_bundle: не удалось загрузить {}: {}", path, e));
+        }
+        if let Some(proxy) = &http.proxy
+            && let Err(e) = Proxy::all(proxy)
+        {
+            problems.push(format!("http.proxy: некорректный адрес {:?}: {}", proxy, e));
+        }
+    }
+
+    /// Пороги важности: дни не меньше 1 и не повторяются, ближе к истечению
+    /// важность не снижается
+    fn validate_thresholds(&self, problems: &mut Vec<String>) {
+        let Some(thresholds) = &self.ssl_thresholds else {
+            return;
+        };
+        if thresholds.is_empty() {
+            problems.push("ssl_thresholds: не задан ни один порог".to_string());
+        }
+        let mut sorted: Vec<SeverityThreshold> = thresholds.clone();
+        sorted.sort_by_key(|threshold| threshold.days);
+        for threshold in &sorted {
+            if threshold.days < 1 {
+                problems.push(format!(
+                    "ssl_thresholds: days должно быть не меньше 1, указано {}",
+                    threshold.days
+                ));
+            }
+        }
+        for pair in sorted.windows(2) {
+            if pair[0].days == pair[1].days {
+                problems
+                    .push(format!("ssl_thresholds: порог {} дней повторяется", pair[0].days));
+            } else if pair[0].severity < pair[1].severity {
+                problems.push(format!(
+                    "ssl_thresholds: важность порога {} дней ({}) ниже, \
+                     чем у порога {} дней ({})",
+                    pair[0].days,
+                    pair[0].severity.as_str(),
+                    pair[1].days,
+                    pair[1].severity.as_str()
+                ));
+            }
+        }
+    }
+
+    fn validate_logging(&self, problems: &mut Vec<String>) {
+        let log = &self.log_config;
+        if EnvFilter::try_new(&log.log_level).is_err() {
+            problems.push(format!(
+                "log_config.log_level: некорректный уровень {:?} (trace, debug, info, warn, error)",
+                log.log_level
+            ));
+        }
+        for (module, level) in &log.log_levels {
+            if EnvFilter::try_new(format!("{}={}", module, level)).is_err() {
+                problems.push(format!(
+                    "log_config.log_levels.{}: некорректный уровень {:?}",
+                    module, level
+                ));
+            }
+        }
+
+        let logstash = [
+            log.logstash_host.is_some(),
+            log.logstash_port.is_some(),
+            log.app_name.is_some(),
+        ];
+        if logstash.iter().any(|set| *set) && !logstash.iter().all(|set| *set) {
+            problems.push(
+                "log_config: logstash_host, logstash_port и app_name задаются только вместе"
+                    .to_string(),
+            );
+        }
+        if let Some(host) = &log.logstash_host {
+            if host.trim().is_empty() {
+                problems.push("log_config.logstash_host: пустое значение".to_string());
+            } else if host.contains("://") || host.contains('/') {
+                problems.push(format!(
+                    "log_config.logstash_host: ожидается имя хоста или IP без схемы, указано {:?}",
+                    host
+                ));
+            }
+        }
+        if log.logstash_port == Some(0) {
+            problems
+                .push("log_config.logstash_port: должен быть от 1 до 65535".to_string());
+        }
+        if let Some(tls) = &log.logstash_tls {
+            if log.logstash_transport != LogTransport::Tcp {
+                problems.push(
+                    "log_config.logstash_tls: TLS поддерживается только для транспорта tcp"
+                        .to_string(),
+                );
+            }
+            if tls.cert_file.is_some() != tls.key_file.is_some() {
+                problems.push(
+                    "log_config.logstash_tls: cert_file и key_file задаются только вместе"
+                        .to_string(),
+                );
+            }
+            let files = [
+                ("ca_file", &tls.ca_file),
+                ("cert_file", &tls.cert_file),
+                ("key_file", &tls.key_file),
+            ];
+            for (field, path) in files {
+                if let Some(path) = path
+                    && let Err(e) = std::fs::metadata(path)
+                {
+                    problems.push(format!(
+                        "log_config.logstash_tls.{}: {}: {}",
+                        field, path, e
+                    ));
+                }
+            }
+        }
+        if let Err(e) =
+            TimestampFormat::new(log.timestamp_format.as_deref(), log.timezone.as_deref())
+        {
+            problems.push(format!("log_config: {}", e));
+        }
+        if log.rate_limit_per_minute == Some(0) {
+            problems
+                .push("log_config.rate_limit_per_minute: должно быть больше 0".to_string());
+        }
+        if let Some(otlp) = &log.otlp {
+            validate_otlp_endpoint("log_config.otlp", otlp, problems);
+        }
+    }
+
+    fn validate_metrics(&self, problems: &mut Vec<String>) {
+        let metrics = &self.metrics;
+        if let Some(prometheus) = &metrics.prometheus
+            && prometheus.listen.parse::<SocketAddr>().is_err()
+        {
+            problems.push(format!(
+                "metrics.prometheus.listen: ожидается адрес вида 0.0.0.0:9184, указано {:?}",
+                prometheus.listen
+            ));
+        }
+        if let Some(otlp) = &metrics.otlp {
+            validate_otlp_endpoint("metrics.otlp", otlp, problems);
+        }
+        if metrics.otlp_interval_secs == 0 {
+            problems.push("metrics.otlp_interval_secs: должно быть больше 0".to_string());
+        }
+    }
+}
+
+fn validate_otlp_endpoint(path: &str, otlp: &OtlpConfig, problems: &mut Vec<String>) {
+    if !(otlp.endpoint.starts_with("http://") || otlp.endpoint.starts_with("https://")) {
+        problems.push(format!(
+            "{}.endpoint: ожидается адрес вида http://collector:4318, указано {:?}",
+            path, otlp.endpoint
+        ));
+    }
+}
+
+/// Типы для сообщения об ошибке: встроенные (без псевдонимов прежних версий)
+/// и зарегистрированные плагинами
+fn available_types(builtin: &[&str], registered: Vec<String>) -> String {
+    builtin
+        .iter()
+        .filter(|kind| kind.chars().all(|c| c.is_ascii_lowercase()))
+        .map(|kind| kind.to_string())
+        .chain(registered)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Нулевой таймаут означает мгновенную ошибку каждого запроса
+fn validate_timeouts(
+    path: &str,
+    timeout_secs: Option<u64>,
+    connect_timeout_secs: Option<u64>,
+    problems: &mut Vec<String>,
+) {
+    for (field, value) in
+        [("timeout_secs", timeout_secs), ("connect_timeout_secs", connect_timeout_secs)]
+    {
+        if value == Some(0) {
+            problems.push(format!("{}.{}: должно быть больше 0", path, field));
+        }
+    }
+}
+
+/// Пустая подпись не видна в сообщении, скорее всего это ошибка в конфигурации
+fn validate_labels(path: &str, labels: &NotifierLabels, problems: &mut Vec<String>) {
+    for (severity, icon) in &labels.icons {
+        if icon.trim().is_empty() {
+            problems.push(format!(
+                "{}.labels.icons.{}: пустое значение",
+                path,
+                severity.as_str()
+            ));
+        }
+    }
+    for (name, header) in labels.headers.iter() {
+        if header.trim().is_empty() {
+            problems.push(format!("{}.labels.headers.{}: пустое значение", path, name));
+        }
+    }
+}
@@ -1,3 +1,3 @@
 mod services;
 
-pub use services::SERVICES;
\ No newline at end of file
+pub use services::{RunOptions, SERVICES, ServicesInj};
\ No newline at end of file
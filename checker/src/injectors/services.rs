@@ -1,70 +1,801 @@
-use crate::config::{NotifierConfig, ServiceConfig, SourceConfig, CONFIG};
+use crate::api::ApiServer;
+use crate::config::{
+    NotifierConfig, PluginConfig, ServiceConfig, SourceConfig, VenafiPlatform,
+};
 use crate::services::{
+    acm::AcmInventory,
+    artifacts::ArtifactService,
+    compliance_report::ComplianceReportService,
+    azure::{AzureKeyVaultInventory, ClientSecret},
+    budget::CheckBudgets,
+    calendar::BusinessCalendar,
+    checks::WhoisCheckOptions,
+    ct::{CtCertificate, CtMonitor},
     domain_checker::DomainCheckerService,
-    notifiers::{BaseNotifierTrait, ConsoleNotifierService, TelegramNotifierService},
-    sources::{DomainSourceTrait, FileSourceService, SelectelSourceService},
+    gcp::GcpInventory,
+    http::HttpSettings,
+    inventory::CertificateInventoryTrait,
+    mail::MailCheckOptions,
+    overrides::DomainOverrides,
+    renewal::RenewalIndex,
+    notifiers::{
+        BaseNotifierTrait, ConsoleNotifierService, MaintenanceNotifier,
+        TelegramNotifierService,
+    },
+    report::{CheckError, ErrorKind, Severity},
+    registry::{PluginContext, REGISTRY},
+    sources::{
+        DockerSourceService, DomainSourceTrait, F5SourceService, FileSourceService,
+        HaproxySourceService, ListSourceService, NetScalerSourceService,
+        SelectelSourceService, UrlListCache, UrlSourceService,
+    },
+    state::StateService,
+    vault::VaultClient,
+    vault_pki::VaultPkiInventory,
+    venafi::{self, VenafiInventory},
+};
+use base::prelude::{
+    anyhow::{anyhow, bail, Result},
+    chrono::Duration,
+    once_cell::sync::Lazy,
+    tracing,
 };
-use base::prelude::once_cell::sync::Lazy;
+use reqwest::Client;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Параметры конкретного запуска проверки, заданные из командной строки
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    pub dry_run: bool,
+    pub only_ssl: bool,
+    pub only_domains: bool,
+    /// Имена источников, которыми ограничен запуск (пусто — все)
+    pub sources: Vec<String>,
+    /// Имена модулей уведомлений, которыми ограничен запуск (пусто — все)
+    pub notifiers: Vec<String>,
+    /// Каталог для цепочек сертификатов проверенных хостов
+    pub dump_certs: Option<PathBuf>,
+}
+
+impl RunOptions {
+    fn selected(selection: &[String], name: &str) -> bool {
+        selection.is_empty() || selection.iter().any(|s| s == name)
+    }
+}
 
 pub static SERVICES: Lazy<ServicesInj> = Lazy::new(|| ServicesInj::new(None));
 
 #[derive(Clone)]
 pub struct ServicesInj {
     pub conf: &'static ServiceConfig,
-    #[allow(dead_code)]
+    pub state: Arc<StateService>,
+    /// HTTP-клиент, общий для источников и модулей уведомлений
+    pub http_client: Client,
+    /// Архив отчётов запусков, если задан `results_dir`
+    pub artifacts: Option<Arc<ArtifactService>>,
+    /// Отчёты о соответствии за периоды, если задан `compliance_report`
+    pub compliance_reports: Option<Arc<ComplianceReportService>>,
+    /// Кэш условных запросов источников `url`: источники создаются на каждый запуск
+    url_caches: HashMap<String, Arc<UrlListCache>>,
+    /// Бюджеты проверок, общие для всех запусков и профилей демона
+    budgets: Arc<CheckBudgets>,
+    /// Сервисы профилей конфигурации со своими источниками, уведомлениями
+    /// и состоянием
+    pub profiles: BTreeMap<String, ServicesInj>,
     dcl: &'static str,
 }
 
 impl ServicesInj {
     pub fn new(conf: Option<&'static ServiceConfig>) -> Self {
-        let conf = conf.unwrap_or(&CONFIG);
-        Self { conf, dcl: "ServicesInj" }
+        let conf = conf.unwrap_or_else(ServiceConfig::get);
+        Self::with_budgets(conf, Arc::new(CheckBudgets::new(&conf.budgets)))
+    }
+
+    /// Сервисы конфигурации с общими бюджетами: профиль получает бюджеты демона
+    fn with_budgets(conf: &'static ServiceConfig, budgets: Arc<CheckBudgets>) -> Self {
+        let state = Arc::new(StateService::new(conf.state_path.as_deref()));
+        let http_client = HttpSettings::shared_client(&conf.http);
+        let artifacts = conf.results_dir.as_deref().map(ArtifactService::new).map(Arc::new);
+        let compliance_reports = conf
+            .compliance_report
+            .as_ref()
+            .map(|config| Arc::new(ComplianceReportService::new(config)));
+        let url_caches = conf
+            .sources
+            .iter()
+            .filter(|(_, source)| matches!(source, SourceConfig::UrlConfig { .. }))
+            .map(|(name, _)| (name.clone(), Arc::default()))
+            .collect();
+        let profiles = conf
+            .profile_configs
+            .iter()
+            .map(|(name, profile)| {
+                (name.clone(), ServicesInj::with_budgets(profile, budgets.clone()))
+            })
+            .collect();
+        Self {
+            conf,
+            state,
+            http_client,
+            artifacts,
+            compliance_reports,
+            url_caches,
+            budgets,
+            profiles,
+            dcl: "ServicesInj",
+        }
+    }
+
+    /// Сервисы, выполняющие проверки: выбранный профиль, все профили или,
+    /// если профилей нет, корневая конфигурация. Имя — `None` для корневой
+    pub fn runners(&self, profile: Option<&str>) -> Result<Vec<(Option<&str>, &Self)>> {
+        match profile {
+            Some(name) => {
+                let (name, services) = self.profiles.get_key_value(name).ok_or_else(|| {
+                    anyhow!(
+                        "Профиль {:?} не найден в конфигурации, заданы: {:?}",
+                        name,
+                        self.profiles.keys().collect::<Vec<_>>()
+                    )
+                })?;
+                Ok(vec![(Some(name.as_str()), services)])
+            }
+            None if self.profiles.is_empty() => Ok(vec![(None, self)]),
+            None => Ok(self
+                .profiles
+                .iter()
+                .map(|(name, services)| (Some(name.as_str()), services))
+                .collect()),
+        }
     }
 
-    fn source(&self, name: &str) -> Box<dyn DomainSourceTrait> {
+    /// Единственный сервис проверок для команд, работающих с одним состоянием:
+    /// при заданных профилях профиль нужно выбрать
+    pub fn runner(&self, profile: Option<&str>) -> Result<&Self> {
+        match self.runners(profile)?.as_slice() {
+            [(_, services)] => Ok(services),
+            _ => bail!(
+                "В конфигурации несколько профилей, укажите --profile: {:?}",
+                self.profiles.keys().collect::<Vec<_>>()
+            ),
+        }
+    }
+
+    fn source(&self, name: &str) -> Option<Box<dyn DomainSourceTrait>> {
         let conf = &self.conf.sources[name];
-        match conf {
+        let source: Box<dyn DomainSourceTrait> = match conf {
             SourceConfig::FileConfig { filename } => {
                 Box::new(FileSourceService::new(filename))
             }
-            SourceConfig::SelectelConfig { account_id, password, project_name, user } => {
+            SourceConfig::SelectelConfig {
+                account_id,
+                password,
+                project_name,
+                user,
+                timeout_secs,
+                connect_timeout_secs,
+                retries,
+                retry_interval_secs,
+                ..
+            } => {
+                let http = self.http_settings(
+                    HttpSettings::SELECTEL,
+                    *timeout_secs,
+                    *connect_timeout_secs,
+                    *retries,
+                    *retry_interval_secs,
+                );
                 Box::new(SelectelSourceService::new(
                     account_id,
                     password,
                     project_name,
                     user,
+                    http,
                 ))
             }
-        }
+            SourceConfig::UrlConfig {
+                url,
+                bearer_token,
+                username,
+                password,
+                timeout_secs,
+                connect_timeout_secs,
+                retries,
+                retry_interval_secs,
+                ..
+            } => {
+                let http = self.http_settings(
+                    HttpSettings::URL_LIST,
+                    *timeout_secs,
+                    *connect_timeout_secs,
+                    *retries,
+                    *retry_interval_secs,
+                );
+                let mut source = UrlSourceService::new(url, http);
+                if let Some(cache) = self.url_caches.get(name) {
+                    source = source.with_cache(cache.clone());
+                }
+                if !bearer_token.is_empty() {
+                    source = source.with_bearer_token(bearer_token);
+                } else if let Some(username) = username {
+                    source = source.with_basic_auth(username, password);
+                }
+                Box::new(source)
+            }
+            SourceConfig::DockerConfig {
+                endpoint,
+                owner_label,
+                timeout_secs,
+                connect_timeout_secs,
+                retries,
+                retry_interval_secs,
+            } => {
+                let http = self.http_settings(
+                    HttpSettings::DOCKER,
+                    *timeout_secs,
+                    *connect_timeout_secs,
+                    *retries,
+                    *retry_interval_secs,
+                );
+                let endpoint =
+                    endpoint.as_deref().unwrap_or(DockerSourceService::DEFAULT_ENDPOINT);
+                let mut source = DockerSourceService::new(endpoint, http);
+                if let Some(label) = owner_label {
+                    source = source.with_owner_label(label);
+                }
+                Box::new(source)
+            }
+            SourceConfig::F5Config {
+                address,
+                username,
+                password,
+                partition,
+                timeout_secs,
+                connect_timeout_secs,
+                retries,
+                retry_interval_secs,
+                ..
+            } => {
+                let http = self.http_settings(
+                    HttpSettings::LOAD_BALANCER,
+                    *timeout_secs,
+                    *connect_timeout_secs,
+                    *retries,
+                    *retry_interval_secs,
+                );
+                let mut source = F5SourceService::new(address, username, password, http);
+                if let Some(partition) = partition {
+                    source = source.with_partition(partition);
+                }
+                Box::new(source)
+            }
+            SourceConfig::NetScalerConfig {
+                address,
+                username,
+                password,
+                timeout_secs,
+                connect_timeout_secs,
+                retries,
+                retry_interval_secs,
+                ..
+            } => {
+                let http = self.http_settings(
+                    HttpSettings::LOAD_BALANCER,
+                    *timeout_secs,
+                    *connect_timeout_secs,
+                    *retries,
+                    *retry_interval_secs,
+                );
+                Box::new(NetScalerSourceService::new(address, username, password, http))
+            }
+            SourceConfig::HaproxyConfig { config, runtime_api, timeout_secs } => {
+                let source = match (config, runtime_api) {
+                    (Some(config), _) => HaproxySourceService::from_config(config),
+                    (None, Some(api)) => HaproxySourceService::from_runtime_api(api),
+                    (None, None) => return None,
+                };
+                Box::new(match timeout_secs.map(std::time::Duration::from_secs) {
+                    Some(timeout) => source.with_timeout(timeout),
+                    None => source,
+                })
+            }
+            SourceConfig::Plugin(plugin) => {
+                let path = format!("sources.{}", name);
+                let context = self.plugin_context(&path, plugin);
+                return self.plugin(&path, REGISTRY.source(&plugin.kind, &context));
+            }
+        };
+        Some(source)
     }
 
-    fn notifier(&self, name: &str) -> Box<dyn BaseNotifierTrait> {
+    fn notifier(&self, name: &str) -> Option<Box<dyn BaseNotifierTrait>> {
         let conf = &self.conf.notifiers[name];
-        match conf {
-            NotifierConfig::Console => Box::new(ConsoleNotifierService::new()),
-            NotifierConfig::Telegram { bot_token, chat_id, retries } => {
-                Box::new(TelegramNotifierService::new(
-                    bot_token,
-                    chat_id,
-                    Some(retries.to_owned()),
-                    None,
-                ))
+        let notifier: Box<dyn BaseNotifierTrait> = match conf {
+            NotifierConfig::Console { labels } => {
+                Box::new(ConsoleNotifierService::new().with_labels(labels.clone()))
+            }
+            NotifierConfig::Telegram {
+                bot_token,
+                chat_id,
+                timeout_secs,
+                connect_timeout_secs,
+                retries,
+                retry_interval_secs,
+                group_by_root,
+                min_severity,
+                labels,
+                owners,
+                mentions,
+                host_mentions,
+                ..
+            } => {
+                let http = self.http_settings(
+                    HttpSettings::TELEGRAM,
+                    *timeout_secs,
+                    *connect_timeout_secs,
+                    *retries,
+                    *retry_interval_secs,
+                );
+                Box::new(
+                    TelegramNotifierService::new(bot_token, chat_id, http)
+                        .with_group_by_root(*group_by_root)
+                        .with_min_severity(min_severity.unwrap_or(Severity::Info))
+                        .with_labels(labels.clone())
+                        .with_owners(owners.clone())
+                        .with_mentions(mentions.clone())
+                        .with_host_mentions(host_mentions.clone()),
+                )
+            }
+            NotifierConfig::Plugin(plugin) => {
+                let path = format!("notifiers.{}", name);
+                let context = self.plugin_context(&path, plugin);
+                return self.plugin(&path, REGISTRY.notifier(&plugin.kind, &context));
             }
+        };
+        Some(notifier)
+    }
+
+    /// Модуль уведомлений секции `name` с её окнами работ, если они есть
+    fn muted(
+        &self,
+        name: &str,
+        notifier: Box<dyn BaseNotifierTrait>,
+    ) -> Box<dyn BaseNotifierTrait> {
+        let windows: Vec<_> = self
+            .conf
+            .maintenance_windows
+            .iter()
+            .filter(|window| window.applies_to_notifier(name))
+            .cloned()
+            .collect();
+        if windows.is_empty() {
+            notifier
+        } else {
+            Box::new(MaintenanceNotifier::new(notifier, windows))
+        }
+    }
+
+    /// Настройки HTTP модуля поверх секции `http` с общим клиентом сервиса
+    fn http_settings(
+        &self,
+        defaults: HttpSettings,
+        timeout_secs: Option<u64>,
+        connect_timeout_secs: Option<u64>,
+        retries: Option<u32>,
+        retry_interval_secs: Option<u64>,
+    ) -> HttpSettings {
+        defaults
+            .with_global(&self.conf.http)
+            .with_overrides(timeout_secs, connect_timeout_secs, retries, retry_interval_secs)
+            .with_shared_client(&self.http_client, connect_timeout_secs)
+    }
+
+    fn plugin_context<'a>(
+        &'a self,
+        path: &'a str,
+        plugin: &'a PluginConfig,
+    ) -> PluginContext<'a> {
+        PluginContext {
+            name: path,
+            settings: &plugin.settings,
+            http: &self.conf.http,
+            client: &self.http_client,
+        }
+    }
+
+    /// Плагин проверен при загрузке конфигурации, поэтому ошибка здесь означает,
+    /// что фабрика перестала его создавать; секция пропускается
+    fn plugin<T: ?Sized>(
+        &self,
+        path: &str,
+        result: Option<Result<Box<T>>>,
+    ) -> Option<Box<T>> {
+        match result.unwrap_or_else(|| Err(anyhow!("тип не зарегистрирован"))) {
+            Ok(plugin) => Some(plugin),
+            Err(e) => {
+                tracing::error!(
+                    dcl = self.dcl,
+                    section = path,
+                    error = %e,
+                    "Не удалось создать плагин"
+                );
+                None
+            }
+        }
+    }
+
+    pub fn api_server(&self) -> Option<ApiServer> {
+        let conf = self.conf.http_server.as_ref()?;
+        let options = RunOptions { dry_run: true, ..Default::default() };
+        let mut server = ApiServer::new(
+            &conf.listen,
+            self.state.clone(),
+            self.domain_checker(&options),
+            self.conf.check_interval_hours,
+        );
+        if !conf.token.is_empty() {
+            server = server.with_token(&conf.token);
         }
+        Some(self.profiles.iter().fold(server, |server, (name, profile)| {
+            server.with_profile(
+                name,
+                profile.state.clone(),
+                profile.domain_checker(&options),
+                profile.conf.check_interval_hours,
+            )
+        }))
     }
 
-    pub fn domain_checker(&self) -> DomainCheckerService {
-        let sources =
-            self.conf.sources.iter().map(|(name, _)| self.source(name)).collect();
+    /// Проверяет, что выбранные источники и модули уведомлений есть в конфигурации
+    pub fn validate_run_options(&self, options: &RunOptions) -> Result<()> {
+        let unknown_sources: Vec<&String> =
+            options.sources.iter().filter(|s| !self.conf.sources.contains_key(*s)).collect();
+        if !unknown_sources.is_empty() {
+            bail!(
+                "Источники не найдены в конфигурации или отключены: {:?}",
+                unknown_sources
+            );
+        }
 
-        let notifiers =
-            self.conf.notifiers.iter().map(|(name, _)| self.notifier(name)).collect();
+        let unknown_notifiers: Vec<&String> = options
+            .notifiers
+            .iter()
+            .filter(|n| !self.conf.notifiers.contains_key(*n))
+            .collect();
+        if !unknown_notifiers.is_empty() {
+            bail!(
+                "Модули уведомлений не найдены в конфигурации или отключены: {:?}",
+                unknown_notifiers
+            );
+        }
 
-        DomainCheckerService::new(
+        Ok(())
+    }
+
+    /// Модули уведомлений запуска. В режиме `dry_run` — только консоль
+    fn notifiers(&self, options: &RunOptions) -> Vec<Box<dyn BaseNotifierTrait>> {
+        if options.dry_run {
+            vec![Box::new(ConsoleNotifierService::new())]
+        } else {
+            self.conf
+                .notifiers
+                .keys()
+                .filter(|name| RunOptions::selected(&options.notifiers, name))
+                .filter_map(|name| self.notifier(name).map(|n| self.muted(name, n)))
+                .collect()
+        }
+    }
+
+    /// Служебное сообщение вне запуска проверки, например о наложении запусков
+    pub async fn notify_exception(&self, options: &RunOptions, msg: &str) {
+        let error = CheckError::new(ErrorKind::Service, msg);
+        self.notify_exceptions(options, &[error]).await;
+    }
+
+    /// Несколько служебных сообщений, отправляемых каждым модулем одним уведомлением
+    pub async fn notify_exceptions(&self, options: &RunOptions, errors: &[CheckError]) {
+        for mut notifier in self.notifiers(options) {
+            for error in errors {
+                notifier.exception(error).await;
+            }
+            if let Err(e) = notifier.commit().await {
+                tracing::error!(dcl = self.dcl, error = %e, "Ошибка отправки уведомления");
+            }
+        }
+    }
+
+    /// Хосты, добавленные по журналам CT. Участвуют только в запусках без выбора
+    /// источников
+    fn ct_source(&self, options: &RunOptions) -> Option<Box<dyn DomainSourceTrait>> {
+        let auto_add = self.conf.ct_monitor.as_ref().is_some_and(|conf| conf.auto_add);
+        if !auto_add || !options.sources.is_empty() {
+            return None;
+        }
+        let hosts: Vec<String> = self.state.ct_hosts().into_iter().collect();
+        (!hosts.is_empty())
+            .then(|| Box::new(ListSourceService::new("ct_monitor", hosts)) as Box<_>)
+    }
+
+    /// Мониторинг журналов Certificate Transparency, если задана секция `ct_monitor`
+    pub fn ct_monitor(&self) -> Option<CtMonitor> {
+        let conf = self.conf.ct_monitor.as_ref()?;
+        let http = self.http_settings(
+            HttpSettings::CRT_SH,
+            conf.timeout_secs,
+            conf.connect_timeout_secs,
+            conf.retries,
+            conf.retry_interval_secs,
+        );
+        Some(CtMonitor::new(conf.domains.clone(), http))
+    }
+
+    /// Добавляет в проверку хосты из новых сертификатов, если включён
+    /// `ct_monitor.auto_add`. Возвращает хосты, которых не было ни среди добавленных
+    /// ранее, ни в отчёте последнего запуска
+    pub fn add_ct_hosts(&self, certificates: &[CtCertificate]) -> Vec<String> {
+        if !self.conf.ct_monitor.as_ref().is_some_and(|conf| conf.auto_add) {
+            return Vec::new();
+        }
+        let checked: HashSet<String> = self
+            .state
+            .last_report()
+            .map(|report| {
+                report.entries.into_iter().map(|entry| entry.hostname).collect()
+            })
+            .unwrap_or_default();
+        let hosts = certificates
+            .iter()
+            .flat_map(CtCertificate::hostnames)
+            .filter(|host| !checked.contains(host));
+        self.state.add_ct_hosts(hosts)
+    }
+
+    /// Сервис проверки. В режиме `dry_run` все уведомления уходят только в консоль
+    pub fn domain_checker(&self, options: &RunOptions) -> DomainCheckerService {
+        let sources = self
+            .conf
+            .sources
+            .keys()
+            .filter(|name| RunOptions::selected(&options.sources, name))
+            .filter_map(|name| self.source(name))
+            .chain(self.ct_source(options))
+            .collect();
+
+        let notifiers = self.notifiers(options);
+
+        let (check_ssl, check_domains) = match (options.only_ssl, options.only_domains) {
+            (true, _) => (true, false),
+            (_, true) => (false, true),
+            _ => (self.conf.check_ssl, self.conf.check_domains),
+        };
+
+        let mut checker = DomainCheckerService::new(
             sources,
             notifiers,
             self.conf.ssl_alarm_days,
             self.conf.alarm_days,
         )
+        .with_checks(check_ssl, check_domains)
+        .with_skipped_labels(
+            self.conf.skip_labels.clone(),
+            self.conf.skip_label_prefixes.clone(),
+        )
+        .with_wildcard_hosts(self.conf.wildcard_labels.clone(), self.conf.wildcard_sample)
+        .with_concurrency(self.conf.concurrency)
+        .with_source_timeout(std::time::Duration::from_secs(
+            self.conf.source_timeout_secs,
+        ))
+        .with_overrides(Arc::new(DomainOverrides::new(&self.conf.domains)))
+        .with_budgets(self.budgets.clone());
+        if let Some(config) = &self.conf.renewal_check {
+            match RenewalIndex::load(config) {
+                Ok(renewals) => checker = checker.with_renewal_index(Arc::new(renewals)),
+                Err(e) => tracing::warn!(
+                    dcl = self.dcl,
+                    e = %e,
+                    "Не удалось загрузить сертификаты продления"
+                ),
+            }
+        }
+        if let Some(thresholds) = &self.conf.ssl_thresholds {
+            checker = checker.with_ssl_thresholds(thresholds.clone());
+        }
+        if let Some(days) = self.conf.acme_alarm_days {
+            checker = checker.with_acme_alarm_days(days);
+        }
+        // Значение проверено при загрузке конфигурации
+        let timezone = self.conf.report_timezone.as_deref().and_then(|tz| tz.parse().ok());
+        if let Some(timezone) = timezone {
+            checker = checker.with_report_timezone(timezone);
+        }
+        if let Some(config) = &self.conf.business_days {
+            checker = checker.with_business_calendar(BusinessCalendar::from(config));
+        }
+        if let Some(secs) = self.conf.max_run_duration_secs {
+            checker = checker.with_max_run_duration(std::time::Duration::from_secs(secs));
+        }
+        if let Some(chunk_size) = self.conf.notify_chunk_size {
+            checker = checker.with_notify_chunk_size(chunk_size);
+        }
+        if let Some(every) = self.conf.progress_log_every {
+            checker = checker.with_progress_log_every(every);
+        }
+        if !self.conf.certificate_files.is_empty() {
+            checker = checker.with_certificate_files(self.conf.certificate_files.clone());
+        }
+        if !self.conf.credentials.is_empty() {
+            checker = checker.with_credentials(self.conf.credentials.clone());
+        }
+        let vault = self.conf.vault.as_ref().map(|config| {
+            let http = self.http_settings(
+                HttpSettings::VAULT,
+                config.timeout_secs,
+                config.connect_timeout_secs,
+                config.retries,
+                config.retry_interval_secs,
+            );
+            let vault = VaultClient::new(&config.address, &config.token, http);
+            match &config.namespace {
+                Some(namespace) => vault.with_namespace(namespace),
+                None => vault,
+            }
+        });
+        if let Some(vault) = &vault {
+            checker = checker.with_vault(vault.clone());
+        }
+        let mut inventories: Vec<Box<dyn CertificateInventoryTrait>> = Vec::new();
+        // Наличие секции vault проверено при загрузке конфигурации
+        if let (Some(config), Some(vault)) = (&self.conf.vault_pki, vault) {
+            let mut pki = VaultPkiInventory::new(vault, &config.mounts)
+                .with_issued(config.issued)
+                .with_tags(config.tags.clone());
+            if let Some(days) = config.issuer_alarm_days {
+                pki = pki.with_issuer_alarm_days(days);
+            }
+            inventories.push(Box::new(pki));
+        }
+        if let Some(config) = &self.conf.acm {
+            let http = self.http_settings(
+                HttpSettings::ACM,
+                config.timeout_secs,
+                config.connect_timeout_secs,
+                config.retries,
+                config.retry_interval_secs,
+            );
+            let acm = AcmInventory::new(&config.regions, &config.role_arns, http)
+                .with_tags(config.tags.clone());
+            inventories.push(Box::new(acm));
+        }
+        if let Some(config) = &self.conf.gcp {
+            let http = self.http_settings(
+                HttpSettings::GCP,
+                config.timeout_secs,
+                config.connect_timeout_secs,
+                config.retries,
+                config.retry_interval_secs,
+            );
+            let mut gcp = GcpInventory::new(&config.projects, http)
+                .with_tags(config.tags.clone());
+            if !config.locations.is_empty() {
+                gcp = gcp.with_locations(config.locations.clone());
+            }
+            if let Some(path) = &config.credentials_file {
+                gcp = gcp.with_credentials_file(path);
+            }
+            inventories.push(Box::new(gcp));
+        }
+        if let Some(config) = &self.conf.azure {
+            let http = self.http_settings(
+                HttpSettings::AZURE,
+                config.timeout_secs,
+                config.connect_timeout_secs,
+                config.retries,
+                config.retry_interval_secs,
+            );
+            let mut azure = AzureKeyVaultInventory::new(&config.vaults, http)
+                .with_tags(config.tags.clone());
+            // Пара client_id и tenant_id проверена при загрузке конфигурации
+            if let (Some(client_id), Some(tenant_id)) =
+                (&config.client_id, &config.tenant_id)
+            {
+                azure = azure.with_client_secret(ClientSecret {
+                    tenant_id: tenant_id.clone(),
+                    client_id: client_id.clone(),
+                    client_secret: config.client_secret.clone(),
+                });
+            }
+            if let Some(client_id) = &config.identity_client_id {
+                azure = azure.with_identity_client_id(client_id);
+            }
+            inventories.push(Box::new(azure));
+        }
+        if let Some(config) = &self.conf.venafi {
+            let http = self.http_settings(
+                HttpSettings::VENAFI,
+                config.timeout_secs,
+                config.connect_timeout_secs,
+                config.retries,
+                config.retry_interval_secs,
+            );
+            // Адрес для TPP проверен при загрузке конфигурации
+            let venafi = match config.platform {
+                VenafiPlatform::Tpp => {
+                    let url = config.url.as_deref().unwrap_or_default();
+                    let venafi = VenafiInventory::tpp(url, &config.token, http);
+                    match &config.folder {
+                        Some(folder) => venafi.with_folder(folder),
+                        None => venafi,
+                    }
+                }
+                VenafiPlatform::Cloud => {
+                    let url = config.url.as_deref().unwrap_or(venafi::CLOUD_URL);
+                    VenafiInventory::cloud(url, &config.token, http)
+                }
+            };
+            inventories.push(Box::new(venafi.with_tags(config.tags.clone())));
+        }
+        if !inventories.is_empty() {
+            checker = checker.with_inventories(inventories);
+        }
+        if let Some(config) = &self.conf.mail_check {
+            let mut mail_options = MailCheckOptions {
+                // Адрес проверен при загрузке конфигурации
+                resolver: config.resolver_addr().and_then(|addr| addr.ok()),
+                ..Default::default()
+            };
+            if let Some(helo) = &config.helo {
+                mail_options.helo = helo.clone();
+            }
+            if let Some(port) = config.port {
+                mail_options.ssl.port = port;
+            }
+            if let Some(secs) = config.timeout_secs {
+                mail_options.ssl.handshake_timeout = std::time::Duration::from_secs(secs);
+            }
+            if let Some(secs) = config.connect_timeout_secs {
+                mail_options.ssl.connect_timeout = std::time::Duration::from_secs(secs);
+            }
+            checker = checker.with_mail_checks(mail_options);
+        }
+        if let Some(config) = &self.conf.revocation_check {
+            checker = checker.with_revocation_check(self.http_settings(
+                HttpSettings::REVOCATION,
+                config.timeout_secs,
+                config.connect_timeout_secs,
+                config.retries,
+                config.retry_interval_secs,
+            ));
+        }
+        if !self.conf.whois_rules.is_empty() {
+            checker = checker.with_whois_options(WhoisCheckOptions {
+                rules: self.conf.whois_rules.clone(),
+                ..Default::default()
+            });
+        }
+        if let Some(dir) = &options.dump_certs {
+            checker = checker.with_cert_dump_dir(dir.clone());
+        }
+
+        if self.conf.notify_issuer_changes {
+            checker = checker.with_known_issuers(self.state.issuers());
+        }
+        if let Some(runs) = self.conf.ssl_error_after_runs {
+            checker = checker.with_ssl_failure_debounce(runs, self.state.ssl_failures());
+        }
+        let Some(previous) = self.state.last_report() else {
+            return checker;
+        };
+        checker = checker.with_error_history(&previous);
+        if self.conf.notify_changes {
+            checker = checker.with_changes_since(&previous);
+        }
+        match self.conf.recheck_healthy_after_hours {
+            Some(hours) => {
+                checker.with_previous_report(&previous, Duration::hours(hours as i64))
+            }
+            None => checker,
+        }
     }
 }
@@ -1,39 +1,375 @@
+mod api;
+mod cli;
 mod config;
 mod injectors;
+mod output;
 mod services;
-use std::env;
+mod tui;
 
-use base::logging::init_logging;
-use base::prelude::{anyhow, tokio, tracing};
-use injectors::SERVICES;
+use base::logging::{self, init_logging_to, LogOutput};
+use base::metrics::init_metrics;
+use base::prelude::{
+    anyhow, chrono::Utc, serde_json, tokio, tracing, tracing::Instrument, uuid::Uuid,
+};
+use clap::Parser;
+use cli::{Cli, Command};
+use config::{OverlapPolicy, ServiceConfig};
+use injectors::{RunOptions, SERVICES, ServicesInj};
+use output::OutputFormat;
+use services::compliance::ComplianceReport;
+use services::ct::{CtCertificate, CtMonitor};
+use services::forecast::{ExpiryForecast, ForecastPeriod};
+use services::report::{CheckError, CheckReport, ErrorKind};
+use services::schedule::RunSchedule;
+use services::trust_store;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::time::MissedTickBehavior;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> anyhow::Result<()> {
-    init_logging(&SERVICES.conf.log_config).await?;
+    let cli = Cli::parse();
+
+    // Команды, не требующие конфигурации
+    match &cli.command {
+        Some(Command::Completions { shell }) => {
+            Cli::print_completions(*shell);
+            return Ok(());
+        }
+        Some(Command::Init(args)) => return args.run(),
+        Some(Command::Man { output_dir }) => return cli::generate_man(output_dir.as_deref()),
+        Some(Command::TrustStore { bundle, days, format }) => {
+            let report = audit_trust_store(bundle.as_deref(), *days)?;
+            print!("{}", output::render(&report, *format)?);
+            std::process::exit(report.exit_code());
+        }
+        Some(Command::Forecast { months, period, report: Some(path), format }) => {
+            let content = std::fs::read_to_string(path)?;
+            let report: CheckReport = serde_json::from_str(&content)?;
+            return print_forecast(&report, *months, *period, *format);
+        }
+        _ => {}
+    }
+
+    // Типы плагинов должны быть зарегистрированы до разбора секций конфигурации
+    #[cfg(feature = "wasm")]
+    services::notifiers::WasmNotifierService::register(&services::registry::REGISTRY);
+
+    if let Err(e) = ServiceConfig::init() {
+        eprintln!("Не удалось загрузить конфигурацию: {:#}", e);
+        std::process::exit(3);
+    }
+
+    if let Some(Command::Forecast { months, period, format, .. }) = cli.command {
+        let services = SERVICES.runner(cli.profile.as_deref())?;
+        services.state.restore();
+        let Some(report) = services.state.last_report() else {
+            eprintln!(
+                "В файле состояния нет отчёта: задайте state_path и дождитесь \
+                 завершения проверки или укажите --report"
+            );
+            std::process::exit(3);
+        };
+        return print_forecast(&report, months, period, format);
+    }
+
+    if let Some(Command::Compliance { period, from, until, min_days, format }) = cli.command
+    {
+        let services = SERVICES.runner(cli.profile.as_deref())?;
+        if services.conf.state_path.is_none() {
+            eprintln!("История продлений хранится в файле состояния: задайте state_path");
+            std::process::exit(3);
+        }
+        services.state.restore();
+        let configured = services.conf.compliance_report.as_ref();
+        let (from, until) = match from {
+            Some(from) => (
+                from.and_time(Default::default()).and_utc(),
+                until.map_or(Utc::now(), |until| {
+                    until.and_time(Default::default()).and_utc()
+                }),
+            ),
+            None => period
+                .or(configured.map(|config| config.period))
+                .unwrap_or_default()
+                .previous(Utc::now()),
+        };
+        let min_days = min_days
+            .or(configured.map(|config| config.min_days))
+            .unwrap_or_else(config::ComplianceReportConfig::default_min_days);
+        let events = services.state.compliance_events();
+        let report = ComplianceReport::build(&events, from, until, min_days);
+        print!("{}", output::render_compliance(&report, format)?);
+        return Ok(());
+    }
+
+    // При машиночитаемом выводе stdout занят результатами, логи уходят в stderr
+    let log_output = match cli.command {
+        Some(Command::SingleShot { format: Some(_) }) => LogOutput::Stderr,
+        Some(Command::Tui) => LogOutput::Sink,
+        _ => LogOutput::Stdout,
+    };
+    init_logging_to(&SERVICES.conf.log_config, log_output).await?;
+    init_metrics(&SERVICES.conf.metrics, SERVICES.conf.log_config.app_name.as_deref())?;
+    services::metrics::describe();
     let dcl: &'static str = "MainApp";
+    let runners = SERVICES.runners(cli.profile.as_deref())?;
+    for (_, services) in &runners {
+        services.state.restore();
+        services.validate_run_options(&cli.run_options())?;
+    }
+
+    if cli.dry_run {
+        tracing::info!(dcl = dcl, "Пробный запуск: уведомления выводятся только в консоль");
+    }
+
+    if let Some(Command::Tui) = cli.command {
+        let services = SERVICES.runner(cli.profile.as_deref())?;
+        let result = tui::TuiApp::run(services.domain_checker(&cli.run_options())).await;
+        logging::shutdown();
+        base::metrics::shutdown();
+        return result;
+    }
 
-    let args: Vec<String> = env::args().collect();
-    if args.iter().any(|a| a == "single_shot") {
+    if let Some(Command::SingleShot { format }) = cli.command {
+        if format.is_some() && runners.len() > 1 {
+            anyhow::bail!("Вывод результатов доступен для одного профиля, укажите --profile");
+        }
         tracing::info!(dcl = dcl, "Запущена одноразовая проверка срока действия доменов");
-        run_check().await?;
-        return Ok(());
+        let mut exit_code = 0;
+        for (profile, services) in runners {
+            let span = profile_span(profile);
+            let result = run_check(services, &cli.run_options()).instrument(span).await;
+            let report = match result {
+                Ok(report) => report,
+                Err(e) => {
+                    tracing::error!(
+                        dcl = dcl,
+                        profile,
+                        %e,
+                        "Ошибка одноразовой проверки"
+                    );
+                    logging::shutdown();
+                    base::metrics::shutdown();
+                    std::process::exit(3);
+                }
+            };
+            if let Some(format) = format {
+                print!("{}", output::render(&report, format)?);
+            }
+            exit_code = exit_code.max(report.exit_code());
+        }
+        logging::shutdown();
+        base::metrics::shutdown();
+        std::process::exit(exit_code);
+    }
+
+
+    if let Some(api_server) = SERVICES.api_server() {
+        tokio::spawn(async move {
+            if let Err(e) = api_server.serve().await {
+                tracing::error!(dcl = "ApiServer", %e, "Ошибка HTTP-сервера");
+            }
+        });
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = listen_run_signal().await {
+            tracing::error!(dcl = "MainApp", %e, "Не удалось подписаться на SIGUSR1");
+        }
+    });
+    tokio::spawn(async move {
+        if let Err(e) = listen_debug_signal().await {
+            tracing::error!(dcl = "MainApp", %e, "Не удалось подписаться на SIGUSR2");
+        }
+    });
+
+    // Каждый профиль проверяется своим циклом со своим интервалом
+    let loops: Vec<_> = runners
+        .into_iter()
+        .map(|(profile, services)| {
+            let options = cli.run_options();
+            if let Some(monitor) = services.ct_monitor() {
+                let watch = watch_ct_logs(services, monitor, options.clone());
+                tokio::spawn(watch.instrument(profile_span(profile)));
+            }
+            let run = run_periodically(services, options);
+            tokio::spawn(run.instrument(profile_span(profile)))
+        })
+        .collect();
+    for handle in loops {
+        handle.await?;
     }
+    Ok(())
+}
 
+/// Span с именем профиля для всех логов его цикла; корневой конфигурации не нужен
+fn profile_span(profile: Option<&str>) -> tracing::Span {
+    match profile {
+        Some(profile) => tracing::info_span!("profile", profile = profile),
+        None => tracing::Span::none(),
+    }
+}
 
-    let interval_hours = SERVICES.conf.check_interval_hours;
-    let mut interval =
-        tokio::time::interval(std::time::Duration::from_secs(interval_hours * 3600));
+/// Периодическая проверка по расписанию и по внеочередным запросам
+async fn run_periodically(services: &'static ServicesInj, options: RunOptions) {
+    let dcl: &'static str = "MainApp";
+    let interval_hours = services.conf.check_interval_hours;
+    let period = std::time::Duration::from_secs(interval_hours * 3600);
+    let overlap_policy = services.conf.overlap_policy;
+    let mut schedule = RunSchedule::new(period, overlap_policy);
     tracing::info!(dcl = dcl, "Запущен периодический процесс проверки срока действия доменов");
 
     loop {
-        interval.tick().await;
-        if let Err(e) = run_check().await {
+        tokio::select! {
+            _ = schedule.tick() => {}
+            _ = services.state.run_requested() => {
+                tracing::info!(dcl = dcl, "Внеочередной запуск проверки");
+            }
+        }
+        let started = std::time::Instant::now();
+        if let Err(e) = run_check(services, &options).await {
             tracing::error!(dcl = dcl, %e, "Ошибка периодической проверки");
         }
+        let elapsed = started.elapsed();
+        if schedule.finished(elapsed).await {
+            let next = match overlap_policy {
+                OverlapPolicy::Skip => {
+                    "пропущенные запуски не выполняются, следующая проверка — по расписанию"
+                }
+                OverlapPolicy::Queue => "следующая проверка запускается сразу",
+            };
+            let msg = format!(
+                "Проверка длилась {} мин, дольше интервала {} ч; {}",
+                elapsed.as_secs() / 60,
+                interval_hours,
+                next
+            );
+            tracing::warn!(dcl = dcl, elapsed = elapsed.as_secs(), "{}", msg);
+            services.notify_exception(&options, &msg).await;
+        }
+    }
+}
+
+async fn run_check(
+    services: &ServicesInj,
+    options: &RunOptions,
+) -> anyhow::Result<CheckReport> {
+    // Идентификатор создаётся заранее, чтобы API состояния показывал его во время запуска
+    let run_id = Uuid::now_v7().to_string();
+    services.state.run_started(&run_id);
+    let mut domain_checker = services.domain_checker(options).with_run_id(&run_id);
+    let result = domain_checker.run().await;
+    services.state.run_finished(&result);
+    if let (Some(artifacts), Ok(report)) = (&services.artifacts, &result) {
+        artifacts.save(report);
+    }
+    if let (Some(compliance_reports), Ok(_)) = (&services.compliance_reports, &result) {
+        compliance_reports.save_due(
+            &services.state.compliance_events(),
+            services.state.compliance_since(),
+            Utc::now(),
+        );
+    }
+    result
+}
+
+/// Отчёт о сертификатах хранилища доверия: заданного набора или системного
+fn audit_trust_store(
+    bundle: Option<&std::path::Path>,
+    days: i64,
+) -> anyhow::Result<CheckReport> {
+    let path = match bundle {
+        Some(path) => path.to_path_buf(),
+        None => trust_store::system_bundle().ok_or_else(|| {
+            anyhow::anyhow!("Системный набор сертификатов не найден, укажите --bundle")
+        })?,
+    };
+    trust_store::audit_bundle(&path, days)
+}
+
+/// Выводит прогноз истечений по отчёту на `months` месяцев от текущего момента
+fn print_forecast(
+    report: &CheckReport,
+    months: u32,
+    period: ForecastPeriod,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let forecast = ExpiryForecast::from_report(report, Utc::now(), months, period);
+    print!("{}", output::render_forecast(&forecast, format)?);
+    Ok(())
+}
+
+/// Периодический опрос журналов Certificate Transparency; о каждом новом
+/// сертификате на отслеживаемые домены уходит уведомление
+async fn watch_ct_logs(
+    services: &'static ServicesInj,
+    monitor: CtMonitor,
+    options: RunOptions,
+) {
+    let dcl = "CtMonitor";
+    let interval_secs = services.conf.ct_monitor.as_ref().map_or(3600, |c| c.interval_secs);
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    tracing::info!(dcl = dcl, domains = ?monitor.domains(), "Запущен мониторинг журналов CT");
+
+    loop {
+        interval.tick().await;
+        let mut seen = services.state.ct_seen();
+        let certificates = monitor.poll(&mut seen).await;
+        services.state.set_ct_seen(seen);
+        if certificates.is_empty() {
+            continue;
+        }
+        tracing::warn!(
+            dcl = dcl,
+            count = certificates.len(),
+            "В журналах CT появились новые сертификаты"
+        );
+        let mut messages: Vec<CheckError> = certificates
+            .iter()
+            .map(CtCertificate::message)
+            .map(|message| CheckError::new(ErrorKind::Ct, message))
+            .collect();
+        let added = services.add_ct_hosts(&certificates);
+        if !added.is_empty() {
+            tracing::info!(dcl = dcl, hosts = ?added, "Хосты из журналов CT добавлены");
+            messages.push(CheckError::new(
+                ErrorKind::Ct,
+                format!("Добавлены в проверку по журналам CT: {}", added.join(", ")),
+            ));
+        }
+        services.notify_exceptions(&options, &messages).await;
     }
 }
 
-async fn run_check() -> anyhow::Result<()> {
-    let mut domain_checker = SERVICES.domain_checker();
-    domain_checker.run().await
+/// SIGUSR1 запрашивает внеочередной запуск проверки во всех профилях
+async fn listen_run_signal() -> anyhow::Result<()> {
+    let mut signal = signal(SignalKind::user_defined1())?;
+    while signal.recv().await.is_some() {
+        for (_, services) in SERVICES.runners(None)? {
+            services.state.request_run();
+        }
+    }
+    Ok(())
+}
+
+/// SIGUSR2 включает отладочные логи сервиса, повторный сигнал возвращает уровень
+/// из конфигурации
+async fn listen_debug_signal() -> anyhow::Result<()> {
+    let mut signal = signal(SignalKind::user_defined2())?;
+    while signal.recv().await.is_some() {
+        let result = match (logging::log_level(), logging::default_log_level()) {
+            (Some(current), Some(initial)) if current != initial => logging::reset_log_level(),
+            // Проверки и источники живут в библиотеке ssl_checker_core
+            (_, initial) => logging::set_log_level(&format!(
+                "{},{}=debug,ssl_checker_core=debug",
+                initial.unwrap_or_default(),
+                env!("CARGO_CRATE_NAME")
+            )),
+        };
+        if let Err(e) = result {
+            tracing::error!(dcl = "MainApp", %e, "Не удалось изменить уровень логирования");
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,62 @@
+use crate::services::report::{CheckKind, CheckReport, EntryStatus};
+
+/// Строки локальной проверки Checkmk: по одному сервису на домен/сертификат.
+/// Хосты без HTTPS (ожидаемые ошибки) в вывод не попадают
+pub(super) fn render_checkmk(report: &CheckReport) -> String {
+    let mut output = String::new();
+
+    for entry in &report.entries {
+        let (prefix, warn_days) = match entry.check {
+            CheckKind::Ssl => ("SSL", report.ssl_alarm_days),
+            CheckKind::Domain => ("Domain", report.alarm_days - 1),
+            CheckKind::Mail => ("Mail", report.ssl_alarm_days),
+            CheckKind::Quic => ("QUIC", report.ssl_alarm_days),
+            CheckKind::Ca => ("CA", report.ssl_alarm_days),
+            CheckKind::Ssh => ("SSH", report.ssl_alarm_days),
+            CheckKind::File => ("File", report.ssl_alarm_days),
+            CheckKind::Credential => ("Credential", report.alarm_days - 1),
+            CheckKind::Inventory => ("Inventory", report.ssl_alarm_days),
+        };
+
+        let (state, metrics, summary) = match (entry.status, entry.days, &entry.error) {
+            (EntryStatus::Skipped, _, _) => continue,
+            (EntryStatus::Error, _, error) => {
+                (3, "-".to_string(), error.clone().unwrap_or_default())
+            }
+            (status, Some(days), _) => {
+                let state = match status {
+                    EntryStatus::Critical => 2,
+                    EntryStatus::Warning => 1,
+                    _ => 0,
+                };
+                let expires = entry
+                    .expiration_date
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                (
+                    state,
+                    format!("days={}", days),
+                    format!(
+                        "Expires {} ({} days left, warn at {}, crit at {})",
+                        expires,
+                        days,
+                        warn_days,
+                        EntryStatus::CRITICAL_DAYS
+                    ),
+                )
+            }
+            _ => continue,
+        };
+
+        output.push_str(&format!(
+            "{} \"{} {}\" {} {}\n",
+            state,
+            prefix,
+            entry.hostname,
+            metrics,
+            summary.replace('\n', " ")
+        ));
+    }
+
+    output
+}
@@ -0,0 +1,189 @@
+use super::format_table;
+use crate::services::compliance::{ComplianceEvent, ComplianceReport};
+use base::prelude::{
+    anyhow::Result,
+    chrono::{Duration, Utc},
+    serde_json,
+};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Формат отчёта о соответствии
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComplianceFormat {
+    /// Страница для браузера; PDF не формируется, страница сохраняется в него
+    /// печатью из браузера
+    Html,
+    /// События периода
+    Csv,
+    Json,
+    Table,
+}
+
+impl ComplianceFormat {
+    /// Расширение файла отчёта
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ComplianceFormat::Html => "html",
+            ComplianceFormat::Csv => "csv",
+            ComplianceFormat::Json => "json",
+            ComplianceFormat::Table => "txt",
+        }
+    }
+}
+
+/// Строка CSV: событие периода и своевременность продления
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    kind: &'static str,
+    check: &'static str,
+    hostname: &'a str,
+    at: String,
+    expiration_date: String,
+    days_before_expiry: Option<i64>,
+    in_time: Option<bool>,
+}
+
+/// Представляет отчёт о соответствии в выбранном формате
+pub fn render_compliance(
+    report: &ComplianceReport,
+    format: ComplianceFormat,
+) -> Result<String> {
+    match format {
+        ComplianceFormat::Html => Ok(render_html(report)),
+        ComplianceFormat::Csv => render_csv(report),
+        ComplianceFormat::Json => Ok(serde_json::to_string_pretty(report)? + "\n"),
+        ComplianceFormat::Table => Ok(render_table(report)),
+    }
+}
+
+fn render_csv(report: &ComplianceReport) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for event in &report.events {
+        writer.serialize(CsvRow {
+            kind: event.kind.as_str(),
+            check: event.check.as_str(),
+            hostname: &event.hostname,
+            at: event.at.to_rfc3339(),
+            expiration_date: event.expiration_date.to_rfc3339(),
+            days_before_expiry: event.days_before_expiry,
+            in_time: event.days_before_expiry.map(|days| days >= report.min_days),
+        })?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Показатели сводки: название и значение
+fn summary(report: &ComplianceReport) -> Vec<(String, String)> {
+    let percent = report
+        .renewed_in_time_percent
+        .map_or("—".to_string(), |percent| format!("{:.1}%", percent));
+    let mean = report
+        .mean_days_before_expiry
+        .map_or("—".to_string(), |days| format!("{:.1}", days));
+    vec![
+        ("Период".to_string(), period_dates(report)),
+        ("Продлений".to_string(), report.renewals.to_string()),
+        (
+            format!("Продлено не позже чем за {} дн. до истечения", report.min_days),
+            format!("{} ({})", report.renewed_in_time, percent),
+        ),
+        ("Среднее число дней до истечения при продлении".to_string(), mean),
+        ("Истёкших сертификатов".to_string(), report.expired.len().to_string()),
+    ]
+}
+
+/// Даты периода включительно: `until` не входит в период
+fn period_dates(report: &ComplianceReport) -> String {
+    format!(
+        "{} — {}",
+        report.from.format("%Y-%m-%d"),
+        (report.until - Duration::seconds(1)).format("%Y-%m-%d")
+    )
+}
+
+fn event_row(event: &ComplianceEvent) -> [String; 4] {
+    [
+        event.at.format("%Y-%m-%d").to_string(),
+        event.hostname.clone(),
+        event.expiration_date.format("%Y-%m-%d").to_string(),
+        event.days_before_expiry.map(|days| days.to_string()).unwrap_or_default(),
+    ]
+}
+
+const EVENT_HEADER: [&str; 4] = ["ЗАМЕЧЕНО", "ХОСТ", "СРОК", "ДНЕЙ ДО ИСТЕЧЕНИЯ"];
+
+fn render_table(report: &ComplianceReport) -> String {
+    let rows: Vec<[String; 2]> =
+        summary(report).into_iter().map(|(name, value)| [name, value]).collect();
+    let mut table = format_table(["ПОКАЗАТЕЛЬ", "ЗНАЧЕНИЕ"], &rows);
+    for (title, events) in [
+        ("Истёкшие сертификаты", &report.expired),
+        ("Несвоевременные продления", &report.late_renewals),
+    ] {
+        if events.is_empty() {
+            continue;
+        }
+        let rows: Vec<[String; 4]> = events.iter().map(event_row).collect();
+        table.push_str(&format!("\n{}:\n", title));
+        table.push_str(&format_table(EVENT_HEADER, &rows));
+    }
+    table
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn html_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) -> String {
+    let cells = |tag: &str, cells: &mut dyn Iterator<Item = String>| {
+        cells
+            .map(|cell| format!("<{0}>{1}</{0}>", tag, escape(&cell)))
+            .collect::<String>()
+    };
+    let mut html = format!(
+        "<table><tr>{}</tr>\n",
+        cells("th", &mut header.iter().map(|h| h.to_string()))
+    );
+    for row in rows {
+        html.push_str(&format!("<tr>{}</tr>\n", cells("td", &mut row.iter().cloned())));
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn render_html(report: &ComplianceReport) -> String {
+    let title = format!("Отчёт о продлении сертификатов за {}", period_dates(report));
+    let rows: Vec<[String; 2]> =
+        summary(report).into_iter().map(|(name, value)| [name, value]).collect();
+    let mut body = format!("<h1>{}</h1>\n", escape(&title));
+    body.push_str(&html_table(["Показатель", "Значение"], &rows));
+    for (heading, events) in [
+        ("Истёкшие сертификаты", &report.expired),
+        ("Несвоевременные продления", &report.late_renewals),
+    ] {
+        body.push_str(&format!("<h2>{}</h2>\n", heading));
+        if events.is_empty() {
+            body.push_str("<p>Нет</p>\n");
+        } else {
+            let rows: Vec<[String; 4]> = events.iter().map(event_row).collect();
+            body.push_str(&html_table(EVENT_HEADER, &rows));
+        }
+    }
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ru\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>\n\
+         body {{ font-family: sans-serif; margin: 2em; }}\n\
+         table {{ border-collapse: collapse; margin-bottom: 1em; }}\n\
+         th, td {{ border: 1px solid #999; padding: 4px 8px; text-align: left; }}\n\
+         @media print {{ body {{ margin: 0; }} }}\n\
+         </style>\n</head>\n<body>\n{}<p>Сформирован {}</p>\n</body>\n</html>\n",
+        escape(&title),
+        body,
+        Utc::now().format("%Y-%m-%d %H:%M UTC")
+    )
+}
@@ -0,0 +1,90 @@
+use super::{OutputFormat, format_table};
+use crate::services::forecast::{ExpiryForecast, ForecastBucket};
+use base::prelude::{
+    anyhow::{Result, bail},
+    serde_json, serde_yaml,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Строка CSV прогноза: число сертификатов периода всего (`group` = `total`),
+/// по издателю или по тегу
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    period_start: String,
+    group: &'static str,
+    key: &'a str,
+    count: usize,
+}
+
+/// Представляет прогноз истечений в выбранном формате
+pub fn render_forecast(
+    forecast: &ExpiryForecast,
+    format: OutputFormat,
+) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(forecast)? + "\n"),
+        OutputFormat::Yaml => Ok(serde_yaml::to_string(forecast)?),
+        OutputFormat::Csv => render_csv(forecast),
+        OutputFormat::Table => Ok(render_table(forecast)),
+        OutputFormat::Nagios | OutputFormat::Checkmk => {
+            bail!("Прогноз выводится в форматах json, yaml, csv и table")
+        }
+    }
+}
+
+fn render_csv(forecast: &ExpiryForecast) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for bucket in &forecast.buckets {
+        let period_start = bucket.start.to_string();
+        let row = |group, key, count| CsvRow {
+            period_start: period_start.clone(),
+            group,
+            key,
+            count,
+        };
+        writer.serialize(row("total", "", bucket.total))?;
+        for (issuer, count) in &bucket.issuers {
+            writer.serialize(row("issuer", issuer, *count))?;
+        }
+        for (tag, count) in &bucket.tags {
+            writer.serialize(row("tag", tag, *count))?;
+        }
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Счётчики вида `Let's Encrypt: 12, Sectigo: 3`, от больших к меньшим
+fn counts(counts: &BTreeMap<String, usize>) -> String {
+    let mut counts: Vec<(&String, &usize)> = counts.iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    counts
+        .iter()
+        .map(|(key, count)| format!("{}: {}", key, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn render_table(forecast: &ExpiryForecast) -> String {
+    let header = ["PERIOD", "TOTAL", "ISSUERS", "TAGS"];
+    let rows: Vec<[String; 4]> = forecast
+        .buckets
+        .iter()
+        .map(|bucket: &ForecastBucket| {
+            [
+                bucket.start.to_string(),
+                bucket.total.to_string(),
+                counts(&bucket.issuers),
+                counts(&bucket.tags),
+            ]
+        })
+        .collect();
+    let mut table = format_table(header, &rows);
+    table.push_str(&format!(
+        "\nВсего до {}: {}, уже истекло: {}\n",
+        forecast.until.format("%Y-%m-%d"),
+        forecast.total(),
+        forecast.expired
+    ));
+    table
+}
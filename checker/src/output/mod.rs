@@ -0,0 +1,136 @@
+mod checkmk;
+mod compliance;
+mod forecast;
+mod nagios;
+
+use crate::services::report::{CheckKind, CheckReport, EntryStatus, ReportEntry};
+use base::prelude::{
+    anyhow::Result,
+    chrono::{DateTime, Utc},
+    serde_json, serde_yaml,
+};
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Формат вывода результатов одноразовой проверки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    Csv,
+    Table,
+    /// Вывод плагина Nagios/Icinga
+    Nagios,
+    /// Локальная проверка Checkmk
+    Checkmk,
+}
+
+pub use compliance::{ComplianceFormat, render_compliance};
+pub use forecast::render_forecast;
+
+/// Представляет отчёт в выбранном формате
+pub fn render(report: &CheckReport, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(report)? + "\n"),
+        OutputFormat::Yaml => Ok(serde_yaml::to_string(report)?),
+        OutputFormat::Csv => render_csv(report),
+        OutputFormat::Table => Ok(render_table(&report.entries)),
+        OutputFormat::Nagios => Ok(nagios::render_nagios(report)),
+        OutputFormat::Checkmk => Ok(checkmk::render_checkmk(report)),
+    }
+}
+
+/// Строка CSV: идентификатор запуска и поля записи отчёта, теги через `;`
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    run_id: &'a str,
+    check: CheckKind,
+    status: EntryStatus,
+    hostname: &'a str,
+    expiration_date: Option<DateTime<Utc>>,
+    days: Option<i64>,
+    issuer: Option<&'a str>,
+    serial: Option<&'a str>,
+    alpn: Option<&'a str>,
+    error: Option<&'a str>,
+    tags: String,
+    owner: Option<&'a str>,
+    usage: String,
+}
+
+impl<'a> CsvRow<'a> {
+    fn new(run_id: &'a str, e: &'a ReportEntry) -> Self {
+        Self {
+            run_id,
+            check: e.check,
+            status: e.status,
+            hostname: &e.hostname,
+            expiration_date: e.expiration_date,
+            days: e.days,
+            issuer: e.issuer.as_deref(),
+            serial: e.serial.as_deref(),
+            alpn: e.alpn.as_deref(),
+            error: e.error.as_deref(),
+            tags: e.tags.join(";"),
+            owner: e.owner.as_deref(),
+            usage: e.usage.iter().map(|u| u.as_str()).collect::<Vec<_>>().join(";"),
+        }
+    }
+}
+
+fn render_csv(report: &CheckReport) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for entry in &report.entries {
+        writer.serialize(CsvRow::new(&report.run_id, entry))?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+fn render_table(entries: &[ReportEntry]) -> String {
+    let header =
+        ["CHECK", "STATUS", "HOSTNAME", "EXPIRES", "DAYS", "ISSUER", "TAGS", "ERROR"];
+    let rows: Vec<[String; 8]> = entries
+        .iter()
+        .map(|e| {
+            [
+                e.check.as_str().to_string(),
+                e.status.as_str().to_string(),
+                e.hostname.clone(),
+                e.expiration_date
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                e.days.map(|d| d.to_string()).unwrap_or_default(),
+                e.issuer.clone().unwrap_or_default(),
+                e.tags.join(","),
+                e.error.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    format_table(header, &rows)
+}
+
+/// Таблица с колонками по ширине самого длинного значения
+fn format_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) -> String {
+    let mut widths = header.map(|h| h.chars().count());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        let line = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        format!("{}\n", line.trim_end())
+    };
+
+    let mut table = format_row(&header.map(String::from));
+    for row in rows {
+        table.push_str(&format_row(row));
+    }
+    table
+}
@@ -0,0 +1,49 @@
+use crate::services::report::{CheckKind, CheckReport, EntryStatus};
+
+/// Строка статуса плагина Nagios/Icinga с perfdata и подробностями.
+/// Пороги perfdata записаны диапазонами вида `N:` (тревога при значении меньше N)
+pub(super) fn render_nagios(report: &CheckReport) -> String {
+    let state = match report.exit_code() {
+        0 => "OK",
+        1 => "WARNING",
+        2 => "CRITICAL",
+        _ => "UNKNOWN",
+    };
+
+    let critical_from = EntryStatus::CRITICAL_DAYS + 1;
+    let perfdata = |label: &str, check: CheckKind, warn_from: i64| {
+        let value = report.min_days(check).map(|d| d.to_string()).unwrap_or("U".into());
+        format!("{}={};{}:;{}:", label, value, warn_from, critical_from)
+    };
+
+    let mut output = format!(
+        "SSL_CHECKER {} - {} critical, {} warning, {} errors of {} checks | {} {} errors={}\n",
+        state,
+        report.count(EntryStatus::Critical),
+        report.count(EntryStatus::Warning),
+        report.count(EntryStatus::Error),
+        report.entries.len(),
+        perfdata("ssl_min_days", CheckKind::Ssl, report.ssl_alarm_days + 1),
+        perfdata("domain_min_days", CheckKind::Domain, report.alarm_days),
+        report.count(EntryStatus::Error),
+    );
+
+    for entry in &report.entries {
+        let details = match (entry.status, entry.days, &entry.error) {
+            (EntryStatus::Critical | EntryStatus::Warning, Some(days), _) => {
+                format!("{} days left", days)
+            }
+            (EntryStatus::Error, _, Some(error)) => error.clone(),
+            _ => continue,
+        };
+        output.push_str(&format!(
+            "[{}] {} {}: {}\n",
+            entry.status.as_str().to_uppercase(),
+            entry.check.as_str(),
+            entry.hostname,
+            details
+        ));
+    }
+
+    output
+}
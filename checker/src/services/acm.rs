@@ -0,0 +1,363 @@
+//! Сертификаты AWS Certificate Manager: сроки и состояние управляемого продления.
+//! Запросы выполняет AWS SDK (постраничная выдача, повторы, ожидание при
+//! троттлинге) с учётными данными из стандартной цепочки AWS или ролей, принятых
+//! через STS, — так читаются сертификаты нескольких учётных записей
+
+use super::http::HttpSettings;
+use super::inventory::{CertificateInventoryTrait, InventoryCertificate};
+use async_trait::async_trait;
+use aws_config::sts::AssumeRoleProvider;
+use aws_sdk_acm::{
+    Client,
+    config::{
+        Builder, Region, SharedCredentialsProvider, retry::RetryConfig,
+        timeout::TimeoutConfig,
+    },
+    types::{
+        CertificateDetail, CertificateType, DomainStatus, Filters, KeyAlgorithm,
+        RenewalEligibility, RenewalStatus,
+    },
+};
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    chrono::DateTime,
+    tracing,
+};
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+/// По умолчанию `ListCertificates` возвращает только ключи RSA 2048
+const KEY_TYPES: [KeyAlgorithm; 7] = [
+    KeyAlgorithm::Rsa1024,
+    KeyAlgorithm::Rsa2048,
+    KeyAlgorithm::Rsa3072,
+    KeyAlgorithm::Rsa4096,
+    KeyAlgorithm::EcPrime256v1,
+    KeyAlgorithm::EcSecp384r1,
+    KeyAlgorithm::EcSecp521r1,
+];
+/// Одновременных запросов `DescribeCertificate`; лимит ACM — 10 в секунду
+const DESCRIBE_CONCURRENCY: usize = 4;
+
+/// Почему ACM не продлит используемый сертификат сам: продление ждёт DNS- или
+/// email-валидации, завершилось ошибкой или сертификат к нему не допущен.
+/// Импортированные сертификаты ACM не продлевает вообще
+fn renewal_problem(certificate: &CertificateDetail) -> Option<String> {
+    if certificate.r#type == Some(CertificateType::Imported)
+        || certificate.in_use_by().is_empty()
+    {
+        return None;
+    }
+    let renewal = certificate.renewal_summary.as_ref().and_then(|summary| {
+        let problem = match summary.renewal_status {
+            RenewalStatus::PendingValidation => "Автопродление ACM ждёт валидации",
+            RenewalStatus::Failed => "Автопродление ACM завершилось ошибкой",
+            _ => return None,
+        };
+        Some((summary, problem))
+    });
+    if let Some((summary, problem)) = renewal {
+        let mut problem = problem.to_string();
+        if let Some(reason) = &summary.renewal_status_reason {
+            problem.push_str(&format!(" ({})", reason.as_str()));
+        }
+        let pending: Vec<&str> = summary
+            .domain_validation_options
+            .iter()
+            .filter(|option| option.validation_status != Some(DomainStatus::Success))
+            .map(|option| option.domain_name.as_str())
+            .collect();
+        if !pending.is_empty() {
+            problem.push_str(&format!(", не подтверждены: {}", pending.join(", ")));
+        }
+        return Some(problem);
+    }
+    (certificate.renewal_eligibility == Some(RenewalEligibility::Ineligible))
+        .then(|| "Сертификат используется, но не допущен к автопродлению ACM".into())
+}
+
+/// Запись инвентаризации с тегами `tags` и регионом. Сертификаты без срока
+/// ещё не выпущены: ожидают первой валидации
+fn inventory_certificate(
+    certificate: CertificateDetail,
+    tags: &[String],
+    region: &str,
+) -> Option<InventoryCertificate> {
+    let expiration_date = DateTime::from_timestamp(certificate.not_after?.secs(), 0)?;
+    let renewal_problem = renewal_problem(&certificate);
+    let mut tags = tags.to_vec();
+    tags.push(region.to_string());
+    if certificate.r#type == Some(CertificateType::Imported) {
+        tags.push("imported".to_string());
+    }
+    if certificate.in_use_by().is_empty() {
+        tags.push("unused".to_string());
+    }
+    Some(InventoryCertificate {
+        renewal_problem,
+        id: certificate.certificate_arn?,
+        domain: certificate.domain_name,
+        expiration_date: Some(expiration_date),
+        issuer: certificate.issuer,
+        serial: certificate.serial.map(|s| s.replace(':', "").to_uppercase()),
+        tags,
+        names: Vec::new(),
+        alarm_days: None,
+    })
+}
+
+/// Сертификаты ACM в заданных регионах своей учётной записи или учётных записей
+/// ролей `role_arns`. Неиспользуемые сертификаты помечаются тегом `unused`,
+/// импортированные — `imported`
+pub(crate) struct AcmInventory {
+    regions: Vec<String>,
+    role_arns: Vec<String>,
+    tags: Vec<String>,
+    http: HttpSettings,
+    dcl: &'static str,
+}
+
+impl AcmInventory {
+    /// Без регионов используется регион из окружения AWS. Из `http` берутся
+    /// таймауты, число повторов и начальная пауза между ними
+    pub fn new(regions: &[String], role_arns: &[String], http: HttpSettings) -> Self {
+        Self {
+            regions: regions.to_vec(),
+            role_arns: role_arns.to_vec(),
+            tags: Vec::new(),
+            http,
+            dcl: "AcmInventory",
+        }
+    }
+
+    /// Теги, добавляемые ко всем записям
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Клиенты ACM по учётным записям (своя или роли, принятые через STS)
+    /// и регионам. Конфигурация загружается на каждый запуск, а временные ключи
+    /// ролей SDK обновляет сам
+    async fn clients(&self) -> Result<Vec<(String, String, Client)>> {
+        let sdk_config = aws_config::load_from_env().await;
+        let regions = if self.regions.is_empty() {
+            let region = sdk_config
+                .region()
+                .ok_or_else(|| anyhow!("AWS region is not configured"))?;
+            vec![region.to_string()]
+        } else {
+            self.regions.clone()
+        };
+        let mut accounts = Vec::new();
+        if self.role_arns.is_empty() {
+            accounts.push(("default".to_string(), None));
+        }
+        for role_arn in &self.role_arns {
+            let provider = AssumeRoleProvider::builder(role_arn)
+                .session_name("ssl-checker")
+                .configure(&sdk_config)
+                .build()
+                .await;
+            accounts
+                .push((role_arn.clone(), Some(SharedCredentialsProvider::new(provider))));
+        }
+
+        let retry = RetryConfig::standard()
+            .with_max_attempts(self.http.retries + 1)
+            .with_initial_backoff(self.http.retry_interval);
+        let timeout = TimeoutConfig::builder()
+            .operation_attempt_timeout(self.http.timeout)
+            .connect_timeout(self.http.connect_timeout)
+            .build();
+        let mut clients = Vec::new();
+        for (account, provider) in &accounts {
+            for region in &regions {
+                let mut config = Builder::from(&sdk_config)
+                    .region(Region::new(region.clone()))
+                    .retry_config(retry.clone())
+                    .timeout_config(timeout.clone());
+                if let Some(provider) = provider {
+                    config = config.credentials_provider(provider.clone());
+                }
+                let client = Client::from_conf(config.build());
+                clients.push((account.clone(), region.clone(), client));
+            }
+        }
+        Ok(clients)
+    }
+
+    /// Сертификаты региона одной учётной записи
+    async fn region_certificates(
+        &self,
+        client: &Client,
+        region: &str,
+    ) -> Result<Vec<InventoryCertificate>> {
+        let filters = Filters::builder().set_key_types(Some(KEY_TYPES.to_vec())).build();
+        let arns: Vec<String> = client
+            .list_certificates()
+            .includes(filters)
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .context("ACM ListCertificates")?
+            .into_iter()
+            .filter_map(|summary| summary.certificate_arn)
+            .collect();
+
+        let described: Vec<Option<CertificateDetail>> = stream::iter(arns)
+            .map(|arn| async move {
+                let output = client
+                    .describe_certificate()
+                    .certificate_arn(&arn)
+                    .send()
+                    .await
+                    .with_context(|| format!("ACM DescribeCertificate {}", arn))?;
+                Ok::<_, base::prelude::anyhow::Error>(output.certificate)
+            })
+            .buffer_unordered(DESCRIBE_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        Ok(described
+            .into_iter()
+            .flatten()
+            .filter_map(|certificate| {
+                inventory_certificate(certificate, &self.tags, region)
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl CertificateInventoryTrait for AcmInventory {
+    async fn get_certificates(&self) -> Result<Vec<InventoryCertificate>> {
+        let mut certificates = Vec::new();
+        for (account, region, client) in self.clients().await? {
+            let found = self
+                .region_certificates(&client, &region)
+                .await
+                .with_context(|| format!("{} in {}", account, region))?;
+            tracing::debug!(
+                dcl = self.dcl,
+                account = account,
+                region = region,
+                count = found.len(),
+                "Загружены сертификаты ACM"
+            );
+            certificates.extend(found);
+        }
+        Ok(certificates)
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_acm::primitives::DateTime as AwsDateTime;
+    use aws_sdk_acm::types::{DomainValidation, FailureReason, RenewalSummary};
+
+    const ARN: &str = "arn:aws:acm:eu-west-1:123456789012:certificate/0f1e2d3c";
+    const LOAD_BALANCER: &str =
+        "arn:aws:elasticloadbalancing:eu-west-1:123456789012:loadbalancer/app/web";
+
+    /// Сертификат, выпущенный ACM, как его возвращает `DescribeCertificate`
+    fn certificate(
+        renewal: Option<RenewalSummary>,
+        in_use_by: &[&str],
+    ) -> CertificateDetail {
+        CertificateDetail::builder()
+            .certificate_arn(ARN)
+            .domain_name("example.com")
+            .subject_alternative_names("example.com")
+            .subject_alternative_names("www.example.com")
+            .serial("0a:1b:2c:3d")
+            .issuer("Amazon")
+            .not_before(AwsDateTime::from_secs(1735689600))
+            .not_after(AwsDateTime::from_secs(1767225600))
+            .r#type(CertificateType::AmazonIssued)
+            .key_algorithm(KeyAlgorithm::Rsa2048)
+            .set_in_use_by(Some(in_use_by.iter().map(|arn| arn.to_string()).collect()))
+            .renewal_eligibility(RenewalEligibility::Eligible)
+            .set_renewal_summary(renewal)
+            .build()
+    }
+
+    fn renewal(
+        status: RenewalStatus,
+        validations: Vec<DomainValidation>,
+    ) -> RenewalSummary {
+        RenewalSummary::builder()
+            .renewal_status(status)
+            .set_domain_validation_options(Some(validations))
+            .updated_at(AwsDateTime::from_secs(1764547200))
+            .build()
+            .unwrap()
+    }
+
+    fn validation(domain: &str, status: DomainStatus) -> DomainValidation {
+        DomainValidation::builder()
+            .domain_name(domain)
+            .validation_status(status)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn healthy_certificate_has_no_renewal_problem() {
+        let certificate = certificate(
+            Some(renewal(RenewalStatus::Success, Vec::new())),
+            &[LOAD_BALANCER],
+        );
+        let entry = inventory_certificate(certificate, &["aws".to_string()], "eu-west-1")
+            .unwrap();
+        assert_eq!(entry.renewal_problem, None);
+        assert_eq!(entry.id, ARN);
+        assert_eq!(entry.serial.as_deref(), Some("0A1B2C3D"));
+        assert_eq!(entry.expiration_date.unwrap().timestamp(), 1767225600);
+        assert_eq!(entry.tags, ["aws", "eu-west-1"]);
+    }
+
+    #[test]
+    fn pending_validation_is_renewal_problem() {
+        let mut summary = renewal(
+            RenewalStatus::PendingValidation,
+            vec![
+                validation("example.com", DomainStatus::Success),
+                validation("www.example.com", DomainStatus::PendingValidation),
+            ],
+        );
+        summary.renewal_status_reason = Some(FailureReason::CaaError);
+        let certificate = certificate(Some(summary), &[LOAD_BALANCER]);
+        let expected = "Автопродление ACM ждёт валидации (CAA_ERROR), \
+                        не подтверждены: www.example.com";
+        assert_eq!(renewal_problem(&certificate).as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn ineligible_certificate_in_use_is_renewal_problem() {
+        let mut certificate = certificate(
+            Some(renewal(RenewalStatus::PendingAutoRenewal, Vec::new())),
+            &[LOAD_BALANCER],
+        );
+        certificate.renewal_eligibility = Some(RenewalEligibility::Ineligible);
+        assert_eq!(
+            renewal_problem(&certificate).as_deref(),
+            Some("Сертификат используется, но не допущен к автопродлению ACM")
+        );
+    }
+
+    #[test]
+    fn unused_certificate_is_tagged_and_not_reported() {
+        let certificate =
+            certificate(Some(renewal(RenewalStatus::Failed, Vec::new())), &[]);
+        let entry = inventory_certificate(certificate, &[], "us-east-1").unwrap();
+        assert_eq!(entry.renewal_problem, None);
+        assert_eq!(entry.tags, ["us-east-1", "unused"]);
+    }
+}
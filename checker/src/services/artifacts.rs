@@ -0,0 +1,51 @@
+use super::report::CheckReport;
+use base::prelude::{
+    anyhow::{Context, Result},
+    serde_json, tracing,
+};
+use std::path::PathBuf;
+
+/// Архив отчётов: каждый запуск сохраняется в отдельный JSON-файл с временем
+/// запуска в имени, независимо от модулей уведомлений
+pub struct ArtifactService {
+    dir: PathBuf,
+    dcl: &'static str,
+}
+
+impl ArtifactService {
+    pub fn new(dir: &str) -> Self {
+        Self { dir: PathBuf::from(dir), dcl: "ArtifactService" }
+    }
+
+    /// Путь к файлу отчёта, например `report-20250101T120000Z.json`
+    fn path(&self, report: &CheckReport) -> PathBuf {
+        let name = format!("report-{}.json", report.checked_at.format("%Y%m%dT%H%M%SZ"));
+        self.dir.join(name)
+    }
+
+    /// Атомарная запись отчёта: во временный файл с последующим переименованием
+    fn write(&self, report: &CheckReport) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir).with_context(|| {
+            format!("Не удалось создать каталог: {}", self.dir.display())
+        })?;
+        let path = self.path(report);
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(report)?).with_context(
+            || format!("Не удалось записать файл: {}", tmp_path.display()),
+        )?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(path)
+    }
+
+    /// Сохраняет отчёт; ошибка записи не прерывает работу сервиса
+    pub fn save(&self, report: &CheckReport) {
+        match self.write(report) {
+            Ok(path) => {
+                tracing::info!(dcl = self.dcl, path = %path.display(), "Отчёт сохранён");
+            }
+            Err(e) => {
+                tracing::error!(dcl = self.dcl, e = %e, "Не удалось сохранить отчёт");
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use super::compliance::{ComplianceEvent, CompliancePeriod, ComplianceReport};
+use crate::config::ComplianceReportConfig;
+use crate::output::{ComplianceFormat, render_compliance};
+use base::prelude::{
+    anyhow::{Context, Result},
+    chrono::{DateTime, Utc},
+    tracing,
+};
+use std::path::{Path, PathBuf};
+
+/// Отчёты о соответствии за завершившиеся периоды: после запуска проверки
+/// сохраняется отчёт за прошедший месяц или квартал, если его ещё нет
+pub struct ComplianceReportService {
+    dir: PathBuf,
+    period: CompliancePeriod,
+    min_days: i64,
+    formats: Vec<ComplianceFormat>,
+    dcl: &'static str,
+}
+
+impl ComplianceReportService {
+    pub fn new(config: &ComplianceReportConfig) -> Self {
+        Self {
+            dir: PathBuf::from(&config.dir),
+            period: config.period,
+            min_days: config.min_days,
+            formats: config.formats.clone(),
+            dcl: "ComplianceReportService",
+        }
+    }
+
+    /// Путь к файлу отчёта, например `compliance-2025-03.html`
+    fn path(&self, label: &str, format: ComplianceFormat) -> PathBuf {
+        self.dir.join(format!("compliance-{}.{}", label, format.extension()))
+    }
+
+    /// Атомарная запись отчёта: во временный файл с последующим переименованием
+    fn write(&self, path: &Path, content: &str) -> Result<()> {
+        std::fs::create_dir_all(&self.dir).with_context(|| {
+            format!("Не удалось создать каталог: {}", self.dir.display())
+        })?;
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, content).with_context(|| {
+            format!("Не удалось записать файл: {}", tmp_path.display())
+        })?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Сохраняет отчёт за последний завершившийся к `now` период в форматах,
+    /// файлов которых ещё нет; ошибка записи не прерывает работу сервиса.
+    /// Период, начавшийся раньше истории продлений `since`, пропускается:
+    /// отчёт без событий за него выглядел бы как период без нарушений
+    pub fn save_due(
+        &self,
+        events: &[ComplianceEvent],
+        since: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) {
+        let (from, until) = self.period.previous(now);
+        let label = self.period.label(from);
+        if since.is_none_or(|since| since > from) {
+            tracing::debug!(
+                dcl = self.dcl,
+                period = label,
+                since = ?since,
+                "История продлений не покрывает период, отчёт не сохраняется"
+            );
+            return;
+        }
+        let missing: Vec<ComplianceFormat> = self
+            .formats
+            .iter()
+            .copied()
+            .filter(|format| !self.path(&label, *format).exists())
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        let report = ComplianceReport::build(events, from, until, self.min_days);
+        for format in missing {
+            let path = self.path(&label, format);
+            let result = render_compliance(&report, format)
+                .and_then(|content| self.write(&path, &content));
+            match result {
+                Ok(()) => tracing::info!(
+                    dcl = self.dcl,
+                    path = %path.display(),
+                    "Отчёт о соответствии сохранён"
+                ),
+                Err(e) => tracing::error!(
+                    dcl = self.dcl,
+                    e = %e,
+                    "Не удалось сохранить отчёт о соответствии"
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::prelude::chrono::TimeZone;
+
+    fn service(dir: &Path) -> ComplianceReportService {
+        ComplianceReportService::new(&ComplianceReportConfig {
+            dir: dir.to_string_lossy().into_owned(),
+            period: CompliancePeriod::Month,
+            min_days: 30,
+            formats: vec![ComplianceFormat::Json],
+        })
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "checker-compliance-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn period_before_history_is_not_reported() {
+        let dir = temp_dir("fresh");
+        let now = Utc.with_ymd_and_hms(2025, 4, 2, 6, 0, 0).unwrap();
+        let report = dir.join("compliance-2025-03.json");
+
+        // Новый файл состояния: истории ещё нет или она началась посреди марта
+        service(&dir).save_due(&[], None, now);
+        assert!(!report.exists());
+        let since = Utc.with_ymd_and_hms(2025, 3, 10, 6, 0, 0).unwrap();
+        service(&dir).save_due(&[], Some(since), now);
+        assert!(!report.exists());
+
+        let since = Utc.with_ymd_and_hms(2025, 2, 27, 6, 0, 0).unwrap();
+        service(&dir).save_due(&[], Some(since), now);
+        assert!(report.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,3 +1,10 @@
-pub(crate) mod domain_checker;
-pub(crate) mod sources;
-pub(crate) mod notifiers;
+pub(crate) use ssl_checker_core::{
+    azure, budget, calendar, checks, compliance, credentials, ct, domain_checker,
+    forecast, gcp, http, inventory, mail, metrics, notifiers, overrides, quic, registry,
+    renewal, report, sources, trust_store, vault, vault_pki, venafi,
+};
+pub(crate) mod acm;
+pub(crate) mod artifacts;
+pub(crate) mod compliance_report;
+pub(crate) mod schedule;
+pub(crate) mod state;
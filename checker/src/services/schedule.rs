@@ -0,0 +1,87 @@
+use crate::config::OverlapPolicy;
+use base::prelude::tokio::time::{self, Interval, MissedTickBehavior};
+use std::time::Duration;
+
+/// Расписание периодической проверки с учётом `overlap_policy`
+pub struct RunSchedule {
+    interval: Interval,
+    period: Duration,
+    policy: OverlapPolicy,
+}
+
+impl RunSchedule {
+    pub fn new(period: Duration, policy: OverlapPolicy) -> Self {
+        let mut interval = time::interval(period);
+        interval.set_missed_tick_behavior(match policy {
+            OverlapPolicy::Skip => MissedTickBehavior::Skip,
+            OverlapPolicy::Queue => MissedTickBehavior::Delay,
+        });
+        Self { interval, period, policy }
+    }
+
+    /// Ждёт очередного запуска по расписанию
+    pub async fn tick(&mut self) {
+        self.interval.tick().await;
+    }
+
+    /// Учитывает завершившийся запуск; `true`, если он длился дольше периода.
+    /// Просроченный запуск tokio выполняет сразу при любом `MissedTickBehavior`,
+    /// поэтому при `skip` он отбрасывается здесь, а следующий наступает по расписанию
+    pub async fn finished(&mut self, elapsed: Duration) -> bool {
+        let overran = elapsed >= self.period;
+        if overran && self.policy == OverlapPolicy::Skip {
+            self.interval.tick().await;
+        }
+        overran
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    const PERIOD: Duration = Duration::from_secs(10);
+
+    /// Запуск, длившийся `elapsed`: часы переводятся вперёд
+    async fn run(schedule: &mut RunSchedule, elapsed: Duration) -> bool {
+        time::advance(elapsed).await;
+        schedule.finished(elapsed).await
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn skip_drops_overdue_run() {
+        let mut schedule = RunSchedule::new(PERIOD, OverlapPolicy::Skip);
+        schedule.tick().await;
+        assert!(run(&mut schedule, Duration::from_secs(25)).await);
+
+        // Запуск в 10 с пропущен, следующий — в 30 с по расписанию
+        assert!(schedule.tick().now_or_never().is_none());
+        time::advance(Duration::from_secs(4)).await;
+        assert!(schedule.tick().now_or_never().is_none());
+        time::advance(Duration::from_secs(1)).await;
+        assert!(schedule.tick().now_or_never().is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn queue_runs_overdue_once() {
+        let mut schedule = RunSchedule::new(PERIOD, OverlapPolicy::Queue);
+        schedule.tick().await;
+        assert!(run(&mut schedule, Duration::from_secs(25)).await);
+
+        assert!(schedule.tick().now_or_never().is_some());
+        assert!(!run(&mut schedule, Duration::from_secs(1)).await);
+        assert!(schedule.tick().now_or_never().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn run_within_period_keeps_schedule() {
+        let mut schedule = RunSchedule::new(PERIOD, OverlapPolicy::Skip);
+        schedule.tick().await;
+        assert!(!run(&mut schedule, Duration::from_secs(3)).await);
+
+        assert!(schedule.tick().now_or_never().is_none());
+        time::advance(Duration::from_secs(7)).await;
+        assert!(schedule.tick().now_or_never().is_some());
+    }
+}
@@ -0,0 +1,429 @@
+use super::compliance::ComplianceEvent;
+use super::report::{CheckKind, CheckReport, EntryStatus};
+use base::prelude::{
+    anyhow::{Context, Result},
+    chrono::{DateTime, Duration, Utc},
+    serde_json,
+    tokio::sync::Notify,
+    tracing,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+/// Итог последнего запуска проверки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunOutcome {
+    Running,
+    Ok,
+    Failed,
+}
+
+/// Сведения о запусках периодической проверки
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunState {
+    pub started_at: DateTime<Utc>,
+    /// Идентификатор последнего запуска, как в логах и уведомлениях
+    pub last_run_id: Option<String>,
+    pub last_run_started: Option<DateTime<Utc>>,
+    pub last_run_finished: Option<DateTime<Utc>>,
+    pub last_run_outcome: Option<RunOutcome>,
+    pub last_error: Option<String>,
+}
+
+/// Содержимое файла состояния
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedState {
+    #[serde(default)]
+    last_run_id: Option<String>,
+    last_run_started: Option<DateTime<Utc>>,
+    last_run_finished: Option<DateTime<Utc>>,
+    last_run_outcome: Option<RunOutcome>,
+    last_error: Option<String>,
+    last_report: Option<CheckReport>,
+    /// Издатель последнего полученного сертификата каждого хоста
+    #[serde(default)]
+    issuers: HashMap<String, String>,
+    /// Наибольший идентификатор crt.sh, уже учтённый для каждого домена мониторинга CT
+    #[serde(default)]
+    ct_seen: HashMap<String, u64>,
+    /// Хосты, добавленные в проверку по журналам CT
+    #[serde(default)]
+    ct_hosts: BTreeSet<String>,
+    /// Число запусков подряд, в которых сертификат хоста не удалось получить
+    #[serde(default)]
+    ssl_failures: HashMap<String, u32>,
+    /// Продления и истечения сертификатов за [`ComplianceEvent::RETENTION_DAYS`] дней
+    #[serde(default)]
+    compliance_events: Vec<ComplianceEvent>,
+    /// С какого времени ведётся история продлений: время первого отчёта,
+    /// с которым сравнивался следующий
+    #[serde(default)]
+    compliance_since: Option<DateTime<Utc>>,
+}
+
+/// Разделяемое состояние демона. При заданном `path` сохраняется в JSON-файл
+/// после каждого запуска и восстанавливается при старте
+pub struct StateService {
+    run: RwLock<RunState>,
+    last_report: RwLock<Option<CheckReport>>,
+    issuers: RwLock<HashMap<String, String>>,
+    ct_seen: RwLock<HashMap<String, u64>>,
+    ct_hosts: RwLock<BTreeSet<String>>,
+    ssl_failures: RwLock<HashMap<String, u32>>,
+    compliance_events: RwLock<Vec<ComplianceEvent>>,
+    compliance_since: RwLock<Option<DateTime<Utc>>>,
+    path: Option<PathBuf>,
+    /// Запись файла состояния: её выполняют и периодический запуск, и мониторинг
+    /// CT, а временный файл у них общий
+    save_lock: Mutex<()>,
+    run_request: Notify,
+    dcl: &'static str,
+}
+
+impl StateService {
+    pub fn new(path: Option<&str>) -> Self {
+        Self {
+            run: RwLock::new(RunState {
+                started_at: Utc::now(),
+                last_run_id: None,
+                last_run_started: None,
+                last_run_finished: None,
+                last_run_outcome: None,
+                last_error: None,
+            }),
+            last_report: RwLock::new(None),
+            issuers: RwLock::new(HashMap::new()),
+            ct_seen: RwLock::new(HashMap::new()),
+            ct_hosts: RwLock::new(BTreeSet::new()),
+            ssl_failures: RwLock::new(HashMap::new()),
+            compliance_events: RwLock::new(Vec::new()),
+            compliance_since: RwLock::new(None),
+            path: path.map(PathBuf::from),
+            save_lock: Mutex::new(()),
+            run_request: Notify::new(),
+            dcl: "StateService",
+        }
+    }
+
+    /// Восстанавливает состояние из файла, если он задан и существует
+    pub fn restore(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        match Self::load(path) {
+            Ok(persisted) => {
+                let mut run = self.run.write().unwrap();
+                run.last_run_id = persisted.last_run_id;
+                run.last_run_started = persisted.last_run_started;
+                run.last_run_finished = persisted.last_run_finished;
+                run.last_run_outcome = persisted.last_run_outcome;
+                run.last_error = persisted.last_error;
+                *self.last_report.write().unwrap() = persisted.last_report;
+                *self.issuers.write().unwrap() = persisted.issuers;
+                *self.ct_seen.write().unwrap() = persisted.ct_seen;
+                *self.ct_hosts.write().unwrap() = persisted.ct_hosts;
+                *self.ssl_failures.write().unwrap() = persisted.ssl_failures;
+                *self.compliance_events.write().unwrap() = persisted.compliance_events;
+                *self.compliance_since.write().unwrap() = persisted.compliance_since;
+            }
+            Err(e) => {
+                tracing::warn!(dcl = self.dcl, e = %e, "Не удалось загрузить файл состояния");
+            }
+        }
+    }
+
+    fn load(path: &Path) -> Result<PersistedState> {
+        if !path.exists() {
+            return Ok(PersistedState::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Не удалось прочитать файл: {}", path.display()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Атомарная запись состояния: во временный файл с последующим переименованием.
+    /// Снимок и запись выполняются под блокировкой, чтобы одновременные записи
+    /// не смешались во временном файле и последней сохранилась свежая копия
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let _guard = self.save_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        let run = self.run_state();
+        let persisted = PersistedState {
+            last_run_id: run.last_run_id,
+            last_run_started: run.last_run_started,
+            last_run_finished: run.last_run_finished,
+            last_run_outcome: run.last_run_outcome,
+            last_error: run.last_error,
+            last_report: self.last_report(),
+            issuers: self.issuers(),
+            ct_seen: self.ct_seen(),
+            ct_hosts: self.ct_hosts(),
+            ssl_failures: self.ssl_failures(),
+            compliance_events: self.compliance_events(),
+            compliance_since: self.compliance_since(),
+        };
+
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(&persisted)?).with_context(
+            || format!("Не удалось записать файл: {}", tmp_path.display()),
+        )?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Запрос внеочередного запуска. Если проверка уже идёт, запуск
+    /// выполнится сразу после её завершения
+    pub fn request_run(&self) {
+        self.run_request.notify_one();
+    }
+
+    /// Ожидание запроса внеочередного запуска
+    pub async fn run_requested(&self) {
+        self.run_request.notified().await
+    }
+
+    pub fn run_state(&self) -> RunState {
+        self.run.read().unwrap().clone()
+    }
+
+    pub fn run_started(&self, run_id: &str) {
+        let mut run = self.run.write().unwrap();
+        run.last_run_id = Some(run_id.to_string());
+        run.last_run_started = Some(Utc::now());
+        run.last_run_outcome = Some(RunOutcome::Running);
+    }
+
+    pub fn run_finished(&self, result: &Result<CheckReport>) {
+        {
+            let mut run = self.run.write().unwrap();
+            run.last_run_finished = Some(Utc::now());
+            match result {
+                Ok(report) => {
+                    run.last_run_outcome = Some(RunOutcome::Ok);
+                    run.last_error = None;
+                    let previous =
+                        self.last_report.write().unwrap().replace(report.clone());
+                    if let Some(previous) = previous {
+                        let horizon =
+                            Utc::now() - Duration::days(ComplianceEvent::RETENTION_DAYS);
+                        let mut events = self.compliance_events.write().unwrap();
+                        events.retain(|event| event.at >= horizon);
+                        events.extend(ComplianceEvent::between(&previous, report));
+                        self.compliance_since
+                            .write()
+                            .unwrap()
+                            .get_or_insert(previous.checked_at);
+                    }
+                    // Хосты, сертификат которых не удалось получить, сохраняют прежнего издателя
+                    let mut issuers = self.issuers.write().unwrap();
+                    for entry in &report.entries {
+                        if entry.check != CheckKind::Ssl {
+                            continue;
+                        }
+                        if let Some(issuer) = &entry.issuer {
+                            issuers.insert(entry.hostname.clone(), issuer.clone());
+                        }
+                    }
+                    // Серия прерывается успешной проверкой или ожидаемой ошибкой;
+                    // хосты, которых нет в отчёте, забываются. У хостов, не проверенных
+                    // за время запуска, серия не меняется
+                    let mut ssl_failures = self.ssl_failures.write().unwrap();
+                    let previous = std::mem::take(&mut *ssl_failures);
+                    for entry in &report.entries {
+                        if entry.check != CheckKind::Ssl
+                            || entry.status != EntryStatus::Error
+                        {
+                            continue;
+                        }
+                        let streak = previous.get(&entry.hostname).copied();
+                        let streak = match (entry.was_checked(), streak) {
+                            (true, streak) => streak.unwrap_or(0) + 1,
+                            (false, Some(streak)) => streak,
+                            (false, None) => continue,
+                        };
+                        ssl_failures.insert(entry.hostname.clone(), streak);
+                    }
+                }
+                Err(e) => {
+                    run.last_run_outcome = Some(RunOutcome::Failed);
+                    run.last_error = Some(e.to_string());
+                }
+            }
+        }
+
+        if let Err(e) = self.save() {
+            tracing::error!(dcl = self.dcl, e = %e, "Не удалось сохранить файл состояния");
+        }
+    }
+
+    /// Отчёт последнего успешного запуска
+    pub fn last_report(&self) -> Option<CheckReport> {
+        self.last_report.read().unwrap().clone()
+    }
+
+    /// Издатели сертификатов хостов по всем успешным запускам
+    pub fn issuers(&self) -> HashMap<String, String> {
+        self.issuers.read().unwrap().clone()
+    }
+
+    /// Продления и истечения сертификатов, замеченные при запусках
+    pub fn compliance_events(&self) -> Vec<ComplianceEvent> {
+        self.compliance_events.read().unwrap().clone()
+    }
+
+    /// С какого времени ведётся история продлений; `None`, пока не было двух
+    /// успешных запусков
+    pub fn compliance_since(&self) -> Option<DateTime<Utc>> {
+        *self.compliance_since.read().unwrap()
+    }
+
+    /// Число неудачных проверок SSL подряд по хостам
+    pub fn ssl_failures(&self) -> HashMap<String, u32> {
+        self.ssl_failures.read().unwrap().clone()
+    }
+
+    /// Отметки мониторинга CT: домен → последний учтённый идентификатор crt.sh
+    pub fn ct_seen(&self) -> HashMap<String, u64> {
+        self.ct_seen.read().unwrap().clone()
+    }
+
+    pub fn set_ct_seen(&self, seen: HashMap<String, u64>) {
+        *self.ct_seen.write().unwrap() = seen;
+        if let Err(e) = self.save() {
+            tracing::error!(dcl = self.dcl, e = %e, "Не удалось сохранить файл состояния");
+        }
+    }
+
+    /// Хосты, добавленные в проверку по журналам CT
+    pub fn ct_hosts(&self) -> BTreeSet<String> {
+        self.ct_hosts.read().unwrap().clone()
+    }
+
+    /// Добавляет хосты в проверку и возвращает те, которых в списке ещё не было
+    pub fn add_ct_hosts(&self, hosts: impl IntoIterator<Item = String>) -> Vec<String> {
+        let added: Vec<String> = {
+            let mut ct_hosts = self.ct_hosts.write().unwrap();
+            hosts.into_iter().filter(|host| ct_hosts.insert(host.clone())).collect()
+        };
+        if !added.is_empty()
+            && let Err(e) = self.save()
+        {
+            tracing::error!(dcl = self.dcl, e = %e, "Не удалось сохранить файл состояния");
+        }
+        added
+    }
+
+    /// Цикл проверки считается зависшим, если с начала последнего запуска
+    /// (или старта демона) прошло больше `max_silence`
+    pub fn is_alive(&self, max_silence: Duration) -> bool {
+        let run = self.run.read().unwrap();
+        let last_activity = match run.last_run_started {
+            Some(started) if started > run.started_at => started,
+            _ => run.started_at,
+        };
+        Utc::now().signed_duration_since(last_activity) <= max_silence
+    }
+
+    /// Готовность — завершён хотя бы один запуск проверки
+    pub fn is_ready(&self) -> bool {
+        self.run.read().unwrap().last_run_finished.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::domain_checker::DomainCheckerService;
+    use crate::services::report::ReportEntry;
+    use base::prelude::anyhow;
+
+    fn ssl_report(entries: Vec<ReportEntry>) -> Result<CheckReport> {
+        let mut report = CheckReport::new(30, 14);
+        for entry in entries {
+            report.push(entry);
+        }
+        Ok(report)
+    }
+
+    #[test]
+    fn compliance_history_starts_at_first_compared_report() {
+        let state = StateService::new(None);
+        let first = ssl_report(Vec::new());
+        let started = first.as_ref().unwrap().checked_at;
+        state.run_finished(&first);
+        assert_eq!(state.compliance_since(), None);
+
+        state.run_finished(&ssl_report(Vec::new()));
+        state.run_finished(&ssl_report(Vec::new()));
+        assert_eq!(state.compliance_since(), Some(started));
+    }
+
+    #[test]
+    fn hosts_not_checked_in_time_keep_failure_streak() {
+        let state = StateService::new(None);
+        let failed =
+            || ReportEntry::failed(CheckKind::Ssl, "a.example.com", "reset", false);
+        let not_checked = |hostname| {
+            ReportEntry::not_checked(
+                CheckKind::Ssl,
+                hostname,
+                DomainCheckerService::NOT_CHECKED_IN_TIME,
+            )
+        };
+
+        state.run_finished(&ssl_report(vec![failed()]));
+        for _ in 0..3 {
+            state.run_finished(&ssl_report(vec![
+                not_checked("a.example.com"),
+                not_checked("b.example.com"),
+            ]));
+        }
+        let streaks = state.ssl_failures();
+        assert_eq!(streaks.get("a.example.com"), Some(&1));
+        assert_eq!(streaks.get("b.example.com"), None);
+
+        state.run_finished(&ssl_report(vec![failed()]));
+        assert_eq!(state.ssl_failures().get("a.example.com"), Some(&2));
+    }
+
+    #[test]
+    fn concurrent_saves_leave_valid_state_file() {
+        let dir =
+            std::env::temp_dir().join(format!("checker-state-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.json");
+        let state = StateService::new(path.to_str());
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                for i in 0..200 {
+                    state.set_ct_seen(HashMap::from([("example.com".to_string(), i)]));
+                }
+            });
+            scope.spawn(|| {
+                for i in 0..200 {
+                    state.add_ct_hosts([format!("host{}.example.com", i)]);
+                }
+            });
+            scope.spawn(|| {
+                for _ in 0..200 {
+                    state.run_finished(&Err(anyhow::anyhow!("ошибка запуска")));
+                }
+            });
+        });
+
+        let persisted = StateService::load(&path).unwrap();
+        assert_eq!(persisted.ct_seen.get("example.com"), Some(&199));
+        assert_eq!(persisted.ct_hosts.len(), 200);
+        assert_eq!(persisted.last_run_outcome, Some(RunOutcome::Failed));
+        assert!(!path.with_extension("tmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
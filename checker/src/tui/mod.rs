@@ -0,0 +1,274 @@
+use crate::services::{
+    domain_checker::DomainCheckerService,
+    report::{CheckProgress, EntryStatus, ReportEntry},
+};
+use base::prelude::{
+    anyhow::Result,
+    tokio::{
+        self,
+        sync::mpsc::{self, UnboundedReceiver, error::TryRecvError},
+    },
+};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Gauge, Paragraph, Row, Table, TableState},
+};
+use std::time::Duration;
+
+/// Столбец сортировки таблицы результатов
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    Days,
+    Hostname,
+    Check,
+    Status,
+}
+
+impl SortColumn {
+    fn next(self) -> Self {
+        match self {
+            SortColumn::Days => SortColumn::Hostname,
+            SortColumn::Hostname => SortColumn::Check,
+            SortColumn::Check => SortColumn::Status,
+            SortColumn::Status => SortColumn::Days,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            SortColumn::Days => "дни",
+            SortColumn::Hostname => "хост",
+            SortColumn::Check => "проверка",
+            SortColumn::Status => "статус",
+        }
+    }
+}
+
+/// Интерактивный режим: запуск проверки с отображением хода и таблицы результатов
+pub struct TuiApp {
+    entries: Vec<ReportEntry>,
+    total: usize,
+    finished: bool,
+    sort: SortColumn,
+    reverse: bool,
+    filter: String,
+    editing_filter: bool,
+    table: TableState,
+}
+
+impl TuiApp {
+    fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            total: 0,
+            finished: false,
+            sort: SortColumn::Days,
+            reverse: false,
+            filter: String::new(),
+            editing_filter: false,
+            table: TableState::default(),
+        }
+    }
+
+    /// Запускает проверку и интерфейс; завершается по нажатию `q`
+    pub async fn run(checker: DomainCheckerService) -> Result<()> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut checker = checker.with_progress(tx);
+        let check_task = tokio::spawn(async move { checker.run().await });
+
+        let result = tokio::task::spawn_blocking(move || {
+            let mut terminal = ratatui::init();
+            let result = TuiApp::new().event_loop(&mut terminal, rx);
+            ratatui::restore();
+            result
+        })
+        .await?;
+
+        check_task.abort();
+        result
+    }
+
+    fn event_loop(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        mut progress: UnboundedReceiver<CheckProgress>,
+    ) -> Result<()> {
+        loop {
+            loop {
+                match progress.try_recv() {
+                    Ok(event) => self.apply(event),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        self.finished = true;
+                        break;
+                    }
+                }
+            }
+
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(Duration::from_millis(100))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && !self.handle_key(key.code)
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    fn apply(&mut self, event: CheckProgress) {
+        match event {
+            CheckProgress::Started { total } => self.total = total,
+            CheckProgress::Added { count } => self.total += count,
+            CheckProgress::Checked(entry) => self.entries.push(*entry),
+            CheckProgress::Finished => self.finished = true,
+        }
+    }
+
+    /// Обработка клавиши; `false` — выход из интерфейса
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        if self.editing_filter {
+            match code {
+                KeyCode::Enter | KeyCode::Esc => self.editing_filter = false,
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Char(c) => self.filter.push(c),
+                _ => {}
+            }
+            return true;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('/') => self.editing_filter = true,
+            KeyCode::Char('c') => self.filter.clear(),
+            KeyCode::Char('s') => self.sort = self.sort.next(),
+            KeyCode::Char('r') => self.reverse = !self.reverse,
+            KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+            _ => {}
+        }
+        true
+    }
+
+    fn visible_entries(&self) -> Vec<&ReportEntry> {
+        let filter = self.filter.to_lowercase();
+        let mut entries: Vec<&ReportEntry> = self
+            .entries
+            .iter()
+            .filter(|e| {
+                filter.is_empty()
+                    || e.hostname.to_lowercase().contains(&filter)
+                    || e.status.as_str().contains(&filter)
+                    || e.issuer.as_deref().unwrap_or("").to_lowercase().contains(&filter)
+            })
+            .collect();
+
+        match self.sort {
+            SortColumn::Days => entries.sort_by_key(|e| (e.days.is_none(), e.days)),
+            SortColumn::Hostname => entries.sort_by(|a, b| a.hostname.cmp(&b.hostname)),
+            SortColumn::Check => entries.sort_by_key(|e| e.check.as_str()),
+            SortColumn::Status => entries.sort_by_key(|e| e.status.as_str()),
+        }
+        if self.reverse {
+            entries.reverse();
+        }
+        entries
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [progress_area, table_area, help_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let done = self.entries.len();
+        let ratio = if self.total > 0 { done as f64 / self.total as f64 } else { 0.0 };
+        let label = if self.finished {
+            format!("Проверка завершена: {} проверок", done)
+        } else {
+            format!("Проверено {} из {}", done, self.total)
+        };
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title("Ход проверки"))
+            .gauge_style(Style::default().fg(Color::Green))
+            .ratio(if self.finished { 1.0 } else { ratio.min(1.0) })
+            .label(label);
+        frame.render_widget(gauge, progress_area);
+
+        let rows: Vec<Row> = self
+            .visible_entries()
+            .into_iter()
+            .map(|e| {
+                let color = match e.status {
+                    EntryStatus::Critical => Color::Red,
+                    EntryStatus::Warning => Color::Yellow,
+                    EntryStatus::Error => Color::Magenta,
+                    EntryStatus::Skipped => Color::DarkGray,
+                    EntryStatus::Ok => Color::Reset,
+                };
+                Row::new(vec![
+                    Cell::from(e.check.as_str()),
+                    Cell::from(e.status.as_str()),
+                    Cell::from(e.hostname.clone()),
+                    Cell::from(e.days.map(|d| d.to_string()).unwrap_or_default()),
+                    Cell::from(
+                        e.expiration_date
+                            .map(|d| d.format("%Y-%m-%d").to_string())
+                            .unwrap_or_default(),
+                    ),
+                    Cell::from(e.issuer.clone().unwrap_or_default()),
+                    Cell::from(e.error.clone().unwrap_or_default()),
+                ])
+                .style(Style::default().fg(color))
+            })
+            .collect();
+
+        let title = format!(
+            "Результаты (сортировка: {}{}){}",
+            self.sort.title(),
+            if self.reverse { ", обратная" } else { "" },
+            if self.filter.is_empty() {
+                String::new()
+            } else {
+                format!(" фильтр: {}", self.filter)
+            }
+        );
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Length(9),
+                Constraint::Percentage(30),
+                Constraint::Length(6),
+                Constraint::Length(11),
+                Constraint::Percentage(20),
+                Constraint::Percentage(30),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                "CHECK", "STATUS", "HOSTNAME", "DAYS", "EXPIRES", "ISSUER", "ERROR",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, table_area, &mut self.table);
+
+        let help = if self.editing_filter {
+            format!("Фильтр: {}▏ (Enter — применить)", self.filter)
+        } else {
+            "q — выход, / — фильтр, c — сбросить фильтр, s — сортировка, r — обратный порядок, ↑↓ — навигация"
+                .to_string()
+        };
+        frame.render_widget(Paragraph::new(help), help_area);
+    }
+}
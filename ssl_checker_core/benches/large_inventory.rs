@@ -0,0 +1,113 @@
+//! Пиковая память и время запуска на большом списке хостов.
+//!
+//! Все хосты взяты из предыдущего отчёта как недавно проверенные, поэтому сеть
+//! не используется и измеряются только накладные расходы самого запуска:
+//! загрузка из источника, отбор хостов, записи отчёта.
+//!
+//! ```bash
+//! cargo bench -p ssl_checker_core --bench large_inventory
+//! HOSTS=500000 cargo bench -p ssl_checker_core --bench large_inventory
+//! ```
+
+use base::prelude::{
+    chrono::{Duration, Utc},
+    tokio,
+};
+use ssl_checker_core::testing::{MockSource, RecordingNotifier};
+use ssl_checker_core::{CheckKind, CheckReport, DomainCheckerService, ReportEntry};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// Аллокатор, считающий текущий и пиковый объём выделенной памяти
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current =
+                ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const DEFAULT_HOSTS: usize = 100_000;
+/// Хостов на одну зону второго уровня
+const HOSTS_PER_ZONE: usize = 50;
+
+fn hostnames(count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("host-{}.zone-{}.com", i, i / HOSTS_PER_ZONE)).collect()
+}
+
+/// Отчёт, в котором все хосты и зоны проверены только что и далеки от окна оповещения
+fn previous_report(hostnames: &[String]) -> CheckReport {
+    let expiration_date = Utc::now() + Duration::days(200);
+    let mut report = CheckReport::new(30, 14);
+    for (i, hostname) in hostnames.iter().enumerate() {
+        report.push(ReportEntry {
+            serial: Some(format!("{:X}", i)),
+            issuer: Some("Benchmark CA".to_string()),
+            ..ReportEntry::ok(CheckKind::Ssl, hostname, expiration_date, 200, false)
+        });
+        if i % HOSTS_PER_ZONE == 0 {
+            let zone = format!("zone-{}.com", i / HOSTS_PER_ZONE);
+            report.push(ReportEntry::ok(
+                CheckKind::Domain,
+                &zone,
+                expiration_date,
+                200,
+                false,
+            ));
+        }
+    }
+    report
+}
+
+fn main() {
+    let count =
+        std::env::var("HOSTS").ok().and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_HOSTS);
+    let runtime =
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+    let hosts = hostnames(count);
+    let previous = previous_report(&hosts);
+    // Учитывается всё, что сервис держит сверх входных данных: копия
+    // предыдущего отчёта, отобранные хосты и записи нового отчёта
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+
+    let checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(hosts))],
+        vec![Box::new(RecordingNotifier::new())],
+        14,
+        30,
+    )
+    .with_previous_report(&previous, Duration::hours(1));
+    let started = Instant::now();
+    let report = runtime.block_on(async move {
+        let mut checker = checker;
+        checker.run().await.unwrap()
+    });
+    let elapsed = started.elapsed();
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+    drop(previous);
+
+    println!("hosts:           {}", count);
+    println!("report entries:  {}", report.entries.len());
+    println!("run time:        {:.2?}", elapsed);
+    println!("peak memory:     {:.1} MiB", peak as f64 / 1024.0 / 1024.0);
+}
@@ -0,0 +1,374 @@
+//! Сертификаты Azure Key Vault. Токен доступа выдаётся Microsoft Entra ID
+//! по секрету приложения или управляемым удостоверением (IMDS)
+
+use crate::http::HttpSettings;
+use crate::inventory::{CertificateInventoryTrait, InventoryCertificate};
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    tracing,
+};
+use reqwest::Client;
+use serde::{Deserialize, de::DeserializeOwned};
+
+const API_VERSION: &str = "7.4";
+const VAULT_RESOURCE: &str = "https://vault.azure.net";
+const LOGIN_URL: &str = "https://login.microsoftonline.com";
+const IMDS_TOKEN_URL: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CertificatePage {
+    #[serde(default)]
+    value: Vec<CertificateItem>,
+    next_link: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CertificateItem {
+    /// `https://<хранилище>.vault.azure.net/certificates/<имя>`
+    id: String,
+    #[serde(default)]
+    attributes: Attributes,
+}
+
+#[derive(Deserialize)]
+struct Attributes {
+    #[serde(default = "enabled")]
+    enabled: bool,
+}
+
+/// Сертификат без `attributes` включён
+impl Default for Attributes {
+    fn default() -> Self {
+        Self { enabled: enabled() }
+    }
+}
+
+fn enabled() -> bool {
+    true
+}
+
+/// Последняя версия сертификата
+#[derive(Deserialize)]
+struct CertificateBundle {
+    /// DER в base64
+    cer: String,
+}
+
+/// Учётные данные приложения Entra ID
+#[derive(Clone)]
+pub struct ClientSecret {
+    pub tenant_id: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+/// Сертификаты хранилищ Azure Key Vault: срок, издатель и имена из последней
+/// версии каждого включённого сертификата. Проверяемые хосты с этими именами,
+/// которые отдают более старый сертификат, попадают в ошибки
+pub struct AzureKeyVaultInventory {
+    vaults: Vec<String>,
+    client_secret: Option<ClientSecret>,
+    /// Клиент пользовательского управляемого удостоверения
+    identity_client_id: Option<String>,
+    tags: Vec<String>,
+    client: Client,
+    http: HttpSettings,
+    dcl: &'static str,
+}
+
+impl AzureKeyVaultInventory {
+    /// `vaults` — имена хранилищ (`kv-prod`) или их адреса
+    /// (`https://kv-prod.vault.azure.net`). Без секрета приложения токен
+    /// запрашивается у управляемого удостоверения
+    pub fn new(vaults: &[String], http: HttpSettings) -> Self {
+        Self {
+            vaults: vaults.iter().map(|vault| Self::vault_url(vault)).collect(),
+            client_secret: None,
+            identity_client_id: None,
+            tags: Vec::new(),
+            client: http.client(),
+            http,
+            dcl: "AzureKeyVaultInventory",
+        }
+    }
+
+    /// Вход приложением Entra ID с секретом
+    pub fn with_client_secret(mut self, client_secret: ClientSecret) -> Self {
+        self.client_secret = Some(client_secret);
+        self
+    }
+
+    /// Пользовательское управляемое удостоверение вместо системного
+    pub fn with_identity_client_id(mut self, client_id: &str) -> Self {
+        self.identity_client_id = Some(client_id.to_string());
+        self
+    }
+
+    /// Теги, добавляемые ко всем записям
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    fn vault_url(vault: &str) -> String {
+        if vault.contains("://") {
+            vault.trim_end_matches('/').to_string()
+        } else {
+            format!("https://{}.vault.azure.net", vault)
+        }
+    }
+
+    /// Токен доступа к Key Vault на один запуск
+    async fn access_token(&self) -> Result<String> {
+        let resp = match &self.client_secret {
+            Some(secret) => {
+                let url = format!("{}/{}/oauth2/v2.0/token", LOGIN_URL, secret.tenant_id);
+                let body = form_urlencoded::Serializer::new(String::new())
+                    .append_pair("grant_type", "client_credentials")
+                    .append_pair("client_id", &secret.client_id)
+                    .append_pair("client_secret", &secret.client_secret)
+                    .append_pair("scope", &format!("{}/.default", VAULT_RESOURCE))
+                    .finish();
+                self.http
+                    .send(|| {
+                        self.client
+                            .post(&url)
+                            .header("content-type", "application/x-www-form-urlencoded")
+                            .body(body.clone())
+                    })
+                    .await?
+            }
+            None => {
+                let mut query =
+                    vec![("api-version", "2018-02-01"), ("resource", VAULT_RESOURCE)];
+                if let Some(client_id) = &self.identity_client_id {
+                    query.push(("client_id", client_id));
+                }
+                let query = form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(query)
+                    .finish();
+                let url = format!("{}?{}", IMDS_TOKEN_URL, query);
+                self.http
+                    .send(|| self.client.get(&url).header("Metadata", "true"))
+                    .await
+                    .context("Azure managed identity endpoint is unreachable")?
+            }
+        };
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("Azure token request returned {}: {}", status, text));
+        }
+        let token: AccessToken =
+            resp.json().await.context("Unexpected Azure token response")?;
+        Ok(token.access_token)
+    }
+
+    /// GET с версией API; ссылка на следующую страницу уже содержит её
+    async fn get<T: DeserializeOwned>(&self, token: &str, url: &str) -> Result<T> {
+        let url = if url.contains("api-version=") {
+            url.to_string()
+        } else {
+            format!("{}?api-version={}", url, API_VERSION)
+        };
+        let url = url.as_str();
+        let resp = self.http.send(|| self.client.get(url).bearer_auth(token)).await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            tracing::error!(
+                dcl = self.dcl,
+                url = url,
+                status = status.to_string(),
+                body = text,
+                "Ошибка запроса к Azure Key Vault"
+            );
+            return Err(anyhow!("Azure Key Vault returned {} for {}", status, url));
+        }
+        resp.json()
+            .await
+            .with_context(|| format!("Unexpected Azure Key Vault response: {}", url))
+    }
+
+    async fn vault_certificates(
+        &self,
+        token: &str,
+        vault: &str,
+    ) -> Result<Vec<InventoryCertificate>> {
+        let mut items = Vec::new();
+        let mut url = format!("{}/certificates", vault);
+        loop {
+            let page: CertificatePage = self.get(token, &url).await?;
+            items.extend(page.value);
+            match page.next_link {
+                Some(next_link) => url = next_link,
+                None => break,
+            }
+        }
+
+        let mut certificates = Vec::new();
+        for item in items.iter().filter(|item| item.attributes.enabled) {
+            let bundle: CertificateBundle = self.get(token, &item.id).await?;
+            certificates.push(self.bundle_certificate(vault, &item.id, &bundle)?);
+        }
+        Ok(certificates)
+    }
+
+    /// Запись инвентаризации по последней версии сертификата `item_id`
+    /// с идентификатором `<хранилище>/certificates/<имя>`
+    fn bundle_certificate(
+        &self,
+        vault: &str,
+        item_id: &str,
+        bundle: &CertificateBundle,
+    ) -> Result<InventoryCertificate> {
+        let vault_name = vault
+            .split_once("://")
+            .map_or(vault, |(_, host)| host)
+            .split('.')
+            .next()
+            .unwrap_or(vault);
+        let der = openssl::base64::decode_block(&bundle.cer)
+            .with_context(|| format!("Invalid certificate body: {}", item_id))?;
+        let id = item_id.strip_prefix(vault).unwrap_or(item_id).trim_start_matches('/');
+        let mut certificate =
+            InventoryCertificate::from_der(&format!("{}/{}", vault_name, id), &der)
+                .with_context(|| format!("Invalid certificate: {}", item_id))?;
+        certificate.tags = self.tags.clone();
+        certificate.tags.push(vault_name.to_string());
+        Ok(certificate)
+    }
+}
+
+#[async_trait]
+impl CertificateInventoryTrait for AzureKeyVaultInventory {
+    async fn get_certificates(&self) -> Result<Vec<InventoryCertificate>> {
+        let token = self.access_token().await?;
+        let mut certificates = Vec::new();
+        for vault in &self.vaults {
+            let found = self
+                .vault_certificates(&token, vault)
+                .await
+                .with_context(|| format!("Key Vault {}", vault))?;
+            tracing::debug!(
+                dcl = self.dcl,
+                vault = vault,
+                count = found.len(),
+                "Загружены сертификаты Azure Key Vault"
+            );
+            certificates.extend(found);
+        }
+        Ok(certificates)
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::prelude::{
+        chrono::Utc,
+        serde_json::{self, json},
+    };
+    use openssl::{
+        asn1::Asn1Time,
+        hash::MessageDigest,
+        pkey::PKey,
+        rsa::Rsa,
+        x509::{X509Builder, X509NameBuilder, extension::SubjectAlternativeName},
+    };
+
+    const VAULT: &str = "https://kv-prod.vault.azure.net";
+
+    /// Тело `cer` ответа Key Vault: DER сертификата в base64
+    fn certificate_body(names: &[&str], days: u32) -> String {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", names[0]).unwrap();
+        let name = name.build();
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        builder.set_not_after(&Asn1Time::days_from_now(days).unwrap()).unwrap();
+        let mut san = SubjectAlternativeName::new();
+        for name in names {
+            san.dns(name);
+        }
+        let san = san.build(&builder.x509v3_context(None, None)).unwrap();
+        builder.append_extension(san).unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        openssl::base64::encode_block(&builder.build().to_der().unwrap())
+    }
+
+    fn inventory() -> AzureKeyVaultInventory {
+        AzureKeyVaultInventory::new(&["kv-prod".to_string()], HttpSettings::AZURE)
+            .with_tags(vec!["azure".to_string()])
+    }
+
+    #[test]
+    fn certificate_page_is_parsed() {
+        let page: CertificatePage = serde_json::from_value(json!({
+            "value": [
+                {
+                    "id": "https://kv-prod.vault.azure.net/certificates/web",
+                    "x5t": "fLi3U52HunIVNXubkEnf8tP6Wbo",
+                    "attributes": { "enabled": true, "exp": 1767225600 }
+                },
+                {
+                    "id": "https://kv-prod.vault.azure.net/certificates/old",
+                    "attributes": { "enabled": false }
+                },
+                { "id": "https://kv-prod.vault.azure.net/certificates/api" }
+            ],
+            "nextLink": "https://kv-prod.vault.azure.net/certificates?api-version=7.4\
+                         &$skiptoken=page-2"
+        }))
+        .unwrap();
+        let enabled: Vec<bool> =
+            page.value.iter().map(|item| item.attributes.enabled).collect();
+        assert_eq!(enabled, [true, false, true]);
+        assert!(page.next_link.unwrap().contains("skiptoken=page-2"));
+    }
+
+    #[test]
+    fn latest_version_is_inventory_certificate() {
+        let bundle: CertificateBundle = serde_json::from_value(json!({
+            "id": "https://kv-prod.vault.azure.net/certificates/web/5b2a",
+            "cer": certificate_body(&["www.example.com", "example.com"], 30),
+            "attributes": { "enabled": true }
+        }))
+        .unwrap();
+        let certificate = inventory()
+            .bundle_certificate(VAULT, &format!("{}/certificates/web", VAULT), &bundle)
+            .unwrap();
+        assert_eq!(certificate.id, "kv-prod/certificates/web");
+        assert_eq!(certificate.domain.as_deref(), Some("www.example.com"));
+        assert_eq!(certificate.names, ["www.example.com", "example.com"]);
+        assert_eq!(certificate.tags, ["azure", "kv-prod"]);
+        assert_eq!(certificate.renewal_problem, None);
+        let days = (certificate.expiration_date.unwrap() - Utc::now()).num_days();
+        assert!((29..=30).contains(&days));
+    }
+
+    #[test]
+    fn invalid_certificate_body_is_error() {
+        let bundle = CertificateBundle { cer: "not base64!".to_string() };
+        let error = inventory()
+            .bundle_certificate(VAULT, &format!("{}/certificates/web", VAULT), &bundle)
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Invalid certificate body"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Бюджеты проверок групп хостов: сколько запросов WHOIS и проверок сертификатов
+//! в минуту и одновременно допускается для хостов с заданными тегами, например
+//! у регистратора клиента с ограничением частоты WHOIS. Бюджеты действуют внутри
+//! общего запуска независимо друг от друга: исчерпавшая бюджет группа ждёт,
+//! остальные проверяются дальше
+
+use crate::config::CheckBudgetConfig;
+use crate::report::CheckKind;
+use base::prelude::{
+    tokio::{
+        sync::{OwnedSemaphorePermit, Semaphore},
+        time::{Instant, sleep_until},
+    },
+    tracing,
+};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Ограничение одного вида проверок группы
+#[derive(Debug, Default)]
+struct Limit {
+    concurrency: Option<Arc<Semaphore>>,
+    /// Интервал между началами проверок и время, раньше которого следующая
+    /// не начнётся
+    pace: Option<(Duration, Mutex<Instant>)>,
+}
+
+impl Limit {
+    fn new(per_minute: Option<u32>, concurrency: Option<usize>) -> Self {
+        Self {
+            concurrency: concurrency.map(|n| Arc::new(Semaphore::new(n.max(1)))),
+            pace: per_minute.map(|n| {
+                (Duration::from_secs(60) / n.max(1), Mutex::new(Instant::now()))
+            }),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.concurrency.is_none() && self.pace.is_none()
+    }
+
+    /// Время начала следующей проверки; проверки идут с равными интервалами
+    fn reserve(&self) -> Option<Instant> {
+        let (interval, next) = self.pace.as_ref()?;
+        let mut next = next.lock().unwrap();
+        let slot = (*next).max(Instant::now());
+        *next = slot + *interval;
+        Some(slot)
+    }
+}
+
+#[derive(Debug)]
+struct Budget {
+    name: String,
+    tags: Vec<String>,
+    whois: Limit,
+    ssl: Limit,
+}
+
+impl Budget {
+    fn applies(&self, tags: &[String]) -> bool {
+        self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+
+    fn limit(&self, kind: CheckKind) -> Option<&Limit> {
+        let limit = match kind {
+            CheckKind::Domain => &self.whois,
+            CheckKind::Ssl => &self.ssl,
+            _ => return None,
+        };
+        (!limit.is_empty()).then_some(limit)
+    }
+}
+
+/// Разрешение на проверку: держит места в ограничениях числа одновременных
+/// проверок до завершения проверки
+#[derive(Debug)]
+pub struct BudgetPermit {
+    _permits: Vec<OwnedSemaphorePermit>,
+}
+
+/// Бюджеты проверок по группам хостов (секция `budgets`, ключ — имя группы).
+/// Хост относится к группе, если у него есть хотя бы один из её тегов; группа
+/// без тегов охватывает все хосты. Ограничиваются проверки WHOIS
+/// ([`CheckKind::Domain`]) и сертификатов хостов ([`CheckKind::Ssl`])
+#[derive(Debug, Default)]
+pub struct CheckBudgets {
+    /// По имени, чтобы места в нескольких группах занимались в одном порядке
+    budgets: Vec<Budget>,
+}
+
+impl CheckBudgets {
+    pub fn new(config: &HashMap<String, CheckBudgetConfig>) -> Self {
+        let mut budgets: Vec<Budget> = config
+            .iter()
+            .map(|(name, budget)| Budget {
+                name: name.clone(),
+                tags: budget.tags.clone(),
+                whois: Limit::new(budget.whois_per_minute, budget.whois_concurrency),
+                ssl: Limit::new(budget.ssl_per_minute, budget.ssl_concurrency),
+            })
+            .collect();
+        budgets.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { budgets }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.budgets.is_empty()
+    }
+
+    /// Группы с ограничениями проверок вида `kind`, к которым относится хост
+    fn matching(&self, kind: CheckKind, tags: &[String]) -> Vec<(&str, &Limit)> {
+        self.budgets
+            .iter()
+            .filter(|budget| budget.applies(tags))
+            .filter_map(|budget| Some((budget.name.as_str(), budget.limit(kind)?)))
+            .collect()
+    }
+
+    /// Порядок проверок: хосты разных групп чередуются, чтобы группа с жёстким
+    /// бюджетом не заняла собой всю очередь запуска. Внутри группы порядок
+    /// сохраняется
+    pub fn schedule<H>(
+        &self,
+        kind: CheckKind,
+        hosts: Vec<(H, Vec<String>)>,
+    ) -> Vec<(H, Vec<String>)> {
+        if self.is_empty() {
+            return hosts;
+        }
+        let mut groups: BTreeMap<Vec<&str>, VecDeque<(H, Vec<String>)>> = BTreeMap::new();
+        for (host, tags) in hosts {
+            let key =
+                self.matching(kind, &tags).into_iter().map(|(name, _)| name).collect();
+            groups.entry(key).or_default().push_back((host, tags));
+        }
+        let mut scheduled = Vec::new();
+        while !groups.is_empty() {
+            groups.retain(|_, queue| {
+                scheduled.extend(queue.pop_front());
+                !queue.is_empty()
+            });
+        }
+        scheduled
+    }
+
+    /// Ждёт места и очереди во всех группах хоста для проверки вида `kind`
+    pub async fn acquire(&self, kind: CheckKind, tags: &[String]) -> BudgetPermit {
+        let matching = self.matching(kind, tags);
+        let mut permits = Vec::new();
+        for (_, limit) in &matching {
+            if let Some(semaphore) = &limit.concurrency
+                && let Ok(permit) = semaphore.clone().acquire_owned().await
+            {
+                permits.push(permit);
+            }
+        }
+        let start = matching.iter().filter_map(|(_, limit)| limit.reserve()).max();
+        if let Some(start) = start.filter(|start| *start > Instant::now()) {
+            tracing::trace!(
+                budgets = ?matching.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+                wait_ms = (start - Instant::now()).as_millis() as u64,
+                "Проверка ждёт бюджета группы"
+            );
+            sleep_until(start).await;
+        }
+        BudgetPermit { _permits: permits }
+    }
+}
@@ -0,0 +1,62 @@
+//! Календарь рабочих дней: окно оповещения можно отсчитывать в рабочих днях,
+//! чтобы срок, выпадающий на понедельник, не обнаруживался в выходные
+
+use crate::config::BusinessDaysConfig;
+use base::prelude::chrono::{DateTime, Datelike, NaiveDate, TimeZone, Weekday};
+use std::collections::HashSet;
+
+/// Выходные дни недели и праздники
+#[derive(Debug, Clone)]
+pub struct BusinessCalendar {
+    weekend: HashSet<Weekday>,
+    holidays: HashSet<NaiveDate>,
+}
+
+impl Default for BusinessCalendar {
+    fn default() -> Self {
+        Self::new([Weekday::Sat, Weekday::Sun], [])
+    }
+}
+
+impl From<&BusinessDaysConfig> for BusinessCalendar {
+    fn from(config: &BusinessDaysConfig) -> Self {
+        Self::new(config.weekend.iter().copied(), config.holidays.iter().copied())
+    }
+}
+
+impl BusinessCalendar {
+    pub fn new(
+        weekend: impl IntoIterator<Item = Weekday>,
+        holidays: impl IntoIterator<Item = NaiveDate>,
+    ) -> Self {
+        Self {
+            weekend: weekend.into_iter().collect(),
+            holidays: holidays.into_iter().collect(),
+        }
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !self.weekend.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// Число рабочих дней после дня `from` до дня `to` включительно; даты берутся
+    /// в часовом поясе аргументов. Для срока, выпадающего на понедельник, в среду
+    /// это 3 (четверг, пятница, понедельник). Если `to` не позже `from`, возвращается
+    /// разница в календарных днях
+    pub fn business_days_until<Z: TimeZone>(
+        &self,
+        from: &DateTime<Z>,
+        to: &DateTime<Z>,
+    ) -> i64 {
+        let (from_date, to_date) = (from.date_naive(), to.date_naive());
+        if to_date <= from_date {
+            return to_date.signed_duration_since(from_date).num_days();
+        }
+        from_date
+            .iter_days()
+            .skip(1)
+            .take_while(|date| *date <= to_date)
+            .filter(|date| self.is_business_day(*date))
+            .count() as i64
+    }
+}
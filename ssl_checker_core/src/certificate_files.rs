@@ -0,0 +1,114 @@
+//! Сертификаты из локальных файлов: подпись кода, S/MIME, клиентские и серверные
+//! сертификаты, которые не проверить подключением к хосту. Файлы PEM и DER
+//! читаются как есть, PKCS#12 (`.p12`, `.pfx`) — с паролем из конфигурации
+
+use crate::checks;
+use crate::config::CertificateFileConfig;
+use crate::report::{CertificateUsage, CheckKind, ReportEntry};
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    chrono::Utc,
+};
+use openssl::pkcs12::Pkcs12;
+use std::path::Path;
+use x509_parser::pem::Pem;
+
+/// Записи отчёта по сертификатам файла: по одной на сертификат, включая цепочку
+/// из PKCS#12, с назначением из Extended Key Usage и тегом `ca` у сертификатов
+/// УЦ. Имя в записи — субъект и путь файла. Файл, который не удалось прочитать,
+/// даёт одну запись с ошибкой
+pub fn scan_file(config: &CertificateFileConfig, alarm_days: i64) -> Vec<ReportEntry> {
+    let entries = read_certificates(Path::new(&config.path), &config.password).and_then(
+        |certificates| {
+            certificates
+                .iter()
+                .map(|der| certificate_entry(der, config, alarm_days))
+                .collect::<Result<Vec<_>>>()
+        },
+    );
+    entries.unwrap_or_else(|e| {
+        let error = format!("{:#}", e);
+        let entry = ReportEntry::failed(CheckKind::File, &config.path, &error, false);
+        vec![entry.with_tags(config.tags.clone())]
+    })
+}
+
+/// Сертификаты файла в DER; формат определяется по расширению и содержимому
+fn read_certificates(path: &Path, password: &str) -> Result<Vec<Vec<u8>>> {
+    let data = std::fs::read(path)
+        .with_context(|| format!("Не удалось прочитать файл: {}", path.display()))?;
+    let pkcs12 = path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| {
+        ext.eq_ignore_ascii_case("p12") || ext.eq_ignore_ascii_case("pfx")
+    });
+    if pkcs12 {
+        let parsed = Pkcs12::from_der(&data)
+            .and_then(|pkcs12| pkcs12.parse2(password))
+            .map_err(|e| anyhow!("PKCS#12: {}", e))?;
+        let certificates = parsed.cert.into_iter().chain(parsed.ca.into_iter().flatten());
+        return Ok(certificates.map(|c| c.to_der()).collect::<Result<_, _>>()?);
+    }
+
+    let mut certificates = Vec::new();
+    for pem in Pem::iter_from_buffer(&data) {
+        let pem = pem.map_err(|e| anyhow!("PEM: {}", e))?;
+        // Закрытые ключи и другие блоки пропускаются
+        if pem.label == "CERTIFICATE" {
+            certificates.push(pem.contents);
+        }
+    }
+    if certificates.is_empty() && !data.starts_with(b"-----") {
+        certificates.push(data);
+    }
+    if certificates.is_empty() {
+        return Err(anyhow!("В файле {} нет сертификатов", path.display()));
+    }
+    Ok(certificates)
+}
+
+fn certificate_entry(
+    der: &[u8],
+    config: &CertificateFileConfig,
+    alarm_days: i64,
+) -> Result<ReportEntry> {
+    let (_, x509) =
+        x509_parser::parse_x509_certificate(der).map_err(|e| anyhow!("X.509: {}", e))?;
+    let certificate = checks::parse_certificate(der)?;
+    let name = format!("{} ({})", checks::subject_name(&x509), config.path);
+    let days = certificate.expiration_date.signed_duration_since(Utc::now()).num_days();
+    let entry = ReportEntry {
+        issuer: Some(certificate.issuer),
+        serial: Some(certificate.serial),
+        usage: usage(&x509),
+        ..ReportEntry::ok(
+            CheckKind::File,
+            &name,
+            certificate.expiration_date,
+            days,
+            days <= alarm_days,
+        )
+    };
+    let mut tags = config.tags.clone();
+    if x509.basic_constraints().ok().flatten().is_some_and(|bc| bc.value.ca) {
+        tags.push("ca".to_string());
+    }
+    Ok(entry.with_tags(tags))
+}
+
+/// Назначения из Extended Key Usage; без расширения сертификат ничем
+/// не ограничен, и список пуст
+fn usage(x509: &x509_parser::certificate::X509Certificate) -> Vec<CertificateUsage> {
+    let Ok(Some(eku)) = x509.extended_key_usage() else {
+        return Vec::new();
+    };
+    let eku = eku.value;
+    [
+        (eku.server_auth, CertificateUsage::ServerAuth),
+        (eku.client_auth, CertificateUsage::ClientAuth),
+        (eku.code_signing, CertificateUsage::CodeSigning),
+        (eku.email_protection, CertificateUsage::Smime),
+    ]
+    .into_iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, usage)| usage)
+    .collect()
+}
@@ -0,0 +1,498 @@
+//! Проверки одного хоста без сервиса, источников и уведомлений: срок действия
+//! сертификата и срок регистрации домена по WHOIS.
+//!
+//! ```no_run
+//! use ssl_checker_core::checks::{self, SslCheckOptions, WhoisCheckOptions};
+//!
+//! # async fn example() -> base::prelude::anyhow::Result<()> {
+//! let options = SslCheckOptions { port: 8443, ..Default::default() };
+//! let certificate = checks::check_ssl_expiry("example.com", &options).await?;
+//! println!("{} истекает {}", certificate.serial, certificate.expiration_date);
+//!
+//! let whois = WhoisCheckOptions::default();
+//! let registration = checks::check_domain_expiration("example.com", &whois).await?;
+//! println!("Домен оплачен до {}", registration.expiration_date);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::http::HttpSettings;
+use crate::whois::{WhoisParseRule, parse_whois_expiry};
+use addr::parse_domain_name;
+use base::prelude::{
+    anyhow::{Result, anyhow},
+    chrono::{DateTime, Utc},
+    once_cell::sync::{Lazy, OnceCell},
+    serde_json::{self, Value},
+    tokio::{self, net::TcpStream},
+    tracing,
+};
+use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use whois_rust::{WhoIs, WhoIsLookupOptions, WhoIsServerValue};
+use x509_parser::extensions::{DistributionPointName, GeneralName, ParsedExtension};
+use x509_parser::oid_registry::OID_PKIX_ACCESS_DESCRIPTOR_OCSP;
+
+const SERVERS_JSON: &str = include_str!("../../servers.json");
+
+static WHOIS_CLIENT: Lazy<WhoIs> = Lazy::new(|| {
+    WhoIs::from_string(SERVERS_JSON)
+        .expect("Не удалось загрузить servers.json из include_str!")
+});
+
+/// Серверы из `servers.json` по зонам, для сообщений о недоступности сервера
+static WHOIS_SERVERS: Lazy<HashMap<String, Value>> = Lazy::new(|| {
+    serde_json::from_str(SERVERS_JSON)
+        .expect("Не удалось загрузить servers.json из include_str!")
+});
+
+/// Коннектор по умолчанию создаётся один раз: загрузка корневых сертификатов
+/// при каждой проверке занимает процессор на время, сравнимое с самим рукопожатием
+static DEFAULT_CONNECTOR: OnceCell<native_tls::TlsConnector> = OnceCell::new();
+
+/// Параметры проверки сертификата
+#[derive(Debug, Clone)]
+pub struct SslCheckOptions {
+    pub port: u16,
+    /// Имя сервера (SNI); по умолчанию — проверяемый хост
+    pub sni: Option<String>,
+    /// Таймаут установки TCP-соединения
+    pub connect_timeout: Duration,
+    /// Таймаут TLS-рукопожатия
+    pub handshake_timeout: Duration,
+    /// TLS-коннектор; по умолчанию принимает любой сертификат, так как
+    /// проверяется только срок действия
+    pub connector: Option<native_tls::TlsConnector>,
+    /// Протоколы, предлагаемые через ALPN (`h2`, `http/1.1`); без них часть
+    /// gRPC-серверов обрывает рукопожатие. Не применяется к заданному `connector`
+    pub alpn: Vec<String>,
+}
+
+impl SslCheckOptions {
+    pub const DEFAULT_PORT: u16 = 443;
+}
+
+impl Default for SslCheckOptions {
+    fn default() -> Self {
+        Self {
+            port: Self::DEFAULT_PORT,
+            sni: None,
+            connect_timeout: Duration::from_secs(5),
+            handshake_timeout: Duration::from_secs(10),
+            connector: None,
+            alpn: Vec::new(),
+        }
+    }
+}
+
+/// Сведения о сертификате, полученные при TLS-рукопожатии
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    pub expiration_date: DateTime<Utc>,
+    /// Серийный номер в шестнадцатеричном виде
+    pub serial: String,
+    /// Организация издателя, `Unknown`, если она не указана
+    pub issuer: String,
+    /// Протокол, согласованный через ALPN, если он запрашивался
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpn: Option<String>,
+    /// Адреса OCSP-респондеров и списков отзыва (CRL) издателя из сертификата
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revocation_urls: Vec<String>,
+}
+
+/// Параметры проверки срока регистрации домена
+#[derive(Debug, Clone)]
+pub struct WhoisCheckOptions {
+    /// Таймаут запроса к WHOIS-серверу
+    pub timeout: Duration,
+    /// Сколько раз переходить к WHOIS-серверу регистратора
+    pub follow: u16,
+    /// WHOIS-сервер `host[:port]` вместо выбранного по зоне домена
+    pub server: Option<String>,
+    /// Сколько раз повторять запрос, если сервер ограничил частоту запросов
+    pub rate_limit_retries: u32,
+    /// Пауза перед первым повтором; каждая следующая вдвое длиннее
+    pub rate_limit_backoff: Duration,
+    /// Правила разбора срока регистрации, проверяемые раньше встроенных
+    pub rules: Vec<WhoisParseRule>,
+}
+
+impl Default for WhoisCheckOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(60),
+            follow: 2,
+            server: None,
+            rate_limit_retries: 2,
+            rate_limit_backoff: Duration::from_secs(30),
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Ответ WHOIS без срока регистрации, который не означает ошибку разбора
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhoisResponseError {
+    /// Сервер ограничил частоту запросов («Query limit exceeded»)
+    RateLimited,
+    /// Данные регистрации скрыты (privacy/GDPR), срока в ответе нет
+    Redacted,
+    /// Реестр зоны не публикует срок регистрации (например, `.de`)
+    NotPublished,
+}
+
+impl WhoisResponseError {
+    const RATE_LIMIT_PATTERNS: &[&str] = &[
+        "limit exceeded",
+        "rate limit",
+        "too many requests",
+        "too many queries",
+        "exceeded the maximum",
+        "quota exceeded",
+        "try again later",
+    ];
+    const REDACTED_PATTERNS: &[&str] =
+        &["redacted", "gdpr", "not disclosed", "data protected", "withheld for privacy"];
+
+    /// Вид ответа, в котором не нашёлся срок регистрации
+    pub(crate) fn classify(whois_text: &str) -> Option<Self> {
+        let text = whois_text.to_lowercase();
+        if Self::RATE_LIMIT_PATTERNS.iter().any(|pattern| text.contains(pattern)) {
+            Some(Self::RateLimited)
+        } else if Self::REDACTED_PATTERNS.iter().any(|pattern| text.contains(pattern)) {
+            Some(Self::Redacted)
+        } else {
+            None
+        }
+    }
+
+    /// Относится ли ошибка проверки к этому виду ответа
+    pub fn matches(error: &base::prelude::anyhow::Error, kind: Self) -> bool {
+        error.downcast_ref::<Self>() == Some(&kind)
+    }
+
+    /// Срок регистрации недоступен по причинам на стороне реестра: данные скрыты
+    /// или не публикуются. Такой ответ — не сбой проверки
+    pub fn hides_expiry(error: &base::prelude::anyhow::Error) -> bool {
+        matches!(error.downcast_ref::<Self>(), Some(Self::Redacted | Self::NotPublished))
+    }
+}
+
+impl std::fmt::Display for WhoisResponseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RateLimited => write!(f, "WHOIS-сервер ограничил частоту запросов"),
+            Self::Redacted => {
+                write!(f, "WHOIS-сервер скрывает данные регистрации (privacy/GDPR)")
+            }
+            Self::NotPublished => {
+                write!(f, "реестр зоны не публикует срок регистрации в WHOIS")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WhoisResponseError {}
+
+/// Срок регистрации домена
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainRegistration {
+    pub expiration_date: DateTime<Utc>,
+}
+
+/// TCP-соединение с хостом и имя сервера для SNI
+pub(crate) async fn connect(
+    hostname: &str,
+    options: &SslCheckOptions,
+) -> Result<(TcpStream, String)> {
+    let hostname_idn = idna::domain_to_ascii(hostname)
+        .map_err(|e| anyhow!("IDN conversion failed: {}", e))?;
+    let server_name = match &options.sni {
+        Some(sni) => idna::domain_to_ascii(sni)
+            .map_err(|e| anyhow!("IDN conversion failed: {}", e))?,
+        None => hostname_idn.clone(),
+    };
+
+    let stream = tokio::time::timeout(
+        options.connect_timeout,
+        TcpStream::connect(format!("{}:{}", hostname_idn, options.port)),
+    )
+    .await
+    .map_err(|_| anyhow!("Connection timed out"))??;
+    Ok((stream, server_name))
+}
+
+/// Подключается к хосту и возвращает сведения о его сертификате
+pub async fn check_ssl_expiry(
+    hostname: &str,
+    options: &SslCheckOptions,
+) -> Result<CertificateInfo> {
+    let (stream, server_name) = connect(hostname, options).await?;
+    tls_certificate(stream, &server_name, options).await
+}
+
+/// TLS-рукопожатие поверх установленного соединения и сертификат сервера.
+/// Используется и после STARTTLS, когда соединение уже прошло диалог протокола
+pub(crate) async fn tls_certificate<S>(
+    stream: S,
+    server_name: &str,
+    options: &SslCheckOptions,
+) -> Result<CertificateInfo>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let connector = match &options.connector {
+        Some(connector) => connector.clone(),
+        // Общий коннектор не предлагает протоколов, с ALPN он собирается для хоста
+        None if !options.alpn.is_empty() => {
+            let protocols: Vec<&str> = options.alpn.iter().map(String::as_str).collect();
+            native_tls::TlsConnector::builder()
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true)
+                .request_alpns(&protocols)
+                .build()?
+        }
+        None => DEFAULT_CONNECTOR
+            .get_or_try_init(|| {
+                native_tls::TlsConnector::builder()
+                    .danger_accept_invalid_certs(true)
+                    .danger_accept_invalid_hostnames(true)
+                    .build()
+            })?
+            .clone(),
+    };
+
+    let connector = tokio_native_tls::TlsConnector::from(connector);
+    let tls_stream = tokio::time::timeout(
+        options.handshake_timeout,
+        connector.connect(server_name, stream),
+    )
+    .await
+    .map_err(|_| anyhow!("TLS handshake timed out"))??;
+
+    let cert = tls_stream
+        .get_ref()
+        .peer_certificate()?
+        .ok_or_else(|| anyhow!("No certificate found"))?;
+    let alpn = tls_stream
+        .get_ref()
+        .negotiated_alpn()?
+        .map(|protocol| String::from_utf8_lossy(&protocol).into_owned());
+
+    Ok(CertificateInfo { alpn, ..parse_certificate(&cert.to_der()?)? })
+}
+
+/// Цепочка сертификатов, которую отдаёт хост, в PEM, начиная с сертификата
+/// сервера. Выполняет отдельное рукопожатие через OpenSSL: native-tls отдаёт
+/// только сертификат сервера. Цепочка не проверяется
+pub async fn fetch_certificate_chain(
+    hostname: &str,
+    options: &SslCheckOptions,
+) -> Result<String> {
+    let (stream, server_name) = connect(hostname, options).await?;
+    let stream = stream.into_std()?;
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(options.handshake_timeout))?;
+    stream.set_write_timeout(Some(options.handshake_timeout))?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut builder = SslConnector::builder(SslMethod::tls_client())?;
+        builder.set_verify(SslVerifyMode::NONE);
+        let mut config = builder.build().configure()?;
+        config.set_verify_hostname(false);
+        let tls_stream = config
+            .connect(&server_name, stream)
+            .map_err(|e| anyhow!("TLS handshake failed: {}", e))?;
+        let chain = tls_stream
+            .ssl()
+            .peer_cert_chain()
+            .ok_or_else(|| anyhow!("No certificate found"))?;
+        let mut pem = Vec::new();
+        for certificate in chain {
+            pem.extend(certificate.to_pem()?);
+        }
+        Ok(String::from_utf8(pem)?)
+    })
+    .await?
+}
+
+/// Корневой домен хоста, для которого проверяется регистрация. IDN приводится
+/// к записи в Unicode, чтобы `пример.рф` и `xn--e1afmkfd.xn--p1ai` были одним доменом
+pub fn root_domain(domain: &str) -> Option<String> {
+    let domain = domain.trim().trim_end_matches('.');
+    let (unicode, result) = idna::domain_to_unicode(domain);
+    let mut d = match result {
+        Ok(()) => unicode,
+        Err(_) => domain.to_lowercase(),
+    };
+
+    if d.starts_with("*.") {
+        d = d[2..].to_string();
+    }
+
+    // Регистрируемый домен по списку публичных суффиксов, а не два последних
+    // уровня: `example.co.uk`, `example.com.br`, `shop.msk.ru`
+    let parsed = parse_domain_name(&d).ok()?;
+    parsed.root().map(str::to_string)
+}
+
+/// Организации издателей, выпускающих сертификаты по ACME с автоматическим продлением
+pub const ACME_ISSUERS: &[&str] = &["Let's Encrypt", "ZeroSSL"];
+
+/// Сертификат выпущен ACME-издателем и должен продлеваться автоматически
+pub fn is_acme_issuer(issuer: &str) -> bool {
+    let issuer = issuer.to_lowercase();
+    ACME_ISSUERS.iter().any(|acme| issuer.contains(&acme.to_lowercase()))
+}
+
+/// Разбирает DER-сертификат
+pub fn parse_certificate(der: &[u8]) -> Result<CertificateInfo> {
+    let (_, cert_parsed) = x509_parser::parse_x509_certificate(der)
+        .map_err(|e| anyhow!("Certificate parse error: {}", e))?;
+
+    let expiry = cert_parsed.validity().not_after;
+    let expiration_date = DateTime::from_timestamp(expiry.timestamp(), 0)
+        .ok_or_else(|| anyhow!("Invalid timestamp"))?;
+
+    let serial = format!("{:X}", cert_parsed.serial);
+
+    let issuer = cert_parsed
+        .issuer()
+        .iter_organization()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .unwrap_or("Unknown")
+        .to_string();
+
+    let revocation_urls = revocation_urls(&cert_parsed);
+
+    Ok(CertificateInfo { expiration_date, serial, issuer, alpn: None, revocation_urls })
+}
+
+/// Имя субъекта сертификата для отчёта: CN, затем OU, иначе полное имя
+pub(crate) fn subject_name(
+    certificate: &x509_parser::certificate::X509Certificate,
+) -> String {
+    certificate
+        .subject()
+        .iter_common_name()
+        .next()
+        .or_else(|| certificate.subject().iter_organizational_unit().next())
+        .and_then(|name| name.as_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| certificate.subject().to_string())
+}
+
+/// HTTP-адреса OCSP из Authority Information Access и CRL из CRL Distribution Points
+fn revocation_urls(
+    certificate: &x509_parser::certificate::X509Certificate,
+) -> Vec<String> {
+    let mut urls = Vec::new();
+    for extension in certificate.extensions() {
+        let names: Vec<&GeneralName> = match extension.parsed_extension() {
+            ParsedExtension::AuthorityInfoAccess(aia) => aia
+                .iter()
+                .filter(|access| access.access_method == OID_PKIX_ACCESS_DESCRIPTOR_OCSP)
+                .map(|access| &access.access_location)
+                .collect(),
+            ParsedExtension::CRLDistributionPoints(points) => points
+                .iter()
+                .filter_map(|point| match &point.distribution_point {
+                    Some(DistributionPointName::FullName(names)) => Some(names),
+                    _ => None,
+                })
+                .flatten()
+                .collect(),
+            _ => continue,
+        };
+        for name in names {
+            if let GeneralName::URI(uri) = name
+                && uri.starts_with("http")
+                && !urls.iter().any(|url| url == uri)
+            {
+                urls.push(uri.to_string());
+            }
+        }
+    }
+    urls
+}
+
+/// Доступность OCSP-респондера или списка отзыва: любой ответ, кроме 5xx.
+/// Тело ответа не читается — большие списки отзыва не скачиваются целиком
+pub async fn check_revocation_endpoint(url: &str, http: &HttpSettings) -> Result<()> {
+    let client = http.client();
+    let response = http.send(|| client.get(url)).await?;
+    if response.status().is_server_error() {
+        return Err(anyhow!("HTTP {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Запрашивает WHOIS домена и возвращает срок его регистрации. Если сервер
+/// ограничил частоту запросов, запрос повторяется с растущей паузой
+/// до `rate_limit_retries` раз
+pub async fn check_domain_expiration(
+    domain: &str,
+    options: &WhoisCheckOptions,
+) -> Result<DomainRegistration> {
+    // Запрос к WHOIS и выбор сервера по зоне — в punycode: `пример.рф` ищется
+    // как `xn--e1afmkfd.xn--p1ai` на сервере зоны `xn--p1ai`
+    let ascii_domain = idna::domain_to_ascii(domain)
+        .map_err(|e| anyhow!("IDN conversion failed: {}", e))?;
+    let mut backoff = options.rate_limit_backoff;
+    let mut attempt = 0;
+    loop {
+        let mut lookup = WhoIsLookupOptions::from_string(&ascii_domain)?;
+        lookup.timeout = Some(options.timeout);
+        lookup.follow = options.follow;
+        if let Some(server) = &options.server {
+            lookup.server = Some(WhoIsServerValue::from_string(server)?);
+        }
+        let lookup_result = WHOIS_CLIENT.lookup_async(lookup).await?;
+
+        match parse_whois_expiry(domain, &lookup_result, &options.rules) {
+            Err(e)
+                if attempt < options.rate_limit_retries
+                    && WhoisResponseError::matches(
+                        &e,
+                        WhoisResponseError::RateLimited,
+                    ) =>
+            {
+                tracing::debug!(
+                    domain = domain,
+                    attempt,
+                    backoff_secs = backoff.as_secs_f64(),
+                    "WHOIS-сервер ограничил частоту запросов, повтор"
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return Ok(DomainRegistration { expiration_date: result? }),
+        }
+    }
+}
+
+/// WHOIS-сервер, к которому обращается проверка домена: заданный в параметрах
+/// или выбранный по самой длинной подходящей зоне из `servers.json`. `None`,
+/// если зона в списке не найдена
+pub fn whois_server(domain: &str, options: &WhoisCheckOptions) -> Option<String> {
+    if let Some(server) = &options.server {
+        return Some(server.clone());
+    }
+    let domain = idna::domain_to_ascii(domain).ok()?;
+    let mut zone = domain.as_str();
+    loop {
+        if let Some(server) = WHOIS_SERVERS.get(zone) {
+            let host = match server {
+                Value::Object(server) => server.get("host")?,
+                server => server,
+            };
+            return host.as_str().map(str::to_string);
+        }
+        zone = zone.split_once('.')?.1;
+    }
+}
@@ -0,0 +1,222 @@
+//! Отчёт о соответствии для аудита: доля сертификатов, продлённых заранее,
+//! истёкшие сертификаты и среднее число дней до истечения на момент продления.
+//! События продления и истечения выводятся сравнением отчётов соседних запусков
+//! и накапливаются в истории
+
+use crate::report::{CheckKind, CheckReport};
+use base::prelude::{
+    anyhow::{Error, anyhow},
+    chrono::{DateTime, Datelike, Duration, Months, NaiveDate, TimeZone, Utc},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Что произошло с сертификатом
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComplianceEventKind {
+    /// Хост стал отдавать сертификат с более поздним сроком
+    Renewed,
+    /// Срок сертификата истёк, а он всё ещё используется
+    Expired,
+}
+
+impl ComplianceEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComplianceEventKind::Renewed => "renewed",
+            ComplianceEventKind::Expired => "expired",
+        }
+    }
+}
+
+/// Продление или истечение сертификата, замеченное при запуске проверки
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplianceEvent {
+    pub kind: ComplianceEventKind,
+    pub check: CheckKind,
+    pub hostname: String,
+    /// Время запуска, в котором событие замечено
+    pub at: DateTime<Utc>,
+    /// Срок прежнего сертификата при продлении или истёкшего сертификата
+    pub expiration_date: DateTime<Utc>,
+    /// Дней до истечения прежнего сертификата на момент продления; отрицательное
+    /// значение — продлён уже после истечения
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days_before_expiry: Option<i64>,
+}
+
+impl ComplianceEvent {
+    /// Сколько дней событий хранится в истории: хватает на годовой аудит
+    pub const RETENTION_DAYS: i64 = 400;
+
+    /// События между отчётами соседних запусков. Продлением считается более
+    /// поздний срок сертификата того же хоста; продление замечается только при
+    /// следующем запуске, поэтому дни до истечения считаются от его времени.
+    /// Истёкший сертификат отмечается один раз, пока его не заменят
+    pub fn between(previous: &CheckReport, current: &CheckReport) -> Vec<Self> {
+        let previous_dates: HashMap<(CheckKind, &str), DateTime<Utc>> = previous
+            .entries
+            .iter()
+            .filter(|entry| entry.check.is_renewable_certificate())
+            .filter_map(|entry| {
+                Some(((entry.check, entry.hostname.as_str()), entry.expiration_date?))
+            })
+            .collect();
+        let at = current.checked_at;
+        let checked_before = previous.checked_at;
+        let mut events = Vec::new();
+        for entry in &current.entries {
+            if !entry.check.is_renewable_certificate() {
+                continue;
+            }
+            let Some(expiration_date) = entry.expiration_date else {
+                continue;
+            };
+            let event = |kind, expiration_date: DateTime<Utc>, days| ComplianceEvent {
+                kind,
+                check: entry.check,
+                hostname: entry.hostname.clone(),
+                at,
+                expiration_date,
+                days_before_expiry: days,
+            };
+            let previous = previous_dates.get(&(entry.check, entry.hostname.as_str()));
+            // Расхождение меньше суток — тот же сертификат с другим округлением
+            if let Some(previous) = previous
+                && expiration_date - *previous > Duration::days(1)
+            {
+                let days = (*previous - at).num_days();
+                events.push(event(ComplianceEventKind::Renewed, *previous, Some(days)));
+            }
+            let already_expired = previous.is_some_and(|previous_date| {
+                *previous_date == expiration_date && expiration_date <= checked_before
+            });
+            if expiration_date <= at && !already_expired {
+                events.push(event(ComplianceEventKind::Expired, expiration_date, None));
+            }
+        }
+        events
+    }
+}
+
+/// Период отчёта о соответствии
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompliancePeriod {
+    /// Календарный месяц
+    #[default]
+    Month,
+    /// Квартал
+    Quarter,
+}
+
+impl CompliancePeriod {
+    fn months(&self) -> u32 {
+        match self {
+            CompliancePeriod::Month => 1,
+            CompliancePeriod::Quarter => 3,
+        }
+    }
+
+    /// Последний завершившийся к `now` период: начало и конец, не включая
+    pub fn previous(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let month0 = now.month0() / self.months() * self.months();
+        let start =
+            NaiveDate::from_ymd_opt(now.year(), month0 + 1, 1).unwrap_or_default();
+        let until = Utc.from_utc_datetime(&start.and_time(Default::default()));
+        (until - Months::new(self.months()), until)
+    }
+
+    /// Обозначение периода, начинающегося в `from`: `2025-03` или `2025-Q1`
+    pub fn label(&self, from: DateTime<Utc>) -> String {
+        match self {
+            CompliancePeriod::Month => from.format("%Y-%m").to_string(),
+            CompliancePeriod::Quarter => {
+                format!("{}-Q{}", from.year(), from.month0() / 3 + 1)
+            }
+        }
+    }
+}
+
+impl FromStr for CompliancePeriod {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "month" => Ok(CompliancePeriod::Month),
+            "quarter" => Ok(CompliancePeriod::Quarter),
+            _ => Err(anyhow!("ожидается month или quarter, указано {:?}", value)),
+        }
+    }
+}
+
+/// Сводка соответствия за период
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    pub from: DateTime<Utc>,
+    /// Конец периода, не включая
+    pub until: DateTime<Utc>,
+    /// Продление считается своевременным не позже чем за столько дней до истечения
+    pub min_days: i64,
+    pub renewals: usize,
+    pub renewed_in_time: usize,
+    /// Доля своевременных продлений, %; без продлений в периоде не определена
+    pub renewed_in_time_percent: Option<f64>,
+    /// Среднее число дней до истечения на момент продления
+    pub mean_days_before_expiry: Option<f64>,
+    /// Истёкшие сертификаты
+    pub expired: Vec<ComplianceEvent>,
+    /// Продления позже `min_days`
+    pub late_renewals: Vec<ComplianceEvent>,
+    /// Все события периода по времени
+    pub events: Vec<ComplianceEvent>,
+}
+
+impl ComplianceReport {
+    /// Сводка по событиям с `from` до `until`
+    pub fn build(
+        events: &[ComplianceEvent],
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+        min_days: i64,
+    ) -> Self {
+        let mut events: Vec<ComplianceEvent> = events
+            .iter()
+            .filter(|event| event.at >= from && event.at < until)
+            .cloned()
+            .collect();
+        events.sort_by(|a, b| (a.at, &a.hostname).cmp(&(b.at, &b.hostname)));
+        let renewal_days: Vec<i64> = events
+            .iter()
+            .filter_map(|event| match event.kind {
+                ComplianceEventKind::Renewed => event.days_before_expiry,
+                ComplianceEventKind::Expired => None,
+            })
+            .collect();
+        let renewed_in_time =
+            renewal_days.iter().filter(|days| **days >= min_days).count();
+        let count = renewal_days.len() as f64;
+        let any = !renewal_days.is_empty();
+        let of_kind = |kind| {
+            events.iter().filter(|event| event.kind == kind).cloned().collect::<Vec<_>>()
+        };
+        Self {
+            from,
+            until,
+            min_days,
+            renewals: renewal_days.len(),
+            renewed_in_time,
+            renewed_in_time_percent: any.then(|| renewed_in_time as f64 * 100.0 / count),
+            mean_days_before_expiry: any
+                .then(|| renewal_days.iter().sum::<i64>() as f64 / count),
+            expired: of_kind(ComplianceEventKind::Expired),
+            late_renewals: of_kind(ComplianceEventKind::Renewed)
+                .into_iter()
+                .filter(|event| event.days_before_expiry.is_some_and(|d| d < min_days))
+                .collect(),
+            events,
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use crate::overrides::DomainOverrides;
+use crate::report::Severity;
+use base::prelude::chrono::{DateTime, NaiveDate, Utc, Weekday};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Общие настройки HTTP-клиентов источников и модулей уведомлений.
+/// Таймауты и повторы здесь задают значения по умолчанию, которые можно
+/// переопределить в секции конкретного источника или модуля уведомлений
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpConfig {
+    /// Заголовок User-Agent; по умолчанию `ssl-checker/<версия>`
+    pub user_agent: Option<String>,
+    /// Проверять сертификаты серверов API
+    #[serde(default = "HttpConfig::default_verify_tls")]
+    pub verify_tls: bool,
+    /// PEM-файл с дополнительными корневыми сертификатами
+    pub ca_bundle: Option<String>,
+    /// Прокси для всех запросов, например `http://proxy.local:3128`
+    pub proxy: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+    pub retries: Option<u32>,
+    pub retry_interval_secs: Option<u64>,
+}
+
+impl HttpConfig {
+    fn default_verify_tls() -> bool {
+        true
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: None,
+            verify_tls: true,
+            ca_bundle: None,
+            proxy: None,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+            retries: None,
+            retry_interval_secs: None,
+        }
+    }
+}
+
+/// Настройки проверки отдельных доменов (секция `domains`, ключ — шаблон имени)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DomainOverrideConfig {
+    /// Порт TLS вместо 443
+    pub port: Option<u16>,
+    /// Имя сервера (SNI), передаваемое при установке TLS-соединения
+    pub sni: Option<String>,
+    /// Протоколы ALPN, предлагаемые при рукопожатии, например `["h2"]` для gRPC
+    pub alpn: Option<Vec<String>>,
+    pub alarm_days: Option<i64>,
+    pub ssl_alarm_days: Option<i64>,
+    /// Теги, добавляемые к записям отчёта
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Команда-владелец, которой адресуются уведомления о хосте
+    pub owner: Option<String>,
+    /// Не проверять сертификат
+    pub skip_ssl: Option<bool>,
+    /// Не проверять срок регистрации через WHOIS
+    pub skip_whois: Option<bool>,
+    /// Проверять и сертификат, который хост отдаёт по QUIC (HTTP/3)
+    pub quic: Option<bool>,
+    /// Проверять сертификат хоста OpenSSH
+    pub ssh: Option<bool>,
+    /// Порт SSH вместо 22
+    pub ssh_port: Option<u16>,
+    /// Порог оповещения для сертификата SSH вместо `ssl_alarm_days`
+    pub ssh_alarm_days: Option<i64>,
+}
+
+/// Бюджет проверок группы хостов (секция `budgets`, ключ — имя группы)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CheckBudgetConfig {
+    /// Теги хостов из секции `domains`; без тегов бюджет действует на все хосты
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Запросов WHOIS в минуту
+    pub whois_per_minute: Option<u32>,
+    /// Одновременных запросов WHOIS
+    pub whois_concurrency: Option<usize>,
+    /// Проверок сертификатов в минуту
+    pub ssl_per_minute: Option<u32>,
+    /// Одновременных проверок сертификатов
+    pub ssl_concurrency: Option<usize>,
+}
+
+/// Сверка с сертификатами, продлёнными на этой машине (секция `renewal_check`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RenewalCheckConfig {
+    /// Каталог certbot, обычно `/etc/letsencrypt`
+    pub certbot_dir: Option<String>,
+    /// Каталог acme.sh, обычно `~/.acme.sh`
+    pub acme_sh_dir: Option<String>,
+}
+
+/// Файл с сертификатами (элемент списка `certificate_files`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CertificateFileConfig {
+    /// Путь к файлу PEM, DER или PKCS#12 (`.p12`, `.pfx`)
+    pub path: String,
+    /// Пароль PKCS#12
+    #[serde(default)]
+    pub password: String,
+    /// Файл, из которого при запуске читается `password`
+    pub password_file: Option<String>,
+    /// Теги, добавляемые к записям отчёта
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Учётные данные со сроком действия (элемент списка `credentials`): ключ API,
+/// токен или пароль сервисной учётной записи. Срок задаётся в конфигурации
+/// или читается из метаданных секрета в Vault
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CredentialConfig {
+    /// Имя в отчёте и уведомлениях
+    pub name: String,
+    /// Дата (`2025-12-31`) или время RFC 3339, когда учётные данные перестанут
+    /// действовать
+    pub expires_at: Option<String>,
+    /// Путь секрета KV версии 2 (`<mount>/<path>`), срок которого хранится
+    /// в `custom_metadata`
+    pub vault: Option<String>,
+    /// Ключ `custom_metadata` со сроком, по умолчанию `expires_at`
+    pub vault_field: Option<String>,
+    /// Порог оповещения, дней; по умолчанию общий `alarm_days`
+    pub alarm_days: Option<i64>,
+    /// Команда-владелец
+    pub owner: Option<String>,
+    /// Теги, добавляемые к записи отчёта
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Окно работ (элемент списка `maintenance_windows`): пока оно действует, находки
+/// по хостам окна записываются в состояние и отчёт, но не уходят в уведомления
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MaintenanceWindowConfig {
+    /// Начало в формате RFC 3339, например `2025-06-01T22:00:00+03:00`
+    pub from: DateTime<Utc>,
+    /// Окончание в формате RFC 3339
+    pub until: DateTime<Utc>,
+    /// Шаблоны хостов как в секции `domains`; пустой список — все хосты
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    /// Секции `notifiers`, которые молчат; пустой список — все модули
+    #[serde(default)]
+    pub notifiers: Vec<String>,
+    /// Причина для логов, например номер заявки на переезд
+    pub reason: Option<String>,
+}
+
+impl MaintenanceWindowConfig {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.from <= now && now < self.until
+    }
+
+    /// Относится ли окно к хосту; суффиксы ` (QUIC)` и ` (SSH)` записей
+    /// не учитываются
+    pub fn covers(&self, hostname: &str) -> bool {
+        let hostname = hostname
+            .trim_end_matches(" (QUIC)")
+            .trim_end_matches(" (SSH)")
+            .trim()
+            .to_lowercase();
+        self.hosts.is_empty()
+            || self.hosts.iter().any(|pattern| {
+                DomainOverrides::matches(&pattern.trim().to_lowercase(), &hostname)
+            })
+    }
+
+    /// Относится ли окно к модулю уведомлений из секции `name`
+    pub fn applies_to_notifier(&self, name: &str) -> bool {
+        self.notifiers.is_empty() || self.notifiers.iter().any(|n| n == name)
+    }
+}
+
+/// Окно оповещения в рабочих днях (секция `business_days`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BusinessDaysConfig {
+    /// Выходные дни недели: `sat`, `sunday` и т. п.
+    #[serde(default = "BusinessDaysConfig::default_weekend")]
+    pub weekend: Vec<Weekday>,
+    /// Праздничные дни в формате `2025-01-01`
+    #[serde(default)]
+    pub holidays: Vec<NaiveDate>,
+}
+
+impl BusinessDaysConfig {
+    fn default_weekend() -> Vec<Weekday> {
+        vec![Weekday::Sat, Weekday::Sun]
+    }
+}
+
+/// Порог оповещения о сертификате: за `days` дней до истечения и ближе запись
+/// получает важность `severity` (элемент списка `ssl_thresholds`)
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeverityThreshold {
+    pub days: i64,
+    pub severity: Severity,
+}
+
+/// Подписи модуля уведомлений (секция `labels`): значки важности и заголовки
+/// разделов вместо встроенных. Не заданные значения остаются встроенными
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotifierLabels {
+    /// Значок или метка для важности: `critical: "[!]"`
+    #[serde(default)]
+    pub icons: BTreeMap<Severity, String>,
+    #[serde(default)]
+    pub headers: SectionHeaders,
+}
+
+impl NotifierLabels {
+    pub fn icon(&self, severity: Severity) -> Option<&str> {
+        self.icons.get(&severity).map(String::as_str)
+    }
+}
+
+/// Заголовки разделов сообщения
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SectionHeaders {
+    /// Отличия от предыдущего запуска
+    pub changes: Option<String>,
+    /// Истекающие сертификаты
+    pub ssl: Option<String>,
+    /// Истекающие домены
+    pub domains: Option<String>,
+    /// Истекающие сертификаты почтовых серверов
+    pub mail: Option<String>,
+    /// Истекающие учётные данные
+    pub credentials: Option<String>,
+    /// Ошибки
+    pub errors: Option<String>,
+}
+
+impl SectionHeaders {
+    /// Все заданные заголовки с именами полей, для проверки конфигурации
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("changes", &self.changes),
+            ("ssl", &self.ssl),
+            ("domains", &self.domains),
+            ("mail", &self.mail),
+            ("credentials", &self.credentials),
+            ("errors", &self.errors),
+        ]
+        .into_iter()
+        .filter_map(|(name, header)| Some((name, header.as_deref()?)))
+    }
+}
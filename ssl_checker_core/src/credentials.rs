@@ -0,0 +1,47 @@
+//! Сроки действия учётных данных: ключей API, токенов и паролей сервисных
+//! учётных записей. Срок задаётся в конфигурации или хранится в метаданных
+//! секрета Vault, чтобы его обновляли вместе с самим секретом
+
+use crate::config::CredentialConfig;
+use crate::vault::VaultClient;
+use base::prelude::{
+    anyhow::{Result, anyhow},
+    chrono::{DateTime, NaiveDate, Utc},
+};
+
+/// Ключ `custom_metadata` секрета Vault со сроком по умолчанию
+pub const DEFAULT_VAULT_FIELD: &str = "expires_at";
+
+/// Срок действия учётных данных: `expires_at` из конфигурации или поле
+/// `custom_metadata` секрета KV версии 2
+pub async fn credential_expiry(
+    config: &CredentialConfig,
+    vault: Option<&VaultClient>,
+) -> Result<DateTime<Utc>> {
+    if let Some(expires_at) = &config.expires_at {
+        return parse_expiry(expires_at);
+    }
+    let path = config
+        .vault
+        .as_deref()
+        .ok_or_else(|| anyhow!("Credential has neither expires_at nor vault path"))?;
+    let vault = vault.ok_or_else(|| anyhow!("Vault is not configured"))?;
+    let field = config.vault_field.as_deref().unwrap_or(DEFAULT_VAULT_FIELD);
+    let metadata = vault.kv_metadata(path).await?;
+    let value = metadata["custom_metadata"][field].as_str().ok_or_else(|| {
+        anyhow!("Vault secret {} has no custom_metadata.{}", path, field)
+    })?;
+    parse_expiry(value)
+}
+
+/// Срок из строки: время RFC 3339 или дата, которая считается истекающей
+/// в начале суток по UTC
+pub fn parse_expiry(value: &str) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_time(Default::default()).and_utc())
+        .map_err(|_| anyhow!("Expected a date (2025-12-31) or RFC 3339 time: {}", value))
+}
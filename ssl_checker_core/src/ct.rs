@@ -0,0 +1,192 @@
+//! Мониторинг журналов Certificate Transparency: сертификаты, выпущенные на
+//! отслеживаемые домены и их поддомены, по данным crt.sh. Уведомление приходит
+//! о каждом новом выпуске, а не только об истекающих сертификатах
+
+use crate::http::HttpSettings;
+use crate::metrics;
+use base::prelude::{
+    anyhow::{Result, anyhow},
+    metrics::counter,
+    tracing,
+};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Запись ответа crt.sh с `output=json`
+#[derive(Debug, Deserialize)]
+struct CrtShEntry {
+    id: u64,
+    #[serde(default)]
+    issuer_name: String,
+    /// Имена сертификата, по одному на строку
+    #[serde(default)]
+    name_value: String,
+    #[serde(default)]
+    not_before: String,
+    #[serde(default)]
+    not_after: String,
+    #[serde(default)]
+    serial_number: String,
+}
+
+/// Сертификат, впервые замеченный в журналах CT
+#[derive(Debug, Clone)]
+pub struct CtCertificate {
+    /// Идентификатор сертификата в crt.sh
+    pub id: u64,
+    /// Отслеживаемый домен, под который попал сертификат
+    pub domain: String,
+    pub issuer: String,
+    pub names: Vec<String>,
+    pub not_before: String,
+    pub not_after: String,
+    pub serial: String,
+    pub url: String,
+}
+
+impl CtCertificate {
+    /// Имена сертификата, которые можно проверить: отслеживаемый домен и его
+    /// поддомены, без wildcard-имён и посторонних доменов из SAN
+    pub fn hostnames(&self) -> Vec<String> {
+        let suffix = format!(".{}", self.domain);
+        let mut hostnames: Vec<String> = self
+            .names
+            .iter()
+            .map(|name| name.trim().trim_end_matches('.').to_lowercase())
+            .filter(|name| !name.starts_with("*."))
+            .filter(|name| *name == self.domain || name.ends_with(&suffix))
+            .collect();
+        hostnames.sort();
+        hostnames.dedup();
+        hostnames
+    }
+
+    /// Текст уведомления о выпуске
+    pub fn message(&self) -> String {
+        format!(
+            "Выпущен новый сертификат для {}: {}; издатель {}, действует с {} по {}, \
+             серийный номер {}\n{}",
+            self.domain,
+            self.names.join(", "),
+            self.issuer,
+            self.not_before,
+            self.not_after,
+            self.serial,
+            self.url
+        )
+    }
+}
+
+/// Опрос crt.sh по списку доменов. Для каждого домена запоминается
+/// наибольший идентификатор уже виденного сертификата
+pub struct CtMonitor {
+    domains: Vec<String>,
+    base_url: String,
+    client: Client,
+    http: HttpSettings,
+    dcl: &'static str,
+}
+
+impl CtMonitor {
+    pub const CRT_SH_URL: &str = "https://crt.sh/";
+
+    pub fn new(domains: Vec<String>, http: HttpSettings) -> Self {
+        Self {
+            domains: domains
+                .iter()
+                .map(|domain| domain.trim_end_matches('.').to_lowercase())
+                .collect(),
+            base_url: Self::CRT_SH_URL.to_string(),
+            client: http.client(),
+            http,
+            dcl: "CtMonitor",
+        }
+    }
+
+    /// Адрес сервиса, совместимого с crt.sh, например зеркала или тестового сервера
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = format!("{}/", url.trim_end_matches('/'));
+        self
+    }
+
+    pub fn domains(&self) -> &[String] {
+        &self.domains
+    }
+
+    /// Сертификаты из журналов, выпущенные на домен или его поддомены
+    async fn fetch(&self, domain: &str) -> Result<Vec<CrtShEntry>> {
+        let ascii = idna::domain_to_ascii(domain)
+            .map_err(|_| anyhow!("некорректное имя домена: {}", domain))?;
+        let mut entries = Vec::new();
+        // Шаблон `%.домен` не включает сам домен, поэтому запросов два
+        for query in [ascii.clone(), format!("%25.{}", ascii)] {
+            let url =
+                format!("{}?q={}&output=json&exclude=expired", self.base_url, query);
+            let resp = self.http.send(|| self.client.get(&url)).await?;
+            if !resp.status().is_success() {
+                return Err(anyhow!("crt.sh ответил {}", resp.status()));
+            }
+            entries.extend(resp.json::<Vec<CrtShEntry>>().await?);
+        }
+        Ok(entries)
+    }
+
+    /// Опрашивает все домены и возвращает сертификаты, которых не было в `seen`,
+    /// обновляя отметки. Первый опрос домена только запоминает текущее состояние
+    /// журналов, чтобы не присылать уведомления обо всех ранее выпущенных
+    /// сертификатах. Домен, который не удалось опросить, пропускается до следующего раза
+    pub async fn poll(&self, seen: &mut HashMap<String, u64>) -> Vec<CtCertificate> {
+        let mut certificates = Vec::new();
+        for domain in &self.domains {
+            let entries = match self.fetch(domain).await {
+                Ok(entries) => entries,
+                Err(e) => {
+                    tracing::warn!(
+                        dcl = self.dcl,
+                        domain = %domain,
+                        e = %e,
+                        "Не удалось получить сертификаты из журналов CT"
+                    );
+                    continue;
+                }
+            };
+            let max_id = entries.iter().map(|entry| entry.id).max();
+            let Some(last_seen) = seen.get(domain).copied() else {
+                seen.insert(domain.clone(), max_id.unwrap_or(0));
+                tracing::info!(
+                    dcl = self.dcl,
+                    domain = %domain,
+                    certificates = entries.len(),
+                    "Журналы CT: запомнено текущее состояние"
+                );
+                continue;
+            };
+
+            // Один сертификат может прийти из обоих запросов
+            let new: BTreeMap<u64, CrtShEntry> = entries
+                .into_iter()
+                .filter(|entry| entry.id > last_seen)
+                .map(|entry| (entry.id, entry))
+                .collect();
+            for entry in new.into_values() {
+                counter!(metrics::CT_CERTIFICATES, "domain" => domain.clone())
+                    .increment(1);
+                certificates.push(CtCertificate {
+                    id: entry.id,
+                    domain: domain.clone(),
+                    issuer: entry.issuer_name,
+                    names: entry.name_value.lines().map(str::to_string).collect(),
+                    not_before: entry.not_before,
+                    not_after: entry.not_after,
+                    serial: entry.serial_number,
+                    url: format!("{}?id={}", self.base_url, entry.id),
+                });
+            }
+            if let Some(max_id) = max_id.filter(|id| *id > last_seen) {
+                seen.insert(domain.clone(), max_id);
+            }
+        }
+        certificates
+    }
+}
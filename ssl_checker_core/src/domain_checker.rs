@@ -0,0 +1,2528 @@
+use super::budget::{BudgetPermit, CheckBudgets};
+use super::calendar::BusinessCalendar;
+use super::certificate_files;
+use super::credentials;
+use super::checks::{
+    self, CertificateInfo, DomainRegistration, SslCheckOptions, WhoisCheckOptions,
+    WhoisResponseError,
+};
+use super::config::{
+    CertificateFileConfig, CredentialConfig, DomainOverrideConfig, SeverityThreshold,
+};
+use super::hooks::CheckHooks;
+use super::http::HttpSettings;
+use super::inventory::{self, CertificateInventoryTrait, InventoryCertificate};
+use super::mail::{self, MailCheckOptions};
+use super::metrics;
+use super::notifiers::BaseNotifierTrait;
+use super::overrides::DomainOverrides;
+use super::quic;
+use super::ssh;
+use super::vault::VaultClient;
+use super::renewal::RenewalIndex;
+use super::report::{
+    ChangedEntry, CheckError, CheckKind, CheckProgress, CheckReport, DomainCheckResult,
+    EntryStatus, ErrorKind, IssuerChange, ReportEntry, RevocationEndpoint, RunChanges,
+    Severity, SourceStats, SslCheckResult,
+};
+use super::sources::DomainSourceTrait;
+use base::prelude::{
+    anyhow::{self, Result},
+    chrono::{DateTime, Duration, Utc},
+    chrono_tz::Tz,
+    metrics::{counter, gauge, histogram},
+    tokio::{
+        self,
+        sync::{
+            Semaphore,
+            mpsc::{self, UnboundedSender},
+        },
+        task::JoinHandle,
+    },
+    tracing::{self, Instrument},
+    uuid::Uuid,
+};
+use futures::FutureExt;
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Во сколько раз очередь запуска длиннее `concurrency`, когда заданы бюджеты групп
+const BUDGET_QUEUE_FACTOR: usize = 4;
+
+/// Успешный результат предыдущего запуска, по которому пересчитывается запись
+struct PreviousCheck {
+    expiration_date: DateTime<Utc>,
+    serial: Option<String>,
+    issuer: Option<String>,
+    alpn: Option<String>,
+    checked_at: Option<DateTime<Utc>>,
+}
+
+/// Паника при загрузке из источника или при проверке хоста. Запуск продолжается,
+/// а хост или источник отмечается ошибочным
+#[derive(Debug)]
+struct TaskPanic(String);
+
+impl std::fmt::Display for TaskPanic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "паника: {}", self.0)
+    }
+}
+
+impl std::error::Error for TaskPanic {}
+
+/// Запись предыдущего запуска в окне оповещения
+struct PreviousAlarm {
+    status: EntryStatus,
+    days: Option<i64>,
+}
+
+pub struct DomainCheckerService {
+    sources: Vec<Box<dyn DomainSourceTrait>>,
+    notifiers: Vec<Box<dyn BaseNotifierTrait>>,
+    ssl_alarm_days: i64,
+    /// Пороги важности сертификатов по возрастанию числа дней
+    ssl_thresholds: Vec<SeverityThreshold>,
+    /// Порог оповещения для сертификатов ACME-издателей
+    acme_alarm_days: Option<i64>,
+    alarm_days: i64,
+    check_ssl: bool,
+    check_domains: bool,
+    overrides: Arc<DomainOverrides>,
+    /// Сертификаты, продлённые certbot и acme.sh, для сверки с сертификатами на серверах
+    renewals: Option<Arc<RenewalIndex>>,
+    /// Сертификаты хранилищ текущего запуска с именами хостов, для той же сверки
+    inventory_renewals: RenewalIndex,
+    ssl_options: SslCheckOptions,
+    whois_options: WhoisCheckOptions,
+    /// Проверка сертификатов почтовых серверов доменов; `None` — не проверять
+    mail_options: Option<MailCheckOptions>,
+    /// Сколько проверок выполняется одновременно
+    concurrency: usize,
+    /// Часовой пояс дат истечения в уведомлениях
+    report_timezone: Tz,
+    /// Календарь, по рабочим дням которого отсчитываются пороги оповещения
+    business_calendar: Option<BusinessCalendar>,
+    /// Ограничение времени загрузки каждого источника
+    source_timeout: std::time::Duration,
+    /// Ограничение времени всего запуска
+    max_run_duration: Option<std::time::Duration>,
+    /// Через сколько уведомлений модули отправляют накопленное, не дожидаясь конца запуска
+    notify_chunk_size: Option<usize>,
+    /// Через сколько проверок выводить ход запуска в лог
+    progress_log_every: Option<usize>,
+    /// Каталог, в который сохраняются цепочки сертификатов проверенных хостов
+    cert_dump_dir: Option<Arc<PathBuf>>,
+    progress: Option<UnboundedSender<CheckProgress>>,
+    hooks: CheckHooks,
+    /// Записи предыдущего запуска в окне оповещения для раздела «Что изменилось»
+    previous_alarms: Option<HashMap<CheckKind, HashMap<String, PreviousAlarm>>>,
+    /// Издатели сертификатов хостов по прошлым запускам
+    known_issuers: Option<HashMap<String, String>>,
+    /// Через сколько неудачных запусков подряд сообщать о неожиданной ошибке SSL
+    ssl_failures_to_notify: u32,
+    /// Число неудачных проверок SSL подряд до текущего запуска по хостам
+    ssl_failure_streaks: HashMap<String, u32>,
+    /// Начало сбоя по ошибкам предыдущего запуска: вид ошибки и хост → `first_seen`
+    error_history: HashMap<(ErrorKind, String), DateTime<Utc>>,
+    /// Успешные результаты предыдущего запуска, которые можно не перепроверять
+    previous: HashMap<CheckKind, HashMap<String, PreviousCheck>>,
+    /// Канал [`DomainCheckerService::run_streaming`]; записи уходят в него, а не в отчёт
+    stream: Option<mpsc::Sender<ReportEntry>>,
+    /// Идентификатор следующего запуска; `None` — создаётся в [`Self::run`]
+    run_id: Option<String>,
+    /// Владельцы хостов, полученные от источников в текущем запуске
+    source_owners: HashMap<String, String>,
+    /// Метки имён, сертификаты которых не проверяются
+    skipped_labels: Vec<String>,
+    /// Начала меток имён, сертификаты которых не проверяются
+    skipped_label_prefixes: Vec<String>,
+    /// Метки имён, которыми проверяются wildcard-записи
+    wildcard_labels: Vec<String>,
+    /// Сколько реальных поддоменов из источников проверять вместо `wildcard_labels`
+    wildcard_sample: usize,
+    /// Проверка доступности OCSP и CRL издателя; `None` — не проверять
+    revocation_http: Option<HttpSettings>,
+    /// Локальные файлы сертификатов: PEM, DER и PKCS#12
+    certificate_files: Vec<CertificateFileConfig>,
+    /// Учётные данные со сроком действия
+    credentials: Vec<CredentialConfig>,
+    /// Vault, из метаданных которого читаются сроки учётных данных
+    vault: Option<VaultClient>,
+    /// Менеджеры сертификатов, например AWS ACM
+    inventories: Vec<Box<dyn CertificateInventoryTrait>>,
+    /// Ограничения частоты и числа одновременных проверок групп хостов
+    budgets: Arc<CheckBudgets>,
+    dcl: &'static str,
+}
+
+impl DomainCheckerService {
+    const EXPECTED_ERRORS: &'static [&'static str] = &[
+        "timed out",
+        "Connection timed out",
+        "Connection refused",
+        "tlsv1 unrecognized name",
+        "tlsv1 alert internal error",
+        "Name has no usable address",
+        "failed to lookup address",
+        "Host is unreachable",
+    ];
+    /// Метка имени, которым проверяется wildcard-запись `*.example.com` по умолчанию
+    pub const DEFAULT_WILDCARD_LABEL: &'static str = "test";
+    /// Метки служебных TXT-записей, имена с которыми не проверяются по умолчанию
+    pub const DEFAULT_SKIPPED_LABELS: &'static [&'static str] =
+        &["_dmarc", "_domainkey", "_acme-challenge", "_spf"];
+    pub const DEFAULT_CONCURRENCY: usize = 100;
+    /// С какого числа доменов зоны их общая ошибка WHOIS сообщается одним сообщением
+    const WHOIS_OUTAGE_MIN_DOMAINS: usize = 2;
+    /// Порог ACME-сертификатов по умолчанию, если `ssl_alarm_days` больше: certbot
+    /// и аналоги продлевают сертификат за 30 дней до истечения
+    pub const DEFAULT_ACME_ALARM_DAYS: i64 = 20;
+    pub const DEFAULT_SOURCE_TIMEOUT: std::time::Duration =
+        std::time::Duration::from_secs(300);
+    /// Ошибка записей, проверка которых не завершилась до истечения времени запуска
+    pub const NOT_CHECKED_IN_TIME: &'static str = "не проверен за отведённое время";
+    /// Формат дат истечения в уведомлениях, с явным смещением от UTC
+    const EXPIRES_AT_FORMAT: &'static str = "%Y-%m-%d %H:%M %:z";
+
+    pub fn new(
+        sources: Vec<Box<dyn DomainSourceTrait>>,
+        notifiers: Vec<Box<dyn BaseNotifierTrait>>,
+        ssl_alarm_days: i64,
+        alarm_days: i64,
+    ) -> Self {
+        Self {
+            sources,
+            notifiers,
+            ssl_alarm_days,
+            ssl_thresholds: Vec::new(),
+            acme_alarm_days: None,
+            alarm_days,
+            check_ssl: true,
+            check_domains: true,
+            overrides: Arc::default(),
+            renewals: None,
+            inventory_renewals: RenewalIndex::default(),
+            ssl_options: SslCheckOptions::default(),
+            whois_options: WhoisCheckOptions::default(),
+            mail_options: None,
+            concurrency: Self::DEFAULT_CONCURRENCY,
+            report_timezone: Tz::UTC,
+            business_calendar: None,
+            source_timeout: Self::DEFAULT_SOURCE_TIMEOUT,
+            max_run_duration: None,
+            notify_chunk_size: None,
+            progress_log_every: None,
+            cert_dump_dir: None,
+            progress: None,
+            hooks: CheckHooks::default(),
+            previous_alarms: None,
+            known_issuers: None,
+            ssl_failures_to_notify: 1,
+            ssl_failure_streaks: HashMap::new(),
+            error_history: HashMap::new(),
+            previous: HashMap::new(),
+            stream: None,
+            run_id: None,
+            source_owners: HashMap::new(),
+            skipped_labels: Self::DEFAULT_SKIPPED_LABELS
+                .iter()
+                .map(|label| label.to_string())
+                .collect(),
+            skipped_label_prefixes: Vec::new(),
+            wildcard_labels: vec![Self::DEFAULT_WILDCARD_LABEL.to_string()],
+            wildcard_sample: 0,
+            revocation_http: None,
+            certificate_files: Vec::new(),
+            credentials: Vec::new(),
+            vault: None,
+            inventories: Vec::new(),
+            budgets: Arc::default(),
+            dcl: "DomainCheckerService",
+        }
+    }
+
+    /// Часовой пояс, в котором модули уведомлений показывают даты истечения
+    /// (по умолчанию UTC)
+    pub fn with_report_timezone(mut self, timezone: Tz) -> Self {
+        self.report_timezone = timezone;
+        self
+    }
+
+    /// Несколько порогов оповещения о сертификатах с разной важностью, например
+    /// 30 → info, 14 → warning, 7 → high, 1 → critical. Наибольший порог заменяет
+    /// `ssl_alarm_days`; запись получает важность ближайшего порога не меньше
+    /// оставшегося срока
+    pub fn with_ssl_thresholds(mut self, mut thresholds: Vec<SeverityThreshold>) -> Self {
+        thresholds.sort_by_key(|threshold| threshold.days);
+        if let Some(widest) = thresholds.last() {
+            self.ssl_alarm_days = widest.days;
+        }
+        self.ssl_thresholds = thresholds;
+        self
+    }
+
+    /// Пороги оповещения `alarm_days`, `ssl_alarm_days` и порогов секции `domains`
+    /// отсчитываются в рабочих днях календаря; даты берутся в часовом поясе
+    /// [`DomainCheckerService::with_report_timezone`]. Статус `critical` по-прежнему
+    /// определяется календарными днями
+    pub fn with_business_calendar(mut self, calendar: BusinessCalendar) -> Self {
+        self.business_calendar = Some(calendar);
+        self
+    }
+
+    /// Имена, сертификаты которых не проверяются: с любой меткой из `labels`
+    /// (по умолчанию [`DomainCheckerService::DEFAULT_SKIPPED_LABELS`]) или меткой,
+    /// начинающейся с одного из `prefixes`, например `autodiscover` или `_sip`.
+    /// Срок регистрации корневого домена таких имён проверяется как обычно
+    pub fn with_skipped_labels(
+        mut self,
+        labels: Vec<String>,
+        prefixes: Vec<String>,
+    ) -> Self {
+        let lowercase =
+            |items: Vec<String>| items.iter().map(|item| item.to_lowercase()).collect();
+        self.skipped_labels = lowercase(labels);
+        self.skipped_label_prefixes = lowercase(prefixes);
+        self
+    }
+
+    /// Имена, которыми проверяется wildcard-запись `*.example.com`: до `sample`
+    /// поддоменов `example.com` первого уровня из источников, а если их нет —
+    /// `labels` (по умолчанию [`DomainCheckerService::DEFAULT_WILDCARD_LABEL`]),
+    /// например `www` и `app` вместо несуществующего `test.example.com`
+    pub fn with_wildcard_hosts(mut self, labels: Vec<String>, sample: usize) -> Self {
+        self.wildcard_labels = labels;
+        self.wildcard_sample = sample;
+        self
+    }
+
+    /// Проверять, доступны ли OCSP-респондеры и списки отзыва издателя у сертификатов
+    /// в окне оповещения: при сбоях инфраструктуры удостоверяющего центра продление
+    /// часто не проходит. Каждый адрес запрашивается один раз за запуск
+    pub fn with_revocation_check(mut self, http: HttpSettings) -> Self {
+        self.revocation_http = Some(http);
+        self
+    }
+
+    /// Порог оповещения для сертификатов Let's Encrypt и ZeroSSL
+    /// ([`checks::ACME_ISSUERS`]); по умолчанию — меньшее из `ssl_alarm_days`
+    /// и [`DomainCheckerService::DEFAULT_ACME_ALARM_DAYS`]. Порог из секции
+    /// `domains` имеет приоритет
+    pub fn with_acme_alarm_days(mut self, days: i64) -> Self {
+        self.acme_alarm_days = Some(days);
+        self
+    }
+
+    /// Включение и отключение проверок сертификатов и сроков регистрации доменов
+    pub fn with_checks(mut self, check_ssl: bool, check_domains: bool) -> Self {
+        self.check_ssl = check_ssl;
+        self.check_domains = check_domains;
+        self
+    }
+
+    /// Настройки проверки отдельных доменов из секции `domains`
+    pub fn with_overrides(mut self, overrides: Arc<DomainOverrides>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Сверка с сертификатами, продлёнными на этой машине: хосты, которые отдают
+    /// сертификат старше продлённого, попадают в отдельное уведомление
+    pub fn with_renewal_index(mut self, renewals: Arc<RenewalIndex>) -> Self {
+        self.renewals = Some(renewals);
+        self
+    }
+
+    /// Таймауты и TLS-коннектор проверки сертификатов; порт и SNI
+    /// из секции `domains` имеют приоритет
+    pub fn with_ssl_options(mut self, options: SslCheckOptions) -> Self {
+        self.ssl_options = options;
+        self
+    }
+
+    /// Параметры запросов WHOIS
+    pub fn with_whois_options(mut self, options: WhoisCheckOptions) -> Self {
+        self.whois_options = options;
+        self
+    }
+
+    /// Ограничение числа одновременных проверок: соединений и открытых
+    /// файловых дескрипторов не больше `concurrency`
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Бюджеты групп хостов: сколько проверок WHOIS и сертификатов хостов с
+    /// тегами группы выполняется в минуту и одновременно. Общее ограничение
+    /// `concurrency` остаётся в силе. Бюджеты действуют между запусками: сервисы
+    /// одного демона должны получать один и тот же экземпляр
+    pub fn with_budgets(mut self, budgets: Arc<CheckBudgets>) -> Self {
+        self.budgets = budgets;
+        self
+    }
+
+    /// Ограничение времени загрузки каждого источника. Источники загружаются
+    /// одновременно; у источника, не уложившегося в `timeout`, проверяются
+    /// хосты, полученные до истечения времени
+    pub fn with_source_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.source_timeout = timeout;
+        self
+    }
+
+    /// Ограничение времени всего запуска, включая загрузку источников. По его
+    /// истечении незавершённые проверки прерываются и попадают в отчёт с ошибкой
+    /// [`DomainCheckerService::NOT_CHECKED_IN_TIME`], а уведомления о готовых
+    /// результатах отправляются как обычно
+    pub fn with_max_run_duration(
+        mut self,
+        max_run_duration: std::time::Duration,
+    ) -> Self {
+        self.max_run_duration = Some(max_run_duration);
+        self
+    }
+
+    /// Доставка уведомлений частями: после каждых `chunk_size` уведомлений
+    /// модули отправляют накопленное ([`BaseNotifierTrait::flush`]), а списки
+    /// хостов с ошибками делятся на сообщения не длиннее `chunk_size` строк
+    pub fn with_notify_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.notify_chunk_size = Some(chunk_size.max(1));
+        self
+    }
+
+    /// Вывод в лог числа выполненных проверок после каждых `every` проверок
+    pub fn with_progress_log_every(mut self, every: usize) -> Self {
+        self.progress_log_every = Some(every.max(1));
+        self
+    }
+
+    /// Проверять сертификаты почтовых серверов: для корневых доменов запрашиваются
+    /// MX-записи, и у каждого сервера берётся сертификат после STARTTLS. Записи
+    /// попадают в отчёт с видом [`CheckKind::Mail`] и отдельный раздел уведомлений
+    pub fn with_mail_checks(mut self, options: MailCheckOptions) -> Self {
+        self.mail_options = Some(options);
+        self
+    }
+
+    /// Проверять сертификаты из локальных файлов вместе с сертификатами хостов:
+    /// подписи кода, S/MIME и другие, которые не получить подключением.
+    /// Записи попадают в отчёт с видом [`CheckKind::File`] и назначением
+    /// из Extended Key Usage; порог оповещения — общий порог SSL
+    pub fn with_certificate_files(mut self, files: Vec<CertificateFileConfig>) -> Self {
+        self.certificate_files = files;
+        self
+    }
+
+    /// Проверять сертификаты менеджеров сертификатов вместе с сертификатами хостов.
+    /// Хранилища загружаются вместе с источниками и с тем же таймаутом; записи
+    /// попадают в отчёт с видом [`CheckKind::Inventory`], а сертификаты, которые
+    /// не продлятся сами, — ещё и в ошибки [`ErrorKind::Renewal`]
+    pub fn with_inventories(
+        mut self,
+        inventories: Vec<Box<dyn CertificateInventoryTrait>>,
+    ) -> Self {
+        self.inventories = inventories;
+        self
+    }
+
+    /// Проверять сроки учётных данных: ключей API, токенов, паролей. Записи
+    /// попадают в отчёт с видом [`CheckKind::Credential`] и в отдельный раздел
+    /// уведомлений; порог по умолчанию — общий порог доменов
+    pub fn with_credentials(mut self, credentials: Vec<CredentialConfig>) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Vault для сроков учётных данных, хранящихся в метаданных секретов
+    pub fn with_vault(mut self, vault: VaultClient) -> Self {
+        self.vault = Some(vault);
+        self
+    }
+
+    /// Идентификатор запуска вместо создаваемого UUIDv7, например чтобы вызывающий
+    /// код заранее записал его в своё состояние. Действует на один запуск
+    pub fn with_run_id(mut self, run_id: &str) -> Self {
+        self.run_id = Some(run_id.to_string());
+        self
+    }
+
+    /// Сохранять цепочку сертификатов каждого проверенного хоста в `dir`
+    /// (`<хост>.pem`), чтобы разобрать странный результат без ручного запуска
+    /// openssl. Цепочка запрашивается отдельным соединением; ошибка сохранения
+    /// попадает только в лог
+    pub fn with_cert_dump_dir(mut self, dir: PathBuf) -> Self {
+        self.cert_dump_dir = Some(Arc::new(dir));
+        self
+    }
+
+    /// Инкрементальная проверка: хосты, успешно проверенные в `previous` не раньше
+    /// `recheck_healthy_after` назад, не проверяются заново, если и с новыми
+    /// сроками остаются за пределами окна оповещения. Их записи переходят
+    /// в отчёт с временем исходной проверки
+    pub fn with_previous_report(
+        mut self,
+        previous: &CheckReport,
+        recheck_healthy_after: Duration,
+    ) -> Self {
+        let fresh_since = Utc::now() - recheck_healthy_after;
+        self.previous.clear();
+        for entry in &previous.entries {
+            if entry.status == EntryStatus::Ok
+                && entry.checked_at.is_some_and(|at| at >= fresh_since)
+            {
+                let Some(expiration_date) = entry.expiration_date else {
+                    continue;
+                };
+                let check = PreviousCheck {
+                    expiration_date,
+                    serial: entry.serial.clone(),
+                    issuer: entry.issuer.clone(),
+                    alpn: entry.alpn.clone(),
+                    checked_at: entry.checked_at,
+                };
+                self.previous
+                    .entry(entry.check)
+                    .or_default()
+                    .insert(entry.hostname.clone(), check);
+            }
+        }
+        self
+    }
+
+    /// Сравнение с предыдущим запуском: модули уведомлений получают записи,
+    /// которые впервые попали в окно оповещения, вышли из него или стали критическими
+    pub fn with_changes_since(mut self, previous: &CheckReport) -> Self {
+        let mut alarms: HashMap<_, HashMap<_, _>> = HashMap::new();
+        for entry in previous.entries.iter().filter(|entry| entry.is_alarm()) {
+            alarms.entry(entry.check).or_default().insert(
+                entry.hostname.clone(),
+                PreviousAlarm { status: entry.status, days: entry.days },
+            );
+        }
+        self.previous_alarms = Some(alarms);
+        self
+    }
+
+    /// Оповещение о смене издателя сертификата: `issuers` — издатели хостов
+    /// по прошлым запускам. Неожиданный перевыпуск у другого удостоверяющего
+    /// центра уходит модулям уведомлений отдельным сообщением
+    pub fn with_known_issuers(mut self, issuers: HashMap<String, String>) -> Self {
+        self.known_issuers = Some(issuers);
+        self
+    }
+
+    /// Неожиданная ошибка SSL сообщается, только если хост не прошёл проверку
+    /// `runs` запусков подряд, считая текущий: одиночный сбой рукопожатия
+    /// не будит дежурного. `streaks` — число неудачных запусков подряд до текущего
+    /// по хостам. Запись с ошибкой попадает в отчёт в любом случае
+    pub fn with_ssl_failure_debounce(
+        mut self,
+        runs: u32,
+        streaks: HashMap<String, u32>,
+    ) -> Self {
+        self.ssl_failures_to_notify = runs.max(1);
+        self.ssl_failure_streaks = streaks;
+        self
+    }
+
+    /// Ошибки предыдущего запуска: ошибка, повторившаяся с тем же видом и хостом,
+    /// сохраняет `first_seen`, и уведомление показывает, как долго длится сбой
+    pub fn with_error_history(mut self, previous: &CheckReport) -> Self {
+        self.error_history = previous
+            .errors
+            .iter()
+            .filter_map(|error| {
+                let (kind, subject) = error.key()?;
+                Some(((kind, subject.to_string()), error.first_seen?))
+            })
+            .collect();
+        self
+    }
+
+    /// Отправлять события хода проверки в канал
+    pub fn with_progress(mut self, progress: UnboundedSender<CheckProgress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Обработчик начала запуска проверки
+    pub fn on_run_start(mut self, hook: impl Fn() + Send + Sync + 'static) -> Self {
+        self.hooks.on_run_start(hook);
+        self
+    }
+
+    /// Обработчик результата проверки каждого хоста, вызывается по мере готовности
+    pub fn on_check_complete(
+        mut self,
+        hook: impl Fn(&ReportEntry) + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks.on_check_complete(hook);
+        self
+    }
+
+    /// Обработчик завершения запуска: отчёт или ошибка, после отправки уведомлений
+    pub fn on_run_finish(
+        mut self,
+        hook: impl Fn(&Result<CheckReport>) + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks.on_run_finish(hook);
+        self
+    }
+
+    /// Заменяет все обработчики событий запуска
+    pub fn with_hooks(mut self, hooks: CheckHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    fn emit(&self, event: CheckProgress) {
+        if let Some(progress) = &self.progress {
+            let _ = progress.send(event);
+        }
+    }
+
+    /// Дата истечения в часовом поясе отчёта
+    fn expires_at(&self, time: DateTime<Utc>) -> String {
+        time.with_timezone(&self.report_timezone)
+            .format(Self::EXPIRES_AT_FORMAT)
+            .to_string()
+    }
+
+    /// Копия записи нужна только подписчику на ход проверки
+    fn emit_checked(&self, entry: &ReportEntry) {
+        if self.progress.is_some() {
+            self.emit(CheckProgress::Checked(Box::new(entry.clone())));
+        }
+    }
+
+    /// Имя хоста для проверки сертификата; уже нормализованное имя
+    /// не копируется, а разделяет память с исходным
+    fn filter_domain(&self, domain: &Arc<str>) -> Option<Arc<str>> {
+        let d = domain.trim().to_lowercase();
+        let labels: Vec<&str> = d.split('.').collect();
+
+        let skipped = |label: &&str| {
+            let prefixes = &self.skipped_label_prefixes;
+            self.skipped_labels.iter().any(|skipped| skipped == label)
+                || prefixes.iter().any(|prefix| label.starts_with(prefix.as_str()))
+        };
+        if labels.iter().any(skipped) {
+            return None;
+        }
+
+        if labels.len() < 2 {
+            return None;
+        }
+
+        if d == **domain { Some(domain.clone()) } else { Some(Arc::from(d)) }
+    }
+
+    async fn notify_ssl_expiration(&mut self, entry: SslCheckResult) {
+        for notifier in &mut self.notifiers {
+            notifier.ssl_expiration(&entry).await;
+        }
+    }
+
+    async fn notify_mail_expiration(&mut self, entry: SslCheckResult) {
+        for notifier in &mut self.notifiers {
+            notifier.mail_expiration(&entry).await;
+        }
+    }
+
+    async fn notify_expiration(&mut self, entry: DomainCheckResult) {
+        for notifier in &mut self.notifiers {
+            notifier.expiration(&entry).await;
+        }
+    }
+
+    async fn notify_credential_expiration(&mut self, entry: DomainCheckResult) {
+        for notifier in &mut self.notifiers {
+            notifier.credential_expiration(&entry).await;
+        }
+    }
+
+    async fn commit(&self) -> Result<()> {
+        for notifier in &self.notifiers {
+            let result = notifier.commit().await;
+            let outcome = if result.is_ok() { "ok" } else { "error" };
+            counter!(
+                metrics::NOTIFIER_SENDS,
+                "notifier" => notifier.get_notifier_name(),
+                "result" => outcome
+            )
+            .increment(1);
+            if let Err(e) = result {
+                tracing::error!(dcl = self.dcl, e = %e, "Commit failed");
+            }
+        }
+        Ok(())
+    }
+
+    async fn notify_exception(&mut self, error: &CheckError) {
+        for notifier in &mut self.notifiers {
+            notifier.exception(error).await;
+        }
+    }
+
+    /// Отмечает, с какого момента повторяется ошибка: `first_seen` переходит
+    /// из предыдущего запуска, новая ошибка начинается в `now`
+    fn track_error(&self, mut error: CheckError, now: DateTime<Utc>) -> CheckError {
+        if let Some((kind, subject)) = error.key() {
+            let first_seen = self.error_history.get(&(kind, subject.to_string()));
+            error.first_seen = Some(first_seen.copied().unwrap_or(now));
+        }
+        error
+    }
+
+    /// Учитывает отправленное уведомление и, если набралась часть, просит
+    /// модули отправить накопленное
+    async fn notified(&mut self, pending: &mut usize) {
+        *pending += 1;
+        let Some(chunk_size) = self.notify_chunk_size else {
+            return;
+        };
+        if *pending < chunk_size {
+            return;
+        }
+        *pending = 0;
+        for notifier in &mut self.notifiers {
+            let result = notifier.flush().await;
+            let outcome = if result.is_ok() { "ok" } else { "error" };
+            counter!(
+                metrics::NOTIFIER_SENDS,
+                "notifier" => notifier.get_notifier_name(),
+                "result" => outcome
+            )
+            .increment(1);
+            if let Err(e) = result {
+                tracing::error!(dcl = self.dcl, e = %e, "Flush failed");
+            }
+        }
+    }
+
+    fn log_progress(&self, checked: usize, total: usize) {
+        if let Some(every) = self.progress_log_every
+            && (checked.is_multiple_of(every) || checked == total)
+        {
+            tracing::info!(dcl = self.dcl, checked, total, "Ход проверки");
+        }
+    }
+
+    fn is_expected_error(err_str: &str) -> bool {
+        Self::EXPECTED_ERRORS.iter().any(|exp_err| err_str.contains(exp_err))
+    }
+
+    /// Запись отчёта по результату проверки срока регистрации домена
+    fn domain_entry(
+        &self,
+        root: &str,
+        result: &Result<DomainRegistration>,
+    ) -> ReportEntry {
+        let settings = self.overrides.resolve(root);
+        let alarm_days = settings.alarm_days.unwrap_or(self.alarm_days);
+        let entry = match result {
+            Ok(registration) => {
+                let days = registration
+                    .expiration_date
+                    .signed_duration_since(Utc::now())
+                    .num_days();
+                let window_days = self.window_days(registration.expiration_date, days);
+                let in_alarm_window =
+                    window_days < alarm_days || days <= EntryStatus::CRITICAL_DAYS;
+                ReportEntry::ok(
+                    CheckKind::Domain,
+                    root,
+                    registration.expiration_date,
+                    days,
+                    in_alarm_window,
+                )
+            }
+            // Скрытый или неопубликованный срок — не сбой проверки: узнать его нельзя
+            Err(e) => ReportEntry::failed(
+                CheckKind::Domain,
+                root,
+                &e.to_string(),
+                WhoisResponseError::hides_expiry(e),
+            ),
+        };
+        let owner = self.owner(root, &settings);
+        entry.with_tags(settings.tags).with_owner(owner)
+    }
+
+    /// Запись о сроке учётных данных; порог — `alarm_days` записи, иначе общий
+    /// порог доменов
+    fn credential_entry(
+        &self,
+        config: &CredentialConfig,
+        result: &Result<DateTime<Utc>>,
+    ) -> ReportEntry {
+        let entry = match result {
+            Ok(expiration_date) => {
+                let alarm_days = config.alarm_days.unwrap_or(self.alarm_days);
+                let days = expiration_date.signed_duration_since(Utc::now()).num_days();
+                let window_days = self.window_days(*expiration_date, days);
+                let in_alarm_window =
+                    window_days < alarm_days || days <= EntryStatus::CRITICAL_DAYS;
+                ReportEntry::ok(
+                    CheckKind::Credential,
+                    &config.name,
+                    *expiration_date,
+                    days,
+                    in_alarm_window,
+                )
+            }
+            Err(e) => ReportEntry::failed(
+                CheckKind::Credential,
+                &config.name,
+                &e.to_string(),
+                false,
+            ),
+        };
+        entry.with_tags(config.tags.clone()).with_owner(config.owner.clone())
+    }
+
+    /// Команда-владелец хоста: указанная источником, иначе из секции `domains`
+    fn owner(&self, hostname: &str, settings: &DomainOverrideConfig) -> Option<String> {
+        self.source_owners
+            .get(&hostname.to_lowercase())
+            .or(settings.owner.as_ref())
+            .cloned()
+    }
+
+    /// Дни до истечения, сравниваемые с порогом оповещения: рабочие дни, если задан
+    /// календарь, иначе `days`
+    fn window_days(&self, expiration_date: DateTime<Utc>, days: i64) -> i64 {
+        let Some(calendar) = &self.business_calendar else {
+            return days;
+        };
+        let now = Utc::now().with_timezone(&self.report_timezone);
+        let expiration_date = expiration_date.with_timezone(&self.report_timezone);
+        calendar.business_days_until(&now, &expiration_date)
+    }
+
+    /// Важность сертификата в окне оповещения по порогам `ssl_thresholds`. Срок
+    /// дальше всех порогов (порог хоста из секции `domains` шире) получает
+    /// важность наибольшего порога
+    fn ssl_severity(&self, window_days: i64, days: i64) -> Severity {
+        if days <= EntryStatus::CRITICAL_DAYS {
+            return Severity::Critical;
+        }
+        self.ssl_thresholds
+            .iter()
+            .find(|threshold| window_days <= threshold.days)
+            .or(self.ssl_thresholds.last())
+            .map_or(Severity::Warning, |threshold| threshold.severity)
+    }
+
+    /// Порог оповещения для сертификатов ACME-издателей: они продлеваются
+    /// задолго до истечения, поэтому окно короче общего `ssl_alarm_days`
+    fn acme_alarm_days(&self) -> i64 {
+        self.acme_alarm_days
+            .unwrap_or_else(|| self.ssl_alarm_days.min(Self::DEFAULT_ACME_ALARM_DAYS))
+    }
+
+    /// Запись о сертификате SSH-хоста. Порог — `ssh_alarm_days` из `domains`,
+    /// иначе общий порог SSL; ACME и продления к сертификатам SSH не относятся
+    fn ssh_entry(&self, hostname: &str, result: &Result<CertificateInfo>) -> ReportEntry {
+        let settings = self.overrides.resolve(hostname);
+        let entry = match result {
+            Ok(certificate) => {
+                let alarm_days = settings.ssh_alarm_days.unwrap_or(self.ssl_alarm_days);
+                let days = certificate
+                    .expiration_date
+                    .signed_duration_since(Utc::now())
+                    .num_days();
+                let window_days = self.window_days(certificate.expiration_date, days);
+                let in_alarm_window =
+                    window_days <= alarm_days || days <= EntryStatus::CRITICAL_DAYS;
+                ReportEntry {
+                    issuer: Some(certificate.issuer.clone()),
+                    serial: Some(certificate.serial.clone()),
+                    ..ReportEntry::ok(
+                        CheckKind::Ssh,
+                        hostname,
+                        certificate.expiration_date,
+                        days,
+                        in_alarm_window,
+                    )
+                }
+                .with_severity(self.ssl_severity(window_days, days))
+            }
+            Err(e) => {
+                let err_str = e.to_string();
+                let is_expected =
+                    !e.is::<TaskPanic>() && Self::is_expected_error(&err_str);
+                ReportEntry::failed(CheckKind::Ssh, hostname, &err_str, is_expected)
+            }
+        };
+        let owner = self.owner(hostname, &settings);
+        entry.with_tags(settings.tags).with_owner(owner)
+    }
+
+    /// Запись отчёта по результату проверки SSL-сертификата
+    fn ssl_entry(&self, hostname: &str, result: &Result<CertificateInfo>) -> ReportEntry {
+        let settings = self.overrides.resolve(hostname);
+        let entry = match result {
+            Ok(certificate) => {
+                let acme = checks::is_acme_issuer(&certificate.issuer);
+                let default_alarm_days =
+                    if acme { self.acme_alarm_days() } else { self.ssl_alarm_days };
+                let ssl_alarm_days =
+                    settings.ssl_alarm_days.unwrap_or(default_alarm_days);
+                let days = certificate
+                    .expiration_date
+                    .signed_duration_since(Utc::now())
+                    .num_days();
+                let window_days = self.window_days(certificate.expiration_date, days);
+                let in_alarm_window =
+                    window_days <= ssl_alarm_days || days <= EntryStatus::CRITICAL_DAYS;
+                // Продление прошло, но новый сертификат не развёрнут на сервере
+                let renewed = self
+                    .renewals
+                    .as_ref()
+                    .and_then(|renewals| renewals.lookup(hostname))
+                    .into_iter()
+                    .chain(self.inventory_renewals.lookup(hostname))
+                    .filter(|renewed| {
+                        renewed.serial != certificate.serial
+                            && renewed.expiration_date > certificate.expiration_date
+                    })
+                    .max_by_key(|renewed| renewed.expiration_date);
+                ReportEntry {
+                    issuer: Some(certificate.issuer.clone()),
+                    serial: Some(certificate.serial.clone()),
+                    acme,
+                    renewed_expiration_date: renewed.map(|r| r.expiration_date),
+                    alpn: certificate.alpn.clone(),
+                    ..ReportEntry::ok(
+                        CheckKind::Ssl,
+                        hostname,
+                        certificate.expiration_date,
+                        days,
+                        in_alarm_window,
+                    )
+                }
+                .with_severity(self.ssl_severity(window_days, days))
+            }
+            Err(e) => {
+                let err_str = e.to_string();
+                let is_expected =
+                    !e.is::<TaskPanic>() && Self::is_expected_error(&err_str);
+                ReportEntry::failed(CheckKind::Ssl, hostname, &err_str, is_expected)
+            }
+        };
+        let owner = self.owner(hostname, &settings);
+        entry.with_tags(settings.tags).with_owner(owner)
+    }
+
+    /// Запись предыдущего запуска о сроке регистрации, если домен можно не перепроверять
+    fn reused_domain_entry(&self, root: &str) -> Option<ReportEntry> {
+        let previous = self.previous.get(&CheckKind::Domain)?.get(root)?;
+        let registration =
+            DomainRegistration { expiration_date: previous.expiration_date };
+        let entry = self.domain_entry(root, &Ok(registration));
+        Self::reused(entry, previous)
+    }
+
+    /// Запись предыдущего запуска о сертификате, если хост можно не перепроверять
+    fn reused_ssl_entry(&self, hostname: &str) -> Option<ReportEntry> {
+        let previous = self.previous.get(&CheckKind::Ssl)?.get(hostname)?;
+        let certificate = CertificateInfo {
+            expiration_date: previous.expiration_date,
+            serial: previous.serial.clone()?,
+            issuer: previous.issuer.clone()?,
+            alpn: previous.alpn.clone(),
+            revocation_urls: Vec::new(),
+        };
+        let entry = self.ssl_entry(hostname, &Ok(certificate));
+        Self::reused(entry, previous)
+    }
+
+    /// Пересчитанная по текущей дате запись пригодна, только если срок
+    /// по-прежнему вне окна оповещения
+    fn reused(entry: ReportEntry, previous: &PreviousCheck) -> Option<ReportEntry> {
+        (entry.status == EntryStatus::Ok && entry.renewed_expiration_date.is_none())
+            .then_some(ReportEntry { checked_at: previous.checked_at, ..entry })
+    }
+
+    /// Параметры подключения к хосту с учётом `domains`
+    fn ssl_options_for(
+        overrides: &DomainOverrides,
+        options: &SslCheckOptions,
+        hostname: &str,
+    ) -> SslCheckOptions {
+        let settings = overrides.resolve(hostname);
+        SslCheckOptions {
+            port: settings.port.unwrap_or(options.port),
+            sni: settings.sni.or_else(|| options.sni.clone()),
+            alpn: settings.alpn.unwrap_or_else(|| options.alpn.clone()),
+            ..options.clone()
+        }
+    }
+
+    /// Включена ли для хоста проверка по QUIC
+    fn checks_quic(&self, hostname: &str) -> bool {
+        self.overrides.resolve(hostname).quic.unwrap_or(false)
+    }
+
+    /// Включена ли для хоста проверка сертификата SSH
+    fn checks_ssh(&self, hostname: &str) -> bool {
+        self.overrides.resolve(hostname).ssh.unwrap_or(false)
+    }
+
+    /// Сертификат SSH-хоста на порту `ssh_port` из `domains`, по умолчанию 22.
+    /// Таймауты те же, что у проверки SSL
+    async fn check_ssh_with_overrides(
+        overrides: &DomainOverrides,
+        options: &SslCheckOptions,
+        hostname: &str,
+    ) -> Result<CertificateInfo> {
+        let options = SslCheckOptions {
+            port: overrides.resolve(hostname).ssh_port.unwrap_or(ssh::DEFAULT_PORT),
+            ..options.clone()
+        };
+        ssh::check_ssh_host_certificate(hostname, &options).await
+    }
+
+    /// Сертификат хоста по QUIC, если проверка включена для него в `domains`.
+    /// Протоколы `alpn` из `domains` относятся к TCP, по QUIC предлагается `h3`
+    async fn check_quic_with_overrides(
+        overrides: &DomainOverrides,
+        options: &SslCheckOptions,
+        hostname: &str,
+    ) -> Option<Result<CertificateInfo>> {
+        if !overrides.resolve(hostname).quic.unwrap_or(false) {
+            return None;
+        }
+        let options = SslCheckOptions {
+            alpn: Vec::new(),
+            ..Self::ssl_options_for(overrides, options, hostname)
+        };
+        let check = quic::check_quic_certificate(hostname, &options);
+        Some(Self::catch_panic("quic", check).await)
+    }
+
+    /// Проверка сертификата с учётом порта, SNI и ALPN из секции `domains`
+    async fn check_ssl_with_overrides(
+        overrides: &DomainOverrides,
+        options: &SslCheckOptions,
+        hostname: &str,
+    ) -> Result<CertificateInfo> {
+        let options = Self::ssl_options_for(overrides, options, hostname);
+        checks::check_ssl_expiry(hostname, &options).await
+    }
+
+    /// Цепочка сертификатов хоста в PEM, с теми же параметрами подключения,
+    /// что и при проверке
+    pub async fn certificate_chain(&self, hostname: &str) -> Result<String> {
+        let options = Self::ssl_options_for(&self.overrides, &self.ssl_options, hostname);
+        checks::fetch_certificate_chain(hostname, &options).await
+    }
+
+    /// Сохраняет цепочку сертификатов хоста в `dir`
+    async fn dump_certificate_chain(
+        dir: &Path,
+        overrides: &DomainOverrides,
+        options: &SslCheckOptions,
+        hostname: &str,
+    ) -> Result<()> {
+        let options = Self::ssl_options_for(overrides, options, hostname);
+        let chain = checks::fetch_certificate_chain(hostname, &options).await?;
+        // Имя хоста из источника не должно выводить файл за пределы каталога
+        let name: String = hostname
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+            .collect();
+        tokio::fs::create_dir_all(dir).await?;
+        tokio::fs::write(dir.join(format!("{}.pem", name)), chain).await?;
+        Ok(())
+    }
+
+    /// Хосты для проверки WHOIS без отключённых через `skip_whois`
+    fn whois_targets<'a>(
+        &self,
+        hostnames: impl Iterator<Item = &'a Arc<str>>,
+    ) -> HashSet<String> {
+        hostnames
+            .filter_map(|h| checks::root_domain(h))
+            .filter(|root| !self.overrides.resolve(root).skip_whois.unwrap_or(false))
+            .collect()
+    }
+
+    /// Хосты для проверки сертификатов без отключённых через `skip_ssl`;
+    /// wildcard-записи заменяются представительными именами
+    fn ssl_targets<'a>(
+        &self,
+        hostnames: impl Iterator<Item = &'a Arc<str>>,
+    ) -> HashSet<Arc<str>> {
+        let hostnames: Vec<&Arc<str>> = hostnames.collect();
+        let samples = self.wildcard_samples(&hostnames);
+        hostnames
+            .into_iter()
+            .flat_map(|h| match Self::wildcard_base(h) {
+                Some(base) => self.wildcard_hosts(&base, &samples),
+                None => vec![h.clone()],
+            })
+            .filter_map(|h| self.filter_domain(&h))
+            .filter(|host| !self.overrides.resolve(host).skip_ssl.unwrap_or(false))
+            .collect()
+    }
+
+    /// Доступность OCSP и CRL сертификата записи в окне оповещения; результаты
+    /// по адресам запоминаются в `cache` до конца запуска
+    async fn check_revocation(
+        &self,
+        entry: &mut ReportEntry,
+        result: &Result<CertificateInfo>,
+        cache: &mut HashMap<String, Option<String>>,
+    ) {
+        let (Some(http), Ok(certificate)) = (&self.revocation_http, result) else {
+            return;
+        };
+        if !entry.is_alarm() {
+            return;
+        }
+        for url in &certificate.revocation_urls {
+            if !cache.contains_key(url) {
+                let error = checks::check_revocation_endpoint(url, http)
+                    .await
+                    .err()
+                    .map(|e| e.to_string());
+                if let Some(error) = &error {
+                    tracing::warn!(
+                        dcl = self.dcl,
+                        hostname = %entry.hostname,
+                        url = %url,
+                        error = %error,
+                        "OCSP или CRL издателя недоступен"
+                    );
+                }
+                cache.insert(url.clone(), error);
+            }
+            entry
+                .revocation
+                .push(RevocationEndpoint { url: url.clone(), error: cache[url].clone() });
+        }
+    }
+
+    /// Домен wildcard-записи `*.example.com` в нижнем регистре
+    fn wildcard_base(hostname: &str) -> Option<String> {
+        let hostname = hostname.trim().to_lowercase();
+        hostname.strip_prefix("*.").map(str::to_string)
+    }
+
+    /// Реальные поддомены первого уровня доменов wildcard-записей, не больше
+    /// `wildcard_sample` на домен; одинаковые от запуска к запуску
+    fn wildcard_samples(
+        &self,
+        hostnames: &[&Arc<str>],
+    ) -> HashMap<String, Vec<Arc<str>>> {
+        if self.wildcard_sample == 0 {
+            return HashMap::new();
+        }
+        let mut samples: HashMap<String, Vec<Arc<str>>> = hostnames
+            .iter()
+            .filter_map(|h| Self::wildcard_base(h))
+            .map(|base| (base, Vec::new()))
+            .collect();
+        for hostname in hostnames {
+            let name = hostname.trim().to_lowercase();
+            if let Some((_, parent)) = name.split_once('.')
+                && let Some(hosts) = samples.get_mut(parent)
+                && !name.starts_with("*.")
+            {
+                hosts.push(Arc::from(name.as_str()));
+            }
+        }
+        for hosts in samples.values_mut() {
+            hosts.sort();
+            hosts.dedup();
+            hosts.truncate(self.wildcard_sample);
+        }
+        samples
+    }
+
+    /// Имена, которыми проверяется wildcard-запись домена `base`
+    fn wildcard_hosts(
+        &self,
+        base: &str,
+        samples: &HashMap<String, Vec<Arc<str>>>,
+    ) -> Vec<Arc<str>> {
+        match samples.get(base) {
+            Some(hosts) if !hosts.is_empty() => hosts.clone(),
+            _ => self
+                .wildcard_labels
+                .iter()
+                .map(|label| Arc::from(format!("{}.{}", label.trim(), base)))
+                .collect(),
+        }
+    }
+
+    /// Проверка одного хоста без уведомлений: сертификат и срок регистрации домена
+    pub async fn check_host(&self, hostname: &str) -> Vec<ReportEntry> {
+        let mut entries = Vec::new();
+        let hostname: Arc<str> = Arc::from(hostname);
+
+        for root in self.whois_targets(std::iter::once(&hostname)) {
+            let _budget = self.acquire_budget(CheckKind::Domain, &root).await;
+            let result =
+                checks::check_domain_expiration(&root, &self.whois_options).await;
+            entries.push(self.domain_entry(&root, &result));
+        }
+
+        for ssl_hostname in self.ssl_targets(std::iter::once(&hostname)) {
+            let budget = self.acquire_budget(CheckKind::Ssl, &ssl_hostname).await;
+            let result = Self::check_ssl_with_overrides(
+                &self.overrides,
+                &self.ssl_options,
+                &ssl_hostname,
+            )
+            .await;
+            drop(budget);
+            entries.push(self.ssl_entry(&ssl_hostname, &result));
+            let quic_result = Self::check_quic_with_overrides(
+                &self.overrides,
+                &self.ssl_options,
+                &ssl_hostname,
+            );
+            if let Some(result) = quic_result.await {
+                entries.push(ReportEntry {
+                    check: CheckKind::Quic,
+                    ..self.ssl_entry(&ssl_hostname, &result)
+                });
+            }
+        }
+
+        if self.check_ssl && self.checks_ssh(&hostname) {
+            let result = Self::check_ssh_with_overrides(
+                &self.overrides,
+                &self.ssl_options,
+                &hostname,
+            )
+            .await;
+            entries.push(self.ssh_entry(&hostname, &result));
+        }
+
+        entries
+    }
+
+    /// Запуск проверки. Идентификатор запуска попадает в поле `run_id` span-а
+    /// `check_run`, в отчёт и в модули уведомлений
+    pub async fn run(&mut self) -> Result<CheckReport> {
+        let started = Instant::now();
+        let run_id =
+            self.run_id.take().unwrap_or_else(|| Uuid::now_v7().to_string());
+        self.hooks.run_started();
+        let result = self
+            .run_checks(&run_id)
+            .instrument(tracing::info_span!("check_run", run_id = %run_id))
+            .await;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        counter!(metrics::RUNS, "result" => outcome).increment(1);
+        histogram!(metrics::RUN_DURATION).record(started.elapsed().as_secs_f64());
+        self.hooks.run_finished(&result);
+        result
+    }
+
+    /// Запуск проверки с выдачей результатов по мере готовности, для больших зон
+    /// и интерфейсов реального времени. Записи не накапливаются в памяти:
+    /// каждая отправляется в канал, а возвращаемый отчёт содержит только время
+    /// запуска и пороги. Уведомления отправляются в конце запуска, как и в [`run`].
+    /// Канал ограничен `buffer` записями: медленный получатель притормаживает
+    /// обработку результатов, закрытый получатель не прерывает проверку.
+    ///
+    /// [`run`]: DomainCheckerService::run
+    pub fn run_streaming(
+        mut self,
+        buffer: usize,
+    ) -> (mpsc::Receiver<ReportEntry>, JoinHandle<Result<CheckReport>>) {
+        let (sender, receiver) = mpsc::channel(buffer.max(1));
+        self.stream = Some(sender);
+        // Канал закрывается вместе с сервисом по завершении проверки
+        let handle = tokio::spawn(async move { self.run().await });
+        (receiver, handle)
+    }
+
+    /// Сохраняет запись в отчёте или отправляет её в канал потокового запуска
+    async fn deliver(&self, report: &mut CheckReport, entry: ReportEntry) {
+        report.stats.record(&entry);
+        self.track_change(&mut report.changes, &entry);
+        self.track_issuer(&mut report.issuer_changes, &entry);
+        match &self.stream {
+            Some(stream) => {
+                let _ = stream.send(entry).await;
+            }
+            None => report.push(entry),
+        }
+    }
+
+    fn track_change(&self, changes: &mut RunChanges, entry: &ReportEntry) {
+        let Some(previous_alarms) = &self.previous_alarms else {
+            return;
+        };
+        let previous =
+            previous_alarms.get(&entry.check).and_then(|p| p.get(&entry.hostname));
+        let changed = |previous_days| ChangedEntry {
+            check: entry.check,
+            hostname: entry.hostname.clone(),
+            days: entry.days,
+            previous_days,
+        };
+        match previous {
+            None if entry.is_alarm() => changes.new.push(changed(None)),
+            Some(previous) if entry.status == EntryStatus::Ok => {
+                changes.renewed.push(changed(previous.days))
+            }
+            Some(previous)
+                if previous.status == EntryStatus::Warning
+                    && entry.status == EntryStatus::Critical =>
+            {
+                changes.closer.push(changed(previous.days))
+            }
+            _ => {}
+        }
+    }
+
+    fn track_issuer(&self, issuer_changes: &mut Vec<IssuerChange>, entry: &ReportEntry) {
+        let (Some(known_issuers), Some(issuer)) = (&self.known_issuers, &entry.issuer)
+        else {
+            return;
+        };
+        if entry.check != CheckKind::Ssl {
+            return;
+        }
+        match known_issuers.get(&entry.hostname) {
+            Some(previous_issuer) if previous_issuer != issuer => {
+                issuer_changes.push(IssuerChange {
+                    hostname: entry.hostname.clone(),
+                    previous_issuer: previous_issuer.clone(),
+                    issuer: issuer.clone(),
+                })
+            }
+            _ => {}
+        }
+    }
+
+    /// Хосты в порядке запуска проверок с тегами для бюджетов групп
+    fn budgeted<H: AsRef<str>>(
+        &self,
+        kind: CheckKind,
+        hosts: Vec<H>,
+    ) -> Vec<(H, Vec<String>)> {
+        if self.budgets.is_empty() {
+            return hosts.into_iter().map(|host| (host, Vec::new())).collect();
+        }
+        let hosts = hosts
+            .into_iter()
+            .map(|host| {
+                let tags = self.overrides.resolve(host.as_ref()).tags;
+                (host, tags)
+            })
+            .collect();
+        self.budgets.schedule(kind, hosts)
+    }
+
+    /// Место в бюджетах групп хоста для проверки вне запуска: проверки по запросу
+    /// расходуют те же бюджеты, что и запуски
+    async fn acquire_budget(&self, kind: CheckKind, host: &str) -> BudgetPermit {
+        self.budgets.acquire(kind, &self.overrides.resolve(host).tags).await
+    }
+
+    /// Сколько проверок запускается одновременно. С бюджетами очередь длиннее:
+    /// в ней ждут проверки групп, исчерпавших бюджет, а выполняются не более
+    /// `concurrency` проверок
+    fn queue_width(&self) -> usize {
+        if self.budgets.is_empty() {
+            self.concurrency
+        } else {
+            self.concurrency.saturating_mul(BUDGET_QUEUE_FACTOR)
+        }
+    }
+
+    /// Ожидание `future` не дольше срока запуска; `None`, если срок истёк
+    async fn before_deadline<F: Future>(
+        deadline: Option<tokio::time::Instant>,
+        future: F,
+    ) -> Option<F::Output> {
+        match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, future).await.ok(),
+            None => Some(future.await),
+        }
+    }
+
+    /// Выполняет `future`, превращая панику в ошибку [`TaskPanic`], чтобы упавшая
+    /// задача не прерывала запуск и не пропадала без следа
+    async fn catch_panic<T>(
+        task: &'static str,
+        future: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        match AssertUnwindSafe(future).catch_unwind().await {
+            Ok(result) => result,
+            Err(payload) => {
+                counter!(metrics::TASK_PANICS, "task" => task).increment(1);
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "причина неизвестна".to_string());
+                Err(TaskPanic(message).into())
+            }
+        }
+    }
+
+    fn record_check(entry: &ReportEntry) {
+        counter!(
+            metrics::CHECKS,
+            "kind" => entry.check.as_str(),
+            "status" => entry.status.as_str()
+        )
+        .increment(1);
+    }
+
+    async fn run_checks(&mut self, run_id: &str) -> Result<CheckReport> {
+        let started = Instant::now();
+        let mut report = CheckReport {
+            run_id: run_id.to_string(),
+            ..CheckReport::new(self.alarm_days, self.ssl_alarm_days)
+        };
+        for notifier in &mut self.notifiers {
+            notifier.run_started(run_id).await;
+        }
+        // Имена хранятся один раз: списки хостов для проверки и отчёт ссылаются на них
+        let mut hostnames: HashSet<Arc<str>> = HashSet::new();
+        let mut source_errors = Vec::new();
+
+        let deadline =
+            self.max_run_duration.map(|duration| tokio::time::Instant::now() + duration);
+        let timeout = self.source_timeout;
+        let source_deadline = {
+            let deadline_by_timeout = tokio::time::Instant::now() + timeout;
+            deadline
+                .map_or(deadline_by_timeout, |deadline| deadline.min(deadline_by_timeout))
+        };
+        let loads = self.sources.iter().map(|source| {
+            let span =
+                tracing::info_span!("source_fetch", source = source.get_source_name());
+            // Хосты принимаются по мере загрузки; полученные до ошибки
+            // или истечения времени проверяются
+            async move {
+                let mut domains = Vec::new();
+                let load = async {
+                    let mut stream = source.stream_domains();
+                    while let Some(domain) = stream.next().await {
+                        domains.push(Arc::<str>::from(domain?));
+                    }
+                    Ok::<_, anyhow::Error>(())
+                };
+                let loaded = tokio::time::timeout_at(
+                    source_deadline,
+                    Self::catch_panic("source", load),
+                )
+                .await
+                .unwrap_or_else(|_| {
+                    Err(anyhow::anyhow!(
+                        "превышено время загрузки ({} с)",
+                        timeout.as_secs()
+                    ))
+                });
+                (source.get_source_name(), domains, loaded, source.owners())
+            }
+            .instrument(span)
+        });
+
+        let inventory_loads = self.inventories.iter().map(|inventory| {
+            let span = tracing::info_span!(
+                "inventory_fetch",
+                source = inventory.get_source_name()
+            );
+            async move {
+                let loaded = tokio::time::timeout_at(
+                    source_deadline,
+                    Self::catch_panic("inventory", inventory.get_certificates()),
+                )
+                .await
+                .unwrap_or_else(|_| {
+                    Err(anyhow::anyhow!(
+                        "превышено время загрузки ({} с)",
+                        timeout.as_secs()
+                    ))
+                });
+                (inventory.get_source_name(), loaded)
+            }
+            .instrument(span)
+        });
+
+        let (loaded_sources, loaded_inventories) = futures::future::join(
+            futures::future::join_all(loads),
+            futures::future::join_all(inventory_loads),
+        )
+        .await;
+        self.source_owners.clear();
+        for (source_name, domains, loaded, owners) in loaded_sources {
+            let owners = owners.into_iter().map(|(host, owner)| (host.to_lowercase(), owner));
+            self.source_owners.extend(owners);
+            report.stats.sources.push(SourceStats {
+                name: source_name.to_string(),
+                loaded: domains.len(),
+                failed: loaded.is_err(),
+            });
+            hostnames.extend(domains);
+            if let Err(e) = loaded {
+                counter!(metrics::SOURCE_ERRORS, "source" => source_name).increment(1);
+                tracing::error!(
+                    dcl = self.dcl,
+                    e = %e,
+                    source=source_name,
+                    "Ошибка загрузки из источника"
+                );
+                let error = CheckError::new(ErrorKind::Source, e.to_string())
+                    .with_source(source_name);
+                source_errors.push(self.track_error(error, report.checked_at));
+            }
+        }
+        // Сертификаты хранилищ учитываются в статистике источников, ошибки
+        // загрузки — как ошибки источника
+        let mut inventory_certificates: Vec<InventoryCertificate> = Vec::new();
+        for (source_name, loaded) in loaded_inventories {
+            report.stats.sources.push(SourceStats {
+                name: source_name.to_string(),
+                loaded: loaded.as_ref().map_or(0, Vec::len),
+                failed: loaded.is_err(),
+            });
+            match loaded {
+                Ok(certificates) => inventory_certificates.extend(certificates),
+                Err(e) => {
+                    counter!(metrics::SOURCE_ERRORS, "source" => source_name)
+                        .increment(1);
+                    tracing::error!(
+                        dcl = self.dcl,
+                        e = %e,
+                        source = source_name,
+                        "Ошибка загрузки из менеджера сертификатов"
+                    );
+                    let error = CheckError::new(ErrorKind::Source, e.to_string())
+                        .with_source(source_name);
+                    source_errors.push(self.track_error(error, report.checked_at));
+                }
+            }
+        }
+
+        self.inventory_renewals = RenewalIndex::from_inventory(&inventory_certificates);
+
+        for error in source_errors {
+            self.notify_exception(&error).await;
+            report.errors.push(error);
+        }
+
+        // Сертификаты из файлов проверяются и без списка доменов
+        if hostnames.is_empty()
+            && self.certificate_files.is_empty()
+            && self.credentials.is_empty()
+            && inventory_certificates.is_empty()
+        {
+            tracing::warn!(dcl = self.dcl, "Не удалось загрузить список доменов");
+            self.emit(CheckProgress::Finished);
+            return Ok(report);
+        }
+
+        tracing::info!(dcl = self.dcl, count = hostnames.len(), "Загружены домены");
+        gauge!(metrics::HOSTNAMES).set(hostnames.len() as f64);
+        if self.stream.is_none() {
+            report.hostnames = hostnames.iter().cloned().collect();
+            report.hostnames.sort();
+        }
+
+        let mut expiring_domains: HashMap<String, DomainCheckResult> = HashMap::new();
+        // Ошибки проверок; отправляются после всех проверок, сгруппированными по виду
+        let mut failures: Vec<CheckError> = Vec::new();
+        // Ошибки SSL, о которых ещё рано сообщать: попадают только в отчёт
+        let mut debounced: Vec<CheckError> = Vec::new();
+        // Зона → число проверенных доменов и домены с ошибкой WHOIS
+        let mut whois_zones: HashMap<String, (usize, Vec<String>)> = HashMap::new();
+
+        let root_hostnames: HashSet<String> = if self.check_domains {
+            self.whois_targets(hostnames.iter())
+        } else {
+            HashSet::new()
+        };
+        let ssl_hostnames: HashSet<Arc<str>> = if self.check_ssl {
+            self.ssl_targets(hostnames.iter())
+        } else {
+            HashSet::new()
+        };
+        // Сертификаты SSH проверяются у хостов с `ssh: true` в `domains`,
+        // независимо от `skip_ssl`; wildcard-записи не проверяются
+        let ssh_hostnames: Vec<Arc<str>> = if self.check_ssl {
+            hostnames
+                .iter()
+                .filter(|hostname| !hostname.starts_with("*.") && self.checks_ssh(hostname))
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        // Файлы читаются сразу: это быстро, и число записей известно только после
+        // разбора
+        let file_entries: Vec<ReportEntry> = if self.check_ssl {
+            self.certificate_files
+                .iter()
+                .flat_map(|file| certificate_files::scan_file(file, self.ssl_alarm_days))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let inventory_entries: Vec<ReportEntry> = if self.check_ssl {
+            inventory_certificates
+                .iter()
+                .map(|certificate| {
+                    inventory::certificate_entry(certificate, self.ssl_alarm_days)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        drop(inventory_certificates);
+        // Отбираются только имена: записи пересчитываются при передаче в отчёт,
+        // чтобы не держать их в памяти дважды
+        let (reused_roots, root_hostnames): (Vec<String>, Vec<String>) = root_hostnames
+            .into_iter()
+            .partition(|root| self.reused_domain_entry(root).is_some());
+        let (reused_ssl, ssl_hostnames): (Vec<Arc<str>>, Vec<Arc<str>>) = ssl_hostnames
+            .into_iter()
+            .partition(|hostname| self.reused_ssl_entry(hostname).is_some());
+        let reused_count = reused_roots.len() + reused_ssl.len();
+        let quic_count = ssl_hostnames.iter().filter(|h| self.checks_quic(h)).count();
+        let total = reused_count
+            + root_hostnames.len()
+            + ssl_hostnames.len()
+            + quic_count
+            + ssh_hostnames.len()
+            + file_entries.len()
+            + inventory_entries.len()
+            + self.credentials.len();
+        self.emit(CheckProgress::Started { total });
+        if self.stream.is_none() {
+            report.entries.reserve_exact(total);
+        }
+
+        let mut checked = 0;
+        if reused_count > 0 {
+            tracing::info!(
+                dcl = self.dcl,
+                count = reused_count,
+                "Пропущены недавно проверенные хосты вне окна оповещения"
+            );
+        }
+        let reused =
+            reused_roots.iter().filter_map(|root| self.reused_domain_entry(root)).chain(
+                reused_ssl.iter().filter_map(|hostname| self.reused_ssl_entry(hostname)),
+            );
+        for entry in reused {
+            counter!(metrics::CHECKS_REUSED, "kind" => entry.check.as_str()).increment(1);
+            self.hooks.check_completed(&entry);
+            self.emit_checked(&entry);
+            self.deliver(&mut report, entry).await;
+            checked += 1;
+            self.log_progress(checked, total);
+        }
+
+        // Хосты, проверка которых ещё не завершилась; нужны только при ограничении
+        // времени запуска
+        let mut unchecked_roots: HashSet<String> = HashSet::new();
+        let mut unchecked_ssl: HashSet<Arc<str>> = HashSet::new();
+        let mut unchecked_ssh: HashSet<Arc<str>> = HashSet::new();
+        let mut unchecked_credentials: HashSet<String> = HashSet::new();
+        if deadline.is_some() {
+            unchecked_credentials.extend(self.credentials.iter().map(|c| c.name.clone()));
+            unchecked_roots.extend(root_hostnames.iter().cloned());
+            unchecked_ssl.extend(ssl_hostnames.iter().cloned());
+            unchecked_ssh.extend(ssh_hostnames.iter().cloned());
+        }
+
+        // Проверки выполняются не более `concurrency` одновременно, результаты
+        // обрабатываются по мере готовности. Проверки групп, исчерпавших бюджет,
+        // ждут в очереди, не занимая мест остальных
+        let whois_options = self.whois_options.clone();
+        let budgets = self.budgets.clone();
+        let slots = Semaphore::new(self.concurrency);
+        let root_hostnames = self.budgeted(CheckKind::Domain, root_hostnames);
+        let mut domain_checks = stream::iter(root_hostnames)
+            .map(|(root, tags)| {
+                let span = tracing::info_span!("whois_check", domain = %root);
+                let (options, budgets, slots) = (&whois_options, &budgets, &slots);
+                async move {
+                    let _budget = budgets.acquire(CheckKind::Domain, &tags).await;
+                    let _slot = slots.acquire().await;
+                    let started = Instant::now();
+                    let result = Self::catch_panic(
+                        "domain",
+                        checks::check_domain_expiration(&root, options),
+                    )
+                    .await;
+                    histogram!(metrics::CHECK_DURATION, "kind" => "domain")
+                        .record(started.elapsed().as_secs_f64());
+                    (root, result)
+                }
+                .instrument(span)
+            })
+            .buffer_unordered(self.queue_width());
+
+        while let Some(Some((root, check_result))) =
+            Self::before_deadline(deadline, domain_checks.next()).await
+        {
+            unchecked_roots.remove(&root);
+            let zone = root.split_once('.').map_or(root.as_str(), |(_, zone)| zone);
+            let whois_zone = whois_zones.entry(zone.to_string()).or_default();
+            whois_zone.0 += 1;
+            let entry = self.domain_entry(&root, &check_result);
+            Self::record_check(&entry);
+            self.hooks.check_completed(&entry);
+            self.emit_checked(&entry);
+
+            match check_result {
+                Ok(registration) => {
+                    if entry.is_alarm() {
+                        expiring_domains.insert(
+                            root.clone(),
+                            DomainCheckResult {
+                                hostname: root.clone(),
+                                expiration_date: registration.expiration_date,
+                                expires_at: self.expires_at(registration.expiration_date),
+                                days: entry.days.unwrap_or_default(),
+                                severity: entry.severity.unwrap_or_default(),
+                                owner: entry.owner.clone(),
+                            },
+                        );
+                    }
+                }
+                Err(e) if e.is::<TaskPanic>() => {
+                    tracing::error!(
+                        dcl = self.dcl,
+                        domain = root,
+                        error = %e,
+                        "Паника при проверке домена"
+                    );
+                    failures.push(
+                        CheckError::new(ErrorKind::Panic, format!("домен: {}", e))
+                            .with_hostname(root.clone()),
+                    );
+                }
+                Err(e) if entry.status == EntryStatus::Skipped => {
+                    tracing::debug!(
+                        dcl = self.dcl,
+                        domain = root,
+                        error = %e,
+                        "WHOIS скрывает срок регистрации (пропускаем)"
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        dcl = self.dcl,
+                        domain = root,
+                        error = %e,
+                        "Ошибка проверки домена"
+                    );
+                    whois_zone.1.push(root.clone());
+                    failures.push(
+                        CheckError::new(ErrorKind::Domain, e.to_string())
+                            .with_hostname(root.clone()),
+                    );
+                }
+            }
+
+            self.deliver(&mut report, entry).await;
+            checked += 1;
+            self.log_progress(checked, total);
+        }
+
+        let mut expiring_ssl: HashMap<String, SslCheckResult> = HashMap::new();
+        // Доступность OCSP и CRL по адресам: у одного издателя они общие
+        let mut revocation_cache: HashMap<String, Option<String>> = HashMap::new();
+        let (overrides, ssl_options) = (self.overrides.clone(), self.ssl_options.clone());
+        let cert_dump_dir = self.cert_dump_dir.clone();
+        let slots = Semaphore::new(self.concurrency);
+        let mut ssl_checks = stream::iter(self.budgeted(CheckKind::Ssl, ssl_hostnames))
+            .map(|(hostname, tags)| {
+                let span = tracing::info_span!("ssl_check", hostname = %hostname);
+                let (overrides, options) = (&overrides, &ssl_options);
+                let (budgets, slots) = (&budgets, &slots);
+                let cert_dump_dir = cert_dump_dir.as_deref();
+                async move {
+                    let _budget = budgets.acquire(CheckKind::Ssl, &tags).await;
+                    let _slot = slots.acquire().await;
+                    let started = Instant::now();
+                    let result = Self::catch_panic(
+                        "ssl",
+                        Self::check_ssl_with_overrides(overrides, options, &hostname),
+                    )
+                    .await;
+                    histogram!(metrics::CHECK_DURATION, "kind" => "ssl")
+                        .record(started.elapsed().as_secs_f64());
+                    let quic_result = Self::check_quic_with_overrides(
+                        overrides,
+                        options,
+                        &hostname,
+                    )
+                    .await;
+                    if let Some(dir) = cert_dump_dir.filter(|_| result.is_ok()) {
+                        let dumped =
+                            Self::dump_certificate_chain(dir, overrides, options, &hostname);
+                        if let Err(e) = dumped.await {
+                            tracing::warn!(
+                                hostname = %hostname,
+                                error = %e,
+                                "Не удалось сохранить цепочку сертификатов"
+                            );
+                        }
+                    }
+                    (hostname, result, quic_result)
+                }
+                .instrument(span)
+            })
+            .buffer_unordered(self.queue_width());
+
+        while let Some(Some((hostname, check_result, quic_result))) =
+            Self::before_deadline(deadline, ssl_checks.next()).await
+        {
+            unchecked_ssl.remove(&hostname);
+            let tcp_certificate = check_result.as_ref().ok().cloned();
+            let mut entry = self.ssl_entry(&hostname, &check_result);
+            self.check_revocation(&mut entry, &check_result, &mut revocation_cache).await;
+            Self::record_check(&entry);
+            self.hooks.check_completed(&entry);
+            self.emit_checked(&entry);
+
+            match check_result {
+                Ok(CertificateInfo { expiration_date, serial, issuer, .. }) => {
+                    if let Some(renewed) = entry.renewed_expiration_date {
+                        let message = format!(
+                            "на сервере до {}, продлён до {}",
+                            self.expires_at(expiration_date),
+                            self.expires_at(renewed)
+                        );
+                        failures.push(
+                            CheckError::new(ErrorKind::Undeployed, message)
+                                .with_hostname(hostname.to_string()),
+                        );
+                    }
+                    if entry.is_alarm() {
+                        // Один сертификат на нескольких хостах — одна запись
+                        let more =
+                            expiring_ssl.get(&serial).map_or(0, |prev| prev.more + 1);
+                        expiring_ssl.insert(
+                            serial.clone(),
+                            SslCheckResult {
+                                hostname: hostname.to_string(),
+                                serial,
+                                issuer,
+                                expiration_date,
+                                expires_at: self.expires_at(expiration_date),
+                                days: entry.days.unwrap_or_default(),
+                                more,
+                                acme: entry.acme,
+                                severity: entry.severity.unwrap_or_default(),
+                                owner: entry.owner.clone(),
+                                unreachable_revocation: entry.unreachable_revocation(),
+                            },
+                        );
+                    }
+                }
+                Err(e) if e.is::<TaskPanic>() => {
+                    tracing::error!(
+                        dcl = self.dcl,
+                        hostname = %hostname,
+                        error = %e,
+                        "Паника при проверке SSL"
+                    );
+                    failures.push(
+                        CheckError::new(ErrorKind::Panic, format!("сертификат: {}", e))
+                            .with_hostname(hostname.to_string()),
+                    );
+                }
+                Err(e) => {
+                    if entry.status == EntryStatus::Skipped {
+                        tracing::debug!(
+                            dcl = self.dcl,
+                            hostname = %hostname,
+                            error = %e,
+                            "Ожидаемая ошибка SSL (пропускаем)"
+                        );
+                    } else {
+                        let streak = self
+                            .ssl_failure_streaks
+                            .get(&*hostname)
+                            .map_or(1, |streak| streak + 1);
+                        tracing::warn!(
+                            dcl = self.dcl,
+                            hostname = %hostname,
+                            error = %e,
+                            streak,
+                            "Неожиданная ошибка SSL"
+                        );
+                        let error = CheckError::new(ErrorKind::Ssl, e.to_string())
+                            .with_hostname(hostname.to_string());
+                        if streak >= self.ssl_failures_to_notify {
+                            failures.push(error);
+                        } else {
+                            debounced.push(error);
+                        }
+                    }
+                }
+            }
+
+            self.deliver(&mut report, entry).await;
+            checked += 1;
+            self.log_progress(checked, total);
+
+            let Some(quic_result) = quic_result else {
+                continue;
+            };
+            let entry = ReportEntry {
+                check: CheckKind::Quic,
+                ..self.ssl_entry(&hostname, &quic_result)
+            };
+            Self::record_check(&entry);
+            self.hooks.check_completed(&entry);
+            self.emit_checked(&entry);
+            match (&quic_result, &tcp_certificate) {
+                // Тот же сертификат, что и по TCP, уже учтён в уведомлениях
+                (Ok(quic), Some(tcp)) if quic.serial == tcp.serial => {}
+                (Ok(quic), tcp) => {
+                    if let Some(tcp) = tcp {
+                        tracing::warn!(
+                            dcl = self.dcl,
+                            hostname = %hostname,
+                            tcp = %tcp.serial,
+                            quic = %quic.serial,
+                            "Сертификат по QUIC отличается от TCP"
+                        );
+                        let message = format!(
+                            "TCP {} до {}, QUIC {} до {}",
+                            tcp.serial,
+                            self.expires_at(tcp.expiration_date),
+                            quic.serial,
+                            self.expires_at(quic.expiration_date)
+                        );
+                        failures.push(
+                            CheckError::new(ErrorKind::QuicMismatch, message)
+                                .with_hostname(hostname.to_string()),
+                        );
+                    }
+                    if entry.is_alarm() {
+                        let more = expiring_ssl
+                            .get(&quic.serial)
+                            .map_or(0, |prev| prev.more + 1);
+                        expiring_ssl.insert(
+                            quic.serial.clone(),
+                            SslCheckResult {
+                                hostname: format!("{} (QUIC)", hostname),
+                                serial: quic.serial.clone(),
+                                issuer: quic.issuer.clone(),
+                                expiration_date: quic.expiration_date,
+                                expires_at: self.expires_at(quic.expiration_date),
+                                days: entry.days.unwrap_or_default(),
+                                more,
+                                acme: entry.acme,
+                                severity: entry.severity.unwrap_or_default(),
+                                owner: entry.owner.clone(),
+                                unreachable_revocation: entry.unreachable_revocation(),
+                            },
+                        );
+                    }
+                }
+                (Err(e), _) if e.is::<TaskPanic>() => {
+                    failures.push(
+                        CheckError::new(ErrorKind::Panic, format!("QUIC: {}", e))
+                            .with_hostname(hostname.to_string()),
+                    );
+                }
+                (Err(e), _) if entry.status == EntryStatus::Skipped => {
+                    tracing::debug!(
+                        dcl = self.dcl,
+                        hostname = %hostname,
+                        error = %e,
+                        "Ожидаемая ошибка QUIC (пропускаем)"
+                    );
+                }
+                (Err(e), _) => {
+                    tracing::warn!(
+                        dcl = self.dcl,
+                        hostname = %hostname,
+                        error = %e,
+                        "Ошибка проверки по QUIC"
+                    );
+                    failures.push(
+                        CheckError::new(ErrorKind::Quic, e.to_string())
+                            .with_hostname(hostname.to_string()),
+                    );
+                }
+            }
+            self.deliver(&mut report, entry).await;
+            checked += 1;
+            self.log_progress(checked, total);
+        }
+
+        drop(domain_checks);
+        drop(ssl_checks);
+
+        let mut ssh_checks = stream::iter(ssh_hostnames)
+            .map(|hostname| {
+                let span = tracing::info_span!("ssh_check", hostname = %hostname);
+                let (overrides, options) = (&overrides, &ssl_options);
+                async move {
+                    let started = Instant::now();
+                    let result = Self::catch_panic(
+                        "ssh",
+                        Self::check_ssh_with_overrides(overrides, options, &hostname),
+                    )
+                    .await;
+                    histogram!(metrics::CHECK_DURATION, "kind" => "ssh")
+                        .record(started.elapsed().as_secs_f64());
+                    (hostname, result)
+                }
+                .instrument(span)
+            })
+            .buffer_unordered(self.concurrency);
+
+        while let Some(Some((hostname, check_result))) =
+            Self::before_deadline(deadline, ssh_checks.next()).await
+        {
+            unchecked_ssh.remove(&hostname);
+            let entry = self.ssh_entry(&hostname, &check_result);
+            Self::record_check(&entry);
+            self.hooks.check_completed(&entry);
+            self.emit_checked(&entry);
+
+            match check_result {
+                Ok(CertificateInfo { expiration_date, serial, issuer, .. }) => {
+                    if entry.is_alarm() {
+                        // Серийные номера сертификатов SSH часто не заполняются,
+                        // поэтому запись — на каждый хост
+                        expiring_ssl.insert(
+                            format!("ssh:{}", hostname),
+                            SslCheckResult {
+                                hostname: format!("{} (SSH)", hostname),
+                                serial,
+                                issuer,
+                                expiration_date,
+                                expires_at: self.expires_at(expiration_date),
+                                days: entry.days.unwrap_or_default(),
+                                more: 0,
+                                acme: false,
+                                severity: entry.severity.unwrap_or_default(),
+                                owner: entry.owner.clone(),
+                                unreachable_revocation: Vec::new(),
+                            },
+                        );
+                    }
+                }
+                Err(e) if e.is::<TaskPanic>() => {
+                    failures.push(
+                        CheckError::new(ErrorKind::Panic, format!("SSH: {}", e))
+                            .with_hostname(hostname.to_string()),
+                    );
+                }
+                Err(e) if entry.status == EntryStatus::Skipped => {
+                    tracing::debug!(
+                        dcl = self.dcl,
+                        hostname = %hostname,
+                        error = %e,
+                        "Ожидаемая ошибка SSH (пропускаем)"
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        dcl = self.dcl,
+                        hostname = %hostname,
+                        error = %e,
+                        "Ошибка проверки сертификата SSH"
+                    );
+                    failures.push(
+                        CheckError::new(ErrorKind::Ssh, e.to_string())
+                            .with_hostname(hostname.to_string()),
+                    );
+                }
+            }
+
+            self.deliver(&mut report, entry).await;
+            checked += 1;
+            self.log_progress(checked, total);
+        }
+        drop(ssh_checks);
+
+        for entry in file_entries {
+            let entry = match (entry.expiration_date, entry.days) {
+                (Some(expiration_date), Some(days)) => {
+                    let window_days = self.window_days(expiration_date, days);
+                    entry.with_severity(self.ssl_severity(window_days, days))
+                }
+                _ => entry,
+            };
+            Self::record_check(&entry);
+            self.hooks.check_completed(&entry);
+            self.emit_checked(&entry);
+
+            if let Some(error) = &entry.error {
+                tracing::warn!(
+                    dcl = self.dcl,
+                    path = entry.hostname,
+                    error = %error,
+                    "Ошибка чтения файла сертификатов"
+                );
+                failures.push(
+                    CheckError::new(ErrorKind::CertificateFile, error.clone())
+                        .with_hostname(entry.hostname.clone()),
+                );
+            } else if entry.is_alarm()
+                && let Some(expiration_date) = entry.expiration_date
+            {
+                expiring_ssl.insert(
+                    format!("file:{}", entry.hostname),
+                    SslCheckResult {
+                        hostname: entry.hostname.clone(),
+                        serial: entry.serial.clone().unwrap_or_default(),
+                        issuer: entry.issuer.clone().unwrap_or_default(),
+                        expiration_date,
+                        expires_at: self.expires_at(expiration_date),
+                        days: entry.days.unwrap_or_default(),
+                        more: 0,
+                        acme: false,
+                        severity: entry.severity.unwrap_or_default(),
+                        owner: entry.owner.clone(),
+                        unreachable_revocation: Vec::new(),
+                    },
+                );
+            }
+
+            self.deliver(&mut report, entry).await;
+            checked += 1;
+            self.log_progress(checked, total);
+        }
+
+        for entry in inventory_entries {
+            let severity = match (entry.expiration_date, entry.days) {
+                (Some(expiration_date), Some(days)) => {
+                    let window_days = self.window_days(expiration_date, days);
+                    Some(self.ssl_severity(window_days, days))
+                }
+                _ => None,
+            };
+            let entry = match severity {
+                Some(severity) => entry.with_severity(severity),
+                None => entry,
+            };
+            Self::record_check(&entry);
+            self.hooks.check_completed(&entry);
+            self.emit_checked(&entry);
+
+            // Сертификат, который не продлится сам, попадает в ошибки, а если срок
+            // подходит к концу — и в оповещение о сроке
+            if let Some(error) = &entry.error {
+                tracing::warn!(
+                    dcl = self.dcl,
+                    certificate = entry.hostname,
+                    error = %error,
+                    "Сертификат не продлится автоматически"
+                );
+                failures.push(
+                    CheckError::new(ErrorKind::Renewal, error.clone())
+                        .with_hostname(entry.hostname.clone()),
+                );
+            }
+            let in_window = entry.is_alarm()
+                || entry.error.is_some()
+                    && entry.days.is_some_and(|days| days <= self.ssl_alarm_days);
+            if in_window && let Some(expiration_date) = entry.expiration_date {
+                expiring_ssl.insert(
+                    format!("inventory:{}", entry.hostname),
+                    SslCheckResult {
+                        hostname: entry.hostname.clone(),
+                        serial: entry.serial.clone().unwrap_or_default(),
+                        issuer: entry.issuer.clone().unwrap_or_default(),
+                        expiration_date,
+                        expires_at: self.expires_at(expiration_date),
+                        days: entry.days.unwrap_or_default(),
+                        more: 0,
+                        acme: false,
+                        severity: entry.severity.or(severity).unwrap_or_default(),
+                        owner: entry.owner.clone(),
+                        unreachable_revocation: Vec::new(),
+                    },
+                );
+            }
+
+            self.deliver(&mut report, entry).await;
+            checked += 1;
+            self.log_progress(checked, total);
+        }
+
+        let mut expiring_credentials: Vec<DomainCheckResult> = Vec::new();
+        let credentials = self.credentials.clone();
+        let vault = self.vault.clone();
+        let mut credential_checks = stream::iter(credentials)
+            .map(|config| {
+                let vault = vault.as_ref();
+                async move {
+                    let result = Self::catch_panic(
+                        "credential",
+                        credentials::credential_expiry(&config, vault),
+                    )
+                    .await;
+                    (config, result)
+                }
+            })
+            .buffer_unordered(self.concurrency);
+
+        while let Some(Some((config, check_result))) =
+            Self::before_deadline(deadline, credential_checks.next()).await
+        {
+            unchecked_credentials.remove(&config.name);
+            let entry = self.credential_entry(&config, &check_result);
+            Self::record_check(&entry);
+            self.hooks.check_completed(&entry);
+            self.emit_checked(&entry);
+
+            match check_result {
+                Ok(expiration_date) => {
+                    if entry.is_alarm() {
+                        expiring_credentials.push(DomainCheckResult {
+                            hostname: config.name.clone(),
+                            expiration_date,
+                            expires_at: self.expires_at(expiration_date),
+                            days: entry.days.unwrap_or_default(),
+                            severity: entry.severity.unwrap_or_default(),
+                            owner: entry.owner.clone(),
+                        });
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        dcl = self.dcl,
+                        credential = config.name,
+                        error = %e,
+                        "Не удалось узнать срок учётных данных"
+                    );
+                    let kind = if e.is::<TaskPanic>() {
+                        ErrorKind::Panic
+                    } else {
+                        ErrorKind::Credential
+                    };
+                    failures.push(
+                        CheckError::new(kind, e.to_string()).with_hostname(config.name),
+                    );
+                }
+            }
+
+            self.deliver(&mut report, entry).await;
+            checked += 1;
+            self.log_progress(checked, total);
+        }
+        drop(credential_checks);
+
+        let mut expiring_mail: HashMap<String, SslCheckResult> = HashMap::new();
+        let mut unchecked_mail: HashSet<String> = HashSet::new();
+        if let Some(mail_options) = self.mail_options.clone() {
+            let roots: HashSet<String> =
+                hostnames.iter().filter_map(|h| checks::root_domain(h)).collect();
+            let mut resolves = stream::iter(roots)
+                .map(|root| {
+                    let options = &mail_options;
+                    async move {
+                        let result = mail::resolve_mx(&root, options).await;
+                        (root, result)
+                    }
+                })
+                .buffer_unordered(self.concurrency);
+            // Один почтовый сервер может обслуживать много доменов
+            let mut mail_hostnames: HashSet<String> = HashSet::new();
+            while let Some(Some((root, result))) =
+                Self::before_deadline(deadline, resolves.next()).await
+            {
+                match result {
+                    Ok(exchanges) => mail_hostnames.extend(exchanges),
+                    Err(e) => tracing::warn!(
+                        dcl = self.dcl,
+                        domain = root,
+                        error = %e,
+                        "Не удалось получить MX-записи домена"
+                    ),
+                }
+            }
+            drop(resolves);
+            // Число почтовых серверов известно только после запроса MX
+            self.emit(CheckProgress::Added { count: mail_hostnames.len() });
+            let total = total + mail_hostnames.len();
+            if deadline.is_some() {
+                unchecked_mail.extend(mail_hostnames.iter().cloned());
+            }
+
+            let mut mail_checks = stream::iter(mail_hostnames)
+                .map(|hostname| {
+                    let span = tracing::info_span!("mail_check", hostname = %hostname);
+                    let options = &mail_options;
+                    async move {
+                        let started = Instant::now();
+                        let result = Self::catch_panic(
+                            "mail",
+                            mail::check_starttls(&hostname, options),
+                        )
+                        .await;
+                        histogram!(metrics::CHECK_DURATION, "kind" => "mail")
+                            .record(started.elapsed().as_secs_f64());
+                        (hostname, result)
+                    }
+                    .instrument(span)
+                })
+                .buffer_unordered(self.concurrency);
+
+            while let Some(Some((hostname, check_result))) =
+                Self::before_deadline(deadline, mail_checks.next()).await
+            {
+                unchecked_mail.remove(&hostname);
+                let mut entry = ReportEntry {
+                    check: CheckKind::Mail,
+                    ..self.ssl_entry(&hostname, &check_result)
+                };
+                self.check_revocation(&mut entry, &check_result, &mut revocation_cache)
+                    .await;
+                Self::record_check(&entry);
+                self.hooks.check_completed(&entry);
+                self.emit_checked(&entry);
+
+                match check_result {
+                    Ok(CertificateInfo { expiration_date, serial, issuer, .. }) => {
+                        if entry.is_alarm() {
+                            let more = expiring_mail
+                                .get(&serial)
+                                .map_or(0, |prev| prev.more + 1);
+                            expiring_mail.insert(
+                                serial.clone(),
+                                SslCheckResult {
+                                    hostname: hostname.clone(),
+                                    serial,
+                                    issuer,
+                                    expiration_date,
+                                    expires_at: self.expires_at(expiration_date),
+                                    days: entry.days.unwrap_or_default(),
+                                    more,
+                                    acme: entry.acme,
+                                    severity: entry.severity.unwrap_or_default(),
+                                    owner: entry.owner.clone(),
+                                    unreachable_revocation: entry
+                                        .unreachable_revocation(),
+                                },
+                            );
+                        }
+                    }
+                    Err(e) if e.is::<TaskPanic>() => {
+                        tracing::error!(
+                            dcl = self.dcl,
+                            hostname = %hostname,
+                            error = %e,
+                            "Паника при проверке почтового сервера"
+                        );
+                        let message = format!("почтовый сервер: {}", e);
+                        failures.push(
+                            CheckError::new(ErrorKind::Panic, message)
+                                .with_hostname(hostname.clone()),
+                        );
+                    }
+                    Err(e) => {
+                        if entry.status == EntryStatus::Skipped {
+                            tracing::debug!(
+                                dcl = self.dcl,
+                                hostname = %hostname,
+                                error = %e,
+                                "Ожидаемая ошибка почтового сервера (пропускаем)"
+                            );
+                        } else {
+                            tracing::warn!(
+                                dcl = self.dcl,
+                                hostname = %hostname,
+                                error = %e,
+                                "Ошибка проверки почтового сервера"
+                            );
+                            failures.push(
+                                CheckError::new(ErrorKind::Mail, e.to_string())
+                                    .with_hostname(hostname.clone()),
+                            );
+                        }
+                    }
+                }
+
+                self.deliver(&mut report, entry).await;
+                checked += 1;
+                self.log_progress(checked, total);
+            }
+        }
+
+        let unchecked_count = unchecked_roots.len()
+            + unchecked_ssl.len()
+            + unchecked_ssh.len()
+            + unchecked_credentials.len()
+            + unchecked_mail.len();
+        if unchecked_count > 0 {
+            tracing::warn!(
+                dcl = self.dcl,
+                count = unchecked_count,
+                "Истекло время запуска, проверка прервана"
+            );
+            let unchecked = unchecked_roots
+                .iter()
+                .map(|root| (CheckKind::Domain, root.as_str()))
+                .chain(
+                    unchecked_ssl.iter().map(|hostname| (CheckKind::Ssl, &**hostname)),
+                )
+                .chain(
+                    unchecked_ssh.iter().map(|hostname| (CheckKind::Ssh, &**hostname)),
+                )
+                .chain(
+                    unchecked_credentials
+                        .iter()
+                        .map(|name| (CheckKind::Credential, name.as_str())),
+                )
+                .chain(
+                    unchecked_mail
+                        .iter()
+                        .map(|hostname| (CheckKind::Mail, hostname.as_str())),
+                );
+            for (check, hostname) in unchecked {
+                let entry =
+                    ReportEntry::not_checked(check, hostname, Self::NOT_CHECKED_IN_TIME);
+                Self::record_check(&entry);
+                self.hooks.check_completed(&entry);
+                self.emit_checked(&entry);
+                self.deliver(&mut report, entry).await;
+            }
+        }
+
+        if unchecked_count > 0 {
+            failures.push(CheckError::new(
+                ErrorKind::Deadline,
+                format!(
+                    "истекло время запуска ({} с), не проверено хостов: {}",
+                    self.max_run_duration.unwrap_or_default().as_secs(),
+                    unchecked_count
+                ),
+            ));
+        }
+        // Если не ответил ни один домен зоны, скорее всего недоступен её WHOIS-сервер:
+        // вместо ошибки по каждому домену отправляется одно сообщение
+        let mut whois_outages: Vec<_> = whois_zones
+            .into_iter()
+            .filter(|(_, (checked, failed))| {
+                failed.len() >= Self::WHOIS_OUTAGE_MIN_DOMAINS && failed.len() == *checked
+            })
+            .collect();
+        whois_outages.sort();
+        for (zone, (_, failed)) in whois_outages {
+            failures.retain(|error| {
+                error.kind != ErrorKind::Domain
+                    || !error.hostname.as_ref().is_some_and(|root| failed.contains(root))
+            });
+            let server = checks::whois_server(&failed[0], &self.whois_options)
+                .unwrap_or_else(|| format!("WHOIS-сервер зоны .{}", zone));
+            tracing::error!(
+                dcl = self.dcl,
+                server = %server,
+                zone = %zone,
+                count = failed.len(),
+                "WHOIS-сервер не ответил ни по одному домену зоны"
+            );
+            let message =
+                format!("пропущено доменов зоны .{}: {}", zone, failed.len());
+            failures.push(
+                CheckError::new(ErrorKind::WhoisOutage, message).with_source(server),
+            );
+        }
+        if !report.issuer_changes.is_empty() {
+            tracing::warn!(
+                dcl = self.dcl,
+                count = report.issuer_changes.len(),
+                "Сменился издатель сертификатов"
+            );
+            report.issuer_changes.sort_by(|a, b| a.hostname.cmp(&b.hostname));
+            failures.extend(report.issuer_changes.iter().map(|change| {
+                let message = format!("{} → {}", change.previous_issuer, change.issuer);
+                CheckError::new(ErrorKind::IssuerChange, message)
+                    .with_hostname(change.hostname.clone())
+            }));
+        }
+        let undeployed =
+            failures.iter().filter(|error| error.kind == ErrorKind::Undeployed).count();
+        if undeployed > 0 {
+            tracing::warn!(
+                dcl = self.dcl,
+                count = undeployed,
+                "Продлённые сертификаты не развёрнуты на серверах"
+            );
+        }
+
+        let checked_at = report.checked_at;
+        let mut failures: Vec<CheckError> = failures
+            .into_iter()
+            .map(|error| self.track_error(error, checked_at))
+            .collect();
+        failures.sort_by(|a, b| (a.kind, &a.hostname).cmp(&(b.kind, &b.hostname)));
+        let mut pending = 0;
+        for error in &failures {
+            self.notify_exception(error).await;
+            self.notified(&mut pending).await;
+        }
+        report.errors.extend(failures);
+        for error in debounced {
+            report.errors.push(self.track_error(error, checked_at));
+        }
+
+        let mut expiring_list: Vec<_> = expiring_domains.into_values().collect();
+        expiring_list.sort_by_key(|entry| entry.days);
+
+        for entry in expiring_list {
+            self.notify_expiration(entry).await;
+            self.notified(&mut pending).await;
+        }
+
+        expiring_credentials.sort_by_key(|entry| entry.days);
+        for entry in expiring_credentials {
+            self.notify_credential_expiration(entry).await;
+            self.notified(&mut pending).await;
+        }
+
+        let mut expiring_ssl_list: Vec<_> = expiring_ssl.into_values().collect();
+        expiring_ssl_list.sort_by_key(|entry| entry.days);
+
+        for entry in expiring_ssl_list {
+            self.notify_ssl_expiration(entry).await;
+            self.notified(&mut pending).await;
+        }
+
+        let mut expiring_mail_list: Vec<_> = expiring_mail.into_values().collect();
+        expiring_mail_list.sort_by_key(|entry| entry.days);
+
+        for entry in expiring_mail_list {
+            self.notify_mail_expiration(entry).await;
+            self.notified(&mut pending).await;
+        }
+
+        report.changes.sort();
+        report.stats.duration = started.elapsed();
+        for notifier in &mut self.notifiers {
+            if !report.changes.is_empty() {
+                notifier.changes(&report.changes).await;
+            }
+            notifier.stats(&report.stats).await;
+        }
+        self.commit().await?;
+
+        tracing::info!(dcl = self.dcl, "Проверка завершена");
+
+        report.sort();
+        self.emit(CheckProgress::Finished);
+        Ok(report)
+    }
+}
@@ -0,0 +1,155 @@
+//! Прогноз истечений сертификатов по неделям или месяцам: сколько сертификатов
+//! предстоит продлить в каждом периоде, с разбивкой по издателям и тегам.
+//! Строится по отчёту запуска, например последнему из файла состояния
+
+use crate::report::CheckReport;
+use base::prelude::{
+    anyhow::{Error, anyhow},
+    chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
+
+/// Ключ издателя, если он неизвестен
+const UNKNOWN_ISSUER: &str = "unknown";
+
+/// Длина периода прогноза
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForecastPeriod {
+    /// Неделя с понедельника
+    Week,
+    /// Календарный месяц
+    #[default]
+    Month,
+}
+
+impl ForecastPeriod {
+    /// Первый день периода, в который попадает `date`
+    fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            ForecastPeriod::Week => {
+                date - Days::new(u64::from(date.weekday().num_days_from_monday()))
+            }
+            ForecastPeriod::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn next(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            ForecastPeriod::Week => start + Days::new(7),
+            ForecastPeriod::Month => start + Months::new(1),
+        }
+    }
+}
+
+impl FromStr for ForecastPeriod {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "week" => Ok(ForecastPeriod::Week),
+            "month" => Ok(ForecastPeriod::Month),
+            _ => Err(anyhow!("ожидается week или month, указано {:?}", value)),
+        }
+    }
+}
+
+/// Сертификаты, истекающие в одном периоде
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForecastBucket {
+    /// Первый день периода: понедельник недели или первое число месяца
+    pub start: NaiveDate,
+    pub total: usize,
+    /// Число сертификатов по издателям
+    pub issuers: BTreeMap<String, usize>,
+    /// Число сертификатов по тегам; сертификат без тегов сюда не попадает
+    pub tags: BTreeMap<String, usize>,
+}
+
+/// Прогноз истечений на ближайшие месяцы
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiryForecast {
+    /// Время проверки отчёта, по которому построен прогноз
+    pub checked_at: DateTime<Utc>,
+    pub period: ForecastPeriod,
+    /// Конец горизонта прогноза, не включая
+    pub until: DateTime<Utc>,
+    /// Сертификаты, срок которых уже истёк
+    pub expired: usize,
+    /// Периоды подряд от текущего до конца горизонта, в том числе пустые
+    pub buckets: Vec<ForecastBucket>,
+}
+
+impl ExpiryForecast {
+    /// Прогноз по сертификатам отчёта на `months` месяцев от `now`. Учитываются
+    /// сертификаты хостов, почтовых серверов, SSH, файлов и хранилищ сертификатов,
+    /// но не хранилища доверия. Сертификат, который отдают несколько хостов,
+    /// считается один раз — по издателю и серийному номеру
+    pub fn from_report(
+        report: &CheckReport,
+        now: DateTime<Utc>,
+        months: u32,
+        period: ForecastPeriod,
+    ) -> Self {
+        let until = now + Months::new(months);
+        let today = now.date_naive();
+        let mut buckets = Vec::new();
+        let mut start = period.start(today);
+        while start < until.date_naive() {
+            buckets.push(ForecastBucket {
+                start,
+                total: 0,
+                issuers: BTreeMap::new(),
+                tags: BTreeMap::new(),
+            });
+            start = period.next(start);
+        }
+
+        let mut seen = HashSet::new();
+        let mut expired = 0;
+        for entry in &report.entries {
+            let renewable = entry.check.is_renewable_certificate();
+            let Some(expiration_date) = entry.expiration_date.filter(|_| renewable)
+            else {
+                continue;
+            };
+            let key = match &entry.serial {
+                Some(serial) => (entry.issuer.clone(), serial.clone()),
+                None => (None, entry.hostname.clone()),
+            };
+            if !seen.insert(key) {
+                continue;
+            }
+            if expiration_date <= now {
+                expired += 1;
+                continue;
+            }
+            if expiration_date >= until {
+                continue;
+            }
+            let start = period.start(expiration_date.date_naive());
+            let Some(bucket) = buckets.iter_mut().find(|bucket| bucket.start == start)
+            else {
+                continue;
+            };
+            bucket.total += 1;
+            let issuer = entry.issuer.as_deref().filter(|issuer| !issuer.is_empty());
+            *bucket
+                .issuers
+                .entry(issuer.unwrap_or(UNKNOWN_ISSUER).to_string())
+                .or_default() += 1;
+            for tag in &entry.tags {
+                *bucket.tags.entry(tag.clone()).or_default() += 1;
+            }
+        }
+
+        Self { checked_at: report.checked_at, period, until, expired, buckets }
+    }
+
+    /// Всего сертификатов, истекающих в горизонте прогноза
+    pub fn total(&self) -> usize {
+        self.buckets.iter().map(|bucket| bucket.total).sum()
+    }
+}
@@ -0,0 +1,689 @@
+//! Сертификаты Google Cloud: Certificate Manager и классические SSL-сертификаты
+//! балансировщиков (Compute Engine) со сроками и состоянием выпуска управляемых
+//! сертификатов. Токен доступа выдаётся по ключу сервисного аккаунта или
+//! сервером метаданных
+
+use crate::http::HttpSettings;
+use crate::inventory::{CertificateInventoryTrait, InventoryCertificate};
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow, bail},
+    chrono::{DateTime, Utc},
+    serde_json::{self, json},
+    tokio, tracing,
+};
+use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+use reqwest::Client;
+use serde::{Deserialize, de::DeserializeOwned};
+use std::collections::{HashMap, HashSet};
+
+const SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform.read-only";
+/// `urn:ietf:params:oauth:grant-type:jwt-bearer` в кодировке формы
+const JWT_BEARER_GRANT: &str = "urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer";
+const METADATA_TOKEN_URL: &str = "http://metadata.google.internal/computeMetadata/v1/\
+                                  instance/service-accounts/default/token";
+const CERTIFICATE_MANAGER_URL: &str = "https://certificatemanager.googleapis.com/v1";
+const COMPUTE_URL: &str = "https://compute.googleapis.com/compute/v1";
+
+/// Ключ сервисного аккаунта; разбираются только нужные поля
+#[derive(Deserialize)]
+struct ServiceAccountKey {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    client_email: String,
+    #[serde(default)]
+    private_key: String,
+    #[serde(default = "default_token_uri")]
+    token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    "https://oauth2.googleapis.com/token".to_string()
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManagerPage {
+    #[serde(default)]
+    certificates: Vec<ManagerCertificate>,
+    next_page_token: Option<String>,
+}
+
+/// Сертификат Certificate Manager
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManagerCertificate {
+    name: String,
+    #[serde(default)]
+    san_dnsnames: Vec<String>,
+    expire_time: Option<DateTime<Utc>>,
+    managed: Option<ManagerProvisioning>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManagerProvisioning {
+    #[serde(default)]
+    domains: Vec<String>,
+    /// `PROVISIONING`, `FAILED` или `ACTIVE`
+    state: Option<String>,
+    provisioning_issue: Option<ProvisioningIssue>,
+    #[serde(default)]
+    authorization_attempt_info: Vec<AuthorizationAttempt>,
+}
+
+#[derive(Deserialize)]
+struct ProvisioningIssue {
+    reason: Option<String>,
+    details: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthorizationAttempt {
+    domain: String,
+    /// `AUTHORIZING`, `AUTHORIZED` или `FAILED`
+    state: Option<String>,
+    failure_reason: Option<String>,
+}
+
+/// Страница агрегированного списка Compute Engine: элементы по регионам
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AggregatedPage<T> {
+    #[serde(default = "HashMap::new")]
+    items: HashMap<String, T>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SslCertificatesScope {
+    #[serde(default)]
+    ssl_certificates: Vec<SslCertificate>,
+}
+
+/// Классический SSL-сертификат балансировщика
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SslCertificate {
+    self_link: String,
+    /// `MANAGED` или `SELF_MANAGED`
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    expire_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    subject_alternative_names: Vec<String>,
+    managed: Option<SslCertificateProvisioning>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SslCertificateProvisioning {
+    #[serde(default)]
+    domains: Vec<String>,
+    status: Option<String>,
+    /// Состояние по доменам: `ACTIVE`, `PROVISIONING`, `FAILED_NOT_VISIBLE`,
+    /// `FAILED_CAA_CHECKING` и другие `FAILED_*`
+    #[serde(default)]
+    domain_status: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TargetProxiesScope {
+    #[serde(default)]
+    target_https_proxies: Vec<TargetProxy>,
+}
+
+/// Страница глобального списка целевых SSL-прокси
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SslProxiesPage {
+    #[serde(default)]
+    items: Vec<TargetProxy>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TargetProxy {
+    #[serde(default)]
+    ssl_certificates: Vec<String>,
+}
+
+impl ManagerCertificate {
+    /// Почему Google не выпустит или не продлит сертификат: ошибка выпуска,
+    /// проблема авторизации доменов (DNS, CAA) или лимит запросов
+    fn provisioning_problem(&self) -> Option<String> {
+        let managed = self.managed.as_ref()?;
+        let failed: Vec<String> = managed
+            .authorization_attempt_info
+            .iter()
+            .filter(|attempt| attempt.state.as_deref() == Some("FAILED"))
+            .map(|attempt| match &attempt.failure_reason {
+                Some(reason) => format!("{} ({})", attempt.domain, reason),
+                None => attempt.domain.clone(),
+            })
+            .collect();
+        let state = managed.state.as_deref().unwrap_or("STATE_UNSPECIFIED");
+        if state != "FAILED" && managed.provisioning_issue.is_none() && failed.is_empty()
+        {
+            return None;
+        }
+        let mut problem = format!("Управляемый сертификат в состоянии {}", state);
+        if let Some(issue) = &managed.provisioning_issue {
+            let reason = issue.reason.as_deref().unwrap_or("UNKNOWN");
+            problem.push_str(&format!(", проблема выпуска {}", reason));
+            if let Some(details) = &issue.details {
+                problem.push_str(&format!(" ({})", details));
+            }
+        }
+        if !failed.is_empty() {
+            problem.push_str(&format!(", не авторизованы: {}", failed.join(", ")));
+        }
+        Some(problem)
+    }
+}
+
+impl SslCertificate {
+    /// Ошибка выпуска или продления управляемого сертификата, привязанного
+    /// к балансировщику. Непривязанный сертификат Google не выпускает, это
+    /// не считается ошибкой
+    fn provisioning_problem(&self) -> Option<String> {
+        let managed = self.managed.as_ref()?;
+        let status = managed.status.as_deref().unwrap_or("STATUS_UNSPECIFIED");
+        let mut failed: Vec<String> = managed
+            .domain_status
+            .iter()
+            .filter(|(_, status)| status.starts_with("FAILED"))
+            .map(|(domain, status)| format!("{} ({})", domain, status))
+            .collect();
+        failed.sort();
+        let status_failed = matches!(
+            status,
+            "PROVISIONING_FAILED" | "PROVISIONING_FAILED_PERMANENTLY" | "RENEWAL_FAILED"
+        );
+        if !status_failed && failed.is_empty() {
+            return None;
+        }
+        let mut problem = format!("Управляемый сертификат в состоянии {}", status);
+        if !failed.is_empty() {
+            problem.push_str(&format!(", ошибка доменов: {}", failed.join(", ")));
+        }
+        Some(problem)
+    }
+
+    /// Путь ресурса без адреса API: `projects/<проект>/global/sslCertificates/<имя>`
+    fn resource(&self) -> &str {
+        self.self_link
+            .split_once("/compute/v1/")
+            .map_or(self.self_link.as_str(), |(_, path)| path)
+    }
+}
+
+/// Сертификаты Google Cloud в проектах `projects`: из Certificate Manager
+/// (в расположениях `locations`) и классические SSL-сертификаты балансировщиков.
+/// Классические сертификаты, не привязанные к целевым прокси, помечаются тегом
+/// `unused`, загруженные вручную — `self-managed`
+pub struct GcpInventory {
+    projects: Vec<String>,
+    locations: Vec<String>,
+    credentials_file: Option<String>,
+    tags: Vec<String>,
+    client: Client,
+    http: HttpSettings,
+    dcl: &'static str,
+}
+
+impl GcpInventory {
+    /// Расположения Certificate Manager по умолчанию
+    pub const DEFAULT_LOCATIONS: &[&str] = &["global"];
+
+    pub fn new(projects: &[String], http: HttpSettings) -> Self {
+        Self {
+            projects: projects.to_vec(),
+            locations: Self::DEFAULT_LOCATIONS.iter().map(|l| l.to_string()).collect(),
+            credentials_file: None,
+            tags: Vec::new(),
+            client: http.client(),
+            http,
+            dcl: "GcpInventory",
+        }
+    }
+
+    /// Расположения Certificate Manager, например `global` и `europe-west1`
+    pub fn with_locations(mut self, locations: Vec<String>) -> Self {
+        self.locations = locations;
+        self
+    }
+
+    /// Файл ключа сервисного аккаунта; без него — `GOOGLE_APPLICATION_CREDENTIALS`,
+    /// а затем сервер метаданных
+    pub fn with_credentials_file(mut self, path: &str) -> Self {
+        self.credentials_file = Some(path.to_string());
+        self
+    }
+
+    /// Теги, добавляемые ко всем записям
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Токен доступа на один запуск
+    async fn access_token(&self) -> Result<String> {
+        let path = self
+            .credentials_file
+            .clone()
+            .or_else(|| std::env::var("GOOGLE_APPLICATION_CREDENTIALS").ok());
+        let Some(path) = path else {
+            let resp = self
+                .http
+                .send(|| {
+                    self.client
+                        .get(METADATA_TOKEN_URL)
+                        .header("Metadata-Flavor", "Google")
+                })
+                .await
+                .context("GCP metadata server is unreachable")?;
+            return Self::token_from(resp).await;
+        };
+
+        let text = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Не удалось прочитать ключ GCP: {}", path))?;
+        let key: ServiceAccountKey = serde_json::from_str(&text)
+            .with_context(|| format!("Unexpected GCP credentials file: {}", path))?;
+        if key.kind != "service_account" {
+            bail!("Unsupported GCP credentials type {:?}: {}", key.kind, path);
+        }
+        let assertion = Self::assertion(&key)?;
+        let body = format!("grant_type={}&assertion={}", JWT_BEARER_GRANT, assertion);
+        let resp = self
+            .http
+            .send(|| {
+                self.client
+                    .post(&key.token_uri)
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(body.clone())
+            })
+            .await?;
+        Self::token_from(resp).await
+    }
+
+    /// JWT сервисного аккаунта, подписанный RS256
+    fn assertion(key: &ServiceAccountKey) -> Result<String> {
+        let now = Utc::now().timestamp();
+        let header = json!({ "alg": "RS256", "typ": "JWT" });
+        let claims = json!({
+            "iss": key.client_email,
+            "scope": SCOPE,
+            "aud": key.token_uri,
+            "iat": now,
+            "exp": now + 3600,
+        });
+        let input = format!(
+            "{}.{}",
+            Self::base64url(header.to_string().as_bytes()),
+            Self::base64url(claims.to_string().as_bytes())
+        );
+        let private_key = PKey::private_key_from_pem(key.private_key.as_bytes())
+            .context("Invalid GCP service account private key")?;
+        let mut signer = Signer::new(MessageDigest::sha256(), &private_key)?;
+        signer.update(input.as_bytes())?;
+        Ok(format!("{}.{}", input, Self::base64url(&signer.sign_to_vec()?)))
+    }
+
+    fn base64url(data: &[u8]) -> String {
+        openssl::base64::encode_block(data)
+            .replace('+', "-")
+            .replace('/', "_")
+            .trim_end_matches('=')
+            .to_string()
+    }
+
+    async fn token_from(resp: reqwest::Response) -> Result<String> {
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("GCP token request returned {}: {}", status, text));
+        }
+        let token: AccessToken =
+            resp.json().await.context("Unexpected GCP token response")?;
+        Ok(token.access_token)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, token: &str, url: &str) -> Result<T> {
+        let resp = self.http.send(|| self.client.get(url).bearer_auth(token)).await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            tracing::error!(
+                dcl = self.dcl,
+                url = url,
+                status = status.to_string(),
+                body = text,
+                "Ошибка запроса к API Google Cloud"
+            );
+            return Err(anyhow!("Google Cloud API returned {} for {}", status, url));
+        }
+        resp.json()
+            .await
+            .with_context(|| format!("Unexpected Google Cloud response: {}", url))
+    }
+
+    /// Все страницы списка; `next` достаёт элементы и токен следующей страницы
+    async fn pages<P, T>(
+        &self,
+        token: &str,
+        url: &str,
+        next: impl Fn(P) -> (Vec<T>, Option<String>),
+    ) -> Result<Vec<T>>
+    where
+        P: DeserializeOwned,
+    {
+        let mut items = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let page_url = match &page_token {
+                Some(page_token) => format!("{}?pageToken={}", url, page_token),
+                None => url.to_string(),
+            };
+            let (page, next_token) = next(self.get(token, &page_url).await?);
+            items.extend(page);
+            page_token = next_token;
+            if page_token.is_none() {
+                return Ok(items);
+            }
+        }
+    }
+
+    fn certificate(
+        &self,
+        project: &str,
+        id: &str,
+        domain: Option<&String>,
+        expiration_date: Option<DateTime<Utc>>,
+        renewal_problem: Option<String>,
+        extra_tags: &[&str],
+    ) -> InventoryCertificate {
+        let mut tags = self.tags.clone();
+        tags.push(project.to_string());
+        tags.extend(extra_tags.iter().map(|tag| tag.to_string()));
+        InventoryCertificate {
+            id: id.to_string(),
+            domain: domain.cloned(),
+            expiration_date,
+            issuer: None,
+            serial: None,
+            renewal_problem,
+            tags,
+            names: Vec::new(),
+            alarm_days: None,
+        }
+    }
+
+    async fn manager_certificates(
+        &self,
+        token: &str,
+        project: &str,
+    ) -> Result<Vec<InventoryCertificate>> {
+        let mut certificates = Vec::new();
+        for location in &self.locations {
+            let url = format!(
+                "{}/projects/{}/locations/{}/certificates",
+                CERTIFICATE_MANAGER_URL, project, location
+            );
+            let found = self
+                .pages(token, &url, |page: ManagerPage| {
+                    (page.certificates, page.next_page_token)
+                })
+                .await?;
+            for certificate in found {
+                let problem = certificate.provisioning_problem();
+                // Сертификат без срока ещё выпускается; интересен только с ошибкой
+                if certificate.expire_time.is_none() && problem.is_none() {
+                    continue;
+                }
+                let domain = certificate
+                    .san_dnsnames
+                    .first()
+                    .or(certificate.managed.as_ref().and_then(|m| m.domains.first()));
+                let extra: &[&str] =
+                    if certificate.managed.is_none() { &["self-managed"] } else { &[] };
+                certificates.push(self.certificate(
+                    project,
+                    &certificate.name,
+                    domain,
+                    certificate.expire_time,
+                    problem,
+                    extra,
+                ));
+            }
+        }
+        Ok(certificates)
+    }
+
+    async fn classic_certificates(
+        &self,
+        token: &str,
+        project: &str,
+    ) -> Result<Vec<InventoryCertificate>> {
+        let url =
+            format!("{}/projects/{}/aggregated/sslCertificates", COMPUTE_URL, project);
+        let found = self
+            .pages(token, &url, |page: AggregatedPage<SslCertificatesScope>| {
+                let items = page.items.into_values().flat_map(|s| s.ssl_certificates);
+                (items.collect(), page.next_page_token)
+            })
+            .await?;
+
+        let url =
+            format!("{}/projects/{}/aggregated/targetHttpsProxies", COMPUTE_URL, project);
+        let mut proxies = self
+            .pages(token, &url, |page: AggregatedPage<TargetProxiesScope>| {
+                let items = page.items.into_values().flat_map(|s| s.target_https_proxies);
+                (items.collect(), page.next_page_token)
+            })
+            .await?;
+        let url = format!("{}/projects/{}/global/targetSslProxies", COMPUTE_URL, project);
+        proxies.extend(
+            self.pages(token, &url, |page: SslProxiesPage| {
+                (page.items, page.next_page_token)
+            })
+            .await?,
+        );
+        let in_use: HashSet<&str> = proxies
+            .iter()
+            .flat_map(|proxy| &proxy.ssl_certificates)
+            .map(|link| link.as_str())
+            .collect();
+
+        let mut certificates = Vec::new();
+        for certificate in &found {
+            let used = in_use.contains(certificate.self_link.as_str());
+            let problem = certificate.provisioning_problem().filter(|_| used);
+            if certificate.expire_time.is_none() && problem.is_none() {
+                continue;
+            }
+            let mut extra = Vec::new();
+            if certificate.kind.as_deref() == Some("SELF_MANAGED") {
+                extra.push("self-managed");
+            }
+            if !used {
+                extra.push("unused");
+            }
+            let domain = certificate
+                .subject_alternative_names
+                .first()
+                .or(certificate.managed.as_ref().and_then(|m| m.domains.first()));
+            certificates.push(self.certificate(
+                project,
+                certificate.resource(),
+                domain,
+                certificate.expire_time,
+                problem,
+                &extra,
+            ));
+        }
+        Ok(certificates)
+    }
+}
+
+#[async_trait]
+impl CertificateInventoryTrait for GcpInventory {
+    async fn get_certificates(&self) -> Result<Vec<InventoryCertificate>> {
+        let token = self.access_token().await?;
+        let mut certificates = Vec::new();
+        for project in &self.projects {
+            let manager = self
+                .manager_certificates(&token, project)
+                .await
+                .with_context(|| format!("Certificate Manager in {}", project))?;
+            let classic = self
+                .classic_certificates(&token, project)
+                .await
+                .with_context(|| format!("SSL certificates in {}", project))?;
+            tracing::debug!(
+                dcl = self.dcl,
+                project = project,
+                manager = manager.len(),
+                classic = classic.len(),
+                "Загружены сертификаты Google Cloud"
+            );
+            certificates.extend(manager);
+            certificates.extend(classic);
+        }
+        Ok(certificates)
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::prelude::serde_json::Value;
+
+    fn manager_page(managed: Value) -> ManagerPage {
+        serde_json::from_value(json!({
+            "certificates": [{
+                "name": "projects/shop/locations/global/certificates/www",
+                "createTime": "2025-01-01T00:00:00.000000Z",
+                "sanDnsnames": ["www.example.com"],
+                "expireTime": "2026-04-01T12:00:00Z",
+                "scope": "DEFAULT",
+                "managed": managed
+            }],
+            "nextPageToken": "page-2"
+        }))
+        .unwrap()
+    }
+
+    fn classic_certificate(managed: Value) -> SslCertificate {
+        let page: AggregatedPage<SslCertificatesScope> = serde_json::from_value(json!({
+            "kind": "compute#sslCertificateAggregatedList",
+            "items": {
+                "global": {
+                    "sslCertificates": [{
+                        "kind": "compute#sslCertificate",
+                        "name": "web",
+                        "selfLink": "https://www.googleapis.com/compute/v1/\
+                                     projects/shop/global/sslCertificates/web",
+                        "type": "MANAGED",
+                        "subjectAlternativeNames": ["example.com"],
+                        "expireTime": "2026-04-01T12:00:00.000-07:00",
+                        "managed": managed
+                    }]
+                },
+                "regions/europe-west1": {
+                    "warning": { "code": "NO_RESULTS_ON_PAGE" }
+                }
+            }
+        }))
+        .unwrap();
+        page.items.into_values().flat_map(|scope| scope.ssl_certificates).next().unwrap()
+    }
+
+    #[test]
+    fn active_manager_certificate_has_no_problem() {
+        let page = manager_page(json!({
+            "domains": ["www.example.com"],
+            "state": "ACTIVE",
+            "authorizationAttemptInfo": [
+                { "domain": "www.example.com", "state": "AUTHORIZED" }
+            ]
+        }));
+        assert_eq!(page.next_page_token.as_deref(), Some("page-2"));
+        let certificate = &page.certificates[0];
+        assert_eq!(certificate.provisioning_problem(), None);
+        assert_eq!(
+            certificate.expire_time.unwrap().to_rfc3339(),
+            "2026-04-01T12:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn failed_manager_authorization_is_problem() {
+        let page = manager_page(json!({
+            "domains": ["www.example.com"],
+            "state": "PROVISIONING",
+            "provisioningIssue": {
+                "reason": "AUTHORIZATION_ISSUE",
+                "details": "DNS record is not visible"
+            },
+            "authorizationAttemptInfo": [{
+                "domain": "www.example.com",
+                "state": "FAILED",
+                "failureReason": "CAA"
+            }]
+        }));
+        let expected = "Управляемый сертификат в состоянии PROVISIONING, \
+                        проблема выпуска AUTHORIZATION_ISSUE \
+                        (DNS record is not visible), \
+                        не авторизованы: www.example.com (CAA)";
+        assert_eq!(
+            page.certificates[0].provisioning_problem().as_deref(),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn active_classic_certificate_has_no_problem() {
+        let certificate = classic_certificate(json!({
+            "domains": ["example.com"],
+            "status": "ACTIVE",
+            "domainStatus": { "example.com": "ACTIVE" }
+        }));
+        assert_eq!(certificate.provisioning_problem(), None);
+        assert_eq!(certificate.resource(), "projects/shop/global/sslCertificates/web");
+    }
+
+    #[test]
+    fn failed_classic_domains_are_problem() {
+        let certificate = classic_certificate(json!({
+            "domains": ["example.com", "www.example.com"],
+            "status": "PROVISIONING",
+            "domainStatus": {
+                "www.example.com": "FAILED_NOT_VISIBLE",
+                "example.com": "ACTIVE"
+            }
+        }));
+        assert_eq!(
+            certificate.provisioning_problem().as_deref(),
+            Some(
+                "Управляемый сертификат в состоянии PROVISIONING, ошибка доменов: \
+                 www.example.com (FAILED_NOT_VISIBLE)"
+            )
+        );
+    }
+}
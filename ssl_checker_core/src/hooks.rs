@@ -0,0 +1,55 @@
+use crate::report::{CheckReport, ReportEntry};
+use base::prelude::anyhow::Result;
+use std::sync::Arc;
+
+type RunStartHook = Arc<dyn Fn() + Send + Sync>;
+type CheckCompleteHook = Arc<dyn Fn(&ReportEntry) + Send + Sync>;
+type RunFinishHook = Arc<dyn Fn(&Result<CheckReport>) + Send + Sync>;
+
+/// Обработчики событий запуска проверки, заданные встраивающим сервисом.
+/// Вызываются синхронно в ходе проверки, поэтому не должны блокировать поток:
+/// долгую работу стоит передать в канал или `tokio::spawn`
+#[derive(Clone, Default)]
+pub struct CheckHooks {
+    run_start: Vec<RunStartHook>,
+    check_complete: Vec<CheckCompleteHook>,
+    run_finish: Vec<RunFinishHook>,
+}
+
+impl CheckHooks {
+    pub fn on_run_start(&mut self, hook: impl Fn() + Send + Sync + 'static) {
+        self.run_start.push(Arc::new(hook));
+    }
+
+    pub fn on_check_complete(
+        &mut self,
+        hook: impl Fn(&ReportEntry) + Send + Sync + 'static,
+    ) {
+        self.check_complete.push(Arc::new(hook));
+    }
+
+    pub fn on_run_finish(
+        &mut self,
+        hook: impl Fn(&Result<CheckReport>) + Send + Sync + 'static,
+    ) {
+        self.run_finish.push(Arc::new(hook));
+    }
+
+    pub(crate) fn run_started(&self) {
+        for hook in &self.run_start {
+            hook();
+        }
+    }
+
+    pub(crate) fn check_completed(&self, entry: &ReportEntry) {
+        for hook in &self.check_complete {
+            hook(entry);
+        }
+    }
+
+    pub(crate) fn run_finished(&self, result: &Result<CheckReport>) {
+        for hook in &self.run_finish {
+            hook(result);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use crate::config::HttpConfig;
+use base::prelude::{anyhow::Result, tokio, tracing};
+use reqwest::{Certificate, Client, ClientBuilder, Proxy, RequestBuilder, Response};
+use std::time::Duration;
+
+/// Параметры HTTP-клиента, таймауты и повторы запросов сервиса,
+/// обращающегося к внешнему API
+#[derive(Debug, Clone)]
+pub struct HttpSettings {
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    pub retries: u32,
+    pub retry_interval: Duration,
+    pub user_agent: Option<String>,
+    pub verify_tls: bool,
+    pub ca_bundle: Option<String>,
+    pub proxy: Option<String>,
+    /// Общий клиент, пул соединений которого используется вместо собственного
+    pub shared_client: Option<Client>,
+}
+
+impl HttpSettings {
+    /// Значения по умолчанию для Telegram
+    pub const TELEGRAM: Self = Self::from_secs(3, 1, 5, 1);
+    /// Значения по умолчанию для API Selectel
+    pub const SELECTEL: Self = Self::from_secs(30, 10, 3, 1);
+    /// Значения по умолчанию для источника со списком хостов по HTTP(S)
+    pub const URL_LIST: Self = Self::from_secs(30, 10, 3, 1);
+    /// Значения по умолчанию для API демона Docker
+    pub const DOCKER: Self = Self::from_secs(30, 5, 2, 1);
+    /// Значения по умолчанию для API балансировщиков F5 BIG-IP и Citrix ADC
+    pub const LOAD_BALANCER: Self = Self::from_secs(30, 10, 3, 1);
+    /// Значения по умолчанию для API AWS Certificate Manager
+    pub const ACM: Self = Self::from_secs(30, 10, 3, 1);
+    /// Значения по умолчанию для API Azure Key Vault
+    pub const AZURE: Self = Self::from_secs(30, 10, 3, 1);
+    /// Значения по умолчанию для API Google Cloud
+    pub const GCP: Self = Self::from_secs(30, 10, 3, 1);
+    /// Значения по умолчанию для API Venafi TPP и TLS Protect Cloud
+    pub const VENAFI: Self = Self::from_secs(30, 10, 3, 1);
+    /// Значения по умолчанию для API HashiCorp Vault
+    pub const VAULT: Self = Self::from_secs(10, 5, 3, 1);
+    /// Значения по умолчанию для crt.sh, который долго отвечает на запросы по крупным доменам
+    pub const CRT_SH: Self = Self::from_secs(60, 10, 3, 5);
+    /// Значения по умолчанию для OCSP-респондеров и списков отзыва издателей
+    pub const REVOCATION: Self = Self::from_secs(10, 5, 1, 1);
+    /// Значения по умолчанию для запросов WASM-плагинов уведомлений
+    pub const WASM: Self = Self::from_secs(10, 5, 3, 1);
+    /// Значения по умолчанию для общего клиента; таймаут запроса каждый сервис задаёт свой
+    pub const SHARED: Self = Self::from_secs(30, 10, 0, 1);
+    /// Сколько держать открытым неиспользуемое соединение пула
+    const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+    /// Интервал TCP keep-alive соединений пула
+    const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+    pub const fn from_secs(
+        timeout_secs: u64,
+        connect_timeout_secs: u64,
+        retries: u32,
+        retry_interval_secs: u64,
+    ) -> Self {
+        Self {
+            timeout: Duration::from_secs(timeout_secs),
+            connect_timeout: Duration::from_secs(connect_timeout_secs),
+            retries,
+            retry_interval: Duration::from_secs(retry_interval_secs),
+            user_agent: None,
+            verify_tls: true,
+            ca_bundle: None,
+            proxy: None,
+            shared_client: None,
+        }
+    }
+
+    /// Общий HTTP-клиент с настройками секции `http`. Источники и модули уведомлений,
+    /// получившие его через [`HttpSettings::with_shared_client`], переиспользуют
+    /// соединения между запросами и запусками
+    pub fn shared_client(http: &HttpConfig) -> Client {
+        Self::SHARED.with_global(http).client()
+    }
+
+    /// Использует общий клиент вместо собственного, если секция не задаёт свой
+    /// `connect_timeout_secs`: таймаут подключения задаётся только при создании клиента
+    pub fn with_shared_client(
+        mut self,
+        client: &Client,
+        connect_timeout_secs: Option<u64>,
+    ) -> Self {
+        if connect_timeout_secs.is_none() {
+            self.shared_client = Some(client.clone());
+        }
+        self
+    }
+
+    /// Применяет общую секцию `http` конфигурации
+    pub fn with_global(self, http: &HttpConfig) -> Self {
+        Self {
+            user_agent: http.user_agent.clone(),
+            verify_tls: http.verify_tls,
+            ca_bundle: http.ca_bundle.clone(),
+            proxy: http.proxy.clone(),
+            ..self
+        }
+        .with_overrides(
+            http.timeout_secs,
+            http.connect_timeout_secs,
+            http.retries,
+            http.retry_interval_secs,
+        )
+    }
+
+    /// Заменяет значения по умолчанию заданными в конфигурации
+    pub fn with_overrides(
+        mut self,
+        timeout_secs: Option<u64>,
+        connect_timeout_secs: Option<u64>,
+        retries: Option<u32>,
+        retry_interval_secs: Option<u64>,
+    ) -> Self {
+        if let Some(secs) = timeout_secs {
+            self.timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = connect_timeout_secs {
+            self.connect_timeout = Duration::from_secs(secs);
+        }
+        if let Some(retries) = retries {
+            self.retries = retries;
+        }
+        if let Some(secs) = retry_interval_secs {
+            self.retry_interval = Duration::from_secs(secs);
+        }
+        self
+    }
+
+    pub fn client(&self) -> Client {
+        if let Some(client) = &self.shared_client {
+            return client.clone();
+        }
+        self.try_client().unwrap_or_else(|e| {
+            tracing::error!(
+                dcl = "HttpSettings",
+                error = %e,
+                "Не удалось создать HTTP-клиент, используются настройки по умолчанию"
+            );
+            Client::new()
+        })
+    }
+
+    /// Клиент, который подключается через Unix-сокет `path` вместо TCP, например
+    /// к демону Docker. Общий клиент и прокси не используются
+    #[cfg(unix)]
+    pub fn unix_client(&self, path: &str) -> Client {
+        self.try_builder()
+            .and_then(|builder| Ok(builder.unix_socket(path).build()?))
+            .unwrap_or_else(|e| {
+                tracing::error!(
+                    dcl = "HttpSettings",
+                    error = %e,
+                    path,
+                    "Не удалось создать HTTP-клиент для Unix-сокета"
+                );
+                Client::builder().unix_socket(path).build().unwrap_or_default()
+            })
+    }
+
+    fn try_client(&self) -> Result<Client> {
+        Ok(self.try_builder()?.build()?)
+    }
+
+    fn try_builder(&self) -> Result<ClientBuilder> {
+        let user_agent = self
+            .user_agent
+            .clone()
+            .unwrap_or_else(|| format!("ssl-checker/{}", env!("CARGO_PKG_VERSION")));
+        let mut builder = Client::builder()
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout)
+            .pool_idle_timeout(Self::POOL_IDLE_TIMEOUT)
+            .tcp_keepalive(Self::TCP_KEEPALIVE)
+            .user_agent(user_agent)
+            .tls_danger_accept_invalid_certs(!self.verify_tls);
+        if let Some(path) = &self.ca_bundle {
+            builder = builder.tls_certs_merge(Self::load_ca_bundle(path)?);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        Ok(builder)
+    }
+
+    /// Читает корневые сертификаты из PEM-файла
+    pub fn load_ca_bundle(path: &str) -> Result<Vec<Certificate>> {
+        let pem = std::fs::read(path)?;
+        Ok(Certificate::from_pem_bundle(&pem)?)
+    }
+
+    /// Отправляет запрос, повторяя его при сетевых ошибках, 429 и 5xx.
+    /// Последний ответ возвращается как есть, даже если он неуспешный.
+    /// Таймаут задаётся на каждый запрос, так как клиент может быть общим
+    pub async fn send(
+        &self,
+        request: impl Fn() -> RequestBuilder,
+    ) -> reqwest::Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let result = request().timeout(self.timeout).send().await;
+            let retryable = match &result {
+                Ok(response) => {
+                    response.status().is_server_error()
+                        || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                Err(_) => true,
+            };
+            if !retryable || attempt >= self.retries {
+                return result;
+            }
+            attempt += 1;
+            tokio::time::sleep(self.retry_interval).await;
+        }
+    }
+}
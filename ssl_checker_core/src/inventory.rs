@@ -0,0 +1,132 @@
+//! Сертификаты из менеджеров и хранилищ сертификатов (AWS ACM и других): срок
+//! и состояние продления берутся из API хранилища, а не подключением к хосту.
+//! Хранилище подключается реализацией [`CertificateInventoryTrait`]
+
+use crate::checks;
+use crate::renewal::RenewalIndex;
+use crate::report::{CheckKind, EntryStatus, ReportEntry};
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Result, anyhow},
+    chrono::{DateTime, Utc},
+};
+use x509_parser::extensions::GeneralName;
+
+/// Сертификат из хранилища
+#[derive(Debug, Clone)]
+pub struct InventoryCertificate {
+    /// Идентификатор в хранилище, например ARN
+    pub id: String,
+    /// Основное имя сертификата
+    pub domain: Option<String>,
+    /// Срок; нет у сертификата, который ещё не выпущен
+    pub expiration_date: Option<DateTime<Utc>>,
+    pub issuer: Option<String>,
+    pub serial: Option<String>,
+    /// Выпуск или продление не пройдёт само, например из-за неподтверждённой
+    /// DNS-валидации
+    pub renewal_problem: Option<String>,
+    pub tags: Vec<String>,
+    /// Имена хостов из SAN. Проверяемые хосты с этими именами, которые отдают
+    /// более старый сертификат, попадают в ошибки [`ErrorKind::Undeployed`]
+    ///
+    /// [`ErrorKind::Undeployed`]: crate::report::ErrorKind::Undeployed
+    pub names: Vec<String>,
+    /// Собственный порог оповещения, например для издателей; по умолчанию
+    /// `ssl_alarm_days`
+    pub alarm_days: Option<i64>,
+}
+
+impl InventoryCertificate {
+    /// Сертификат по DER: срок, издатель, серийный номер и имена из SAN (или CN);
+    /// основное имя — первое из них
+    pub fn from_der(id: &str, der: &[u8]) -> Result<Self> {
+        let info = checks::parse_certificate(der)?;
+        let (_, x509) = x509_parser::parse_x509_certificate(der)
+            .map_err(|e| anyhow!("Certificate parse error: {}", e))?;
+        let mut names: Vec<String> = x509
+            .subject_alternative_name()
+            .ok()
+            .flatten()
+            .into_iter()
+            .flat_map(|san| &san.value.general_names)
+            .filter_map(|name| match name {
+                GeneralName::DNSName(name) => Some(name.to_string()),
+                GeneralName::IPAddress(bytes) => RenewalIndex::ip_address(bytes),
+                _ => None,
+            })
+            .collect();
+        if names.is_empty() {
+            names.extend(
+                x509.subject()
+                    .iter_common_name()
+                    .filter_map(|cn| cn.as_str().ok().map(str::to_string)),
+            );
+        }
+        Ok(Self {
+            id: id.to_string(),
+            domain: names.first().cloned(),
+            expiration_date: Some(info.expiration_date),
+            issuer: Some(info.issuer),
+            serial: Some(info.serial),
+            renewal_problem: None,
+            tags: Vec::new(),
+            names,
+            alarm_days: None,
+        })
+    }
+}
+
+#[async_trait]
+pub trait CertificateInventoryTrait: Send + Sync {
+    async fn get_certificates(&self) -> Result<Vec<InventoryCertificate>>;
+
+    fn get_source_name(&self) -> &'static str;
+}
+
+/// Запись отчёта по сертификату хранилища: имя — основное имя и идентификатор.
+/// Проблема выпуска или продления делает запись ошибочной, срок в ней сохраняется
+pub fn certificate_entry(
+    certificate: &InventoryCertificate,
+    alarm_days: i64,
+) -> ReportEntry {
+    let name = match &certificate.domain {
+        Some(domain) => format!("{} ({})", domain, certificate.id),
+        None => certificate.id.clone(),
+    };
+    let Some(expiration_date) = certificate.expiration_date else {
+        let problem = certificate
+            .renewal_problem
+            .as_deref()
+            .unwrap_or("Certificate is not issued yet");
+        return ReportEntry {
+            issuer: certificate.issuer.clone(),
+            serial: certificate.serial.clone(),
+            ..ReportEntry::failed(CheckKind::Inventory, &name, problem, false)
+        }
+        .with_tags(certificate.tags.clone());
+    };
+    let days = expiration_date.signed_duration_since(Utc::now()).num_days();
+    let alarm_days = certificate.alarm_days.unwrap_or(alarm_days);
+    let entry = ReportEntry {
+        issuer: certificate.issuer.clone(),
+        serial: certificate.serial.clone(),
+        ..ReportEntry::ok(
+            CheckKind::Inventory,
+            &name,
+            expiration_date,
+            days,
+            days <= alarm_days,
+        )
+    };
+    let entry = match &certificate.renewal_problem {
+        Some(problem) => ReportEntry {
+            status: EntryStatus::Error,
+            error: Some(problem.clone()),
+            severity: None,
+            ..entry
+        },
+        None => entry,
+    };
+    entry.with_tags(certificate.tags.clone())
+}
@@ -0,0 +1,138 @@
+//! Проверка сроков действия SSL-сертификатов и регистрации доменов.
+//!
+//! Библиотека, на которой построен `checker`; её можно встроить в другой
+//! сервис вместо запуска бинарника. Основные части:
+//!
+//! - [`sources`] — откуда берутся имена хостов ([`DomainSourceTrait`]);
+//! - [`notifiers`] — куда уходят уведомления ([`BaseNotifierTrait`]);
+//! - [`domain_checker`] — сам запуск проверки ([`DomainCheckerService`]);
+//! - [`calendar`] — окно оповещения в рабочих днях ([`BusinessCalendar`]);
+//! - [`budget`] — ограничения частоты проверок групп хостов ([`CheckBudgets`]);
+//! - [`checks`] — отдельные проверки сертификата и WHOIS одного хоста;
+//! - [`whois`] — правила разбора срока регистрации по зонам ([`WhoisParseRule`]);
+//! - [`mail`] — сертификаты почтовых серверов домена (MX и STARTTLS);
+//! - [`quic`] — сертификаты, которые хосты отдают по QUIC (фича `quic`);
+//! - [`ssh`] — сертификаты хостов OpenSSH, подписанные SSH-УЦ;
+//! - [`ct`] — уведомления о новых сертификатах в журналах Certificate Transparency ([`CtMonitor`]);
+//! - [`renewal`] — сверка с сертификатами, продлёнными certbot и acme.sh ([`RenewalIndex`]);
+//! - [`trust_store`] — сроки корневых и промежуточных сертификатов хранилища доверия;
+//! - [`certificate_files`] — сертификаты из локальных файлов PEM, DER и PKCS#12;
+//! - [`credentials`] — сроки ключей API и токенов, в том числе из метаданных Vault;
+//! - [`inventory`] — сертификаты из менеджеров и хранилищ сертификатов ([`CertificateInventoryTrait`]);
+//! - [`vault`] — клиент HTTP API HashiCorp Vault ([`VaultClient`]);
+//! - [`vault_pki`] — издатели и сертификаты движка PKI Vault ([`VaultPkiInventory`]);
+//! - [`gcp`] — сертификаты Google Cloud и состояние их выпуска ([`GcpInventory`]);
+//! - [`azure`] — сертификаты Azure Key Vault ([`AzureKeyVaultInventory`]);
+//! - [`venafi`] — сертификаты Venafi TPP и TLS Protect Cloud ([`VenafiInventory`]);
+//! - [`hooks`] — обработчики событий запуска для встраивающего сервиса ([`CheckHooks`]);
+//! - `testing` — моки и локальные серверы для тестов (фича `testing`);
+//! - [`report`] — результаты проверки ([`CheckReport`], [`ReportEntry`]);
+//! - [`forecast`] — прогноз истечений по неделям и месяцам ([`ExpiryForecast`]);
+//! - [`compliance`] — отчёт о своевременности продлений ([`ComplianceReport`]);
+//! - [`registry`] — регистрация собственных типов источников и модулей уведомлений,
+//!   которые `checker` создаёт по полю `type` конфигурации ([`REGISTRY`]).
+//!
+//! ```no_run
+//! use ssl_checker_core::{
+//!     CheckKind, ConsoleNotifierService, DomainCheckerService, FileSourceService,
+//! };
+//!
+//! # async fn example() -> base::prelude::anyhow::Result<()> {
+//! let mut checker = DomainCheckerService::new(
+//!     vec![Box::new(FileSourceService::new("domains.txt"))],
+//!     vec![Box::new(ConsoleNotifierService::new())],
+//!     14,
+//!     30,
+//! )
+//! .on_check_complete(|entry| println!("{} {:?}", entry.hostname, entry.status));
+//! let report = checker.run().await?;
+//! println!("Минимум дней до истечения SSL: {:?}", report.min_days(CheckKind::Ssl));
+//!
+//! // Один хост без уведомлений
+//! let entries = checker.check_host("example.com").await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Метрики пишутся через фасад `metrics`: чтобы их получать, встраивающий
+//! сервис устанавливает свой рекордер и может вызвать [`metrics::describe`].
+
+pub mod azure;
+pub mod budget;
+pub mod calendar;
+pub mod certificate_files;
+pub mod checks;
+pub mod compliance;
+pub mod config;
+pub mod credentials;
+pub mod ct;
+pub mod domain_checker;
+pub mod forecast;
+pub mod gcp;
+pub mod hooks;
+pub mod http;
+pub mod inventory;
+pub mod mail;
+pub mod metrics;
+pub mod notifiers;
+pub mod overrides;
+pub mod quic;
+pub mod registry;
+pub mod renewal;
+pub mod report;
+pub mod sources;
+pub mod ssh;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod trust_store;
+pub mod vault;
+pub mod vault_pki;
+pub mod venafi;
+pub mod whois;
+
+pub use azure::AzureKeyVaultInventory;
+pub use budget::CheckBudgets;
+pub use calendar::BusinessCalendar;
+pub use checks::{
+    CertificateInfo, DomainRegistration, SslCheckOptions, WhoisCheckOptions,
+    WhoisResponseError,
+};
+pub use compliance::{
+    ComplianceEvent, ComplianceEventKind, CompliancePeriod, ComplianceReport,
+};
+pub use config::{
+    BusinessDaysConfig, CertificateFileConfig, CheckBudgetConfig, CredentialConfig,
+    DomainOverrideConfig, HttpConfig, MaintenanceWindowConfig, NotifierLabels,
+    RenewalCheckConfig, SectionHeaders, SeverityThreshold,
+};
+pub use ct::{CtCertificate, CtMonitor};
+pub use domain_checker::DomainCheckerService;
+pub use forecast::{ExpiryForecast, ForecastBucket, ForecastPeriod};
+pub use gcp::GcpInventory;
+pub use hooks::CheckHooks;
+pub use http::HttpSettings;
+pub use inventory::{CertificateInventoryTrait, InventoryCertificate};
+pub use mail::MailCheckOptions;
+pub use notifiers::{
+    BaseNotifierTrait, ConsoleNotifierService, MaintenanceNotifier,
+    TelegramNotifierService,
+};
+#[cfg(feature = "wasm")]
+pub use notifiers::WasmNotifierService;
+pub use overrides::DomainOverrides;
+pub use registry::{PluginContext, REGISTRY};
+pub use renewal::RenewalIndex;
+pub use report::{
+    CertificateUsage, ChangedEntry, CheckError, CheckKind, CheckProgress, CheckReport,
+    CheckStats, DomainCheckResult, EntryStatus, ErrorKind, IssuerChange, ReportEntry,
+    RunChanges, RunStats, Severity, SourceStats, SslCheckResult,
+};
+pub use sources::{
+    DockerSourceService, DomainSourceTrait, F5SourceService, FileSourceService,
+    HaproxySourceService, ListSourceService, NetScalerSourceService,
+    SelectelSourceService, UrlListCache, UrlSourceService,
+};
+pub use vault::VaultClient;
+pub use vault_pki::VaultPkiInventory;
+pub use venafi::VenafiInventory;
+pub use whois::WhoisParseRule;
@@ -0,0 +1,156 @@
+//! Сертификаты почтовых серверов: MX-записи корневого домена и сертификат,
+//! который сервер отдаёт после STARTTLS в SMTP-диалоге
+
+use crate::checks::{self, CertificateInfo, SslCheckOptions};
+use base::prelude::{
+    anyhow::{Result, anyhow, bail},
+    tokio::{
+        self,
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpStream,
+    },
+};
+use std::net::{IpAddr, SocketAddr};
+use trust_dns_client::client::{Client, SyncClient};
+use trust_dns_client::op::ResponseCode;
+use trust_dns_client::rr::{DNSClass, Name, RData, RecordType};
+use trust_dns_client::udp::UdpClientConnection;
+
+/// Параметры проверки почтовых серверов
+#[derive(Debug, Clone)]
+pub struct MailCheckOptions {
+    /// DNS-сервер для запроса MX; по умолчанию первый `nameserver` из `/etc/resolv.conf`
+    pub resolver: Option<SocketAddr>,
+    /// Имя, которым проверка представляется в EHLO
+    pub helo: String,
+    /// Порт и таймауты; таймаут рукопожатия ограничивает и SMTP-диалог
+    pub ssl: SslCheckOptions,
+}
+
+impl MailCheckOptions {
+    pub const DEFAULT_PORT: u16 = 25;
+}
+
+impl Default for MailCheckOptions {
+    fn default() -> Self {
+        Self {
+            resolver: None,
+            helo: "ssl-checker".to_string(),
+            ssl: SslCheckOptions { port: Self::DEFAULT_PORT, ..Default::default() },
+        }
+    }
+}
+
+/// Первый DNS-сервер из `/etc/resolv.conf`
+fn system_resolver() -> Option<SocketAddr> {
+    let resolv_conf = std::fs::read_to_string("/etc/resolv.conf").ok()?;
+    resolv_conf.lines().find_map(|line| {
+        let address = line.trim().strip_prefix("nameserver")?.trim();
+        Some(SocketAddr::new(address.parse::<IpAddr>().ok()?, 53))
+    })
+}
+
+/// Почтовые серверы домена по возрастанию приоритета. Домен без MX-записей
+/// или с нулевой записью `MX 0 .` не принимает почту — список пуст
+pub async fn resolve_mx(domain: &str, options: &MailCheckOptions) -> Result<Vec<String>> {
+    let resolver = options
+        .resolver
+        .or_else(system_resolver)
+        .ok_or_else(|| anyhow!("не найден DNS-сервер для запроса MX"))?;
+    let name = Name::from_ascii(format!("{}.", idna::domain_to_ascii(domain)?))?;
+    let timeout = options.ssl.connect_timeout;
+
+    let response = tokio::task::spawn_blocking(move || {
+        let client =
+            SyncClient::new(UdpClientConnection::with_timeout(resolver, timeout)?);
+        client.query(&name, DNSClass::IN, RecordType::MX)
+    })
+    .await??;
+    match response.response_code() {
+        ResponseCode::NoError => {}
+        ResponseCode::NXDomain => return Ok(Vec::new()),
+        code => bail!("DNS-сервер {} ответил {}", resolver, code),
+    }
+
+    let mut exchanges: Vec<(u16, String)> = response
+        .answers()
+        .iter()
+        .filter_map(|record| match record.data() {
+            Some(RData::MX(mx)) => Some((
+                mx.preference(),
+                mx.exchange().to_ascii().trim_end_matches('.').to_lowercase(),
+            )),
+            _ => None,
+        })
+        .filter(|(_, exchange)| !exchange.is_empty())
+        .collect();
+    exchanges.sort();
+    let mut hosts: Vec<String> = Vec::new();
+    for (_, exchange) in exchanges {
+        if !hosts.contains(&exchange) {
+            hosts.push(exchange);
+        }
+    }
+    Ok(hosts)
+}
+
+/// Ответ SMTP-сервера: код и строки многострочного ответа
+async fn read_reply(reader: &mut BufReader<TcpStream>) -> Result<(u16, Vec<String>)> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            bail!("SMTP server closed connection");
+        }
+        let line = line.trim_end();
+        let code = line
+            .get(..3)
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or_else(|| anyhow!("Unexpected SMTP reply: {}", line))?;
+        lines.push(line.get(4..).unwrap_or_default().to_string());
+        // `250-...` — продолжение, `250 ...` — последняя строка ответа
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok((code, lines));
+        }
+    }
+}
+
+/// SMTP-диалог до начала TLS: приветствие, EHLO и STARTTLS
+async fn starttls(stream: TcpStream, helo: &str) -> Result<TcpStream> {
+    let mut reader = BufReader::new(stream);
+    let (code, lines) = read_reply(&mut reader).await?;
+    if code != 220 {
+        bail!("SMTP greeting {}: {}", code, lines.join(" "));
+    }
+
+    reader.get_mut().write_all(format!("EHLO {}\r\n", helo).as_bytes()).await?;
+    let (code, lines) = read_reply(&mut reader).await?;
+    if code != 250 {
+        bail!("SMTP EHLO {}: {}", code, lines.join(" "));
+    }
+    if !lines.iter().any(|line| line.eq_ignore_ascii_case("STARTTLS")) {
+        bail!("SMTP server does not support STARTTLS");
+    }
+
+    reader.get_mut().write_all(b"STARTTLS\r\n").await?;
+    let (code, lines) = read_reply(&mut reader).await?;
+    if code != 220 {
+        bail!("SMTP STARTTLS {}: {}", code, lines.join(" "));
+    }
+    Ok(reader.into_inner())
+}
+
+/// Подключается к почтовому серверу и возвращает сертификат, полученный после STARTTLS
+pub async fn check_starttls(
+    hostname: &str,
+    options: &MailCheckOptions,
+) -> Result<CertificateInfo> {
+    let (stream, server_name) = checks::connect(hostname, &options.ssl).await?;
+    let stream = tokio::time::timeout(
+        options.ssl.handshake_timeout,
+        starttls(stream, &options.helo),
+    )
+    .await
+    .map_err(|_| anyhow!("SMTP dialogue timed out"))??;
+    checks::tls_certificate(stream, &server_name, &options.ssl).await
+}
@@ -0,0 +1,35 @@
+use base::prelude::metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
+
+/// Выполненные проверки, метки `kind` (domain, ssl) и `status`
+pub const CHECKS: &str = "ssl_checker_checks_total";
+/// Проверки, пропущенные из-за недавнего успешного результата, метка `kind`
+pub const CHECKS_REUSED: &str = "ssl_checker_checks_reused_total";
+/// Длительность одной проверки, метка `kind`
+pub const CHECK_DURATION: &str = "ssl_checker_check_duration_seconds";
+/// Запуски проверки, метка `result` (ok, error)
+pub const RUNS: &str = "ssl_checker_runs_total";
+pub const RUN_DURATION: &str = "ssl_checker_run_duration_seconds";
+/// Число хостов, загруженных из источников при последнем запуске
+pub const HOSTNAMES: &str = "ssl_checker_hostnames";
+/// Ошибки загрузки доменов, метка `source`
+pub const SOURCE_ERRORS: &str = "ssl_checker_source_errors_total";
+/// Паники, перехваченные при загрузке и проверках, метка `task` (source, domain, ssl)
+pub const TASK_PANICS: &str = "ssl_checker_task_panics_total";
+/// Отправки уведомлений, метки `notifier` и `result` (ok, error)
+pub const NOTIFIER_SENDS: &str = "ssl_checker_notifier_sends_total";
+/// Новые сертификаты из журналов Certificate Transparency, метка `domain`
+pub const CT_CERTIFICATES: &str = "ssl_checker_ct_certificates_total";
+
+/// Описания метрик для экспортеров; вызывается один раз после их установки
+pub fn describe() {
+    describe_counter!(CHECKS, "Выполненные проверки доменов и сертификатов");
+    describe_counter!(CHECKS_REUSED, "Проверки, результат которых взят из предыдущего запуска");
+    describe_histogram!(CHECK_DURATION, Unit::Seconds, "Длительность одной проверки");
+    describe_counter!(RUNS, "Запуски проверки");
+    describe_histogram!(RUN_DURATION, Unit::Seconds, "Длительность запуска проверки");
+    describe_gauge!(HOSTNAMES, "Хосты, загруженные из источников при последнем запуске");
+    describe_counter!(SOURCE_ERRORS, "Ошибки загрузки доменов из источников");
+    describe_counter!(TASK_PANICS, "Паники при загрузке доменов и проверках");
+    describe_counter!(NOTIFIER_SENDS, "Отправки уведомлений");
+    describe_counter!(CT_CERTIFICATES, "Новые сертификаты в журналах Certificate Transparency");
+}
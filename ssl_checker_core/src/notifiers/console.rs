@@ -0,0 +1,318 @@
+use super::BaseNotifierTrait;
+use crate::config::NotifierLabels;
+use crate::report::{
+    CheckError, DomainCheckResult, RunChanges, RunStats, Severity, SslCheckResult,
+};
+use async_trait::async_trait;
+use base::prelude::{anyhow::Result, chrono::Utc, tracing};
+use std::cmp::Reverse;
+
+pub struct ConsoleNotifierService {
+    pub ssl_entries: Vec<SslCheckResult>,
+    pub mail_entries: Vec<SslCheckResult>,
+    pub domain_entries: Vec<DomainCheckResult>,
+    pub credential_entries: Vec<DomainCheckResult>,
+    pub errors: Vec<CheckError>,
+    pub changes: Option<RunChanges>,
+    pub stats: Option<RunStats>,
+    labels: NotifierLabels,
+    dcl: &'static str,
+}
+
+impl Default for ConsoleNotifierService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConsoleNotifierService {
+    pub fn new() -> Self {
+        Self {
+            ssl_entries: Vec::new(),
+            mail_entries: Vec::new(),
+            domain_entries: Vec::new(),
+            credential_entries: Vec::new(),
+            errors: Vec::new(),
+            changes: None,
+            stats: None,
+            labels: NotifierLabels::default(),
+            dcl: "ConsoleNotifierService",
+        }
+    }
+
+    /// Метки важности и заголовки разделов вместо встроенных
+    pub fn with_labels(mut self, labels: NotifierLabels) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Метка важности перед записью, по умолчанию `[warning]` и т. п.
+    fn label(&self, severity: Severity) -> String {
+        match self.labels.icon(severity) {
+            Some(icon) => icon.to_string(),
+            None => format!("[{}]", severity.as_str()),
+        }
+    }
+
+    /// Сертификаты сайтов или почтовых серверов; `noun` — начало строки записи
+    /// Команда-владелец в конце строки записи
+    fn owner_note(owner: Option<&str>) -> String {
+        owner.map(|owner| format!("; владелец: {}", owner)).unwrap_or_default()
+    }
+
+    fn format_ssl_entries(&self, entries: &[SslCheckResult], noun: &str) -> Vec<String> {
+        let mut entries: Vec<&SslCheckResult> = entries.iter().collect();
+        entries.sort_by_key(|entry| (Reverse(entry.severity), entry.days));
+        entries
+            .into_iter()
+            .map(|entry| {
+                let days = entry.days as i32;
+                let day_word = self.format_days(days);
+                let mut more_info = if entry.more > 0 {
+                    format!(" (+{})", entry.more)
+                } else {
+                    String::new()
+                };
+                if entry.acme {
+                    more_info = format!("{}; {}", more_info, self.acme_note());
+                }
+                if !entry.unreachable_revocation.is_empty() {
+                    let note = self.revocation_note(&entry.unreachable_revocation);
+                    more_info = format!("{}; {}", more_info, note);
+                }
+                more_info.push_str(&Self::owner_note(entry.owner.as_deref()));
+
+                let expires_at = self.expires_at_note(&entry.expires_at);
+
+                if days >= 0 {
+                    format!(
+                        "{} {} {} ({}) истекает через: {} {}{} для {}{}",
+                        self.label(entry.severity),
+                        noun,
+                        entry.serial,
+                        entry.issuer,
+                        days,
+                        day_word,
+                        expires_at,
+                        entry.hostname,
+                        more_info
+                    )
+                } else {
+                    format!(
+                        "{} {} {} ({}) истёк: {} {} назад{} для {}{}",
+                        self.label(entry.severity),
+                        noun,
+                        entry.serial,
+                        entry.issuer,
+                        days.abs(),
+                        day_word,
+                        expires_at,
+                        entry.hostname,
+                        more_info
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn format_domain_entries(&self) -> Vec<String> {
+        let mut entries: Vec<&DomainCheckResult> = self.domain_entries.iter().collect();
+        entries.sort_by_key(|entry| (Reverse(entry.severity), entry.days));
+        entries
+            .into_iter()
+            .map(|entry| {
+                let days = entry.days as i32;
+                let day_word = self.format_days(days);
+                let expires_at = self.expires_at_note(&entry.expires_at);
+                let owner = Self::owner_note(entry.owner.as_deref());
+                if days >= 0 {
+                    format!(
+                        "- Домен {} истекает через {} {}{}{}",
+                        entry.hostname, days, day_word, expires_at, owner
+                    )
+                } else {
+                    format!(
+                        "Домен истёк: {} {} назад{}{}",
+                        days.abs(),
+                        day_word,
+                        expires_at,
+                        owner
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn format_credential_entries(&self) -> Vec<String> {
+        let mut entries: Vec<&DomainCheckResult> =
+            self.credential_entries.iter().collect();
+        entries.sort_by_key(|entry| (Reverse(entry.severity), entry.days));
+        entries
+            .into_iter()
+            .map(|entry| {
+                let days = entry.days as i32;
+                let day_word = self.format_days(days);
+                let expires_at = self.expires_at_note(&entry.expires_at);
+                let owner = Self::owner_note(entry.owner.as_deref());
+                if days >= 0 {
+                    format!(
+                        "{} {} истекает через {} {}{}{}",
+                        self.label(entry.severity),
+                        entry.hostname,
+                        days,
+                        day_word,
+                        expires_at,
+                        owner
+                    )
+                } else {
+                    format!(
+                        "{} {} истёк: {} {} назад{}{}",
+                        self.label(entry.severity),
+                        entry.hostname,
+                        days.abs(),
+                        day_word,
+                        expires_at,
+                        owner
+                    )
+                }
+            })
+            .collect()
+    }
+
+    /// Ошибки, сгруппированные по виду: заголовок группы и ошибки под ним
+    fn format_errors(&self) -> Vec<String> {
+        let now = Utc::now();
+        CheckError::grouped(&self.errors)
+            .into_iter()
+            .flat_map(|(kind, errors)| {
+                std::iter::once(format!("{}:", kind.title()))
+                    .chain(errors.into_iter().map(move |e| format!("- {}", e.line(now))))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl BaseNotifierTrait for ConsoleNotifierService {
+    async fn ssl_expiration(&mut self, entry: &SslCheckResult) {
+        self.ssl_entries.push(entry.clone());
+    }
+    async fn mail_expiration(&mut self, entry: &SslCheckResult) {
+        self.mail_entries.push(entry.clone());
+    }
+    async fn exception(&mut self, error: &CheckError) {
+        self.errors.push(error.clone());
+    }
+    async fn expiration(&mut self, entry: &DomainCheckResult) {
+        self.domain_entries.push(entry.clone());
+    }
+
+    async fn credential_expiration(&mut self, entry: &DomainCheckResult) {
+        self.credential_entries.push(entry.clone());
+    }
+
+    async fn changes(&mut self, changes: &RunChanges) {
+        self.changes = Some(changes.clone());
+    }
+
+    async fn stats(&mut self, stats: &RunStats) {
+        self.stats = Some(stats.clone());
+    }
+
+    fn get_notifier_name(&self) -> &'static str {
+        self.dcl
+    }
+
+    async fn commit(&self) -> Result<()> {
+        let headers = &self.labels.headers;
+        if let Some(changes) = &self.changes {
+            tracing::warn!(
+                dcl = self.dcl,
+                "{}\n{}",
+                headers.changes.as_deref().unwrap_or("Что изменилось:"),
+                changes.lines().join("\n")
+            );
+        }
+
+        if let Some(stats) = &self.stats {
+            tracing::info!(
+                dcl = self.dcl,
+                "Статистика запуска:\n{}",
+                stats.lines().join("\n")
+            );
+        }
+
+        let ssl_messages = self.format_ssl_entries(&self.ssl_entries, "Сертификат");
+        let mail_messages =
+            self.format_ssl_entries(&self.mail_entries, "Сертификат почтового сервера");
+        let domain_messages = self.format_domain_entries();
+        let credential_messages = self.format_credential_entries();
+        let error_messages = self.format_errors();
+
+        if ssl_messages.is_empty()
+            && mail_messages.is_empty()
+            && domain_messages.is_empty()
+            && credential_messages.is_empty()
+            && error_messages.is_empty()
+        {
+            tracing::warn!(dcl = self.dcl, "Отсутствуют сообщения для отправки");
+            return Ok(());
+        }
+
+        if !ssl_messages.is_empty() {
+            tracing::warn!(
+                dcl = self.dcl,
+                "{}\n{}",
+                headers
+                    .ssl
+                    .as_deref()
+                    .unwrap_or("Срок действия SSL‑сертификатов истекает:"),
+                ssl_messages.join("\n")
+            );
+        }
+
+        if !mail_messages.is_empty() {
+            tracing::warn!(
+                dcl = self.dcl,
+                "{}\n{}",
+                headers
+                    .mail
+                    .as_deref()
+                    .unwrap_or("Срок действия сертификатов почтовых серверов истекает:"),
+                mail_messages.join("\n")
+            );
+        }
+
+        if !domain_messages.is_empty() {
+            tracing::warn!(
+                dcl = self.dcl,
+                "{}\n{}",
+                headers.domains.as_deref().unwrap_or("Срок действия доменов истекает:"),
+                domain_messages.join("\n")
+            );
+        }
+
+        if !credential_messages.is_empty() {
+            tracing::warn!(
+                dcl = self.dcl,
+                "{}\n{}",
+                headers
+                    .credentials
+                    .as_deref()
+                    .unwrap_or("Срок действия учётных данных истекает:"),
+                credential_messages.join("\n")
+            );
+        }
+
+        if !error_messages.is_empty() {
+            tracing::error!(
+                dcl = self.dcl,
+                "{}\n{}",
+                headers.errors.as_deref().unwrap_or("Произошли ошибки:"),
+                error_messages.join("\n")
+            );
+        }
+
+        Ok(())
+    }
+}
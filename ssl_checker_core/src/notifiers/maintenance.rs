@@ -0,0 +1,123 @@
+use super::BaseNotifierTrait;
+use crate::config::MaintenanceWindowConfig;
+use crate::report::{
+    CheckError, DomainCheckResult, RunChanges, RunStats, SslCheckResult,
+};
+use async_trait::async_trait;
+use base::prelude::{anyhow::Result, chrono::Utc, tracing};
+
+/// Обёртка модуля уведомлений, которая не передаёт ему записи и ошибки хостов
+/// из действующих окон работ. Остальное, включая статистику и служебные ошибки
+/// без хоста, передаётся без изменений
+pub struct MaintenanceNotifier {
+    inner: Box<dyn BaseNotifierTrait>,
+    windows: Vec<MaintenanceWindowConfig>,
+    /// Хосты, записи которых не переданы за запуск
+    muted: Vec<String>,
+    dcl: &'static str,
+}
+
+impl MaintenanceNotifier {
+    pub fn new(
+        inner: Box<dyn BaseNotifierTrait>,
+        windows: Vec<MaintenanceWindowConfig>,
+    ) -> Self {
+        Self { inner, windows, muted: Vec::new(), dcl: "MaintenanceNotifier" }
+    }
+
+    /// Действующее окно работ, в которое попадает хост
+    fn window(&self, hostname: &str) -> Option<&MaintenanceWindowConfig> {
+        let now = Utc::now();
+        self.windows.iter().find(|w| w.is_active(now) && w.covers(hostname))
+    }
+
+    /// Запоминает хост, если его запись нужно подавить
+    fn mute(&mut self, hostname: &str) -> bool {
+        let Some(window) = self.window(hostname) else {
+            return false;
+        };
+        tracing::debug!(
+            dcl = self.dcl,
+            notifier = self.inner.get_notifier_name(),
+            hostname,
+            reason = window.reason.as_deref().unwrap_or_default(),
+            "Запись не отправлена: окно работ"
+        );
+        if !self.muted.iter().any(|h| h == hostname) {
+            self.muted.push(hostname.to_string());
+        }
+        true
+    }
+}
+
+#[async_trait]
+impl BaseNotifierTrait for MaintenanceNotifier {
+    async fn run_started(&mut self, run_id: &str) {
+        self.muted.clear();
+        self.inner.run_started(run_id).await
+    }
+
+    async fn ssl_expiration(&mut self, entry: &SslCheckResult) {
+        if !self.mute(&entry.hostname) {
+            self.inner.ssl_expiration(entry).await
+        }
+    }
+
+    async fn mail_expiration(&mut self, entry: &SslCheckResult) {
+        if !self.mute(&entry.hostname) {
+            self.inner.mail_expiration(entry).await
+        }
+    }
+
+    async fn exception(&mut self, error: &CheckError) {
+        if error.hostname.as_deref().is_none_or(|hostname| !self.mute(hostname)) {
+            self.inner.exception(error).await
+        }
+    }
+
+    async fn expiration(&mut self, entry: &DomainCheckResult) {
+        if !self.mute(&entry.hostname) {
+            self.inner.expiration(entry).await
+        }
+    }
+
+    async fn credential_expiration(&mut self, entry: &DomainCheckResult) {
+        if !self.mute(&entry.hostname) {
+            self.inner.credential_expiration(entry).await
+        }
+    }
+
+    async fn changes(&mut self, changes: &RunChanges) {
+        let mut changes = changes.clone();
+        for entries in [&mut changes.new, &mut changes.renewed, &mut changes.closer] {
+            entries.retain(|entry| self.window(&entry.hostname).is_none());
+        }
+        if !changes.is_empty() {
+            self.inner.changes(&changes).await
+        }
+    }
+
+    async fn stats(&mut self, stats: &RunStats) {
+        self.inner.stats(stats).await
+    }
+
+    async fn commit(&self) -> Result<()> {
+        if !self.muted.is_empty() {
+            tracing::info!(
+                dcl = self.dcl,
+                notifier = self.inner.get_notifier_name(),
+                hosts = ?self.muted,
+                "Записи хостов не отправлены: действует окно работ"
+            );
+        }
+        self.inner.commit().await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    fn get_notifier_name(&self) -> &'static str {
+        self.inner.get_notifier_name()
+    }
+}
@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+
+mod console;
+mod maintenance;
+mod telegram;
+#[cfg(feature = "wasm")]
+mod wasm;
+pub use console::ConsoleNotifierService;
+pub use maintenance::MaintenanceNotifier;
+pub use telegram::TelegramNotifierService;
+#[cfg(feature = "wasm")]
+pub use wasm::WasmNotifierService;
+
+use crate::report::{CheckError, DomainCheckResult, RunChanges, RunStats, SslCheckResult};
+use base::prelude::anyhow::Result;
+
+#[async_trait]
+pub trait BaseNotifierTrait: Send + Sync {
+    /// Начало запуска с идентификатором `run_id`, передаётся до всех записей.
+    /// Идентификатор стоит указывать в уведомлениях, чтобы найти запуск в логах.
+    /// По умолчанию не используется
+    async fn run_started(&mut self, _run_id: &str) {}
+
+    /// Добавление SSL-записи
+    async fn ssl_expiration(&mut self, entry: &SslCheckResult);
+
+    /// Добавление сертификата почтового сервера. По умолчанию передаётся
+    /// в `ssl_expiration`, как обычный сертификат
+    async fn mail_expiration(&mut self, entry: &SslCheckResult) {
+        self.ssl_expiration(entry).await
+    }
+
+    /// Добавление ошибки. Модули выводят ошибки сгруппированными по виду
+    /// ([`CheckError::grouped`]) с длительностью сбоя ([`CheckError::line`])
+    async fn exception(&mut self, error: &CheckError);
+
+    /// Добавление обычной записи (домены)
+    async fn expiration(&mut self, entry: &DomainCheckResult);
+
+    /// Добавление учётных данных с истекающим сроком; `hostname` записи —
+    /// имя из конфигурации. По умолчанию передаётся в `expiration`
+    async fn credential_expiration(&mut self, entry: &DomainCheckResult) {
+        self.expiration(entry).await
+    }
+
+    /// Отличия от предыдущего запуска, передаются перед `commit`, если они есть.
+    /// По умолчанию не используются
+    async fn changes(&mut self, _changes: &RunChanges) {}
+
+    /// Статистика запуска, передаётся перед `commit`. По умолчанию не используется
+    async fn stats(&mut self, _stats: &RunStats) {}
+
+    /// Обязательный метод — аналог commit()
+    async fn commit(&self) -> Result<()>;
+
+    /// Отправка накопленного до конца запуска при доставке уведомлений частями
+    /// (`DomainCheckerService::with_notify_chunk_size`). Отправленное не должно
+    /// повторно уйти в `commit`. По умолчанию всё отправляется в `commit`
+    async fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Имя модуля уведомлений для логов и метрик
+    fn get_notifier_name(&self) -> &'static str;
+
+    /// Пометка сертификата ACME-издателя в окне оповещения
+    fn acme_note(&self) -> &'static str {
+        "ожидается автоматическое продление — проверьте процесс обновления"
+    }
+
+    /// Пометка сертификата, OCSP или CRL издателя которого недоступны
+    fn revocation_note(&self, urls: &[String]) -> String {
+        format!(
+            "недоступны OCSP/CRL издателя ({}) — продление может не пройти",
+            urls.join(", ")
+        )
+    }
+
+    /// Дата истечения в скобках после срока; пусто, если дата не передана
+    fn expires_at_note(&self, expires_at: &str) -> String {
+        if expires_at.is_empty() { String::new() } else { format!(" ({})", expires_at) }
+    }
+
+    /// Вспомогательный метод (не async)
+    fn format_days(&self, n: i32) -> &'static str {
+        let n = n.abs();
+        if (11..=14).contains(&(n % 100)) {
+            return "дней";
+        }
+        match n % 10 {
+            1 => "день",
+            2..=4 => "дня",
+            _ => "дней",
+        }
+    }
+}
@@ -0,0 +1,761 @@
+use super::BaseNotifierTrait;
+use crate::checks;
+use crate::config::NotifierLabels;
+use crate::http::HttpSettings;
+use crate::overrides::DomainOverrides;
+use crate::report::{
+    CheckError, DomainCheckResult, RunChanges, RunStats, Severity, SslCheckResult,
+};
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{self, Result},
+    chrono::Utc,
+    serde_json::json,
+    tokio,
+};
+use reqwest::Client;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+pub struct TelegramNotifierService {
+    ssl_entries: Vec<SslCheckResult>,
+    mail_entries: Vec<SslCheckResult>,
+    domain_entries: Vec<DomainCheckResult>,
+    credential_entries: Vec<DomainCheckResult>,
+    errors: Vec<CheckError>,
+    changes: Option<RunChanges>,
+    stats: Option<RunStats>,
+    /// Идентификатор запуска для подписи под каждым сообщением
+    run_id: Option<String>,
+    chat_id: String,
+    http: HttpSettings,
+    api_url: String,
+    client: Client,
+    group_by_root: bool,
+    min_severity: Severity,
+    /// Команды-владельцы, записи которых отправляются в этот чат; пусто — все записи
+    owners: Vec<String>,
+    /// Упоминания команд-владельцев в записях: команда → `@username`
+    mentions: HashMap<String, String>,
+    /// Упоминания по шаблонам имён хостов, от более точного шаблона к менее точному
+    host_mentions: Vec<(String, String)>,
+    labels: NotifierLabels,
+}
+
+impl TelegramNotifierService {
+    const MAX_MESSAGE_LENGTH: usize = 4096;
+    /// Наибольшая длина списка хостов в одной записи группы: остальное место
+    /// сообщения занимают заголовки группы и раздела, упоминания и подпись
+    const MAX_GROUP_HOSTS_LENGTH: usize = 3000;
+    const API_URL: &'static str = "https://api.telegram.org";
+
+    pub fn new(bot_token: &str, chat_id: &str, http: HttpSettings) -> Self {
+        let api_url = format!("{}/bot{}/sendMessage", Self::API_URL, bot_token);
+        let client = http.client();
+
+        Self {
+            ssl_entries: Vec::new(),
+            mail_entries: Vec::new(),
+            domain_entries: Vec::new(),
+            credential_entries: Vec::new(),
+            errors: Vec::new(),
+            changes: None,
+            stats: None,
+            run_id: None,
+            chat_id: chat_id.to_string(),
+            http,
+            api_url,
+            client,
+            group_by_root: false,
+            min_severity: Severity::Info,
+            owners: Vec::new(),
+            mentions: HashMap::new(),
+            host_mentions: Vec::new(),
+            labels: NotifierLabels::default(),
+        }
+    }
+
+    /// Записи ниже указанной важности в этот чат не отправляются
+    pub fn with_min_severity(mut self, min_severity: Severity) -> Self {
+        self.min_severity = min_severity;
+        self
+    }
+
+    /// В этот чат отправляются только записи хостов указанных команд-владельцев,
+    /// например чат команды платежей. Ошибки проверки отправляются во все чаты
+    pub fn with_owners(mut self, owners: Vec<String>) -> Self {
+        self.owners = owners;
+        self
+    }
+
+    /// Упоминания команд-владельцев, добавляемые к записям их хостов, например
+    /// `payments` → `@payments_oncall`
+    pub fn with_mentions(mut self, mentions: HashMap<String, String>) -> Self {
+        self.mentions = mentions;
+        self
+    }
+
+    /// Упоминания ответственных по шаблонам имён хостов, как в секции `domains`:
+    /// `*.pay.example.com` → `@ivan`. К записи добавляются упоминания всех
+    /// подходящих шаблонов
+    pub fn with_host_mentions(mut self, mentions: HashMap<String, String>) -> Self {
+        let mut mentions: Vec<(String, String)> = mentions
+            .into_iter()
+            .map(|(pattern, mention)| (pattern.trim().to_lowercase(), mention))
+            .collect();
+        mentions.sort_by(|(a, _), (b, _)| {
+            DomainOverrides::specificity(b)
+                .cmp(&DomainOverrides::specificity(a))
+                .then_with(|| a.cmp(b))
+        });
+        self.host_mentions = mentions;
+        self
+    }
+
+    /// Запись проходит фильтры важности и владельца
+    fn accepts(&self, severity: Severity, owner: Option<&String>) -> bool {
+        severity >= self.min_severity
+            && (self.owners.is_empty() || owner.is_some_and(|o| self.owners.contains(o)))
+    }
+
+    /// Владелец и его упоминание для строки записи
+    fn owner_note(&self, owner: &str) -> String {
+        let mut note = format!("<b>{}</b>", html_escape::encode_text(owner));
+        if let Some(mention) = self.mentions.get(owner) {
+            note = format!("{} {}", note, html_escape::encode_text(mention));
+        }
+        note
+    }
+
+    /// Упоминания ответственных за хост; пометки ` (QUIC)` и ` (SSH)` не учитываются
+    fn host_mentions(&self, hostname: &str) -> Vec<&str> {
+        let hostname = hostname.split(' ').next().unwrap_or_default().to_lowercase();
+        let mut mentions: Vec<&str> = Vec::new();
+        for (pattern, mention) in &self.host_mentions {
+            let mention = mention.as_str();
+            if DomainOverrides::matches(pattern, &hostname)
+                && !mentions.contains(&mention)
+            {
+                mentions.push(mention);
+            }
+        }
+        mentions
+    }
+
+    /// Строки записи с владельцем и ответственными за хост
+    fn owner_lines(&self, hostname: &str, owner: Option<&str>) -> String {
+        let mut lines = String::new();
+        if let Some(owner) = owner {
+            lines.push_str(&format!("├ Владелец: {}\n", self.owner_note(owner)));
+        }
+        let mentions = self.host_mentions(hostname);
+        if !mentions.is_empty() {
+            let mentions = html_escape::encode_text(&mentions.join(" ")).to_string();
+            lines.push_str(&format!("├ Ответственные: {}\n", mentions));
+        }
+        lines
+    }
+
+    /// Сертификаты хостов одного корневого домена выводятся одной записью с числом
+    /// сертификатов и ближайшим истечением; список хостов свёрнут под спойлером
+    pub fn with_group_by_root(mut self, group_by_root: bool) -> Self {
+        self.group_by_root = group_by_root;
+        self
+    }
+
+    /// Адрес сервера Bot API вместо api.telegram.org, например собственного
+    /// telegram-bot-api или тестового сервера
+    pub fn with_api_url(mut self, url: &str) -> Self {
+        self.api_url = self.api_url.replacen(Self::API_URL, url.trim_end_matches('/'), 1);
+        self
+    }
+
+    /// Значки важности и заголовки разделов вместо встроенных. Заголовок
+    /// экранируется и выводится жирным
+    pub fn with_labels(mut self, labels: NotifierLabels) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    fn icon(&self, severity: Severity) -> &str {
+        self.labels.icon(severity).unwrap_or(severity.icon())
+    }
+
+    fn header(&self, header: Option<&str>, default: &str) -> String {
+        match header {
+            Some(header) => format!("<b>{}</b>", html_escape::encode_text(header)),
+            None => default.to_string(),
+        }
+    }
+
+    /// Подпись с идентификатором запуска, по которому сообщение находится в логах
+    fn footer(&self) -> String {
+        match &self.run_id {
+            Some(run_id) => {
+                format!("\n\n<code>run_id={}</code>", html_escape::encode_text(run_id))
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Разбивает сообщения на чанки по лимиту Telegram
+    fn chunk_messages(&self, header: &str, messages: &[String]) -> Vec<Vec<String>> {
+        let separator_length = 2;
+        let mut chunks = Vec::new();
+        let mut current_chunk = Vec::new();
+        // Подпись с идентификатором запуска занимает место в каждом сообщении
+        let header_length = header.len() + self.footer().len();
+        let mut current_length = header_length + separator_length;
+
+        for msg in messages {
+            let msg_length = msg.len() + separator_length;
+
+            if current_length + msg_length > Self::MAX_MESSAGE_LENGTH {
+                if !current_chunk.is_empty() {
+                    chunks.push(current_chunk);
+                    current_chunk = Vec::new();
+                }
+                current_chunk.push(msg.clone());
+                current_length =
+                    header_length + separator_length + msg.len() + separator_length;
+            } else {
+                current_chunk.push(msg.clone());
+                current_length += msg_length;
+            }
+        }
+
+        if !current_chunk.is_empty() {
+            chunks.push(current_chunk);
+        }
+
+        chunks
+    }
+
+    /// Отправляет список сообщений с заголовком
+    async fn send_messages(&self, header: &str, messages: Vec<String>) -> Result<()> {
+        let chunks = self.chunk_messages(header, &messages);
+        let footer = self.footer();
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let prefix = if chunks.len() > 1 {
+                format!("[{}/{}] ", i + 1, chunks.len())
+            } else {
+                String::new()
+            };
+
+            let text =
+                format!("{}{}\n\n{}{}", prefix, header, chunk.join("\n\n"), footer);
+            self.send_message(&text).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Отправляет одно сообщение в Telegram с retry логикой
+    async fn send_message(&self, text: &str) -> Result<()> {
+        for attempt in 0..=self.http.retries {
+            match self
+                .client
+                .post(&self.api_url)
+                .json(&json!({
+                    "chat_id": &self.chat_id,
+                    "text": text,
+                    "parse_mode": "HTML",
+                    "disable_web_page_preview": true
+                }))
+                .send()
+                .await
+            {
+                Ok(response) => {
+                    if response.status().is_success() {
+                        return Ok(());
+                    } else {
+                        eprintln!(
+                            "ERROR: Telegram API returned status: {}",
+                            response.status()
+                        );
+                    }
+                }
+                Err(e) => {
+                    eprintln!("ERROR: Ошибка отправки сообщения в Telegram: {}", e);
+                }
+            }
+
+            if attempt == self.http.retries {
+                eprintln!("ERROR: Превышено количество попыток отправки");
+                return Err(anyhow::anyhow!(
+                    "Failed to send message after {} retries",
+                    self.http.retries
+                ));
+            }
+
+            tokio::time::sleep(self.http.retry_interval).await;
+        }
+
+        Ok(())
+    }
+
+    /// Форматирует информацию о SSL сертификатах
+    fn format_ssl_entries(&self) -> Vec<String> {
+        if self.group_by_root {
+            return self.format_ssl_groups();
+        }
+        let mut entries: Vec<&SslCheckResult> = self.ssl_entries.iter().collect();
+        entries.sort_by_key(|entry| (Reverse(entry.severity), entry.days));
+        entries.into_iter().map(|entry| self.format_ssl_entry(entry)).collect()
+    }
+
+    /// Сертификаты почтовых серверов; на хост нет ссылки, сервер не отвечает по HTTPS
+    fn format_mail_entries(&self) -> Vec<String> {
+        let mut entries: Vec<&SslCheckResult> = self.mail_entries.iter().collect();
+        entries.sort_by_key(|entry| (Reverse(entry.severity), entry.days));
+        entries
+            .into_iter()
+            .map(|entry| {
+                let host =
+                    format!("<code>{}</code>", html_escape::encode_text(&entry.hostname));
+                self.format_certificate(entry, "Сертификат почтового сервера", host)
+            })
+            .collect()
+    }
+
+    /// Сертификаты, сгруппированные по корневому домену. Домен с одним сертификатом
+    /// выводится обычной записью
+    fn format_ssl_groups(&self) -> Vec<String> {
+        let mut groups: Vec<(String, Vec<&SslCheckResult>)> = Vec::new();
+        for entry in &self.ssl_entries {
+            let root = checks::root_domain(&entry.hostname)
+                .unwrap_or_else(|| entry.hostname.clone());
+            match groups.iter_mut().find(|(name, _)| *name == root) {
+                Some((_, entries)) => entries.push(entry),
+                None => groups.push((root, vec![entry])),
+            }
+        }
+        for (_, entries) in &mut groups {
+            entries.sort_by_key(|entry| entry.days);
+        }
+        groups.sort_by_key(|(_, entries)| {
+            (Reverse(entries.iter().map(|entry| entry.severity).max()), entries[0].days)
+        });
+
+        groups
+            .into_iter()
+            .flat_map(|(root, entries)| match entries.as_slice() {
+                [entry] => vec![self.format_ssl_entry(entry)],
+                _ => self.format_ssl_group(&root, &entries),
+            })
+            .collect()
+    }
+
+    /// Свёрнутая запись корневого домена, `entries` отсортированы по сроку. Длинный
+    /// список хостов делится на несколько записей, каждая помещается в сообщение
+    fn format_ssl_group(&self, root: &str, entries: &[&SslCheckResult]) -> Vec<String> {
+        let days = entries[0].days as i32;
+        let count = entries.len();
+        let nearest = if days >= 0 {
+            format!("ближайшее истечение через {} {}", days, self.format_days(days))
+        } else {
+            format!("ближайший истёк {} {} назад", days.abs(), self.format_days(days))
+        };
+        let severity =
+            entries.iter().map(|entry| entry.severity).max().unwrap_or_default();
+        let icon = self.icon(severity);
+        let hosts: Vec<String> = entries
+            .iter()
+            .map(|entry| {
+                let acme = if entry.acme { " ♻️" } else { "" };
+                let revocation = if entry.unreachable_revocation.is_empty() {
+                    ""
+                } else {
+                    " ⚠️ OCSP/CRL"
+                };
+                format!(
+                    "{}: {} дн.{}{}{}",
+                    html_escape::encode_text(&entry.hostname),
+                    entry.days,
+                    self.expires_at_note(&entry.expires_at),
+                    acme,
+                    revocation
+                )
+            })
+            .collect();
+        let mut owners: Vec<&str> =
+            entries.iter().filter_map(|entry| entry.owner.as_deref()).collect();
+        owners.sort();
+        owners.dedup();
+        let owners = if owners.is_empty() {
+            String::new()
+        } else {
+            let notes: Vec<String> =
+                owners.into_iter().map(|owner| self.owner_note(owner)).collect();
+            format!("\nВладелец: {}", notes.join(", "))
+        };
+        let mut mentions: Vec<&str> = Vec::new();
+        for entry in entries {
+            for mention in self.host_mentions(&entry.hostname) {
+                if !mentions.contains(&mention) {
+                    mentions.push(mention);
+                }
+            }
+        }
+        let owners = if mentions.is_empty() {
+            owners
+        } else {
+            let mentions = html_escape::encode_text(&mentions.join(" ")).to_string();
+            format!("{}\nОтветственные: {}", owners, mentions)
+        };
+
+        let mut parts: Vec<String> = Vec::new();
+        for host in hosts {
+            match parts.last_mut() {
+                Some(part) if part.len() + host.len() < Self::MAX_GROUP_HOSTS_LENGTH => {
+                    part.push('\n');
+                    part.push_str(&host);
+                }
+                _ => parts.push(host),
+            }
+        }
+        let total = parts.len();
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(i, part)| {
+                let number = match total {
+                    1 => String::new(),
+                    _ => format!(" ({}/{})", i + 1, total),
+                };
+                format!(
+                    "{} <b>{}</b>{} — {} {}, {}{}\n\
+                     <blockquote expandable>{}</blockquote>",
+                    icon,
+                    html_escape::encode_text(root),
+                    number,
+                    count,
+                    Self::certificates_word(count),
+                    nearest,
+                    owners,
+                    part
+                )
+            })
+            .collect()
+    }
+
+    fn certificates_word(n: usize) -> &'static str {
+        if (11..=14).contains(&(n % 100)) {
+            return "сертификатов";
+        }
+        match n % 10 {
+            1 => "сертификат",
+            2..=4 => "сертификата",
+            _ => "сертификатов",
+        }
+    }
+
+    fn format_ssl_entry(&self, entry: &SslCheckResult) -> String {
+        let host = format!(
+            "<a href=\"https://{}\">{}</a>",
+            entry.hostname,
+            html_escape::encode_text(&entry.hostname)
+        );
+        self.format_certificate(entry, "Сертификат", host)
+    }
+
+    /// Запись о сертификате; `host` — уже размеченное имя хоста
+    fn format_certificate(
+        &self,
+        entry: &SslCheckResult,
+        title: &str,
+        host: String,
+    ) -> String {
+        let issuer = html_escape::encode_text(&entry.issuer);
+        let days = entry.days as i32;
+        let day_word = self.format_days(days);
+        let more_info =
+            if entry.more > 0 { format!(" (+{})", entry.more) } else { String::new() };
+
+        let expires_at = self.expires_at_note(&entry.expires_at);
+        let exp_words = if days >= 0 {
+            format!("Истекает через: <b>{} {}</b>{}", days, day_word, expires_at)
+        } else {
+            format!("Истёк: <b>{} {} назад</b>{}", days.abs(), day_word, expires_at)
+        };
+
+        let icon = self.icon(entry.severity);
+        let acme_note = if entry.acme {
+            format!("├ ♻️ <i>{}</i>\n", self.acme_note())
+        } else {
+            String::new()
+        };
+        let revocation_note = if entry.unreachable_revocation.is_empty() {
+            String::new()
+        } else {
+            let note = self.revocation_note(&entry.unreachable_revocation);
+            format!("├ ⚠️ <i>{}</i>\n", html_escape::encode_text(&note))
+        };
+        let owner_note = self.owner_lines(&entry.hostname, entry.owner.as_deref());
+
+        format!(
+            "{} <b>{} {}</b>\n\
+                ├ Издатель: <code>{}</code>\n\
+                ├ Хост: {}{}\n\
+                {}{}{}└ {}",
+            icon,
+            title,
+            entry.serial,
+            issuer,
+            host,
+            more_info,
+            owner_note,
+            acme_note,
+            revocation_note,
+            exp_words
+        )
+    }
+
+    /// Форматирует информацию о доменах
+    fn format_domain_entries(&self) -> Vec<String> {
+        let mut entries: Vec<&DomainCheckResult> = self.domain_entries.iter().collect();
+        entries.sort_by_key(|entry| (Reverse(entry.severity), entry.days));
+        entries
+            .into_iter()
+            .map(|entry| {
+                let hostname_escaped = html_escape::encode_text(&entry.hostname);
+                let days = entry.days as i32;
+                let day_word = self.format_days(days);
+
+                let expires_at = self.expires_at_note(&entry.expires_at);
+                let exp_words = if days >= 0 {
+                    format!("Истекает через: <b>{} {}</b>{}", days, day_word, expires_at)
+                } else {
+                    format!(
+                        "Истёк: <b>{} {} назад</b>{}",
+                        days.abs(),
+                        day_word,
+                        expires_at
+                    )
+                };
+
+                let icon = self.icon(entry.severity);
+
+                let url = format!("https://{}", entry.hostname);
+                let owner_note =
+                    self.owner_lines(&entry.hostname, entry.owner.as_deref());
+                format!(
+                    "{} <b>Домен</b>: <a href=\"{}\">{}</a>\n{}└ {}",
+                    icon, url, hostname_escaped, owner_note, exp_words
+                )
+            })
+            .collect()
+    }
+
+    /// Учётные данные: имя из конфигурации без ссылки
+    fn format_credential_entries(&self) -> Vec<String> {
+        let mut entries: Vec<&DomainCheckResult> =
+            self.credential_entries.iter().collect();
+        entries.sort_by_key(|entry| (Reverse(entry.severity), entry.days));
+        entries
+            .into_iter()
+            .map(|entry| {
+                let days = entry.days as i32;
+                let day_word = self.format_days(days);
+                let expires_at = self.expires_at_note(&entry.expires_at);
+                let exp_words = if days >= 0 {
+                    format!("Истекает через: <b>{} {}</b>{}", days, day_word, expires_at)
+                } else {
+                    format!(
+                        "Истёк: <b>{} {} назад</b>{}",
+                        days.abs(),
+                        day_word,
+                        expires_at
+                    )
+                };
+                let owner_note =
+                    self.owner_lines(&entry.hostname, entry.owner.as_deref());
+                format!(
+                    "{} <b>{}</b>\n{}└ {}",
+                    self.icon(entry.severity),
+                    html_escape::encode_text(&entry.hostname),
+                    owner_note,
+                    exp_words
+                )
+            })
+            .collect()
+    }
+
+    /// Форматирует отличия от предыдущего запуска, одна группа на сообщение
+    fn format_changes(&self) -> Vec<String> {
+        let Some(changes) = &self.changes else {
+            return Vec::new();
+        };
+        let closer = format!("{} Стали критическими", self.icon(Severity::Critical));
+        [
+            ("🆕 Новые в окне оповещения", &changes.new),
+            ("✅ Продлены", &changes.renewed),
+            (closer.as_str(), &changes.closer),
+        ]
+        .into_iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .map(|(title, entries)| {
+            let lines: Vec<String> = entries
+                .iter()
+                .map(|entry| {
+                    let days =
+                        entry.days.map(|d| format!(": {} дн.", d)).unwrap_or_default();
+                    format!("├ {}{}", html_escape::encode_text(&entry.hostname), days)
+                })
+                .collect();
+            format!("<b>{}</b>\n{}", title, lines.join("\n"))
+        })
+        .collect()
+    }
+
+    /// Форматирует статистику запуска для подписи под последним сообщением
+    fn format_stats(&self) -> Option<String> {
+        let lines = self.stats.as_ref()?.lines();
+        let escaped: Vec<_> = lines
+            .iter()
+            .map(|line| html_escape::encode_text(line).into_owned())
+            .collect();
+        Some(format!("<i>{}</i>", escaped.join("\n")))
+    }
+
+    /// Форматирует ошибки, сгруппированные по виду: заголовок группы дописывается
+    /// к первой ошибке, чтобы при разбиении на сообщения он не отрывался от неё
+    fn format_errors(&self) -> Vec<String> {
+        let now = Utc::now();
+        CheckError::grouped(&self.errors)
+            .into_iter()
+            .flat_map(|(kind, errors)| {
+                let title = format!(
+                    "{} <b>{}</b> ({}):",
+                    self.icon(Severity::Critical),
+                    html_escape::encode_text(kind.title()),
+                    errors.len()
+                );
+                errors.into_iter().enumerate().map(move |(i, error)| {
+                    let line = format!(
+                        "<code>{}</code>",
+                        html_escape::encode_text(&error.line(now))
+                    );
+                    if i == 0 { format!("{}\n{}", title, line) } else { line }
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl BaseNotifierTrait for TelegramNotifierService {
+    async fn run_started(&mut self, run_id: &str) {
+        self.run_id = Some(run_id.to_string());
+    }
+
+    async fn ssl_expiration(&mut self, entry: &SslCheckResult) {
+        if self.accepts(entry.severity, entry.owner.as_ref()) {
+            self.ssl_entries.push(entry.clone());
+        }
+    }
+
+    async fn mail_expiration(&mut self, entry: &SslCheckResult) {
+        if self.accepts(entry.severity, entry.owner.as_ref()) {
+            self.mail_entries.push(entry.clone());
+        }
+    }
+
+    async fn exception(&mut self, error: &CheckError) {
+        self.errors.push(error.clone());
+    }
+
+    async fn expiration(&mut self, entry: &DomainCheckResult) {
+        if self.accepts(entry.severity, entry.owner.as_ref()) {
+            self.domain_entries.push(entry.clone());
+        }
+    }
+
+    async fn credential_expiration(&mut self, entry: &DomainCheckResult) {
+        if self.accepts(entry.severity, entry.owner.as_ref()) {
+            self.credential_entries.push(entry.clone());
+        }
+    }
+
+    async fn changes(&mut self, changes: &RunChanges) {
+        self.changes = Some(changes.clone());
+    }
+
+    async fn stats(&mut self, stats: &RunStats) {
+        self.stats = Some(stats.clone());
+    }
+
+    fn get_notifier_name(&self) -> &'static str {
+        "TelegramNotifierService"
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let result = self.commit().await;
+        self.ssl_entries.clear();
+        self.mail_entries.clear();
+        self.domain_entries.clear();
+        self.credential_entries.clear();
+        self.errors.clear();
+        result
+    }
+
+    async fn commit(&self) -> Result<()> {
+        let headers = &self.labels.headers;
+        let mut sections: Vec<(String, Vec<String>)> = [
+            (
+                self.header(headers.changes.as_deref(), "🔄 <b>Что изменилось:</b>"),
+                self.format_changes(),
+            ),
+            (
+                self.header(
+                    headers.ssl.as_deref(),
+                    "⚠️ <b>Срок действия SSL‑сертификатов истекает:</b>",
+                ),
+                self.format_ssl_entries(),
+            ),
+            (
+                self.header(
+                    headers.mail.as_deref(),
+                    "📧 <b>Срок действия сертификатов почтовых серверов истекает:</b>",
+                ),
+                self.format_mail_entries(),
+            ),
+            (
+                self.header(
+                    headers.domains.as_deref(),
+                    "⚠️ <b>Срок действия доменов истекает:</b>",
+                ),
+                self.format_domain_entries(),
+            ),
+            (
+                self.header(
+                    headers.credentials.as_deref(),
+                    "🔑 <b>Срок действия учётных данных истекает:</b>",
+                ),
+                self.format_credential_entries(),
+            ),
+            (
+                self.header(headers.errors.as_deref(), "🔴 <b>Произошли ошибки:</b>"),
+                self.format_errors(),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, messages)| !messages.is_empty())
+        .collect();
+
+        // Статистика дописывается к последнему сообщению; если отправлять нечего,
+        // отдельное сообщение со статистикой не отправляется
+        if let (Some((_, messages)), Some(stats)) =
+            (sections.last_mut(), self.format_stats())
+        {
+            messages.push(stats);
+        }
+
+        for (header, messages) in sections {
+            self.send_messages(&header, messages).await?;
+        }
+
+        Ok(())
+    }
+}
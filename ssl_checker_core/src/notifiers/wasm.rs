@@ -0,0 +1,278 @@
+use super::BaseNotifierTrait;
+use crate::http::HttpSettings;
+use crate::registry::{PluginContext, PluginRegistry};
+use crate::report::{CheckError, DomainCheckResult, SslCheckResult};
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{self, Context, Result, bail},
+    chrono::Utc,
+    serde_json, tokio, tracing,
+};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wasmtime::{Caller, Engine, Linker, Memory, Module, Store};
+
+/// Модуль уведомлений, логика которого задана WASM-модулем (экспериментально).
+///
+/// ABI модуля:
+/// - экспорт `memory` — линейная память;
+/// - экспорт `alloc(len: i32) -> i32` — выделяет буфер, в который хост пишет событие;
+/// - экспорт `notify(ptr: i32, len: i32)` — событие в JSON с полем `kind`
+///   (`run`, `ssl`, `domain` или `error`); при каждом `commit` первым приходит
+///   `run` с `run_id` запуска;
+/// - экспорт `commit() -> i64` — `(ptr << 32) | len` JSON-массива запросов
+///   `[{"url": ..., "headers": {...}, "body": ...}]`, которые хост отправит POST-ом;
+///   нулевая длина — отправлять нечего;
+/// - импорт `env.log(ptr: i32, len: i32)` (необязательный) — строка в лог сервиса.
+///
+/// Модуль создаётся заново при каждом `commit` и не имеет доступа к сети и файлам:
+/// запросы выполняет хост с настройками секции `http`
+pub struct WasmNotifierService {
+    path: String,
+    engine: Engine,
+    module: Module,
+    events: Vec<Vec<u8>>,
+    /// Событие `run`, которое передаётся модулю перед остальными
+    run_event: Option<Vec<u8>>,
+    http: HttpSettings,
+    client: Client,
+    dcl: &'static str,
+}
+
+/// Событие, передаваемое в `notify`
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum WasmEvent<'a> {
+    Run { run_id: &'a str },
+    Ssl(&'a SslCheckResult),
+    Domain(&'a DomainCheckResult),
+    /// `message` — строка ошибки с длительностью сбоя, `error` — её поля
+    Error { message: String, error: &'a CheckError },
+}
+
+/// Запрос, возвращённый из `commit`
+#[derive(Deserialize)]
+struct WasmRequest {
+    url: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+/// Секция `type: wasm` конфигурации
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WasmNotifierConfig {
+    /// Путь к `.wasm`-файлу
+    path: String,
+    timeout_secs: Option<u64>,
+    connect_timeout_secs: Option<u64>,
+    retries: Option<u32>,
+    retry_interval_secs: Option<u64>,
+}
+
+impl WasmNotifierService {
+    /// Ограничение числа инструкций на один `commit`, чтобы зациклившийся модуль
+    /// не блокировал запуск
+    const FUEL: u64 = 1_000_000_000;
+
+    pub fn new(path: &str, http: HttpSettings) -> Result<Self> {
+        let mut config = wasmtime::Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+        let module = Module::from_file(&engine, path)
+            .with_context(|| format!("Не удалось загрузить WASM-модуль {}", path))?;
+        let client = http.client();
+
+        Ok(Self {
+            path: path.to_string(),
+            engine,
+            module,
+            events: Vec::new(),
+            run_event: None,
+            http,
+            client,
+            dcl: "WasmNotifierService",
+        })
+    }
+
+    /// Регистрирует тип `wasm` в реестре модулей уведомлений
+    pub fn register(registry: &PluginRegistry) {
+        registry.register_notifier("wasm", |context: &PluginContext| {
+            let conf: WasmNotifierConfig = context.settings()?;
+            let http = HttpSettings::WASM
+                .with_global(context.http)
+                .with_overrides(
+                    conf.timeout_secs,
+                    conf.connect_timeout_secs,
+                    conf.retries,
+                    conf.retry_interval_secs,
+                )
+                .with_shared_client(context.client, conf.connect_timeout_secs);
+            Ok(Box::new(Self::new(&conf.path, http)?))
+        });
+    }
+
+    fn serialize(&self, event: WasmEvent) -> Option<Vec<u8>> {
+        serde_json::to_vec(&event)
+            .inspect_err(|e| {
+                tracing::error!(
+                    dcl = self.dcl,
+                    error = %e,
+                    "Не удалось сериализовать событие"
+                )
+            })
+            .ok()
+    }
+
+    fn push_event(&mut self, event: WasmEvent) {
+        if let Some(event) = self.serialize(event) {
+            self.events.push(event);
+        }
+    }
+
+    /// Передаёт события модулю и возвращает запросы, которые он сформировал
+    fn run(
+        engine: &Engine,
+        module: &Module,
+        path: &str,
+        events: &[Vec<u8>],
+    ) -> Result<Vec<WasmRequest>> {
+        let mut store = Store::new(engine, ());
+        store.set_fuel(Self::FUEL)?;
+
+        let mut linker = Linker::new(engine);
+        let plugin = path.to_string();
+        linker.func_wrap(
+            "env",
+            "log",
+            move |mut caller: Caller<'_, ()>, ptr: i32, len: i32| -> Result<()> {
+                let memory = caller
+                    .get_export("memory")
+                    .and_then(|export| export.into_memory())
+                    .context("Модуль не экспортирует memory")?;
+                let message = Self::read(&memory, &caller, ptr as u32, len as u32)?;
+                tracing::info!(
+                    dcl = "WasmNotifierService",
+                    plugin = %plugin,
+                    "{}",
+                    String::from_utf8_lossy(&message)
+                );
+                Ok(())
+            },
+        )?;
+
+        let instance = linker.instantiate(&mut store, module)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .context("Модуль не экспортирует memory")?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let notify = instance.get_typed_func::<(i32, i32), ()>(&mut store, "notify")?;
+        let commit = instance.get_typed_func::<(), i64>(&mut store, "commit")?;
+
+        for event in events {
+            let len = i32::try_from(event.len())?;
+            let ptr = alloc.call(&mut store, len)?;
+            memory
+                .write(&mut store, ptr as u32 as usize, event)
+                .context("alloc вернул указатель за пределами памяти")?;
+            notify.call(&mut store, (ptr, len))?;
+        }
+
+        let packed = commit.call(&mut store, ())? as u64;
+        let (ptr, len) = ((packed >> 32) as u32, packed as u32);
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        let output = Self::read(&memory, &store, ptr, len)?;
+        serde_json::from_slice(&output).context("commit вернул некорректный JSON")
+    }
+
+    fn read(
+        memory: &Memory,
+        store: impl wasmtime::AsContext,
+        ptr: u32,
+        len: u32,
+    ) -> Result<Vec<u8>> {
+        let mut buffer = vec![0; len as usize];
+        if memory.read(store, ptr as usize, &mut buffer).is_err() {
+            bail!(
+                "Буфер {}..{} за пределами памяти модуля",
+                ptr,
+                ptr as u64 + len as u64
+            );
+        }
+        Ok(buffer)
+    }
+}
+
+#[async_trait]
+impl BaseNotifierTrait for WasmNotifierService {
+    async fn run_started(&mut self, run_id: &str) {
+        self.run_event = self.serialize(WasmEvent::Run { run_id });
+    }
+
+    async fn ssl_expiration(&mut self, entry: &SslCheckResult) {
+        self.push_event(WasmEvent::Ssl(entry));
+    }
+
+    async fn exception(&mut self, error: &CheckError) {
+        let message = error.line(Utc::now());
+        self.push_event(WasmEvent::Error { message, error });
+    }
+
+    async fn expiration(&mut self, entry: &DomainCheckResult) {
+        self.push_event(WasmEvent::Domain(entry));
+    }
+
+    fn get_notifier_name(&self) -> &'static str {
+        self.dcl
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let result = self.commit().await;
+        self.events.clear();
+        result
+    }
+
+    async fn commit(&self) -> Result<()> {
+        if self.events.is_empty() {
+            return Ok(());
+        }
+
+        // Выполнение модуля — синхронная работа, не занимающая потоки рантайма
+        let (engine, module) = (self.engine.clone(), self.module.clone());
+        let path = self.path.clone();
+        let events: Vec<Vec<u8>> =
+            self.run_event.iter().chain(&self.events).cloned().collect();
+        let requests = tokio::task::spawn_blocking(move || {
+            Self::run(&engine, &module, &path, &events)
+        })
+        .await?
+        .with_context(|| format!("Ошибка выполнения WASM-модуля {}", self.path))?;
+
+        for request in requests {
+            let response = self
+                .http
+                .send(|| {
+                    let mut builder =
+                        self.client.post(&request.url).body(request.body.clone());
+                    for (name, value) in &request.headers {
+                        builder = builder.header(name, value);
+                    }
+                    builder
+                })
+                .await?;
+            if !response.status().is_success() {
+                return Err(anyhow::anyhow!(
+                    "{} ответил статусом {}",
+                    request.url,
+                    response.status()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::config::DomainOverrideConfig;
+use std::collections::HashMap;
+
+/// Настройки проверки из секции `domains`, сопоставленные хостам по шаблонам.
+/// Шаблон — имя хоста, в котором `*` заменяет любую последовательность символов
+/// (`*.example.com`, `api-*.example.org`)
+#[derive(Debug, Default)]
+pub struct DomainOverrides {
+    /// Правила в порядке возрастания точности шаблона
+    rules: Vec<(String, DomainOverrideConfig)>,
+}
+
+impl DomainOverrides {
+    pub fn new(config: &HashMap<String, DomainOverrideConfig>) -> Self {
+        let mut rules: Vec<(String, DomainOverrideConfig)> = config
+            .iter()
+            .map(|(pattern, settings)| (pattern.trim().to_lowercase(), settings.clone()))
+            .collect();
+        rules.sort_by_key(|(pattern, _)| Self::specificity(pattern));
+        Self { rules }
+    }
+
+    /// Итоговые настройки хоста: значения более точного шаблона имеют приоритет,
+    /// теги всех подходящих шаблонов объединяются
+    pub fn resolve(&self, hostname: &str) -> DomainOverrideConfig {
+        let hostname = hostname.trim().to_lowercase();
+        let mut resolved = DomainOverrideConfig::default();
+        for (pattern, settings) in &self.rules {
+            if Self::matches(pattern, &hostname) {
+                resolved.merge(settings);
+            }
+        }
+        resolved
+    }
+
+    /// Точное имя важнее любого шаблона, среди шаблонов — более длинный
+    pub(crate) fn specificity(pattern: &str) -> (bool, usize) {
+        (!pattern.contains('*'), pattern.chars().filter(|c| *c != '*').count())
+    }
+
+    /// Подходит ли хост к шаблону; оба в нижнем регистре
+    pub(crate) fn matches(pattern: &str, hostname: &str) -> bool {
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = hostname.strip_prefix(first) else {
+            return false;
+        };
+
+        let parts: Vec<&str> = parts.collect();
+        let Some((last, middle)) = parts.split_last() else {
+            return rest.is_empty();
+        };
+        for part in middle {
+            match rest.find(part) {
+                Some(pos) => rest = &rest[pos + part.len()..],
+                None => return false,
+            }
+        }
+        rest.ends_with(last)
+    }
+}
+
+impl DomainOverrideConfig {
+    fn merge(&mut self, other: &DomainOverrideConfig) {
+        self.port = other.port.or(self.port);
+        self.sni = other.sni.clone().or(self.sni.take());
+        self.alpn = other.alpn.clone().or(self.alpn.take());
+        self.alarm_days = other.alarm_days.or(self.alarm_days);
+        self.ssl_alarm_days = other.ssl_alarm_days.or(self.ssl_alarm_days);
+        self.skip_ssl = other.skip_ssl.or(self.skip_ssl);
+        self.skip_whois = other.skip_whois.or(self.skip_whois);
+        self.quic = other.quic.or(self.quic);
+        self.ssh = other.ssh.or(self.ssh);
+        self.ssh_port = other.ssh_port.or(self.ssh_port);
+        self.ssh_alarm_days = other.ssh_alarm_days.or(self.ssh_alarm_days);
+        self.owner = other.owner.clone().or(self.owner.take());
+        for tag in &other.tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+    }
+}
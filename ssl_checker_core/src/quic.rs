@@ -0,0 +1,150 @@
+//! Сертификат, который хост отдаёт по QUIC (HTTP/3). CDN иногда обслуживают
+//! UDP/443 другим сертификатом, чем TCP, и его срок нужно проверять отдельно.
+//! Без фичи `quic` проверка завершается ошибкой
+
+use crate::checks::{CertificateInfo, SslCheckOptions};
+use base::prelude::anyhow::Result;
+#[cfg(feature = "quic")]
+use {
+    crate::checks,
+    base::prelude::{anyhow::anyhow, tokio},
+    quinn::crypto::rustls::{HandshakeData, QuicClientConfig},
+    rustls::client::danger::{
+        HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+    },
+    rustls::crypto::CryptoProvider,
+    rustls::pki_types::{CertificateDer, ServerName, UnixTime},
+    rustls::{DigitallySignedStruct, SignatureScheme},
+    std::net::SocketAddr,
+    std::sync::Arc,
+};
+
+/// Собрана ли библиотека с поддержкой QUIC
+pub const SUPPORTED: bool = cfg!(feature = "quic");
+
+/// Протокол ALPN по умолчанию: хосты HTTP/3 без него отклоняют рукопожатие
+pub const DEFAULT_ALPN: &str = "h3";
+
+/// Подключается к хосту по QUIC и возвращает сведения о его сертификате.
+/// Порт, SNI, таймауты и протоколы ALPN берутся из `options`
+#[cfg(feature = "quic")]
+pub async fn check_quic_certificate(
+    hostname: &str,
+    options: &SslCheckOptions,
+) -> Result<CertificateInfo> {
+    let hostname_idn = idna::domain_to_ascii(hostname)
+        .map_err(|e| anyhow!("IDN conversion failed: {}", e))?;
+    let server_name = match &options.sni {
+        Some(sni) => idna::domain_to_ascii(sni)
+            .map_err(|e| anyhow!("IDN conversion failed: {}", e))?,
+        None => hostname_idn.clone(),
+    };
+    let address = tokio::time::timeout(
+        options.connect_timeout,
+        tokio::net::lookup_host((hostname_idn.as_str(), options.port)),
+    )
+    .await
+    .map_err(|_| anyhow!("Connection timed out"))??
+    .next()
+    .ok_or_else(|| anyhow!("Name has no usable address"))?;
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut crypto = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)))
+        .with_no_client_auth();
+    crypto.alpn_protocols = if options.alpn.is_empty() {
+        vec![DEFAULT_ALPN.as_bytes().to_vec()]
+    } else {
+        options.alpn.iter().map(|protocol| protocol.as_bytes().to_vec()).collect()
+    };
+
+    let bind: SocketAddr =
+        if address.is_ipv6() { "[::]:0".parse()? } else { "0.0.0.0:0".parse()? };
+    let mut endpoint = quinn::Endpoint::client(bind)?;
+    endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(
+        QuicClientConfig::try_from(crypto)?,
+    )));
+    let connection = tokio::time::timeout(
+        options.connect_timeout + options.handshake_timeout,
+        endpoint.connect(address, &server_name)?,
+    )
+    .await
+    .map_err(|_| anyhow!("QUIC handshake timed out"))??;
+
+    let certificates = connection
+        .peer_identity()
+        .and_then(|identity| identity.downcast::<Vec<CertificateDer<'static>>>().ok())
+        .ok_or_else(|| anyhow!("No certificate found"))?;
+    let certificate =
+        certificates.first().ok_or_else(|| anyhow!("No certificate found"))?;
+    let alpn = connection
+        .handshake_data()
+        .and_then(|data| data.downcast::<HandshakeData>().ok())
+        .and_then(|data| data.protocol)
+        .map(|protocol| String::from_utf8_lossy(&protocol).into_owned());
+    connection.close(0u32.into(), b"");
+
+    Ok(CertificateInfo { alpn, ..checks::parse_certificate(certificate)? })
+}
+
+#[cfg(not(feature = "quic"))]
+pub async fn check_quic_certificate(
+    _hostname: &str,
+    _options: &SslCheckOptions,
+) -> Result<CertificateInfo> {
+    base::prelude::anyhow::bail!("проверка по QUIC недоступна: сборка без фичи `quic`")
+}
+
+/// Принимает любой сертификат: как и по TCP, проверяется только срок действия.
+/// Подписи рукопожатия проверяются, чтобы сервер действительно владел ключом
+#[cfg(feature = "quic")]
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<CryptoProvider>);
+
+#[cfg(feature = "quic")]
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
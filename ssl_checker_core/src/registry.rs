@@ -0,0 +1,111 @@
+use crate::config::HttpConfig;
+use crate::notifiers::BaseNotifierTrait;
+use crate::sources::DomainSourceTrait;
+use base::prelude::{
+    anyhow::{Context, Result},
+    once_cell::sync::Lazy,
+    serde_json,
+};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Реестр источников и модулей уведомлений, подключаемых без изменения `checker`.
+/// Регистрация выполняется до загрузки конфигурации; секция, `type` которой
+/// не относится к встроенным модулям, создаётся фабрикой, зарегистрированной под этим типом
+pub static REGISTRY: Lazy<PluginRegistry> = Lazy::new(PluginRegistry::default);
+
+pub type SourceFactory =
+    Arc<dyn Fn(&PluginContext) -> Result<Box<dyn DomainSourceTrait>> + Send + Sync>;
+pub type NotifierFactory =
+    Arc<dyn Fn(&PluginContext) -> Result<Box<dyn BaseNotifierTrait>> + Send + Sync>;
+
+/// Секция конфигурации, передаваемая фабрике плагина
+#[derive(Debug, Clone, Copy)]
+pub struct PluginContext<'a> {
+    /// Имя секции, например `notifiers.<name>`
+    pub name: &'a str,
+    /// Поля секции, кроме `type` и `enabled`
+    pub settings: &'a serde_json::Value,
+    /// Общая секция `http`
+    pub http: &'a HttpConfig,
+    /// Общий HTTP-клиент сервиса, см. [`crate::HttpSettings::with_shared_client`]
+    pub client: &'a Client,
+}
+
+impl PluginContext<'_> {
+    /// Разбирает поля секции в структуру плагина
+    pub fn settings<T: DeserializeOwned>(&self) -> Result<T> {
+        T::deserialize(self.settings)
+            .with_context(|| format!("Некорректные настройки секции {}", self.name))
+    }
+}
+
+#[derive(Default)]
+pub struct PluginRegistry {
+    sources: RwLock<HashMap<String, SourceFactory>>,
+    notifiers: RwLock<HashMap<String, NotifierFactory>>,
+}
+
+impl PluginRegistry {
+    /// Регистрирует источник доменов; повторная регистрация типа заменяет фабрику
+    pub fn register_source<F>(&self, kind: &str, factory: F)
+    where
+        F: Fn(&PluginContext) -> Result<Box<dyn DomainSourceTrait>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let mut sources = self.sources.write().unwrap_or_else(|e| e.into_inner());
+        sources.insert(kind.to_string(), Arc::new(factory));
+    }
+
+    /// Регистрирует модуль уведомлений; повторная регистрация типа заменяет фабрику
+    pub fn register_notifier<F>(&self, kind: &str, factory: F)
+    where
+        F: Fn(&PluginContext) -> Result<Box<dyn BaseNotifierTrait>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let mut notifiers = self.notifiers.write().unwrap_or_else(|e| e.into_inner());
+        notifiers.insert(kind.to_string(), Arc::new(factory));
+    }
+
+    /// Создаёт источник зарегистрированного типа; `None`, если тип не зарегистрирован
+    pub fn source(
+        &self,
+        kind: &str,
+        context: &PluginContext,
+    ) -> Option<Result<Box<dyn DomainSourceTrait>>> {
+        let factory =
+            self.sources.read().unwrap_or_else(|e| e.into_inner()).get(kind)?.clone();
+        Some(factory(context))
+    }
+
+    /// Создаёт модуль уведомлений зарегистрированного типа; `None`, если тип не зарегистрирован
+    pub fn notifier(
+        &self,
+        kind: &str,
+        context: &PluginContext,
+    ) -> Option<Result<Box<dyn BaseNotifierTrait>>> {
+        let factory =
+            self.notifiers.read().unwrap_or_else(|e| e.into_inner()).get(kind)?.clone();
+        Some(factory(context))
+    }
+
+    pub fn source_types(&self) -> Vec<String> {
+        let sources = self.sources.read().unwrap_or_else(|e| e.into_inner());
+        let mut types: Vec<String> = sources.keys().cloned().collect();
+        types.sort();
+        types
+    }
+
+    pub fn notifier_types(&self) -> Vec<String> {
+        let notifiers = self.notifiers.read().unwrap_or_else(|e| e.into_inner());
+        let mut types: Vec<String> = notifiers.keys().cloned().collect();
+        types.sort();
+        types
+    }
+}
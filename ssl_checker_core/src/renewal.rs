@@ -0,0 +1,205 @@
+//! Сверка сертификатов на серверах с результатами автоматического продления:
+//! сертификаты, выпущенные certbot и acme.sh, читаются с диска и сопоставляются
+//! хостам по именам из SAN. Так же сверяются сертификаты из хранилищ
+//! с известными именами хостов
+
+use crate::config::RenewalCheckConfig;
+use crate::inventory::InventoryCertificate;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    chrono::{DateTime, Utc},
+    tracing,
+};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use x509_parser::extensions::GeneralName;
+use x509_parser::pem::Pem;
+
+/// Сертификат, выпущенный при последнем продлении
+#[derive(Debug, Clone)]
+pub struct RenewedCertificate {
+    /// Файл сертификата или идентификатор в хранилище
+    pub path: PathBuf,
+    pub expiration_date: DateTime<Utc>,
+    pub serial: String,
+}
+
+/// Сертификаты продления, сопоставленные именам хостов
+#[derive(Debug, Default)]
+pub struct RenewalIndex {
+    /// Имя из SAN (в нижнем регистре, возможно `*.example.com`) → сертификат
+    by_name: HashMap<String, Arc<RenewedCertificate>>,
+    dcl: &'static str,
+}
+
+impl RenewalIndex {
+    /// Читает сертификаты из каталогов конфигурации. Файлы, которые не удалось
+    /// разобрать, пропускаются с предупреждением
+    pub fn load(config: &RenewalCheckConfig) -> Result<Self> {
+        let mut index = Self { by_name: HashMap::new(), dcl: "RenewalIndex" };
+        if let Some(dir) = &config.certbot_dir {
+            for path in Self::certbot_certificates(Path::new(dir))? {
+                index.add(&path);
+            }
+        }
+        if let Some(dir) = &config.acme_sh_dir {
+            for path in Self::acme_sh_certificates(Path::new(dir))? {
+                index.add(&path);
+            }
+        }
+        Ok(index)
+    }
+
+    /// Сертификаты хранилищ с именами хостов и серийным номером; из нескольких
+    /// сертификатов одного имени остаётся самый поздний
+    pub fn from_inventory(certificates: &[InventoryCertificate]) -> Self {
+        let mut index = Self { by_name: HashMap::new(), dcl: "RenewalIndex" };
+        for certificate in certificates {
+            let (Some(expiration_date), Some(serial)) =
+                (certificate.expiration_date, &certificate.serial)
+            else {
+                continue;
+            };
+            let renewed = Arc::new(RenewedCertificate {
+                path: PathBuf::from(&certificate.id),
+                expiration_date,
+                serial: serial.clone(),
+            });
+            for name in &certificate.names {
+                let name = name.to_lowercase();
+                let later = index
+                    .by_name
+                    .get(&name)
+                    .is_none_or(|known| known.expiration_date < expiration_date);
+                if later {
+                    index.by_name.insert(name, renewed.clone());
+                }
+            }
+        }
+        index
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    /// Сертификат продления для хоста: по точному имени или по wildcard-имени
+    /// родительского домена
+    pub fn lookup(&self, hostname: &str) -> Option<&RenewedCertificate> {
+        let hostname = hostname.trim_end_matches('.').to_lowercase();
+        if let Some(certificate) = self.by_name.get(&hostname) {
+            return Some(certificate);
+        }
+        let (_, parent) = hostname.split_once('.')?;
+        self.by_name.get(&format!("*.{}", parent)).map(|c| &**c)
+    }
+
+    /// Пути `cert = ...` из `renewal/*.conf` certbot
+    fn certbot_certificates(dir: &Path) -> Result<Vec<PathBuf>> {
+        let renewal = dir.join("renewal");
+        let mut paths = Vec::new();
+        for entry in Self::read_dir(&renewal)? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "conf") {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path).with_context(|| {
+                format!("Не удалось прочитать файл: {}", path.display())
+            })?;
+            let cert = content.lines().find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                (key.trim() == "cert").then(|| PathBuf::from(value.trim()))
+            });
+            if let Some(cert) = cert {
+                paths.push(cert);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Файлы `<домен>/<домен>.cer` и `<домен>_ecc/<домен>.cer` acme.sh
+    fn acme_sh_certificates(dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for entry in Self::read_dir(dir)? {
+            let path = entry.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let domain = name.strip_suffix("_ecc").unwrap_or(name);
+            let cert = path.join(format!("{}.cer", domain));
+            if cert.is_file() {
+                paths.push(cert);
+            }
+        }
+        Ok(paths)
+    }
+
+    fn read_dir(dir: &Path) -> Result<Vec<std::fs::DirEntry>> {
+        std::fs::read_dir(dir)
+            .with_context(|| format!("Не удалось прочитать каталог: {}", dir.display()))?
+            .collect::<std::io::Result<_>>()
+            .map_err(Into::into)
+    }
+
+    fn add(&mut self, path: &Path) {
+        match Self::parse(path) {
+            Ok((certificate, names)) => {
+                let certificate = Arc::new(certificate);
+                for name in names {
+                    self.by_name.insert(name.to_lowercase(), certificate.clone());
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    dcl = self.dcl,
+                    path = %path.display(),
+                    e = %e,
+                    "Не удалось разобрать сертификат продления"
+                );
+            }
+        }
+    }
+
+    /// Адрес IPv4 или IPv6 из SAN `iPAddress`
+    pub(crate) fn ip_address(bytes: &[u8]) -> Option<String> {
+        let address: IpAddr = match bytes.len() {
+            4 => <[u8; 4]>::try_from(bytes).ok()?.into(),
+            16 => <[u8; 16]>::try_from(bytes).ok()?.into(),
+            _ => return None,
+        };
+        Some(address.to_string())
+    }
+
+    /// Первый сертификат PEM-файла и имена из его SAN
+    fn parse(path: &Path) -> Result<(RenewedCertificate, Vec<String>)> {
+        let data = std::fs::read(path)
+            .with_context(|| format!("Не удалось прочитать файл: {}", path.display()))?;
+        let pem = Pem::iter_from_buffer(&data)
+            .next()
+            .ok_or_else(|| anyhow!("сертификат не найден"))??;
+        let x509 = pem.parse_x509()?;
+        let names = x509
+            .subject_alternative_name()?
+            .map(|san| {
+                san.value
+                    .general_names
+                    .iter()
+                    .filter_map(|name| match name {
+                        GeneralName::DNSName(name) => Some(name.to_string()),
+                        GeneralName::IPAddress(bytes) => Self::ip_address(bytes),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let info = crate::checks::parse_certificate(&pem.contents)?;
+        let certificate = RenewedCertificate {
+            path: path.to_path_buf(),
+            expiration_date: info.expiration_date,
+            serial: info.serial,
+        };
+        Ok((certificate, names))
+    }
+}
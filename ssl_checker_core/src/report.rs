@@ -0,0 +1,809 @@
+use base::prelude::chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Тип проверки, к которому относится запись отчёта
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckKind {
+    Domain,
+    Ssl,
+    /// Сертификат почтового сервера (MX), полученный через STARTTLS
+    Mail,
+    /// Сертификат, который хост отдаёт по QUIC (HTTP/3)
+    Quic,
+    /// Корневой или промежуточный сертификат хранилища доверия
+    Ca,
+    /// Сертификат хоста OpenSSH, подписанный SSH-УЦ
+    Ssh,
+    /// Сертификат из локального файла (PEM, DER или PKCS#12)
+    File,
+    /// Срок действия учётных данных: ключа API, токена или пароля
+    Credential,
+    /// Сертификат из менеджера сертификатов, например AWS ACM
+    Inventory,
+}
+
+impl CheckKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckKind::Domain => "domain",
+            CheckKind::Ssl => "ssl",
+            CheckKind::Mail => "mail",
+            CheckKind::Quic => "quic",
+            CheckKind::Ca => "ca",
+            CheckKind::Ssh => "ssh",
+            CheckKind::File => "file",
+            CheckKind::Credential => "credential",
+            CheckKind::Inventory => "inventory",
+        }
+    }
+
+    /// Сертификат, который продлевает сам владелец: всё, кроме сроков доменов,
+    /// учётных данных и сертификатов хранилища доверия
+    pub fn is_renewable_certificate(&self) -> bool {
+        matches!(
+            self,
+            CheckKind::Ssl
+                | CheckKind::Mail
+                | CheckKind::Quic
+                | CheckKind::Ssh
+                | CheckKind::File
+                | CheckKind::Inventory
+        )
+    }
+}
+
+/// Состояние записи отчёта
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryStatus {
+    /// Срок действия за пределами окна оповещения
+    Ok,
+    /// Срок действия в окне оповещения
+    Warning,
+    /// Срок истёк или истекает в ближайшие дни
+    Critical,
+    /// Ожидаемая ошибка (хост недоступен или не обслуживает HTTPS)
+    Skipped,
+    /// Проверку выполнить не удалось
+    Error,
+}
+
+impl EntryStatus {
+    /// Число дней, начиная с которого запись считается критической
+    pub const CRITICAL_DAYS: i64 = 2;
+
+    pub fn from_days(days: i64, in_alarm_window: bool) -> Self {
+        if days <= Self::CRITICAL_DAYS {
+            EntryStatus::Critical
+        } else if in_alarm_window {
+            EntryStatus::Warning
+        } else {
+            EntryStatus::Ok
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryStatus::Ok => "ok",
+            EntryStatus::Warning => "warning",
+            EntryStatus::Critical => "critical",
+            EntryStatus::Skipped => "skipped",
+            EntryStatus::Error => "error",
+        }
+    }
+}
+
+/// Важность записи в окне оповещения. Для сертификатов задаётся порогами
+/// [`crate::config::SeverityThreshold`], для доменов следует из статуса
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    #[default]
+    Warning,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+
+    /// Значок по умолчанию для модулей уведомлений
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Severity::Info => "🔵",
+            Severity::Warning => "🟡",
+            Severity::High => "🟠",
+            Severity::Critical => "🔴",
+        }
+    }
+
+    /// Важность записи в окне оповещения без заданных порогов
+    fn from_status(status: EntryStatus) -> Option<Self> {
+        match status {
+            EntryStatus::Warning => Some(Severity::Warning),
+            EntryStatus::Critical => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Результат проверки одного хоста (включая успешные проверки)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportEntry {
+    pub check: CheckKind,
+    pub status: EntryStatus,
+    pub hostname: String,
+    pub expiration_date: Option<DateTime<Utc>>,
+    pub days: Option<i64>,
+    pub issuer: Option<String>,
+    pub serial: Option<String>,
+    pub error: Option<String>,
+    /// Теги из секции `domains` конфигурации
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Команда-владелец хоста: из источника или из секции `domains` конфигурации
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Время проверки; у записи, взятой из предыдущего запуска, — время исходной
+    /// проверки, у хоста, не проверенного за время запуска, — `None`
+    #[serde(default)]
+    pub checked_at: Option<DateTime<Utc>>,
+    /// Сертификат ACME-издателя (Let's Encrypt, ZeroSSL), продлевается автоматически
+    #[serde(default)]
+    pub acme: bool,
+    /// Срок сертификата, продлённого на диске, если сервер отдаёт более старый
+    #[serde(default)]
+    pub renewed_expiration_date: Option<DateTime<Utc>>,
+    /// Важность записи в окне оповещения
+    #[serde(default)]
+    pub severity: Option<Severity>,
+    /// Протокол, согласованный с хостом через ALPN
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpn: Option<String>,
+    /// Доступность OCSP и CRL издателя; проверяется у сертификатов в окне оповещения
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revocation: Vec<RevocationEndpoint>,
+    /// Назначение сертификата из расширения Extended Key Usage; заполняется
+    /// у сертификатов из локальных файлов
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub usage: Vec<CertificateUsage>,
+}
+
+/// Назначение сертификата (Extended Key Usage)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateUsage {
+    /// Аутентификация TLS-сервера
+    ServerAuth,
+    /// Аутентификация TLS-клиента
+    ClientAuth,
+    /// Подпись кода
+    CodeSigning,
+    /// Защита электронной почты (S/MIME)
+    Smime,
+}
+
+impl CertificateUsage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CertificateUsage::ServerAuth => "server_auth",
+            CertificateUsage::ClientAuth => "client_auth",
+            CertificateUsage::CodeSigning => "code_signing",
+            CertificateUsage::Smime => "smime",
+        }
+    }
+}
+
+/// Адрес OCSP-респондера или списка отзыва издателя и результат обращения к нему
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationEndpoint {
+    pub url: String,
+    /// Ошибка обращения; `None` — адрес доступен
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RevocationEndpoint {
+    pub fn is_reachable(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl ReportEntry {
+    pub fn ok(
+        check: CheckKind,
+        hostname: &str,
+        expiration_date: DateTime<Utc>,
+        days: i64,
+        in_alarm_window: bool,
+    ) -> Self {
+        let status = EntryStatus::from_days(days, in_alarm_window);
+        Self {
+            check,
+            status,
+            hostname: hostname.to_string(),
+            expiration_date: Some(expiration_date),
+            days: Some(days),
+            issuer: None,
+            serial: None,
+            error: None,
+            tags: Vec::new(),
+            owner: None,
+            checked_at: Some(Utc::now()),
+            acme: false,
+            renewed_expiration_date: None,
+            alpn: None,
+            revocation: Vec::new(),
+            usage: Vec::new(),
+            severity: Severity::from_status(status),
+        }
+    }
+
+    /// Важность записи в окне оповещения; критическая важность делает критическим
+    /// и статус
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        if self.is_alarm() {
+            self.severity = Some(severity);
+            if severity == Severity::Critical {
+                self.status = EntryStatus::Critical;
+            }
+        }
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_owner(mut self, owner: Option<String>) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    /// Адреса OCSP и CRL издателя, к которым не удалось обратиться
+    pub fn unreachable_revocation(&self) -> Vec<String> {
+        self.revocation
+            .iter()
+            .filter(|endpoint| !endpoint.is_reachable())
+            .map(|endpoint| endpoint.url.clone())
+            .collect()
+    }
+
+    /// Запись попадает в окно оповещения
+    pub fn is_alarm(&self) -> bool {
+        matches!(self.status, EntryStatus::Warning | EntryStatus::Critical)
+    }
+
+    /// Проверка выполнялась; `false` у хостов, не проверенных за время запуска
+    pub fn was_checked(&self) -> bool {
+        self.checked_at.is_some()
+    }
+
+    /// Хост, проверка которого не началась или не завершилась за время запуска
+    pub fn not_checked(check: CheckKind, hostname: &str, error: &str) -> Self {
+        Self { checked_at: None, ..Self::failed(check, hostname, error, false) }
+    }
+
+    pub fn failed(check: CheckKind, hostname: &str, error: &str, expected: bool) -> Self {
+        Self {
+            check,
+            status: if expected { EntryStatus::Skipped } else { EntryStatus::Error },
+            hostname: hostname.to_string(),
+            expiration_date: None,
+            days: None,
+            issuer: None,
+            serial: None,
+            error: Some(error.to_string()),
+            tags: Vec::new(),
+            owner: None,
+            checked_at: Some(Utc::now()),
+            acme: false,
+            renewed_expiration_date: None,
+            alpn: None,
+            revocation: Vec::new(),
+            usage: Vec::new(),
+            severity: None,
+        }
+    }
+}
+
+/// Сертификат в окне оповещения, передаваемый модулям уведомлений
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SslCheckResult {
+    pub hostname: String,
+    pub serial: String,
+    pub issuer: String,
+    pub expiration_date: DateTime<Utc>,
+    /// Дата истечения в часовом поясе отчёта с явным смещением, для вывода
+    #[serde(default)]
+    pub expires_at: String,
+    pub days: i64,
+    /// Сколько ещё хостов отдают этот же сертификат
+    #[serde(default)]
+    pub more: usize,
+    /// Сертификат ACME-издателя: ожидается автоматическое продление, и попадание
+    /// в окно оповещения означает сбой процесса обновления
+    #[serde(default)]
+    pub acme: bool,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Команда-владелец хоста
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Недоступные OCSP-респондеры и списки отзыва издателя: продление может не пройти
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unreachable_revocation: Vec<String>,
+}
+
+/// Домен в окне оповещения, передаваемый модулям уведомлений
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainCheckResult {
+    pub hostname: String,
+    pub expiration_date: DateTime<Utc>,
+    /// Дата истечения в часовом поясе отчёта с явным смещением, для вывода
+    #[serde(default)]
+    pub expires_at: String,
+    pub days: i64,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Команда-владелец домена
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+/// Число хостов, загруженных из источника
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceStats {
+    pub name: String,
+    pub loaded: usize,
+    /// Загрузка завершилась ошибкой или по таймауту
+    pub failed: bool,
+}
+
+/// Итоги проверок одного типа
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CheckStats {
+    /// Проверка выполнена, включая записи в окне оповещения
+    pub ok: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+/// Статистика запуска: сводка о работе самого сервиса для модулей уведомлений
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    pub sources: Vec<SourceStats>,
+    pub ssl: CheckStats,
+    pub domains: CheckStats,
+    #[serde(default)]
+    pub mail: CheckStats,
+    #[serde(default)]
+    pub quic: CheckStats,
+    #[serde(default)]
+    pub ca: CheckStats,
+    #[serde(default)]
+    pub ssh: CheckStats,
+    #[serde(default)]
+    pub files: CheckStats,
+    #[serde(default)]
+    pub credentials: CheckStats,
+    #[serde(default)]
+    pub inventory: CheckStats,
+    pub duration: std::time::Duration,
+}
+
+impl RunStats {
+    pub fn record(&mut self, entry: &ReportEntry) {
+        let stats = match entry.check {
+            CheckKind::Ssl => &mut self.ssl,
+            CheckKind::Domain => &mut self.domains,
+            CheckKind::Mail => &mut self.mail,
+            CheckKind::Quic => &mut self.quic,
+            CheckKind::Ca => &mut self.ca,
+            CheckKind::Ssh => &mut self.ssh,
+            CheckKind::File => &mut self.files,
+            CheckKind::Credential => &mut self.credentials,
+            CheckKind::Inventory => &mut self.inventory,
+        };
+        match entry.status {
+            EntryStatus::Ok | EntryStatus::Warning | EntryStatus::Critical => {
+                stats.ok += 1
+            }
+            EntryStatus::Error => stats.failed += 1,
+            EntryStatus::Skipped => stats.skipped += 1,
+        }
+    }
+
+    /// Строки сводки для текстовых уведомлений
+    pub fn lines(&self) -> Vec<String> {
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| {
+                let failed = if source.failed { " (ошибка)" } else { "" };
+                format!("{} — {}{}", source.name, source.loaded, failed)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut lines = vec![
+            format!("Источники: {}", sources),
+            format!(
+                "SSL: успешно {}, ошибок {}, пропущено {}",
+                self.ssl.ok, self.ssl.failed, self.ssl.skipped
+            ),
+            format!("WHOIS: успешно {}, ошибок {}", self.domains.ok, self.domains.failed),
+        ];
+        let kinds = [
+            ("Почта", self.mail),
+            ("QUIC", self.quic),
+            ("SSH", self.ssh),
+            ("Файлы", self.files),
+            ("Учётные данные", self.credentials),
+            ("Менеджеры сертификатов", self.inventory),
+            ("УЦ", self.ca),
+        ];
+        for (name, stats) in kinds {
+            if stats.ok + stats.failed + stats.skipped > 0 {
+                lines.push(format!(
+                    "{}: успешно {}, ошибок {}, пропущено {}",
+                    name, stats.ok, stats.failed, stats.skipped
+                ));
+            }
+        }
+        lines.push(format!("Длительность: {:.1} с", self.duration.as_secs_f64()));
+        lines
+    }
+}
+
+/// Запись, состояние которой изменилось по сравнению с предыдущим запуском
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedEntry {
+    pub check: CheckKind,
+    pub hostname: String,
+    pub days: Option<i64>,
+    pub previous_days: Option<i64>,
+}
+
+impl ChangedEntry {
+    fn line(&self) -> String {
+        let kind = match self.check {
+            CheckKind::Ssl => "сертификат",
+            CheckKind::Domain => "домен",
+            CheckKind::Mail => "почтовый сертификат",
+            CheckKind::Quic => "сертификат QUIC",
+            CheckKind::Ca => "сертификат УЦ",
+            CheckKind::Ssh => "сертификат SSH",
+            CheckKind::File => "сертификат из файла",
+            CheckKind::Credential => "учётные данные",
+            CheckKind::Inventory => "сертификат из менеджера",
+        };
+        match (self.previous_days, self.days) {
+            (Some(previous), Some(days)) => {
+                format!("{} {}: {} → {} дн.", kind, self.hostname, previous, days)
+            }
+            (_, Some(days)) => format!("{} {}: {} дн.", kind, self.hostname, days),
+            _ => format!("{} {}", kind, self.hostname),
+        }
+    }
+}
+
+/// Смена издателя сертификата хоста по сравнению с предыдущими запусками
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuerChange {
+    pub hostname: String,
+    pub previous_issuer: String,
+    pub issuer: String,
+}
+
+/// Отличия от предыдущего запуска для раздела «Что изменилось» в уведомлениях
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunChanges {
+    /// Впервые попали в окно оповещения
+    pub new: Vec<ChangedEntry>,
+    /// Были в окне оповещения и вышли из него: сертификат или домен продлён
+    pub renewed: Vec<ChangedEntry>,
+    /// Перешли из окна оповещения в критические
+    pub closer: Vec<ChangedEntry>,
+}
+
+impl RunChanges {
+    pub fn is_empty(&self) -> bool {
+        self.new.is_empty() && self.renewed.is_empty() && self.closer.is_empty()
+    }
+
+    pub fn sort(&mut self) {
+        for entries in [&mut self.new, &mut self.renewed, &mut self.closer] {
+            entries.sort_by_key(|e| (e.days, e.hostname.clone()));
+        }
+    }
+
+    /// Строки раздела для текстовых уведомлений
+    pub fn lines(&self) -> Vec<String> {
+        [
+            ("Новые в окне оповещения", &self.new),
+            ("Продлены", &self.renewed),
+            ("Стали критическими", &self.closer),
+        ]
+        .into_iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .flat_map(|(title, entries)| {
+            std::iter::once(format!("{}:", title))
+                .chain(entries.iter().map(|entry| format!("- {}", entry.line())))
+        })
+        .collect()
+    }
+}
+
+/// Вид ошибки запуска; уведомления группируют ошибки по нему в этом порядке
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Источник доменов не загрузился
+    Source,
+    /// Запуск прерван по истечении отведённого времени
+    Deadline,
+    /// WHOIS-сервер не ответил ни по одному домену зоны
+    WhoisOutage,
+    Domain,
+    Ssl,
+    Quic,
+    /// По QUIC отдаётся другой сертификат, чем по TCP
+    QuicMismatch,
+    /// Ошибка проверки сертификата хоста SSH, в том числе его отсутствие
+    Ssh,
+    /// Файл сертификатов не удалось прочитать или разобрать
+    CertificateFile,
+    /// Срок учётных данных не удалось узнать, например из Vault
+    Credential,
+    /// Менеджер сертификатов не сможет продлить сертификат сам
+    Renewal,
+    Mail,
+    /// Проверка завершилась паникой
+    Panic,
+    IssuerChange,
+    /// Продлённый сертификат не развёрнут на сервере
+    Undeployed,
+    /// Новые сертификаты в журналах Certificate Transparency
+    Ct,
+    /// Служебное сообщение вне проверки, например о наложении запусков
+    Service,
+}
+
+impl ErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Source => "source",
+            ErrorKind::Deadline => "deadline",
+            ErrorKind::WhoisOutage => "whois_outage",
+            ErrorKind::Domain => "domain",
+            ErrorKind::Ssl => "ssl",
+            ErrorKind::Quic => "quic",
+            ErrorKind::QuicMismatch => "quic_mismatch",
+            ErrorKind::Ssh => "ssh",
+            ErrorKind::CertificateFile => "certificate_file",
+            ErrorKind::Credential => "credential",
+            ErrorKind::Renewal => "renewal",
+            ErrorKind::Mail => "mail",
+            ErrorKind::Panic => "panic",
+            ErrorKind::IssuerChange => "issuer_change",
+            ErrorKind::Undeployed => "undeployed",
+            ErrorKind::Ct => "ct",
+            ErrorKind::Service => "service",
+        }
+    }
+
+    /// Заголовок группы ошибок в уведомлениях
+    pub fn title(&self) -> &'static str {
+        match self {
+            ErrorKind::Source => "Ошибка загрузки из источника",
+            ErrorKind::Deadline => "Проверка прервана",
+            ErrorKind::WhoisOutage => "WHOIS-сервер недоступен",
+            ErrorKind::Domain => "Ошибка проверки домена",
+            ErrorKind::Ssl => "Ошибка проверки сертификата",
+            ErrorKind::Quic => "Ошибка проверки сертификата по QUIC",
+            ErrorKind::QuicMismatch => {
+                "По QUIC (HTTP/3) отдаётся другой сертификат, чем по TCP"
+            }
+            ErrorKind::Ssh => "Ошибка проверки сертификата SSH-хоста",
+            ErrorKind::CertificateFile => "Ошибка чтения файла сертификатов",
+            ErrorKind::Credential => "Ошибка проверки срока учётных данных",
+            ErrorKind::Renewal => "Сертификат не продлится автоматически",
+            ErrorKind::Mail => "Ошибка проверки сертификата почтового сервера",
+            ErrorKind::Panic => "Проверка завершилась паникой, результат не получен",
+            ErrorKind::IssuerChange => {
+                "Сменился издатель сертификата, проверьте, что перевыпуск был запланирован"
+            }
+            ErrorKind::Undeployed => "Продлённый сертификат не развёрнут на сервере",
+            ErrorKind::Ct => "Журналы Certificate Transparency",
+            ErrorKind::Service => "Сообщение сервиса",
+        }
+    }
+}
+
+/// Ошибка запуска с контекстом: к чему она относится и с каких пор повторяется
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckError {
+    pub kind: ErrorKind,
+    /// Хост или домен; `None` — ошибка не относится к одному хосту
+    #[serde(default)]
+    pub hostname: Option<String>,
+    /// Где возникла ошибка: источник доменов, WHOIS-сервер, вид проверки
+    #[serde(default)]
+    pub source: Option<String>,
+    pub message: String,
+    /// Первый запуск подряд, в котором возникла та же ошибка; `None` — не отслеживается
+    #[serde(default)]
+    pub first_seen: Option<DateTime<Utc>>,
+}
+
+impl CheckError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            hostname: None,
+            source: None,
+            message: message.into(),
+            first_seen: None,
+        }
+    }
+
+    pub fn with_hostname(mut self, hostname: impl Into<String>) -> Self {
+        self.hostname = Some(hostname.into());
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Ключ, по которому ошибка сопоставляется с ошибками прошлых запусков
+    pub fn key(&self) -> Option<(ErrorKind, &str)> {
+        let subject = self.hostname.as_deref().or(self.source.as_deref())?;
+        Some((self.kind, subject))
+    }
+
+    /// Сколько времени ошибка повторяется к моменту `now`, например «3 дн.»;
+    /// `None` для ошибки, впервые возникшей меньше часа назад
+    pub fn failing_for(&self, now: DateTime<Utc>) -> Option<String> {
+        let elapsed = now.signed_duration_since(self.first_seen?);
+        match (elapsed.num_days(), elapsed.num_hours()) {
+            (days, _) if days > 0 => Some(format!("{} дн.", days)),
+            (_, hours) if hours > 0 => Some(format!("{} ч", hours)),
+            _ => None,
+        }
+    }
+
+    /// Строка ошибки без заголовка группы: хост или источник, сообщение
+    /// и длительность сбоя
+    pub fn line(&self, now: DateTime<Utc>) -> String {
+        let mut line = match self.hostname.as_deref().or(self.source.as_deref()) {
+            Some(subject) => format!("{}: {}", subject, self.message),
+            None => self.message.clone(),
+        };
+        if let Some(failing_for) = self.failing_for(now) {
+            line.push_str(&format!(" (сбой длится {})", failing_for));
+        }
+        line
+    }
+
+    /// Ошибки, сгруппированные по виду в порядке [`ErrorKind`]
+    pub fn grouped(errors: &[CheckError]) -> Vec<(ErrorKind, Vec<&CheckError>)> {
+        let mut groups: BTreeMap<ErrorKind, Vec<&CheckError>> = BTreeMap::new();
+        for error in errors {
+            groups.entry(error.kind).or_default().push(error);
+        }
+        groups.into_iter().collect()
+    }
+}
+
+/// Полный набор результатов одного запуска проверки
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckReport {
+    /// Идентификатор запуска (UUIDv7): тот же, что в логах и уведомлениях
+    #[serde(default)]
+    pub run_id: String,
+    pub checked_at: DateTime<Utc>,
+    pub alarm_days: i64,
+    pub ssl_alarm_days: i64,
+    pub entries: Vec<ReportEntry>,
+    /// Все домены, загруженные из источников
+    #[serde(default)]
+    pub hostnames: Vec<Arc<str>>,
+    #[serde(default)]
+    pub stats: RunStats,
+    /// Отличия от предыдущего запуска
+    #[serde(default)]
+    pub changes: RunChanges,
+    /// Хосты, у которых сменился издатель сертификата
+    #[serde(default)]
+    pub issuer_changes: Vec<IssuerChange>,
+    /// Ошибки запуска, включая ещё не отправленные из-за `ssl_error_after_runs`
+    #[serde(default)]
+    pub errors: Vec<CheckError>,
+}
+
+impl CheckReport {
+    pub fn new(alarm_days: i64, ssl_alarm_days: i64) -> Self {
+        Self {
+            run_id: String::new(),
+            checked_at: Utc::now(),
+            alarm_days,
+            ssl_alarm_days,
+            entries: Vec::new(),
+            hostnames: Vec::new(),
+            stats: RunStats::default(),
+            changes: RunChanges::default(),
+            issuer_changes: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Минимальное число оставшихся дней среди успешных проверок указанного типа
+    pub fn min_days(&self, check: CheckKind) -> Option<i64> {
+        self.entries.iter().filter(|e| e.check == check).filter_map(|e| e.days).min()
+    }
+
+    pub fn count(&self, status: EntryStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+
+    pub fn push(&mut self, entry: ReportEntry) {
+        self.entries.push(entry);
+    }
+
+    fn has(&self, status: EntryStatus) -> bool {
+        self.count(status) > 0
+    }
+
+    /// Код завершения процесса для мониторинга:
+    /// 0 — всё в порядке, 1 — есть записи в окне оповещения,
+    /// 2 — есть истёкшие/критические записи, 3 — есть ошибки проверки.
+    /// При нескольких категориях приоритет у 2, затем 1, затем 3
+    pub fn exit_code(&self) -> i32 {
+        if self.has(EntryStatus::Critical) {
+            2
+        } else if self.has(EntryStatus::Warning) {
+            1
+        } else if self.has(EntryStatus::Error) {
+            3
+        } else {
+            0
+        }
+    }
+
+    /// Сортирует записи по числу оставшихся дней (ошибки в конце)
+    pub fn sort(&mut self) {
+        self.entries.sort_by_key(|e| (e.days.is_none(), e.days, e.hostname.clone()));
+    }
+}
+
+/// Событие хода проверки для отображения прогресса
+#[derive(Debug, Clone)]
+pub enum CheckProgress {
+    /// Источники загружены, известно общее число проверок
+    Started { total: usize },
+    /// К общему числу проверок добавились `count`, ставшие известными по ходу
+    /// запуска (почтовые серверы после запроса MX)
+    Added { count: usize },
+    /// Завершена проверка одного хоста
+    Checked(Box<ReportEntry>),
+    /// Проверка завершена
+    Finished,
+}
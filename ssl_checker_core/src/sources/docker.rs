@@ -0,0 +1,196 @@
+use super::DomainSourceTrait;
+use crate::http::HttpSettings;
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    tracing,
+};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Контейнер из ответа `GET /containers/json`
+#[derive(Deserialize)]
+struct Container {
+    #[serde(rename = "Names", default)]
+    names: Vec<String>,
+    #[serde(rename = "Labels", default)]
+    labels: Option<BTreeMap<String, String>>,
+}
+
+/// Хосты из меток запущенных контейнеров Docker: правил маршрутизаторов Traefik
+/// (`traefik.http.routers.<name>.rule=Host(...)`, `HostSNI(...)` у TCP
+/// и `traefik.frontend.rule=Host:...` версии 1) и адресов сайтов
+/// caddy-docker-proxy (`caddy`, `caddy_0`, ...). Подходит для одиночных хостов
+/// без Kubernetes
+pub struct DockerSourceService {
+    /// Адрес API; для Unix-сокета имя хоста не используется
+    base_url: String,
+    client: Client,
+    http: HttpSettings,
+    /// Метка контейнера с командой-владельцем его хостов
+    owner_label: Option<String>,
+    /// Владельцы хостов из последней загрузки
+    owners: Mutex<HashMap<String, String>>,
+    dcl: &'static str,
+}
+
+impl DockerSourceService {
+    /// Сокет демона Docker по умолчанию
+    pub const DEFAULT_ENDPOINT: &'static str = "unix:///var/run/docker.sock";
+
+    /// `endpoint` — `unix:///path/docker.sock`, `tcp://host:2375` или адрес
+    /// `http(s)://`, как в `DOCKER_HOST`
+    pub fn new(endpoint: &str, http: HttpSettings) -> Self {
+        let (base_url, client) = match endpoint.strip_prefix("unix://") {
+            #[cfg(unix)]
+            Some(path) => ("http://docker".to_string(), http.unix_client(path)),
+            #[cfg(not(unix))]
+            Some(_) => ("http://docker".to_string(), http.client()),
+            None => {
+                let url = match endpoint.strip_prefix("tcp://") {
+                    Some(address) => format!("http://{}", address),
+                    None => endpoint.to_string(),
+                };
+                (url.trim_end_matches('/').to_string(), http.client())
+            }
+        };
+        Self {
+            base_url,
+            client,
+            http,
+            owner_label: None,
+            owners: Mutex::default(),
+            dcl: "DockerSourceService",
+        }
+    }
+
+    /// Метка контейнера, значение которой — команда-владелец его хостов
+    pub fn with_owner_label(mut self, label: &str) -> Self {
+        self.owner_label = Some(label.to_string());
+        self
+    }
+
+    /// Хосты из меток одного контейнера
+    fn hosts(labels: &BTreeMap<String, String>) -> Vec<String> {
+        let mut hosts = Vec::new();
+        for (key, value) in labels {
+            if key.starts_with("traefik.") && key.ends_with(".rule") {
+                hosts.extend(Self::traefik_hosts(value));
+            } else if key == "caddy"
+                || key.strip_prefix("caddy_").is_some_and(|n| {
+                    !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())
+                })
+            {
+                hosts.extend(Self::caddy_hosts(value));
+            }
+        }
+        hosts
+    }
+
+    /// Имена из `Host(...)` и `HostSNI(...)` правила Traefik v2/v3 или из
+    /// `Host:a,b` версии 1
+    fn traefik_hosts(rule: &str) -> Vec<String> {
+        if let Some(hosts) = rule.trim().strip_prefix("Host:") {
+            // Условия правила версии 1 разделяются `;`
+            let hosts = hosts.split(';').next().unwrap_or_default();
+            return hosts.split(',').map(|h| h.trim().to_string()).collect();
+        }
+        let mut hosts = Vec::new();
+        let mut rest = rule;
+        while let Some(start) = rest.find("Host") {
+            let preceded =
+                rest[..start].chars().next_back().is_some_and(|c| c.is_alphanumeric());
+            rest = &rest[start + "Host".len()..];
+            let args = rest.strip_prefix('(').or_else(|| rest.strip_prefix("SNI("));
+            let (Some(args), false) = (args, preceded) else {
+                continue;
+            };
+            let Some(end) = args.find(')') else {
+                break;
+            };
+            hosts.extend(
+                args[..end]
+                    .split(',')
+                    .map(|host| host.trim().trim_matches(['`', '"', '\'']).to_string())
+                    .filter(|host| !host.is_empty() && host != "*"),
+            );
+            rest = &args[end..];
+        }
+        hosts
+    }
+
+    /// Адреса сайтов caddy-docker-proxy без схемы, порта и пути; адреса
+    /// только с портом (`:8080`) и шаблоны пропускаются
+    fn caddy_hosts(addresses: &str) -> Vec<String> {
+        addresses
+            .split([',', ' ', '\t'])
+            .filter_map(|address| {
+                let address = address.trim();
+                let address = address
+                    .strip_prefix("https://")
+                    .or_else(|| address.strip_prefix("http://"))
+                    .unwrap_or(address);
+                let host = address.split('/').next()?;
+                let host = host.rsplit_once(':').map_or(host, |(host, _)| host);
+                (!host.is_empty() && !host.contains(['{', '}'])).then(|| host.to_string())
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DomainSourceTrait for DockerSourceService {
+    async fn get_domains(&self) -> Result<Vec<String>> {
+        let url = format!("{}/containers/json", self.base_url);
+        let resp = self.http.send(|| self.client.get(&url)).await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            tracing::error!(
+                dcl = self.dcl,
+                status = status.to_string(),
+                body = text,
+                "Не удалось получить список контейнеров"
+            );
+            return Err(anyhow!("Не удалось получить список контейнеров: {}", status));
+        }
+        let containers: Vec<Container> =
+            resp.json().await.context("Ответ Docker не является списком контейнеров")?;
+
+        let mut domains = Vec::new();
+        let mut seen = HashSet::new();
+        let mut owners = HashMap::new();
+        for container in containers {
+            let labels = container.labels.unwrap_or_default();
+            let hosts = Self::hosts(&labels);
+            tracing::debug!(
+                dcl = self.dcl,
+                container =
+                    container.names.first().map(String::as_str).unwrap_or_default(),
+                hosts = hosts.len(),
+                "Хосты из меток контейнера"
+            );
+            let owner = self.owner_label.as_ref().and_then(|label| labels.get(label));
+            for host in hosts {
+                if let Some(owner) = owner {
+                    owners.insert(host.to_lowercase(), owner.clone());
+                }
+                if seen.insert(host.to_lowercase()) {
+                    domains.push(host);
+                }
+            }
+        }
+        *self.owners.lock().unwrap() = owners;
+        Ok(domains)
+    }
+
+    fn owners(&self) -> HashMap<String, String> {
+        self.owners.lock().unwrap().clone()
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
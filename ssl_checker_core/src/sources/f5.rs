@@ -0,0 +1,195 @@
+use super::DomainSourceTrait;
+use crate::http::HttpSettings;
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    tracing,
+};
+use reqwest::Client;
+use serde::{Deserialize, de::DeserializeOwned};
+use std::collections::{HashMap, HashSet};
+
+/// Коллекция iControl REST; разбираются только нужные поля
+#[derive(Deserialize)]
+struct Collection<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Virtual {
+    full_path: String,
+    #[serde(default)]
+    disabled: bool,
+    profiles_reference: Option<Collection<Profile>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Profile {
+    full_path: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClientSslProfile {
+    full_path: String,
+    cert: Option<String>,
+    #[serde(default)]
+    cert_key_chain: Vec<CertKeyChain>,
+    /// Имя SNI, для которого выбирается профиль; `none`, если не задано
+    server_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CertKeyChain {
+    cert: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SslCert {
+    full_path: String,
+    subject: Option<String>,
+    subject_alternative_name: Option<String>,
+}
+
+/// Хосты виртуальных серверов F5 BIG-IP из iControl REST: имя SNI клиентского
+/// SSL-профиля, а если оно не задано — DNS-имена (или CN) сертификатов профиля.
+/// Учитываются только включённые виртуальные серверы
+pub struct F5SourceService {
+    address: String,
+    username: String,
+    password: String,
+    /// Раздел (partition), виртуальные серверы которого читаются; по умолчанию все
+    partition: Option<String>,
+    client: Client,
+    http: HttpSettings,
+    dcl: &'static str,
+}
+
+impl F5SourceService {
+    /// `address` — адрес интерфейса управления, например `https://bigip.example.com`
+    pub fn new(
+        address: &str,
+        username: &str,
+        password: &str,
+        http: HttpSettings,
+    ) -> Self {
+        Self {
+            address: address.trim_end_matches('/').to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            partition: None,
+            client: http.client(),
+            http,
+            dcl: "F5SourceService",
+        }
+    }
+
+    /// Только виртуальные серверы раздела `partition`, например `Common`
+    pub fn with_partition(mut self, partition: &str) -> Self {
+        self.partition = Some(partition.to_string());
+        self
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<Collection<T>> {
+        let url = format!("{}/mgmt/tm/{}", self.address, path);
+        let resp = self
+            .http
+            .send(|| {
+                self.client.get(&url).basic_auth(&self.username, Some(&self.password))
+            })
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            tracing::error!(
+                dcl = self.dcl,
+                path = path,
+                status = status.to_string(),
+                body = text,
+                "Ошибка запроса к iControl REST"
+            );
+            return Err(anyhow!("iControl REST returned {} for {}", status, path));
+        }
+        resp.json()
+            .await
+            .with_context(|| format!("Unexpected iControl REST response: {}", path))
+    }
+
+    fn in_partition(&self, full_path: &str) -> bool {
+        self.partition.as_deref().is_none_or(|partition| {
+            full_path
+                .strip_prefix('/')
+                .and_then(|path| path.strip_prefix(partition))
+                .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+}
+
+#[async_trait]
+impl DomainSourceTrait for F5SourceService {
+    async fn get_domains(&self) -> Result<Vec<String>> {
+        let virtuals: Collection<Virtual> =
+            self.get("ltm/virtual?expandSubcollections=true").await?;
+        let profiles: Collection<ClientSslProfile> =
+            self.get("ltm/profile/client-ssl").await?;
+        let certificates: Collection<SslCert> = self.get("sys/file/ssl-cert").await?;
+
+        let profiles: HashMap<&str, &ClientSslProfile> =
+            profiles.items.iter().map(|p| (p.full_path.as_str(), p)).collect();
+        let certificates: HashMap<&str, &SslCert> =
+            certificates.items.iter().map(|c| (c.full_path.as_str(), c)).collect();
+
+        let mut domains = Vec::new();
+        let mut seen = HashSet::new();
+        for virtual_server in &virtuals.items {
+            if virtual_server.disabled || !self.in_partition(&virtual_server.full_path) {
+                continue;
+            }
+            let attached =
+                virtual_server.profiles_reference.iter().flat_map(|r| &r.items);
+            // Серверные SSL-профили и прочие профили в список клиентских не попадают
+            for profile in attached.filter_map(|p| profiles.get(p.full_path.as_str())) {
+                let hosts = match profile.server_name.as_deref() {
+                    Some(name) if !name.is_empty() && name != "none" => {
+                        vec![name.to_string()]
+                    }
+                    _ => profile
+                        .cert
+                        .iter()
+                        .chain(
+                            profile.cert_key_chain.iter().filter_map(|c| c.cert.as_ref()),
+                        )
+                        .filter_map(|cert| certificates.get(cert.as_str()))
+                        .flat_map(|cert| {
+                            super::certificate_names(
+                                cert.subject_alternative_name.as_deref(),
+                                cert.subject.as_deref(),
+                            )
+                        })
+                        .collect(),
+                };
+                tracing::debug!(
+                    dcl = self.dcl,
+                    virtual_server = virtual_server.full_path,
+                    profile = profile.full_path,
+                    hosts = hosts.len(),
+                    "Хосты виртуального сервера"
+                );
+                for host in hosts {
+                    if seen.insert(host.to_lowercase()) {
+                        domains.push(host);
+                    }
+                }
+            }
+        }
+        Ok(domains)
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
@@ -0,0 +1,107 @@
+use super::DomainSourceTrait;
+use base::prelude::{
+    anyhow::{Context, Result},
+    tokio::{
+        fs::{self, File},
+        io::{AsyncBufReadExt, BufReader},
+    },
+};
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Текстовый файл, по одному хосту в строке. Строка `host,owner` задаёт
+/// и команду-владельца хоста
+pub struct FileSourceService {
+    filename: String,
+    /// Владельцы хостов из последней загрузки
+    owners: Mutex<HashMap<String, String>>,
+    #[allow(dead_code)]
+    dcl: &'static str,
+}
+
+impl FileSourceService {
+    pub fn new(filename: &str) -> Self {
+        Self {
+            filename: filename.to_string(),
+            owners: Mutex::default(),
+            dcl: "FileSourceService",
+        }
+    }
+
+    fn path(&self) -> String {
+        format!("./{}", self.filename)
+    }
+
+    /// Хост из строки файла; владелец из второй колонки запоминается
+    fn parse_line(&self, mut line: String) -> Option<String> {
+        if let Some((host, owner)) = line.split_once(',') {
+            let (host, owner) = (host.trim(), owner.trim());
+            if !host.is_empty() && !owner.is_empty() {
+                let mut owners = self.owners.lock().unwrap();
+                owners.insert(host.to_lowercase(), owner.to_string());
+            }
+            line = host.to_string();
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+        if trimmed.len() != line.len() {
+            line = trimmed.to_string();
+        }
+        Some(line)
+    }
+}
+
+#[async_trait]
+impl DomainSourceTrait for FileSourceService {
+    async fn get_domains(&self) -> Result<Vec<String>> {
+        let path = self.path();
+
+        let content = fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Не удалось прочитать файл: {}", path))?;
+
+        self.owners.lock().unwrap().clear();
+        let domains = content
+            .lines()
+            .filter_map(|s| self.parse_line(s.to_string()))
+            .collect::<std::collections::HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        Ok(domains)
+    }
+
+    /// Файл читается построчно, без загрузки целиком
+    fn stream_domains(&self) -> BoxStream<'_, Result<String>> {
+        let path = self.path();
+        stream::once(async move {
+            self.owners.lock().unwrap().clear();
+            let file = File::open(&path)
+                .await
+                .with_context(|| format!("Не удалось прочитать файл: {}", path))?;
+            let lines = BufReader::new(file).lines();
+            let domains = stream::try_unfold(lines, move |mut lines| async move {
+                Ok(lines.next_line().await?.map(|line| (line, lines)))
+            })
+            .try_filter_map(|line| {
+                let host = self.parse_line(line);
+                async move { Ok(host) }
+            });
+            Ok::<_, base::prelude::anyhow::Error>(domains)
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    fn owners(&self) -> HashMap<String, String> {
+        self.owners.lock().unwrap().clone()
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
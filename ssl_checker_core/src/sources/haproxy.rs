@@ -0,0 +1,335 @@
+use super::DomainSourceTrait;
+use crate::renewal::RenewalIndex;
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    tokio::{
+        self,
+        io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+        net::TcpStream,
+    },
+    tracing,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use x509_parser::{extensions::GeneralName, pem::Pem};
+
+/// Выборки HAProxy с именем из SNI клиента
+const SNI_FETCHES: &[&str] = &["req.ssl_sni", "req_ssl_sni", "ssl_fc_sni"];
+
+/// Файлы рядом с сертификатом в каталоге `crt`, которые HAProxy загружает
+/// вместе с ним
+const CRT_COMPANIONS: &[&str] = &["key", "ocsp", "issuer", "sctl"];
+
+enum Target {
+    /// Файл конфигурации
+    Config(PathBuf),
+    /// Unix-сокет runtime API
+    Socket(PathBuf),
+    /// Runtime API по TCP, `host:port`
+    Tcp(String),
+}
+
+/// Хосты, TLS которых завершается на HAProxy. Из файла конфигурации берутся
+/// имена сертификатов `crt` (файлов и каталогов), SNI-фильтры `crt-list`
+/// и значения ACL по `req.ssl_sni`/`ssl_fc_sni`; через runtime API — имена
+/// загруженных сертификатов из `show ssl cert`
+pub struct HaproxySourceService {
+    target: Target,
+    timeout: Duration,
+    dcl: &'static str,
+}
+
+impl HaproxySourceService {
+    /// Таймаут ответа runtime API по умолчанию
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Разбор файла конфигурации `haproxy.cfg`
+    pub fn from_config(path: &str) -> Self {
+        Self::new(Target::Config(PathBuf::from(path)))
+    }
+
+    /// Запросы к runtime API: `unix:///run/haproxy/admin.sock` (или просто путь
+    /// к сокету), `tcp://host:9999` или `host:9999`
+    pub fn from_runtime_api(address: &str) -> Self {
+        let target = if let Some(path) = address.strip_prefix("unix://") {
+            Target::Socket(PathBuf::from(path))
+        } else if address.starts_with('/') {
+            Target::Socket(PathBuf::from(address))
+        } else {
+            Target::Tcp(address.strip_prefix("tcp://").unwrap_or(address).to_string())
+        };
+        Self::new(target)
+    }
+
+    fn new(target: Target) -> Self {
+        Self { target, timeout: Self::DEFAULT_TIMEOUT, dcl: "HaproxySourceService" }
+    }
+
+    /// Таймаут одной команды runtime API
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Хосты из файла конфигурации. Относительные пути сертификатов считаются
+    /// от `crt-base`, а без него — от каталога файла конфигурации
+    fn config_hosts(path: &Path) -> Result<Vec<String>> {
+        let text = std::fs::read_to_string(path).with_context(|| {
+            format!("Не удалось прочитать конфигурацию HAProxy: {}", path.display())
+        })?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let mut crt_base = dir.to_path_buf();
+        let mut hosts = Vec::new();
+        for line in text.lines() {
+            let words = Self::words(line);
+            match words.first().copied() {
+                Some("crt-base") => {
+                    if let Some(base) = words.get(1) {
+                        crt_base = dir.join(base);
+                    }
+                }
+                Some("bind") => {
+                    for pair in words.windows(2) {
+                        let path = crt_base.join(pair[1]);
+                        match pair[0] {
+                            "crt" => hosts.extend(Self::crt_hosts(&path)),
+                            "crt-list" => {
+                                hosts.extend(Self::crt_list_hosts(&path, &crt_base)?)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+            hosts.extend(Self::sni_hosts(&words));
+        }
+        Ok(hosts)
+    }
+
+    /// Слова строки конфигурации без комментария
+    fn words(line: &str) -> Vec<&str> {
+        line.split_whitespace().take_while(|word| !word.starts_with('#')).collect()
+    }
+
+    /// Имена сертификата `crt` или всех сертификатов каталога
+    fn crt_hosts(path: &Path) -> Vec<String> {
+        if !path.is_dir() {
+            return Self::certificate_names(path);
+        }
+        let mut files: Vec<PathBuf> = match std::fs::read_dir(path) {
+            Ok(entries) => entries.filter_map(|e| e.ok().map(|e| e.path())).collect(),
+            Err(e) => {
+                tracing::warn!(
+                    dcl = "HaproxySourceService",
+                    path = %path.display(),
+                    error = %e,
+                    "Не удалось прочитать каталог сертификатов"
+                );
+                return Vec::new();
+            }
+        };
+        files.sort();
+        files
+            .iter()
+            .filter(|file| {
+                let hidden = file
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_none_or(|name| name.starts_with('.'));
+                let companion = file
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| CRT_COMPANIONS.contains(&ext));
+                file.is_file() && !hidden && !companion
+            })
+            .flat_map(|file| Self::certificate_names(file))
+            .collect()
+    }
+
+    /// Строки `crt-list`: `<сертификат> [<параметры>] [<SNI-фильтры>]`. Хостами
+    /// считаются фильтры без `!`, а если их нет — имена самого сертификата
+    fn crt_list_hosts(path: &Path, crt_base: &Path) -> Result<Vec<String>> {
+        let text = std::fs::read_to_string(path).with_context(|| {
+            format!("Не удалось прочитать crt-list: {}", path.display())
+        })?;
+        let mut hosts = Vec::new();
+        for line in text.lines() {
+            let words = Self::words(line);
+            let Some((crt, rest)) = words.split_first() else {
+                continue;
+            };
+            let filters = match rest.iter().position(|word| word.ends_with(']')) {
+                Some(end) if rest[0].starts_with('[') => &rest[end + 1..],
+                _ => rest,
+            };
+            let filters: Vec<String> = filters
+                .iter()
+                .filter(|filter| !filter.starts_with('!'))
+                .map(|filter| filter.to_string())
+                .collect();
+            if filters.is_empty() {
+                hosts.extend(Self::crt_hosts(&crt_base.join(crt)));
+            } else {
+                hosts.extend(filters);
+            }
+        }
+        Ok(hosts)
+    }
+
+    /// Значения точного сравнения в ACL по SNI, например
+    /// `use_backend api if { req.ssl_sni -i api.example.com }`. Сравнения по
+    /// суффиксу, подстроке и регулярному выражению пропускаются
+    fn sni_hosts(words: &[&str]) -> Vec<String> {
+        let mut hosts = Vec::new();
+        let mut iter = words.iter();
+        while let Some(word) = iter.next() {
+            let fetch = word.split(',').next().unwrap_or_default();
+            if !SNI_FETCHES.contains(&fetch) {
+                continue;
+            }
+            let mut exact = true;
+            while let Some(&value) = iter.next() {
+                match value {
+                    "-m" => exact = iter.next().is_some_and(|m| *m == "str"),
+                    // Шаблоны из файла здесь не читаются
+                    "-f" | "-M" => exact = false,
+                    "}" | "if" | "unless" | "||" | "or" | "!" => break,
+                    flag if flag.starts_with('-') => {}
+                    host if exact => hosts.push(host.to_string()),
+                    _ => {}
+                }
+            }
+        }
+        hosts
+    }
+
+    /// DNS-имена и IP-адреса из SAN первого сертификата файла, а без них — CN
+    fn certificate_names(path: &Path) -> Vec<String> {
+        let names = std::fs::read(path).map_err(|e| anyhow!(e)).and_then(|data| {
+            let pem = Pem::iter_from_buffer(&data)
+                .filter_map(|pem| pem.ok())
+                .find(|pem| pem.label == "CERTIFICATE")
+                .ok_or_else(|| anyhow!("нет блока CERTIFICATE"))?;
+            let certificate = pem.parse_x509().map_err(|e| anyhow!("X.509: {}", e))?;
+            let mut names: Vec<String> = certificate
+                .subject_alternative_name()
+                .ok()
+                .flatten()
+                .into_iter()
+                .flat_map(|san| &san.value.general_names)
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(name) => Some(name.to_string()),
+                    GeneralName::IPAddress(bytes) => RenewalIndex::ip_address(bytes),
+                    _ => None,
+                })
+                .collect();
+            if names.is_empty() {
+                names.extend(
+                    certificate
+                        .subject()
+                        .iter_common_name()
+                        .filter_map(|cn| cn.as_str().ok().map(str::to_string)),
+                );
+            }
+            Ok(names)
+        });
+        names.unwrap_or_else(|e| {
+            tracing::warn!(
+                dcl = "HaproxySourceService",
+                path = %path.display(),
+                error = %e,
+                "Не удалось прочитать сертификат"
+            );
+            Vec::new()
+        })
+    }
+
+    /// Ответ на одну команду runtime API; сервер закрывает соединение после
+    /// ответа
+    async fn command(&self, command: &str) -> Result<String> {
+        let response = async {
+            match &self.target {
+                #[cfg(unix)]
+                Target::Socket(path) => {
+                    let stream = tokio::net::UnixStream::connect(path).await?;
+                    Self::exchange(stream, command).await
+                }
+                #[cfg(not(unix))]
+                Target::Socket(_) => Err(anyhow!("Unix sockets are not supported")),
+                Target::Tcp(address) => {
+                    let stream = TcpStream::connect(address.as_str()).await?;
+                    Self::exchange(stream, command).await
+                }
+                Target::Config(_) => unreachable!("runtime API is not configured"),
+            }
+        };
+        tokio::time::timeout(self.timeout, response)
+            .await
+            .map_err(|_| anyhow!("HAProxy runtime API timed out: {}", command))?
+            .with_context(|| format!("HAProxy runtime API command failed: {}", command))
+    }
+
+    async fn exchange<S>(mut stream: S, command: &str) -> Result<String>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        stream.write_all(format!("{}\n", command).as_bytes()).await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        Ok(response)
+    }
+
+    /// Хосты загруженных сертификатов: список из `show ssl cert`, затем имена
+    /// из `show ssl cert <файл>`. Незафиксированные транзакции (`*файл`)
+    /// пропускаются
+    async fn runtime_hosts(&self) -> Result<Vec<String>> {
+        let list = self.command("show ssl cert").await?;
+        let mut hosts = Vec::new();
+        for file in list.lines().map(str::trim) {
+            if file.is_empty() || file.starts_with(['#', '*']) {
+                continue;
+            }
+            let details = self.command(&format!("show ssl cert {}", file)).await?;
+            let names = Self::runtime_names(&details);
+            tracing::debug!(
+                dcl = self.dcl,
+                certificate = file,
+                hosts = names.len(),
+                "Имена сертификата HAProxy"
+            );
+            hosts.extend(names);
+        }
+        Ok(hosts)
+    }
+
+    /// Имена из полей `Subject Alternative Name` (`DNS:...`) или `Subject` (`CN=`)
+    /// ответа `show ssl cert <файл>`
+    fn runtime_names(details: &str) -> Vec<String> {
+        let field = |name: &str| {
+            details.lines().find_map(|line| line.strip_prefix(name)).map(str::trim)
+        };
+        super::certificate_names(field("Subject Alternative Name:"), field("Subject:"))
+    }
+}
+
+#[async_trait]
+impl DomainSourceTrait for HaproxySourceService {
+    async fn get_domains(&self) -> Result<Vec<String>> {
+        let hosts = match &self.target {
+            Target::Config(path) => {
+                let path = path.clone();
+                tokio::task::spawn_blocking(move || Self::config_hosts(&path)).await??
+            }
+            _ => self.runtime_hosts().await?,
+        };
+        let mut seen = HashSet::new();
+        Ok(hosts.into_iter().filter(|host| seen.insert(host.to_lowercase())).collect())
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
@@ -0,0 +1,27 @@
+use super::DomainSourceTrait;
+use async_trait::async_trait;
+use base::prelude::anyhow::Result;
+
+/// Источник с заранее известным списком хостов, например найденных в журналах
+/// Certificate Transparency
+pub struct ListSourceService {
+    name: &'static str,
+    hosts: Vec<String>,
+}
+
+impl ListSourceService {
+    pub fn new(name: &'static str, hosts: Vec<String>) -> Self {
+        Self { name, hosts }
+    }
+}
+
+#[async_trait]
+impl DomainSourceTrait for ListSourceService {
+    async fn get_domains(&self) -> Result<Vec<String>> {
+        Ok(self.hosts.clone())
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.name
+    }
+}
@@ -0,0 +1,68 @@
+mod docker;
+mod f5;
+pub mod file;
+mod haproxy;
+mod list;
+mod netscaler;
+mod selectel;
+mod url;
+
+use async_trait::async_trait;
+use base::prelude::anyhow;
+pub use docker::DockerSourceService;
+pub use f5::F5SourceService;
+pub use file::FileSourceService;
+pub use haproxy::HaproxySourceService;
+use futures::stream::{self, BoxStream, StreamExt};
+pub use list::ListSourceService;
+pub use netscaler::NetScalerSourceService;
+pub use selectel::SelectelSourceService;
+pub use url::{UrlListCache, UrlSourceService};
+use std::collections::HashMap;
+
+#[async_trait]
+pub trait DomainSourceTrait: Send + Sync {
+    async fn get_domains(&self) -> anyhow::Result<Vec<String>>;
+
+    /// Потоковая загрузка: хосты передаются сервису по мере получения, не собираясь
+    /// в общий список. После первой ошибки поток завершается. По умолчанию —
+    /// результат [`DomainSourceTrait::get_domains`]
+    fn stream_domains(&self) -> BoxStream<'_, anyhow::Result<String>> {
+        stream::once(self.get_domains())
+            .flat_map(|result| match result {
+                Ok(domains) => stream::iter(domains.into_iter().map(Ok)).left_stream(),
+                Err(e) => stream::once(async { Err(e) }).right_stream(),
+            })
+            .boxed()
+    }
+
+    /// Команды-владельцы хостов, указанные в самом источнике (колонка файла,
+    /// метка ресурса): имя хоста → владелец. Вызывается после загрузки; по умолчанию
+    /// источник владельцев не задаёт
+    fn owners(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    fn get_source_name(&self) -> &'static str;
+}
+
+/// Имена сертификата из текстовых полей API балансировщика: DNS-имена списка SAN
+/// (`DNS:a.example.com, DNS:b.example.com`), а без них — CN субъекта, части которого
+/// разделены `,` или `/`
+fn certificate_names(san: Option<&str>, subject: Option<&str>) -> Vec<String> {
+    let names: Vec<String> = san
+        .into_iter()
+        .flat_map(|san| san.split(','))
+        .filter_map(|name| name.trim().strip_prefix("DNS:"))
+        .map(str::to_string)
+        .collect();
+    if !names.is_empty() {
+        return names;
+    }
+    subject
+        .into_iter()
+        .flat_map(|subject| subject.split(['/', ',']))
+        .filter_map(|part| part.trim().strip_prefix("CN="))
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,148 @@
+use super::DomainSourceTrait;
+use crate::http::HttpSettings;
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    tracing,
+};
+use reqwest::Client;
+use serde::{Deserialize, de::DeserializeOwned};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Deserialize)]
+struct Bindings {
+    #[serde(default)]
+    sslvserver_sslcertkey_binding: Vec<Binding>,
+}
+
+/// Привязка сертификата к SSL-виртуальному серверу
+#[derive(Deserialize)]
+struct Binding {
+    vservername: String,
+    certkeyname: String,
+    /// Сертификат УЦ для проверки клиентов, а не сертификат сервера
+    #[serde(default)]
+    ca: bool,
+}
+
+#[derive(Deserialize)]
+struct CertKeys {
+    #[serde(default)]
+    sslcertkey: Vec<CertKey>,
+}
+
+#[derive(Deserialize)]
+struct CertKey {
+    certkey: String,
+    subject: Option<String>,
+    /// DNS-имена из SAN; есть не во всех версиях NITRO
+    #[serde(default)]
+    sandns: Vec<String>,
+}
+
+/// Хосты SSL-виртуальных серверов Citrix ADC (NetScaler) из NITRO API: DNS-имена
+/// (или CN) сертификатов, привязанных к серверам, включая сертификаты SNI.
+/// Сертификаты УЦ для проверки клиентов пропускаются
+pub struct NetScalerSourceService {
+    address: String,
+    username: String,
+    password: String,
+    client: Client,
+    http: HttpSettings,
+    dcl: &'static str,
+}
+
+impl NetScalerSourceService {
+    /// `address` — адрес NSIP или SNIP с доступом к управлению,
+    /// например `https://adc.example.com`
+    pub fn new(
+        address: &str,
+        username: &str,
+        password: &str,
+        http: HttpSettings,
+    ) -> Self {
+        Self {
+            address: address.trim_end_matches('/').to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            client: http.client(),
+            http,
+            dcl: "NetScalerSourceService",
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, resource: &str) -> Result<T> {
+        let url = format!("{}/nitro/v1/config/{}", self.address, resource);
+        let resp = self
+            .http
+            .send(|| {
+                self.client
+                    .get(&url)
+                    .header("X-NITRO-USER", &self.username)
+                    .header("X-NITRO-PASS", &self.password)
+            })
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            tracing::error!(
+                dcl = self.dcl,
+                resource = resource,
+                status = status.to_string(),
+                body = text,
+                "Ошибка запроса к NITRO API"
+            );
+            return Err(anyhow!("NITRO API returned {} for {}", status, resource));
+        }
+        resp.json()
+            .await
+            .with_context(|| format!("Unexpected NITRO response: {}", resource))
+    }
+}
+
+#[async_trait]
+impl DomainSourceTrait for NetScalerSourceService {
+    async fn get_domains(&self) -> Result<Vec<String>> {
+        let bindings: Bindings =
+            self.get("sslvserver_sslcertkey_binding?bulkbindings=yes").await?;
+        let certificates: CertKeys = self.get("sslcertkey").await?;
+        let certificates: HashMap<&str, &CertKey> =
+            certificates.sslcertkey.iter().map(|c| (c.certkey.as_str(), c)).collect();
+
+        let mut domains = Vec::new();
+        let mut seen = HashSet::new();
+        for binding in bindings.sslvserver_sslcertkey_binding.iter().filter(|b| !b.ca) {
+            let Some(certificate) = certificates.get(binding.certkeyname.as_str()) else {
+                tracing::warn!(
+                    dcl = self.dcl,
+                    vserver = binding.vservername,
+                    certkey = binding.certkeyname,
+                    "Привязанный сертификат не найден"
+                );
+                continue;
+            };
+            let hosts = if certificate.sandns.is_empty() {
+                super::certificate_names(None, certificate.subject.as_deref())
+            } else {
+                certificate.sandns.clone()
+            };
+            tracing::debug!(
+                dcl = self.dcl,
+                vserver = binding.vservername,
+                certkey = binding.certkeyname,
+                hosts = hosts.len(),
+                "Хосты SSL-виртуального сервера"
+            );
+            for host in hosts {
+                if seen.insert(host.to_lowercase()) {
+                    domains.push(host);
+                }
+            }
+        }
+        Ok(domains)
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
@@ -0,0 +1,237 @@
+use super::DomainSourceTrait;
+use crate::http::HttpSettings;
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{self, anyhow, Result},
+    serde_json,
+    tokio::{
+        net::TcpStream,
+        time::{timeout, Duration},
+    },
+    tracing,
+};
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use reqwest::Client;
+use serde::Deserialize;
+
+const ALLOWED_TYPES: &[&str] = &["A", "CNAME"];
+
+/// Страница ответа API доменов; разбираются только нужные поля,
+/// без построения полного JSON-дерева ответа
+#[derive(Deserialize)]
+struct Page<T> {
+    #[serde(default = "Vec::new")]
+    result: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct Zone {
+    #[serde(default)]
+    id: String,
+    #[serde(default = "Zone::default_disabled")]
+    disabled: bool,
+}
+
+impl Zone {
+    fn default_disabled() -> bool {
+        true
+    }
+}
+
+#[derive(Deserialize)]
+struct RecordSet {
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    records: Vec<Record>,
+}
+
+#[derive(Deserialize)]
+struct Record {
+    #[serde(default = "Zone::default_disabled")]
+    disabled: bool,
+}
+
+impl RecordSet {
+    /// Запись учитывается, если её тип проверяется и первая запись набора включена
+    fn is_enabled(&self) -> bool {
+        !self.name.is_empty()
+            && ALLOWED_TYPES.contains(&self.kind.as_str())
+            && self.records.first().is_some_and(|record| !record.disabled)
+    }
+}
+
+pub struct SelectelSourceService {
+    account_id: String,
+    password: String,
+    project_name: String,
+    user: String,
+    client: Client,
+    http: HttpSettings,
+    dcl: &'static str,
+}
+
+impl SelectelSourceService {
+    pub fn new(
+        account_id: &str,
+        password: &str,
+        project_name: &str,
+        user: &str,
+        http: HttpSettings,
+    ) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            password: password.to_string(),
+            project_name: project_name.to_string(),
+            user: user.to_string(),
+            client: http.client(),
+            http,
+            dcl: "SelectelSourceService",
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn has_ssl(host: &str) -> bool {
+        let host = match idna::domain_to_ascii(host) {
+            Ok(h) => h,
+            Err(_) => return false,
+        };
+        let addr = format!("{}:443", host);
+        matches!(
+            timeout(Duration::from_millis(500), TcpStream::connect(addr)).await,
+            Ok(Ok(_stream))
+        )
+    }
+
+    async fn get_auth_token(&self) -> Result<String> {
+        let body = serde_json::json!({
+            "auth": {
+                "identity": {
+                    "methods": ["password"],
+                    "password": {
+                        "user": {
+                            "name": self.user,
+                            "domain": {
+                                "name": self.account_id
+                            },
+                            "password": self.password
+                        }
+                    }
+                },
+                "scope": {
+                    "project": {
+                        "name": self.project_name,
+                        "domain": { "name": self.account_id }
+                    }
+                }
+            }
+        });
+        let resp = self
+            .http
+            .send(|| {
+                self.client
+                    .post("https://cloud.api.selcloud.ru/identity/v3/auth/tokens")
+                    .json(&body)
+            })
+            .await?;
+        let token = resp
+            .headers()
+            .get("X-Subject-Token")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        if !resp.status().is_success() || token.is_none() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            tracing::error!(
+                dcl = self.dcl,
+                status = status.to_string(),
+                body = text,
+                "Не удалось авторизоваться в Selectel"
+            );
+            return Err(anyhow!("Не удалось авторизоваться в Selectel"));
+        }
+        Ok(token.unwrap())
+    }
+
+    async fn get_zones(&self, token: &str) -> Result<Vec<String>> {
+        let resp = self
+            .http
+            .send(|| {
+                self.client
+                    .get("https://api.selectel.ru/domains/v2/zones")
+                    .header("X-Auth-Token", token)
+            })
+            .await?;
+        if !resp.status().is_success() {
+            tracing::error!(
+                dcl = self.dcl,
+                status = resp.status().to_string(),
+                "Не удалось получить список зон"
+            );
+            return Err(anyhow!("Не удалось получить список зон"));
+        }
+        let page = resp.json::<Page<Zone>>().await?;
+        Ok(page
+            .result
+            .into_iter()
+            .filter(|zone| !zone.disabled && !zone.id.is_empty())
+            .map(|zone| zone.id)
+            .collect())
+    }
+
+    /// Хосты одной зоны; при ошибке ответа зона пропускается
+    async fn get_zone_domains(&self, token: &str, zone_id: &str) -> Result<Vec<String>> {
+        let url = format!("https://api.selectel.ru/domains/v2/zones/{zone_id}/rrset");
+        let resp = self
+            .http
+            .send(|| self.client.get(&url).header("X-Auth-Token", token))
+            .await?;
+        if !resp.status().is_success() {
+            tracing::error!("Не удалось получить домены для зоны {zone_id}");
+            return Ok(Vec::new());
+        }
+
+        let page = resp.json::<Page<RecordSet>>().await?;
+        Ok(page
+            .result
+            .into_iter()
+            .filter(RecordSet::is_enabled)
+            .map(|record_set| {
+                let mut name = record_set.name;
+                name.truncate(name.trim_end_matches('.').len());
+                name
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl DomainSourceTrait for SelectelSourceService {
+    async fn get_domains(&self) -> Result<Vec<String>> {
+        self.stream_domains().try_collect().await
+    }
+
+    /// Хосты передаются по мере загрузки зон
+    fn stream_domains(&self) -> BoxStream<'_, Result<String>> {
+        stream::once(async move {
+            let token = self.get_auth_token().await?;
+            let zones = self.get_zones(&token).await?;
+            let domains = stream::iter(zones)
+                .then(move |zone_id| {
+                    let token = token.clone();
+                    async move { self.get_zone_domains(&token, &zone_id).await }
+                })
+                .map_ok(|domains| stream::iter(domains.into_iter().map(Ok)))
+                .try_flatten();
+            Ok::<_, anyhow::Error>(domains)
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
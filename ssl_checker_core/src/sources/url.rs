@@ -0,0 +1,210 @@
+use super::DomainSourceTrait;
+use crate::http::HttpSettings;
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    serde_json, tracing,
+};
+use reqwest::header::{
+    CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+/// Авторизация запроса списка
+enum UrlAuth {
+    Bearer(String),
+    Basic { username: String, password: String },
+}
+
+/// Элемент JSON-списка: имя хоста или объект с хостом и командой-владельцем
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ListItem {
+    Host(String),
+    Entry {
+        host: String,
+        #[serde(default)]
+        owner: Option<String>,
+    },
+}
+
+/// Список из последнего успешного ответа и его валидаторы `ETag` и `Last-Modified`
+#[derive(Debug, Clone, Default)]
+struct CachedList {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    domains: Vec<String>,
+    owners: HashMap<String, String>,
+}
+
+/// Кэш условных запросов источника. Источники создаются на каждый запуск,
+/// поэтому для периодической проверки кэш передаётся снаружи
+/// через [`UrlSourceService::with_cache`]
+#[derive(Debug, Default)]
+pub struct UrlListCache(Mutex<Option<CachedList>>);
+
+/// Список хостов по HTTP(S): по одному хосту в строке (строка `host,owner`
+/// задаёт и владельца, строки с `#` пропускаются) или JSON-массив строк
+/// либо объектов `{"host": ..., "owner": ...}`. Повторные запросы условные:
+/// на ответ `304 Not Modified` возвращается список из кэша
+pub struct UrlSourceService {
+    url: String,
+    auth: Option<UrlAuth>,
+    client: Client,
+    http: HttpSettings,
+    cache: Arc<UrlListCache>,
+    /// Владельцы хостов из последней загрузки
+    owners: Mutex<HashMap<String, String>>,
+    dcl: &'static str,
+}
+
+impl UrlSourceService {
+    pub fn new(url: &str, http: HttpSettings) -> Self {
+        Self {
+            url: url.to_string(),
+            auth: None,
+            client: http.client(),
+            http,
+            cache: Arc::default(),
+            owners: Mutex::default(),
+            dcl: "UrlSourceService",
+        }
+    }
+
+    /// Заголовок `Authorization: Bearer <token>`
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.auth = Some(UrlAuth::Bearer(token.to_string()));
+        self
+    }
+
+    /// Заголовок `Authorization: Basic` с логином и паролем
+    pub fn with_basic_auth(mut self, username: &str, password: &str) -> Self {
+        self.auth = Some(UrlAuth::Basic {
+            username: username.to_string(),
+            password: password.to_string(),
+        });
+        self
+    }
+
+    /// Кэш, общий для источников одной секции в разных запусках
+    pub fn with_cache(mut self, cache: Arc<UrlListCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    fn request(&self, cached: Option<&CachedList>) -> RequestBuilder {
+        let mut request = self.client.get(&self.url);
+        request = match &self.auth {
+            Some(UrlAuth::Bearer(token)) => request.bearer_auth(token),
+            Some(UrlAuth::Basic { username, password }) => {
+                request.basic_auth(username, Some(password))
+            }
+            None => request,
+        };
+        if let Some(etag) = cached.and_then(|c| c.etag.as_deref()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(modified) = cached.and_then(|c| c.last_modified.as_deref()) {
+            request = request.header(IF_MODIFIED_SINCE, modified);
+        }
+        request
+    }
+
+    /// Разбирает тело ответа; JSON определяется по `Content-Type` или по первому `[`
+    fn parse(body: &str, json: bool) -> Result<CachedList> {
+        let mut list = CachedList::default();
+        let mut seen = HashSet::new();
+        let mut push = |host: &str, owner: Option<&str>| {
+            let host = host.trim();
+            if host.is_empty() {
+                return;
+            }
+            if let Some(owner) = owner.map(str::trim).filter(|o| !o.is_empty()) {
+                list.owners.insert(host.to_lowercase(), owner.to_string());
+            }
+            if seen.insert(host.to_string()) {
+                list.domains.push(host.to_string());
+            }
+        };
+        if json || body.trim_start().starts_with('[') {
+            let items: Vec<ListItem> =
+                serde_json::from_str(body).context("Ответ не является списком JSON")?;
+            for item in items {
+                match item {
+                    ListItem::Host(host) => push(&host, None),
+                    ListItem::Entry { host, owner } => push(&host, owner.as_deref()),
+                }
+            }
+        } else {
+            for line in body.lines().map(str::trim) {
+                if line.starts_with('#') {
+                    continue;
+                }
+                match line.split_once(',') {
+                    Some((host, owner)) => push(host, Some(owner)),
+                    None => push(line, None),
+                }
+            }
+        }
+        Ok(list)
+    }
+}
+
+#[async_trait]
+impl DomainSourceTrait for UrlSourceService {
+    async fn get_domains(&self) -> Result<Vec<String>> {
+        let cached = self.cache.0.lock().unwrap().clone();
+        let resp = self.http.send(|| self.request(cached.as_ref())).await?;
+        let status = resp.status();
+
+        if status == StatusCode::NOT_MODIFIED {
+            let cached = cached
+                .ok_or_else(|| anyhow!("Ответ 304 на запрос без кэша: {}", self.url))?;
+            tracing::debug!(
+                dcl = self.dcl,
+                url = self.url,
+                domains = cached.domains.len(),
+                "Список не изменился, используется кэш"
+            );
+            *self.owners.lock().unwrap() = cached.owners;
+            return Ok(cached.domains);
+        }
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            tracing::error!(
+                dcl = self.dcl,
+                url = self.url,
+                status = status.to_string(),
+                body = text,
+                "Не удалось получить список доменов"
+            );
+            return Err(anyhow!("Не удалось получить список доменов: {}", status));
+        }
+
+        let header = |name| {
+            resp.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string)
+        };
+        let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+        let json = header(CONTENT_TYPE).is_some_and(|t| t.contains("json"));
+        let body = resp.text().await?;
+        let list = CachedList { etag, last_modified, ..Self::parse(&body, json)? };
+
+        *self.owners.lock().unwrap() = list.owners.clone();
+        let domains = list.domains.clone();
+        if list.etag.is_some() || list.last_modified.is_some() {
+            *self.cache.0.lock().unwrap() = Some(list);
+        }
+        Ok(domains)
+    }
+
+    fn owners(&self) -> HashMap<String, String> {
+        self.owners.lock().unwrap().clone()
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
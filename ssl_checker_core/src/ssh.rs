@@ -0,0 +1,296 @@
+//! Сертификаты хостов OpenSSH: срок `valid-before` сертификата, которым
+//! сервер подтверждает свой ключ, если хосты подписываются SSH-УЦ.
+//! Рукопожатие выполняется только до ответа на обмен ключами (RFC 4253, 5656)
+
+use crate::checks::{self, CertificateInfo, SslCheckOptions};
+use base::prelude::{
+    anyhow::{Result, anyhow, bail},
+    chrono::{DateTime, Utc},
+    tokio::{
+        self,
+        io::{
+            AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+            BufReader,
+        },
+    },
+};
+use openssl::pkey::PKey;
+
+/// Порт SSH по умолчанию
+pub const DEFAULT_PORT: u16 = 22;
+
+/// Алгоритмы ключа хоста с сертификатом OpenSSH в порядке предпочтения
+pub const CERTIFICATE_ALGORITHMS: &[&str] = &[
+    "ssh-ed25519-cert-v01@openssh.com",
+    "ecdsa-sha2-nistp256-cert-v01@openssh.com",
+    "ecdsa-sha2-nistp384-cert-v01@openssh.com",
+    "ecdsa-sha2-nistp521-cert-v01@openssh.com",
+    "rsa-sha2-512-cert-v01@openssh.com",
+    "rsa-sha2-256-cert-v01@openssh.com",
+    "ssh-rsa-cert-v01@openssh.com",
+];
+
+pub(crate) const MSG_DISCONNECT: u8 = 1;
+pub(crate) const MSG_KEXINIT: u8 = 20;
+pub(crate) const MSG_KEX_ECDH_INIT: u8 = 30;
+pub(crate) const MSG_KEX_ECDH_REPLY: u8 = 31;
+/// Тип сертификата хоста (у сертификатов пользователей — 1)
+pub(crate) const HOST_CERTIFICATE: u32 = 2;
+/// `valid-before` сертификата без срока действия
+const FOREVER: u64 = u64::MAX;
+/// Предел длины пакета: больше обмен ключами не требует
+const MAX_PACKET_LEN: usize = 256 * 1024;
+
+/// Подключается к SSH-серверу и возвращает сведения о сертификате его ключа:
+/// срок `valid-before`, серийный номер и отпечаток ключа УЦ вместо издателя.
+/// Порт и таймауты берутся из `options`; хост без сертификата — ошибка
+pub async fn check_ssh_host_certificate(
+    hostname: &str,
+    options: &SslCheckOptions,
+) -> Result<CertificateInfo> {
+    let (stream, _) = checks::connect(hostname, options).await?;
+    let blob = tokio::time::timeout(options.handshake_timeout, host_key(stream))
+        .await
+        .map_err(|_| anyhow!("SSH handshake timed out"))??;
+    parse_host_certificate(&blob)
+}
+
+/// Ключ хоста из ответа на обмен ключами; соединение после этого закрывается
+async fn host_key<S>(stream: S) -> Result<Vec<u8>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut stream = BufReader::new(stream);
+    exchange_versions(&mut stream, "SSH-2.0-ssl_checker").await?;
+
+    let mut cookie = [0; 16];
+    openssl::rand::rand_bytes(&mut cookie)?;
+    let mut kexinit = vec![MSG_KEXINIT];
+    kexinit.extend(cookie);
+    for list in [
+        "curve25519-sha256,curve25519-sha256@libssh.org",
+        &CERTIFICATE_ALGORITHMS.join(","),
+        "aes128-ctr,aes256-ctr,chacha20-poly1305@openssh.com,aes256-gcm@openssh.com",
+        "aes128-ctr,aes256-ctr,chacha20-poly1305@openssh.com,aes256-gcm@openssh.com",
+        "hmac-sha2-256,hmac-sha2-512,hmac-sha2-256-etm@openssh.com",
+        "hmac-sha2-256,hmac-sha2-512,hmac-sha2-256-etm@openssh.com",
+        "none",
+        "none",
+        "",
+        "",
+    ] {
+        put_string(&mut kexinit, list.as_bytes());
+    }
+    kexinit.extend([0, 0, 0, 0, 0]);
+    write_packet(&mut stream, &kexinit).await?;
+
+    let payload = read_message(&mut stream, MSG_KEXINIT).await?;
+    let mut reader = SshReader::new(&payload);
+    // Тип сообщения, cookie и алгоритмы обмена ключами
+    reader.take(17)?;
+    reader.string()?;
+    let algorithms = reader.str()?;
+    if !algorithms.split(',').any(|a| CERTIFICATE_ALGORITHMS.contains(&a)) {
+        bail!("Host has no SSH certificate (host key algorithms: {})", algorithms);
+    }
+
+    let key = PKey::generate_x25519()?;
+    let mut init = vec![MSG_KEX_ECDH_INIT];
+    put_string(&mut init, &key.raw_public_key()?);
+    write_packet(&mut stream, &init).await?;
+
+    let payload = read_message(&mut stream, MSG_KEX_ECDH_REPLY).await?;
+    let mut reader = SshReader::new(&payload);
+    reader.take(1)?;
+    Ok(reader.string()?.to_vec())
+}
+
+/// Обмен строками версий; строки до версии сервера (баннер) пропускаются
+pub(crate) async fn exchange_versions<S>(
+    stream: &mut BufReader<S>,
+    version: &str,
+) -> Result<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream.get_mut().write_all(format!("{}\r\n", version).as_bytes()).await?;
+    for _ in 0..32 {
+        let mut line = Vec::new();
+        (&mut *stream).take(256).read_until(b'\n', &mut line).await?;
+        if line.is_empty() {
+            bail!("SSH connection closed before version exchange");
+        }
+        let line = String::from_utf8_lossy(&line).trim_end().to_string();
+        if line.starts_with("SSH-2.0-") || line.starts_with("SSH-1.99-") {
+            return Ok(line);
+        }
+        if line.starts_with("SSH-") {
+            bail!("Unsupported SSH protocol version: {}", line);
+        }
+    }
+    bail!("No SSH version string received")
+}
+
+/// Следующее сообщение ожидаемого типа; служебные сообщения пропускаются,
+/// `SSH_MSG_DISCONNECT` становится ошибкой
+pub(crate) async fn read_message<S>(
+    stream: &mut BufReader<S>,
+    expected: u8,
+) -> Result<Vec<u8>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    loop {
+        let payload = read_packet(stream).await?;
+        match payload.first() {
+            Some(&kind) if kind == expected => return Ok(payload),
+            Some(&MSG_DISCONNECT) => {
+                let mut reader = SshReader::new(&payload);
+                reader.take(5)?;
+                bail!("SSH server disconnected: {}", reader.str()?);
+            }
+            Some(_) => continue,
+            None => bail!("Empty SSH packet"),
+        }
+    }
+}
+
+/// Пакет без шифрования: длина, длина выравнивания, данные и выравнивание
+pub(crate) async fn read_packet<S>(stream: &mut BufReader<S>) -> Result<Vec<u8>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let len = stream.read_u32().await? as usize;
+    if !(5..=MAX_PACKET_LEN).contains(&len) {
+        bail!("Invalid SSH packet length: {}", len);
+    }
+    let mut packet = vec![0; len];
+    stream.read_exact(&mut packet).await?;
+    let padding = packet[0] as usize;
+    if padding + 1 > len {
+        bail!("Invalid SSH packet padding: {}", padding);
+    }
+    packet.truncate(len - padding);
+    packet.remove(0);
+    Ok(packet)
+}
+
+pub(crate) async fn write_packet<S>(
+    stream: &mut BufReader<S>,
+    payload: &[u8],
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // Пакет выравнивается до 8 байт, выравнивание — не меньше 4 байт
+    let mut padding = 8 - (5 + payload.len()) % 8;
+    if padding < 4 {
+        padding += 8;
+    }
+    let mut packet = Vec::with_capacity(5 + payload.len() + padding);
+    packet.extend(((1 + payload.len() + padding) as u32).to_be_bytes());
+    packet.push(padding as u8);
+    packet.extend(payload);
+    packet.resize(packet.len() + padding, 0);
+    stream.get_mut().write_all(&packet).await?;
+    Ok(())
+}
+
+pub(crate) fn put_string(buf: &mut Vec<u8>, value: &[u8]) {
+    buf.extend((value.len() as u32).to_be_bytes());
+    buf.extend(value);
+}
+
+/// Сведения о сертификате хоста из его представления в протоколе
+/// (PROTOCOL.certkeys OpenSSH)
+pub fn parse_host_certificate(blob: &[u8]) -> Result<CertificateInfo> {
+    let mut reader = SshReader::new(blob);
+    let kind = reader.str()?;
+    // Поля открытого ключа после nonce зависят от типа
+    let key_fields = match kind {
+        "ssh-ed25519-cert-v01@openssh.com" => 1,
+        "ssh-rsa-cert-v01@openssh.com"
+        | "rsa-sha2-256-cert-v01@openssh.com"
+        | "rsa-sha2-512-cert-v01@openssh.com" => 2,
+        kind if kind.starts_with("ecdsa-sha2-")
+            && kind.ends_with("-cert-v01@openssh.com") =>
+        {
+            2
+        }
+        "ssh-dss-cert-v01@openssh.com" => 4,
+        _ => bail!("Unsupported SSH host key type: {}", kind),
+    };
+    // nonce
+    reader.string()?;
+    for _ in 0..key_fields {
+        reader.string()?;
+    }
+    let serial = reader.u64()?;
+    if reader.u32()? != HOST_CERTIFICATE {
+        bail!("SSH certificate is not a host certificate");
+    }
+    // Идентификатор ключа и имена хостов
+    reader.string()?;
+    reader.string()?;
+    let _valid_after = reader.u64()?;
+    let valid_before = reader.u64()?;
+    // Критические опции, расширения и зарезервированное поле
+    for _ in 0..3 {
+        reader.string()?;
+    }
+    let ca_key = reader.string()?;
+
+    let expiration_date = Some(valid_before)
+        .filter(|&before| before != FOREVER)
+        .and_then(|before| i64::try_from(before).ok())
+        .and_then(|before| DateTime::from_timestamp(before, 0))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+    Ok(CertificateInfo {
+        expiration_date,
+        serial: format!("{:X}", serial),
+        issuer: fingerprint(ca_key),
+        alpn: None,
+        revocation_urls: Vec::new(),
+    })
+}
+
+/// Отпечаток ключа в формате `ssh-keygen -l`: `SHA256:<base64 без выравнивания>`
+pub fn fingerprint(key: &[u8]) -> String {
+    let digest = openssl::base64::encode_block(&openssl::sha::sha256(key));
+    format!("SHA256:{}", digest.trim_end_matches('='))
+}
+
+/// Чтение полей протокола SSH (RFC 4251): `uint32`, `uint64` и `string`
+pub(crate) struct SshReader<'a>(&'a [u8]);
+
+impl<'a> SshReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            bail!("Truncated SSH message");
+        }
+        let (value, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(value)
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into()?))
+    }
+
+    pub(crate) fn string(&mut self) -> Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    pub(crate) fn str(&mut self) -> Result<&'a str> {
+        Ok(std::str::from_utf8(self.string()?)?)
+    }
+}
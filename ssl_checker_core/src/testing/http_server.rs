@@ -0,0 +1,87 @@
+use base::prelude::{
+    anyhow::Result,
+    tokio::{
+        self,
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+        task::JoinHandle,
+    },
+};
+use std::sync::{Arc, Mutex};
+
+/// Локальный HTTP-сервер, запоминающий тела запросов, например для модулей
+/// уведомлений с настраиваемым адресом API. На любой запрос отвечает `200 {}`.
+/// Останавливается при удалении
+pub struct TestHttpServer {
+    url: String,
+    bodies: Arc<Mutex<Vec<String>>>,
+    handle: JoinHandle<()>,
+}
+
+impl TestHttpServer {
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let bodies: Arc<Mutex<Vec<String>>> = Arc::default();
+        let recorded = bodies.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let recorded = recorded.clone();
+                tokio::spawn(async move {
+                    let _ = Self::serve(stream, &recorded).await;
+                });
+            }
+        });
+
+        Ok(Self { url, bodies, handle })
+    }
+
+    /// Читает запрос целиком (тело может быть длиннее одного чтения),
+    /// запоминает тело и отвечает
+    async fn serve(mut stream: TcpStream, bodies: &Mutex<Vec<String>>) -> Result<()> {
+        let mut request = Vec::new();
+        let mut buf = [0; 8192];
+        loop {
+            let n = stream.read(&mut buf).await?;
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if body.len() >= length {
+                    bodies.lock().unwrap().push(body.to_string());
+                    break;
+                }
+            }
+            if n == 0 {
+                return Ok(());
+            }
+        }
+        let response =
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}";
+        stream.write_all(response.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// Адрес `http://127.0.0.1:<порт>`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Тела полученных запросов в порядке получения
+    pub fn bodies(&self) -> Vec<String> {
+        self.bodies.lock().unwrap().clone()
+    }
+}
+
+impl Drop for TestHttpServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
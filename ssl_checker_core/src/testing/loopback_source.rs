@@ -0,0 +1,49 @@
+use crate::sources::DomainSourceTrait;
+use async_trait::async_trait;
+use base::prelude::anyhow::{Result, bail};
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+
+/// Источник для нагрузочных тестов: `count` различных адресов из 127.0.0.0/8,
+/// выдаваемых потоком без общего списка. Все они ведут на локальный хост, так что
+/// их обслуживает один [`TestTlsServer`](super::TestTlsServer), запущенный на `0.0.0.0`
+pub struct LoopbackSource {
+    count: usize,
+}
+
+impl LoopbackSource {
+    /// Сколько различных адресов можно получить
+    pub const MAX_COUNT: usize = 255 * 255 * 254;
+
+    pub fn new(count: usize) -> Self {
+        Self { count }
+    }
+
+    /// Адрес с номером `i`; последний октет не бывает нулевым
+    fn address(i: usize) -> String {
+        let last = i % 254 + 1;
+        let rest = i / 254;
+        format!("127.{}.{}.{}", rest / 255, rest % 255, last)
+    }
+}
+
+#[async_trait]
+impl DomainSourceTrait for LoopbackSource {
+    async fn get_domains(&self) -> Result<Vec<String>> {
+        self.stream_domains().try_collect().await
+    }
+
+    fn stream_domains(&self) -> BoxStream<'_, Result<String>> {
+        if self.count > Self::MAX_COUNT {
+            let count = self.count;
+            return stream::once(async move {
+                bail!("Не больше {} адресов, запрошено {}", Self::MAX_COUNT, count)
+            })
+            .boxed();
+        }
+        stream::iter((0..self.count).map(|i| Ok(Self::address(i)))).boxed()
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        "LoopbackSource"
+    }
+}
@@ -0,0 +1,53 @@
+use crate::sources::DomainSourceTrait;
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Result, anyhow},
+    tokio,
+};
+use std::time::Duration;
+
+/// Источник с фиксированным списком хостов или ошибкой
+pub struct MockSource {
+    domains: Result<Vec<String>, String>,
+    delay: Option<Duration>,
+    dcl: &'static str,
+}
+
+impl MockSource {
+    pub fn new<I, S>(domains: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            domains: Ok(domains.into_iter().map(Into::into).collect()),
+            delay: None,
+            dcl: "MockSource",
+        }
+    }
+
+    /// Источник, загрузка из которого всегда завершается ошибкой
+    pub fn failing(message: &str) -> Self {
+        Self { domains: Err(message.to_string()), delay: None, dcl: "MockSource" }
+    }
+
+    /// Задержка перед ответом, как у медленного API провайдера
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+#[async_trait]
+impl DomainSourceTrait for MockSource {
+    async fn get_domains(&self) -> Result<Vec<String>> {
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+        self.domains.clone().map_err(|e| anyhow!(e))
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
@@ -0,0 +1,47 @@
+//! Тестовое окружение (фича `testing`): источник с заданным списком хостов,
+//! модуль уведомлений, запоминающий всё, что ему передали, локальные TLS-,
+//! QUIC- (с фичей `quic`), SSH- и WHOIS-серверы с заданными сроками действия,
+//! HTTP-сервер, запоминающий запросы, и источник адресов 127.0.0.0/8 для
+//! нагрузочных тестов.
+//!
+//! ```no_run
+//! use base::prelude::chrono::{Duration, Utc};
+//! use ssl_checker_core::testing::{MockSource, RecordingNotifier, TestTlsServer};
+//! use ssl_checker_core::{DomainCheckerService, SslCheckOptions};
+//!
+//! # async fn example() -> base::prelude::anyhow::Result<()> {
+//! let server = TestTlsServer::start(Utc::now() + Duration::days(5)).await?;
+//! let notifier = RecordingNotifier::new();
+//! let mut checker = DomainCheckerService::new(
+//!     vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+//!     vec![Box::new(notifier.clone())],
+//!     14,
+//!     30,
+//! )
+//! .with_checks(true, false)
+//! .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+//! checker.run().await?;
+//! assert_eq!(notifier.recording().ssl.len(), 1);
+//! # Ok(())
+//! # }
+//! ```
+
+mod http_server;
+mod loopback_source;
+mod mock_source;
+#[cfg(feature = "quic")]
+mod quic_server;
+mod recording_notifier;
+mod ssh_server;
+mod tls_server;
+mod whois_server;
+
+pub use http_server::TestHttpServer;
+pub use loopback_source::LoopbackSource;
+pub use mock_source::MockSource;
+#[cfg(feature = "quic")]
+pub use quic_server::TestQuicServer;
+pub use recording_notifier::{Recording, RecordingNotifier};
+pub use ssh_server::TestSshServer;
+pub use tls_server::TestTlsServer;
+pub use whois_server::TestWhoisServer;
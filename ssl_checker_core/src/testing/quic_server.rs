@@ -0,0 +1,82 @@
+use crate::checks::{self, CertificateInfo};
+use crate::quic;
+use base::prelude::{
+    anyhow::Result,
+    chrono::{DateTime, Utc},
+    tokio::{self, task::JoinHandle},
+};
+use quinn::crypto::rustls::QuicServerConfig;
+use rcgen::{CertificateParams, DnType, KeyPair};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+/// Локальный сервер QUIC (HTTP/3) с самоподписанным сертификатом, действующим
+/// до `not_after`. Слушает UDP на `127.0.0.1` и останавливается при удалении
+pub struct TestQuicServer {
+    port: u16,
+    certificate: CertificateInfo,
+    handle: JoinHandle<()>,
+}
+
+impl TestQuicServer {
+    /// Сервер на UDP-порту `port`; порт [`TestTlsServer`](super::TestTlsServer)
+    /// позволяет проверить хост по TCP и по QUIC с одними параметрами
+    pub async fn start_on_port(port: u16, not_after: DateTime<Utc>) -> Result<Self> {
+        let mut params = CertificateParams::new(vec!["localhost".to_string()])?;
+        params.not_after =
+            time::OffsetDateTime::from_unix_timestamp(not_after.timestamp())?;
+        params
+            .distinguished_name
+            .push(DnType::OrganizationName, super::TestTlsServer::ISSUER);
+        let key = KeyPair::generate()?;
+        let cert = params.self_signed(&key)?;
+        let certificate = checks::parse_certificate(cert.der())?;
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut crypto = rustls::ServerConfig::builder_with_provider(provider)
+            .with_protocol_versions(&[&rustls::version::TLS13])?
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![CertificateDer::from(cert.der().to_vec())],
+                PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.serialize_der())),
+            )?;
+        crypto.alpn_protocols = vec![quic::DEFAULT_ALPN.as_bytes().to_vec()];
+        let config = quinn::ServerConfig::with_crypto(Arc::new(
+            QuicServerConfig::try_from(crypto)?,
+        ));
+
+        let endpoint = quinn::Endpoint::server(
+            config,
+            SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+        )?;
+        let port = endpoint.local_addr()?.port();
+        let handle = tokio::spawn(async move {
+            while let Some(incoming) = endpoint.accept().await {
+                // Соединение держится, пока клиент не закроет его после рукопожатия
+                tokio::spawn(async move {
+                    if let Ok(connection) = incoming.await {
+                        connection.closed().await;
+                    }
+                });
+            }
+        });
+
+        Ok(Self { port, certificate, handle })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Сертификат в том виде, в каком его вернёт проверка
+    pub fn certificate(&self) -> &CertificateInfo {
+        &self.certificate
+    }
+}
+
+impl Drop for TestQuicServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
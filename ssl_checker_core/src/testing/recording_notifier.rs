@@ -0,0 +1,100 @@
+use crate::notifiers::BaseNotifierTrait;
+use crate::report::{
+    CheckError, DomainCheckResult, RunChanges, RunStats, SslCheckResult,
+};
+use async_trait::async_trait;
+use base::prelude::anyhow::Result;
+use std::sync::{Arc, Mutex};
+
+/// Всё, что сервис передал модулю уведомлений
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    /// Идентификатор запуска из `run_started`
+    pub run_id: Option<String>,
+    pub ssl: Vec<SslCheckResult>,
+    /// Сертификаты почтовых серверов
+    pub mail: Vec<SslCheckResult>,
+    pub domains: Vec<DomainCheckResult>,
+    /// Учётные данные с истекающим сроком
+    pub credentials: Vec<DomainCheckResult>,
+    pub errors: Vec<CheckError>,
+    /// Отличия от предыдущего запуска
+    pub changes: Option<RunChanges>,
+    /// Статистика последнего запуска
+    pub stats: Option<RunStats>,
+    /// Число вызовов `commit`
+    pub commits: usize,
+    /// Число вызовов `flush`
+    pub flushes: usize,
+}
+
+/// Модуль уведомлений, запоминающий вызовы. Клоны разделяют одну запись,
+/// поэтому клон, оставшийся у теста, видит всё, что получил переданный сервису
+#[derive(Clone, Default)]
+pub struct RecordingNotifier {
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl RecordingNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Копия записанного на текущий момент
+    pub fn recording(&self) -> Recording {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Recording> {
+        self.recording.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl BaseNotifierTrait for RecordingNotifier {
+    async fn run_started(&mut self, run_id: &str) {
+        self.lock().run_id = Some(run_id.to_string());
+    }
+
+    async fn ssl_expiration(&mut self, entry: &SslCheckResult) {
+        self.lock().ssl.push(entry.clone());
+    }
+
+    async fn mail_expiration(&mut self, entry: &SslCheckResult) {
+        self.lock().mail.push(entry.clone());
+    }
+
+    async fn exception(&mut self, error: &CheckError) {
+        self.lock().errors.push(error.clone());
+    }
+
+    async fn expiration(&mut self, entry: &DomainCheckResult) {
+        self.lock().domains.push(entry.clone());
+    }
+
+    async fn credential_expiration(&mut self, entry: &DomainCheckResult) {
+        self.lock().credentials.push(entry.clone());
+    }
+
+    async fn changes(&mut self, changes: &RunChanges) {
+        self.lock().changes = Some(changes.clone());
+    }
+
+    async fn stats(&mut self, stats: &RunStats) {
+        self.lock().stats = Some(stats.clone());
+    }
+
+    async fn commit(&self) -> Result<()> {
+        self.lock().commits += 1;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.lock().flushes += 1;
+        Ok(())
+    }
+
+    fn get_notifier_name(&self) -> &'static str {
+        "RecordingNotifier"
+    }
+}
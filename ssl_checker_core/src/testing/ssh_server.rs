@@ -0,0 +1,145 @@
+use crate::ssh::{self, SshReader};
+use base::prelude::{
+    anyhow::Result,
+    chrono::{DateTime, Utc},
+    tokio::{self, io::BufReader, net::TcpListener, task::JoinHandle},
+};
+use std::sync::Arc;
+
+/// Локальный SSH-сервер, который доводит обмен ключами до ответа с ключом хоста:
+/// сертификатом ed25519 с заданным сроком `valid-before` или ключом
+/// без сертификата. Подпись обмена не настоящая, поэтому настоящий клиент
+/// с ним не соединится. Останавливается при удалении
+pub struct TestSshServer {
+    port: u16,
+    ca_key: Vec<u8>,
+    handle: JoinHandle<()>,
+}
+
+impl TestSshServer {
+    pub const HOST: &'static str = "127.0.0.1";
+    const CERTIFICATE_ALGORITHM: &'static str = "ssh-ed25519-cert-v01@openssh.com";
+    const KEY_ALGORITHM: &'static str = "ssh-ed25519";
+
+    /// Сервер с сертификатом хоста, действующим до `valid_before`
+    pub async fn start(valid_before: DateTime<Utc>) -> Result<Self> {
+        Self::start_with(Some(valid_before)).await
+    }
+
+    /// Сервер, который отдаёт ключ хоста без сертификата
+    pub async fn start_without_certificate() -> Result<Self> {
+        Self::start_with(None).await
+    }
+
+    async fn start_with(valid_before: Option<DateTime<Utc>>) -> Result<Self> {
+        let ca_key = Self::ed25519_key(0xCA);
+        let (algorithm, host_key) = match valid_before {
+            Some(valid_before) => {
+                (Self::CERTIFICATE_ALGORITHM, Self::certificate(valid_before, &ca_key))
+            }
+            None => (Self::KEY_ALGORITHM, Self::ed25519_key(0x01)),
+        };
+        let host_key = Arc::new(host_key);
+        let listener = TcpListener::bind((Self::HOST, 0)).await?;
+        let port = listener.local_addr()?.port();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let host_key = host_key.clone();
+                tokio::spawn(async move {
+                    let _ = Self::serve(stream, algorithm, &host_key).await;
+                });
+            }
+        });
+
+        Ok(Self { port, ca_key, handle })
+    }
+
+    async fn serve(
+        stream: tokio::net::TcpStream,
+        algorithm: &str,
+        host_key: &[u8],
+    ) -> Result<()> {
+        let mut stream = BufReader::new(stream);
+        ssh::exchange_versions(&mut stream, "SSH-2.0-TestSsh").await?;
+        ssh::read_message(&mut stream, ssh::MSG_KEXINIT).await?;
+
+        let mut kexinit = vec![ssh::MSG_KEXINIT];
+        kexinit.extend([0; 16]);
+        for list in [
+            "curve25519-sha256",
+            algorithm,
+            "aes128-ctr",
+            "aes128-ctr",
+            "hmac-sha2-256",
+            "hmac-sha2-256",
+            "none",
+            "none",
+            "",
+            "",
+        ] {
+            ssh::put_string(&mut kexinit, list.as_bytes());
+        }
+        kexinit.extend([0; 5]);
+        ssh::write_packet(&mut stream, &kexinit).await?;
+
+        let init = ssh::read_message(&mut stream, ssh::MSG_KEX_ECDH_INIT).await?;
+        let mut reader = SshReader::new(&init);
+        reader.take(1)?;
+        let client_key = reader.string()?.to_vec();
+
+        let mut reply = vec![ssh::MSG_KEX_ECDH_REPLY];
+        ssh::put_string(&mut reply, host_key);
+        ssh::put_string(&mut reply, &client_key);
+        ssh::put_string(&mut reply, &[0; 64]);
+        ssh::write_packet(&mut stream, &reply).await?;
+        // Клиент закрывает соединение, получив ключ хоста
+        ssh::read_packet(&mut stream).await.ok();
+        Ok(())
+    }
+
+    /// Открытый ключ ed25519 в формате протокола; байты ключа не проверяются
+    fn ed25519_key(fill: u8) -> Vec<u8> {
+        let mut key = Vec::new();
+        ssh::put_string(&mut key, Self::KEY_ALGORITHM.as_bytes());
+        ssh::put_string(&mut key, &[fill; 32]);
+        key
+    }
+
+    /// Сертификат хоста (PROTOCOL.certkeys) с фиктивной подписью
+    fn certificate(valid_before: DateTime<Utc>, ca_key: &[u8]) -> Vec<u8> {
+        let mut principals = Vec::new();
+        ssh::put_string(&mut principals, Self::HOST.as_bytes());
+
+        let mut blob = Vec::new();
+        ssh::put_string(&mut blob, Self::CERTIFICATE_ALGORITHM.as_bytes());
+        ssh::put_string(&mut blob, &[0x5A; 32]);
+        ssh::put_string(&mut blob, &[0x01; 32]);
+        blob.extend(0u64.to_be_bytes());
+        blob.extend(ssh::HOST_CERTIFICATE.to_be_bytes());
+        ssh::put_string(&mut blob, b"test-host");
+        ssh::put_string(&mut blob, &principals);
+        blob.extend(0u64.to_be_bytes());
+        blob.extend((valid_before.timestamp() as u64).to_be_bytes());
+        for _ in 0..3 {
+            ssh::put_string(&mut blob, b"");
+        }
+        ssh::put_string(&mut blob, ca_key);
+        ssh::put_string(&mut blob, &[0; 64]);
+        blob
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Отпечаток ключа УЦ, которым «подписан» сертификат хоста
+    pub fn ca_fingerprint(&self) -> String {
+        ssh::fingerprint(&self.ca_key)
+    }
+}
+
+impl Drop for TestSshServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
@@ -0,0 +1,222 @@
+use crate::checks::{self, CertificateInfo};
+use base::prelude::{
+    anyhow::Result,
+    chrono::{DateTime, Utc},
+    tokio::{
+        self,
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::{TcpListener, TcpStream},
+        task::JoinHandle,
+    },
+};
+use openssl::pkcs12::Pkcs12;
+use openssl::pkey::PKey;
+use openssl::ssl::{AlpnError, SslAcceptor, SslMethod};
+use openssl::x509::X509;
+use rcgen::{
+    CertificateParams, CrlDistributionPoint, DnType, ExtendedKeyUsagePurpose, KeyPair,
+};
+use std::net::{IpAddr, Ipv4Addr};
+
+/// Локальный TLS-сервер с самоподписанным сертификатом, действующим до `not_after`.
+/// Слушает `127.0.0.1` на свободном порту и останавливается при удалении
+pub struct TestTlsServer {
+    port: u16,
+    certificate: CertificateInfo,
+    handle: JoinHandle<()>,
+}
+
+impl TestTlsServer {
+    /// Хост, по которому сервер доступен для проверки
+    pub const HOST: &'static str = "127.0.0.1";
+    /// Организация издателя сертификата
+    pub const ISSUER: &'static str = "ssl_checker testing";
+
+    pub async fn start(not_after: DateTime<Utc>) -> Result<Self> {
+        Self::start_on(Ipv4Addr::LOCALHOST.into(), not_after).await
+    }
+
+    /// Сервер на заданном адресе; с `0.0.0.0` доступен по любому адресу 127.0.0.0/8,
+    /// например для хостов [`LoopbackSource`](super::LoopbackSource)
+    pub async fn start_on(address: IpAddr, not_after: DateTime<Utc>) -> Result<Self> {
+        Self::start_with(address, not_after, false, &[], &[]).await
+    }
+
+    /// SMTP-сервер на `127.0.0.1`, который предлагает STARTTLS и отдаёт сертификат
+    /// после этой команды, — для проверки почтовых серверов
+    pub async fn start_smtp(not_after: DateTime<Utc>) -> Result<Self> {
+        Self::start_with(Ipv4Addr::LOCALHOST.into(), not_after, true, &[], &[]).await
+    }
+
+    /// Сервер на `127.0.0.1`, который выбирает протокол ALPN из `protocols`
+    /// и отклоняет рукопожатие, если клиент предложил только другие протоколы
+    pub async fn start_with_alpn(
+        not_after: DateTime<Utc>,
+        protocols: &[&str],
+    ) -> Result<Self> {
+        Self::start_with(Ipv4Addr::LOCALHOST.into(), not_after, false, protocols, &[])
+            .await
+    }
+
+    /// Сервер на `127.0.0.1`, сертификат которого указывает списки отзыва `crl_urls`,
+    /// — для проверки доступности OCSP и CRL
+    pub async fn start_with_crl(
+        not_after: DateTime<Utc>,
+        crl_urls: &[&str],
+    ) -> Result<Self> {
+        Self::start_with(Ipv4Addr::LOCALHOST.into(), not_after, false, &[], crl_urls)
+            .await
+    }
+
+    async fn start_with(
+        address: IpAddr,
+        not_after: DateTime<Utc>,
+        smtp: bool,
+        alpn: &[&str],
+        crl_urls: &[&str],
+    ) -> Result<Self> {
+        let mut params = CertificateParams::new(vec!["localhost".to_string()])?;
+        params.not_after =
+            time::OffsetDateTime::from_unix_timestamp(not_after.timestamp())?;
+        params.distinguished_name.push(DnType::OrganizationName, Self::ISSUER);
+        if !crl_urls.is_empty() {
+            params.crl_distribution_points = vec![CrlDistributionPoint {
+                uris: crl_urls.iter().map(|url| url.to_string()).collect(),
+            }];
+        }
+        let key = KeyPair::generate()?;
+        let cert = params.self_signed(&key)?;
+        let certificate = checks::parse_certificate(cert.der())?;
+
+        let identity = native_tls::Identity::from_pkcs8(
+            cert.pem().as_bytes(),
+            key.serialize_pem().as_bytes(),
+        )?;
+        let acceptor =
+            tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity)?);
+        // native-tls не выбирает протокол ALPN на стороне сервера
+        let alpn_acceptor = if alpn.is_empty() {
+            None
+        } else {
+            Some(Self::alpn_acceptor(&cert.pem(), &key.serialize_pem(), alpn)?)
+        };
+
+        let listener = TcpListener::bind((address, 0)).await?;
+        let port = listener.local_addr()?.port();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                if let Some(alpn_acceptor) = alpn_acceptor.clone() {
+                    let Ok(stream) = stream.into_std() else {
+                        continue;
+                    };
+                    tokio::task::spawn_blocking(move || {
+                        let _ = stream.set_nonblocking(false);
+                        let _ = alpn_acceptor.accept(stream);
+                    });
+                    continue;
+                }
+                // Рукопожатия достаточно: проверка читает сертификат и закрывает соединение
+                tokio::spawn(async move {
+                    let stream =
+                        if smtp { Self::smtp_dialogue(stream).await } else { Ok(stream) };
+                    if let Ok(stream) = stream {
+                        let _ = acceptor.accept(stream).await;
+                    }
+                });
+            }
+        });
+
+        Ok(Self { port, certificate, handle })
+    }
+
+    /// Акцептор OpenSSL, выбирающий первый из `protocols`, предложенный клиентом
+    fn alpn_acceptor(
+        cert_pem: &str,
+        key_pem: &str,
+        protocols: &[&str],
+    ) -> Result<SslAcceptor> {
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls())?;
+        let certificate = X509::from_pem(cert_pem.as_bytes())?;
+        let key = PKey::private_key_from_pem(key_pem.as_bytes())?;
+        builder.set_certificate(&certificate)?;
+        builder.set_private_key(&key)?;
+        // Список в формате ALPN: длина и имя протокола; живёт до конца теста
+        let wire: &'static [u8] = Vec::leak(
+            protocols
+                .iter()
+                .flat_map(|protocol| {
+                    std::iter::once(protocol.len() as u8).chain(protocol.bytes())
+                })
+                .collect(),
+        );
+        builder.set_alpn_select_callback(move |_, client| {
+            openssl::ssl::select_next_proto(wire, client).ok_or(AlpnError::ALERT_FATAL)
+        });
+        Ok(builder.build())
+    }
+
+    /// Минимальный SMTP-диалог до начала TLS: приветствие, EHLO и STARTTLS
+    async fn smtp_dialogue(stream: TcpStream) -> Result<TcpStream> {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.get_mut().write_all(b"220 localhost ESMTP\r\n").await?;
+        reader.read_line(&mut line).await?;
+        reader.get_mut().write_all(b"250-localhost\r\n250 STARTTLS\r\n").await?;
+        reader.read_line(&mut line).await?;
+        reader.get_mut().write_all(b"220 Ready to start TLS\r\n").await?;
+        Ok(reader.into_inner())
+    }
+
+    /// PEM-сертификат для [`TestTlsServer::HOST`], каким его выпустило бы продление
+    /// certbot или acme.sh, — для проверки сверки с продлёнными сертификатами
+    pub fn renewed_certificate_pem(not_after: DateTime<Utc>) -> Result<String> {
+        let mut params = CertificateParams::new(vec![Self::HOST.to_string()])?;
+        params.not_after =
+            time::OffsetDateTime::from_unix_timestamp(not_after.timestamp())?;
+        params.distinguished_name.push(DnType::OrganizationName, Self::ISSUER);
+        Ok(params.self_signed(&KeyPair::generate()?)?.pem())
+    }
+
+    /// Бандл PKCS#12 с ключом и сертификатом подписи кода и S/MIME, защищённый
+    /// паролем `password`, — для проверки сертификатов из файлов
+    pub fn signing_certificate_pkcs12(
+        not_after: DateTime<Utc>,
+        password: &str,
+    ) -> Result<Vec<u8>> {
+        let mut params = CertificateParams::new(Vec::new())?;
+        params.not_after =
+            time::OffsetDateTime::from_unix_timestamp(not_after.timestamp())?;
+        params.distinguished_name.push(DnType::CommonName, "Release Signing");
+        params.distinguished_name.push(DnType::OrganizationName, Self::ISSUER);
+        params.extended_key_usages = vec![
+            ExtendedKeyUsagePurpose::CodeSigning,
+            ExtendedKeyUsagePurpose::EmailProtection,
+        ];
+        let key = KeyPair::generate()?;
+        let cert = params.self_signed(&key)?;
+        let pkey = PKey::private_key_from_der(&key.serialize_der())?;
+        let x509 = X509::from_der(cert.der())?;
+        let pkcs12 = Pkcs12::builder()
+            .name("release")
+            .pkey(&pkey)
+            .cert(&x509)
+            .build2(password)?;
+        Ok(pkcs12.to_der()?)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Сертификат в том виде, в каком его вернёт проверка
+    pub fn certificate(&self) -> &CertificateInfo {
+        &self.certificate
+    }
+}
+
+impl Drop for TestTlsServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
@@ -0,0 +1,81 @@
+use base::prelude::{
+    anyhow::Result,
+    chrono::{DateTime, SecondsFormat, Utc},
+    tokio::{
+        self,
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+        task::JoinHandle,
+    },
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Локальный WHOIS-сервер с заготовленными ответами по имени домена.
+/// Адрес передаётся в `WhoisCheckOptions::server`; на неизвестные домены
+/// отвечает `No match`. Останавливается при удалении
+pub struct TestWhoisServer {
+    address: String,
+    handle: JoinHandle<()>,
+}
+
+impl TestWhoisServer {
+    pub async fn start(responses: HashMap<String, String>) -> Result<Self> {
+        let responses = Arc::new(responses);
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let address = listener.local_addr()?.to_string();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let responses = responses.clone();
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut query = String::new();
+                    if BufReader::new(reader).read_line(&mut query).await.is_err() {
+                        return;
+                    }
+                    let response =
+                        responses.get(query.trim()).cloned().unwrap_or_else(|| {
+                            format!("No match for \"{}\".\r\n", query.trim())
+                        });
+                    let _ = writer.write_all(response.as_bytes()).await;
+                    let _ = writer.shutdown().await;
+                });
+            }
+        });
+
+        Ok(Self { address, handle })
+    }
+
+    /// Сервер, отвечающий на запросы по каждому домену сроком его регистрации
+    pub async fn with_expiration_dates<'a>(
+        domains: impl IntoIterator<Item = (&'a str, DateTime<Utc>)>,
+    ) -> Result<Self> {
+        let responses = domains
+            .into_iter()
+            .map(|(domain, date)| {
+                (domain.to_string(), Self::registry_response(domain, date))
+            })
+            .collect();
+        Self::start(responses).await
+    }
+
+    /// Ответ в формате реестров gTLD
+    pub fn registry_response(domain: &str, expiration_date: DateTime<Utc>) -> String {
+        format!(
+            "Domain Name: {}\r\nRegistry Expiry Date: {}\r\n",
+            domain.to_uppercase(),
+            expiration_date.to_rfc3339_opts(SecondsFormat::Secs, true)
+        )
+    }
+
+    /// Адрес `host:port` для `WhoisCheckOptions::server`
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+impl Drop for TestWhoisServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
@@ -0,0 +1,78 @@
+//! Аудит хранилища доверенных сертификатов: корневые и промежуточные сертификаты
+//! удостоверяющих центров, срок которых истекает. Полезно для устройств
+//! с хранилищем, которое не обновляется вместе с системой
+
+use crate::checks;
+use crate::report::{CheckKind, CheckReport, ReportEntry};
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    chrono::Utc,
+};
+use std::path::{Path, PathBuf};
+use x509_parser::pem::Pem;
+
+/// Системные наборы корневых сертификатов в порядке поиска (Debian/Ubuntu, RHEL,
+/// openSUSE, Alpine и macOS)
+pub const SYSTEM_BUNDLES: &[&str] = &[
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/ssl/cert.pem",
+];
+
+/// Набор сертификатов хранилища: `SSL_CERT_FILE`, если переменная задана,
+/// иначе первый найденный из [`SYSTEM_BUNDLES`]
+pub fn system_bundle() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("SSL_CERT_FILE") {
+        return Some(PathBuf::from(path));
+    }
+    SYSTEM_BUNDLES.iter().map(PathBuf::from).find(|path| path.is_file())
+}
+
+/// Отчёт по сертификатам PEM-набора `path`: запись вида [`CheckKind::Ca`] на каждый
+/// сертификат с именем субъекта вместо хоста и тегом `root` или `intermediate`.
+/// Сертификаты, истекающие в ближайшие `alarm_days` дней, попадают в окно оповещения;
+/// блоки, которые не удалось разобрать, записываются как ошибки
+pub fn audit_bundle(path: &Path, alarm_days: i64) -> Result<CheckReport> {
+    let data = std::fs::read(path)
+        .with_context(|| format!("Не удалось прочитать файл: {}", path.display()))?;
+    let mut report = CheckReport::new(alarm_days, alarm_days);
+    for (i, pem) in Pem::iter_from_buffer(&data).enumerate() {
+        let name = format!("{}#{}", path.display(), i + 1);
+        let entry = pem
+            .map_err(|e| anyhow!("PEM: {}", e))
+            .and_then(|pem| ca_entry(&pem, alarm_days))
+            .unwrap_or_else(|e| {
+                ReportEntry::failed(CheckKind::Ca, &name, &e.to_string(), false)
+            });
+        report.stats.record(&entry);
+        report.push(entry);
+    }
+    if report.entries.is_empty() {
+        return Err(anyhow!("В файле {} нет сертификатов", path.display()));
+    }
+    report.sort();
+    Ok(report)
+}
+
+/// Запись отчёта о сертификате УЦ
+fn ca_entry(pem: &Pem, alarm_days: i64) -> Result<ReportEntry> {
+    let x509 = pem.parse_x509().map_err(|e| anyhow!("X.509: {}", e))?;
+    let certificate = checks::parse_certificate(&pem.contents)?;
+    let subject = checks::subject_name(&x509);
+    let root = x509.subject() == x509.issuer();
+    let days = certificate.expiration_date.signed_duration_since(Utc::now()).num_days();
+    let entry = ReportEntry {
+        issuer: Some(certificate.issuer),
+        serial: Some(certificate.serial),
+        ..ReportEntry::ok(
+            CheckKind::Ca,
+            &subject,
+            certificate.expiration_date,
+            days,
+            days <= alarm_days,
+        )
+    };
+    let tag = if root { "root" } else { "intermediate" };
+    Ok(entry.with_tags(vec![tag.to_string()]))
+}
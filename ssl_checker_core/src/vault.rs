@@ -0,0 +1,107 @@
+//! Клиент HTTP API HashiCorp Vault: чтение и списки по пути `/v1/...` с токеном
+//! и пространством имён Vault Enterprise
+
+use crate::http::HttpSettings;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    serde_json::Value,
+    tracing,
+};
+use reqwest::{Client, Method, Response, StatusCode};
+
+/// Запросы к Vault с токеном `X-Vault-Token`
+#[derive(Clone)]
+pub struct VaultClient {
+    address: String,
+    token: String,
+    namespace: Option<String>,
+    client: Client,
+    http: HttpSettings,
+    dcl: &'static str,
+}
+
+impl VaultClient {
+    /// Адрес сервера вида `https://vault.example.com:8200`
+    pub fn new(address: &str, token: &str, http: HttpSettings) -> Self {
+        Self {
+            address: address.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            namespace: None,
+            client: http.client(),
+            http,
+            dcl: "VaultClient",
+        }
+    }
+
+    /// Пространство имён Vault Enterprise (заголовок `X-Vault-Namespace`)
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_string());
+        self
+    }
+
+    async fn request(&self, method: Method, path: &str) -> Result<Response> {
+        let url = format!("{}/v1/{}", self.address, path.trim_start_matches('/'));
+        self.http
+            .send(|| {
+                let request = self
+                    .client
+                    .request(method.clone(), &url)
+                    .header("X-Vault-Token", &self.token);
+                match &self.namespace {
+                    Some(namespace) => request.header("X-Vault-Namespace", namespace),
+                    None => request,
+                }
+            })
+            .await
+            .with_context(|| format!("Vault is unreachable: {}", self.address))
+    }
+
+    /// Поле `data` ответа на `GET /v1/<path>`
+    pub async fn read(&self, path: &str) -> Result<Value> {
+        let resp = self.request(Method::GET, path).await?;
+        self.data(resp, path).await
+    }
+
+    /// Ключи `data.keys` ответа на `LIST /v1/<path>`; пустой список Vault
+    /// отдаёт с кодом 404
+    pub async fn list(&self, path: &str) -> Result<Vec<String>> {
+        let method = Method::from_bytes(b"LIST").expect("valid method");
+        let resp = self.request(method, path).await?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        let data = self.data(resp, path).await?;
+        Ok(data["keys"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|key| key.as_str().map(str::to_string))
+            .collect())
+    }
+
+    async fn data(&self, resp: Response, path: &str) -> Result<Value> {
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            tracing::error!(
+                dcl = self.dcl,
+                path = path,
+                status = status.to_string(),
+                body = text,
+                "Ошибка запроса к Vault"
+            );
+            return Err(anyhow!("Vault returned {} for {}", status, path));
+        }
+        let body: Value = resp.json().await.context("Vault response is not JSON")?;
+        Ok(body["data"].clone())
+    }
+
+    /// Метаданные секрета KV версии 2 по пути `<mount>/<path>`
+    pub async fn kv_metadata(&self, path: &str) -> Result<Value> {
+        let (mount, path) = path
+            .trim_matches('/')
+            .split_once('/')
+            .ok_or_else(|| anyhow!("Ожидается путь вида <mount>/<path>: {}", path))?;
+        self.read(&format!("{}/metadata/{}", mount, path)).await
+    }
+}
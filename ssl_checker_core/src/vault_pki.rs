@@ -0,0 +1,171 @@
+//! Сертификаты движка PKI HashiCorp Vault: издатели (корневые и промежуточные УЦ)
+//! и выпущенные неотозванные сертификаты. Истёкший издатель ломает выпуск
+//! незаметно: Vault отказывает в выпуске или обрезает срок новых сертификатов
+
+use crate::inventory::{CertificateInventoryTrait, InventoryCertificate};
+use crate::vault::VaultClient;
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    chrono::Utc,
+    tracing,
+};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::HashSet;
+
+/// Одновременных запросов сертификатов к Vault
+const READ_CONCURRENCY: usize = 8;
+
+/// Издатели и выпущенные сертификаты движков PKI `mounts`. Издатели получают
+/// тег `issuer` и свой порог оповещения, выпущенные сертификаты — тег `issued`;
+/// отозванные и истёкшие выпущенные сертификаты пропускаются
+pub struct VaultPkiInventory {
+    vault: VaultClient,
+    mounts: Vec<String>,
+    issued: bool,
+    issuer_alarm_days: Option<i64>,
+    tags: Vec<String>,
+    dcl: &'static str,
+}
+
+impl VaultPkiInventory {
+    /// `mounts` — пути движков PKI, например `pki` и `pki_int`
+    pub fn new(vault: VaultClient, mounts: &[String]) -> Self {
+        Self {
+            vault,
+            mounts: mounts.iter().map(|m| m.trim_matches('/').to_string()).collect(),
+            issued: true,
+            issuer_alarm_days: None,
+            tags: Vec::new(),
+            dcl: "VaultPkiInventory",
+        }
+    }
+
+    /// Читать ли выпущенные сертификаты; без них проверяются только издатели
+    pub fn with_issued(mut self, issued: bool) -> Self {
+        self.issued = issued;
+        self
+    }
+
+    /// Порог оповещения для издателей: издатель должен быть заменён раньше,
+    /// чем его срок станет короче срока выпускаемых сертификатов
+    pub fn with_issuer_alarm_days(mut self, days: i64) -> Self {
+        self.issuer_alarm_days = Some(days);
+        self
+    }
+
+    /// Теги, добавляемые ко всем записям
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    fn parse(&self, id: &str, pem: &str, tag: &str) -> Result<InventoryCertificate> {
+        let (_, pem) = x509_parser::pem::parse_x509_pem(pem.as_bytes())
+            .map_err(|e| anyhow!("PEM parse error: {}", e))?;
+        let mut certificate = InventoryCertificate::from_der(id, &pem.contents)?;
+        certificate.tags = self.tags.clone();
+        certificate.tags.push(tag.to_string());
+        Ok(certificate)
+    }
+
+    /// Издатели движка (Vault 1.11+), а в более старых версиях — сертификат УЦ
+    /// из `cert/ca`
+    async fn issuers(&self, mount: &str) -> Result<Vec<InventoryCertificate>> {
+        let ids = self.vault.list(&format!("{}/issuers", mount)).await?;
+        let mut issuers = Vec::new();
+        if ids.is_empty() {
+            let data = self.vault.read(&format!("{}/cert/ca", mount)).await?;
+            if let Some(pem) = data["certificate"].as_str().filter(|pem| !pem.is_empty())
+            {
+                issuers.push(self.parse(&format!("{}/cert/ca", mount), pem, "issuer")?);
+            }
+        }
+        for id in ids {
+            let data = self.vault.read(&format!("{}/issuer/{}", mount, id)).await?;
+            let pem = data["certificate"]
+                .as_str()
+                .ok_or_else(|| anyhow!("No certificate in {}/issuer/{}", mount, id))?;
+            let name = data["issuer_name"].as_str().filter(|name| !name.is_empty());
+            let id = format!("{}/issuer/{}", mount, name.unwrap_or(&id));
+            issuers.push(self.parse(&id, pem, "issuer")?);
+        }
+        for issuer in &mut issuers {
+            // Имена УЦ не сверяются с хостами
+            issuer.names.clear();
+            issuer.alarm_days = self.issuer_alarm_days;
+        }
+        Ok(issuers)
+    }
+
+    /// Неотозванные сертификаты движка, срок которых ещё не истёк, кроме
+    /// сертификатов самих издателей
+    async fn issued(
+        &self,
+        mount: &str,
+        issuer_serials: &HashSet<String>,
+    ) -> Result<Vec<InventoryCertificate>> {
+        let serials = self.vault.list(&format!("{}/certs", mount)).await?;
+        let now = Utc::now();
+        let certificates: Vec<Option<InventoryCertificate>> = stream::iter(serials)
+            .map(|serial| async move {
+                let data = self.vault.read(&format!("{}/cert/{}", mount, serial)).await?;
+                if data["revocation_time"].as_i64().is_some_and(|time| time > 0) {
+                    return Ok(None);
+                }
+                let pem = data["certificate"].as_str().ok_or_else(|| {
+                    anyhow!("No certificate in {}/cert/{}", mount, serial)
+                })?;
+                let certificate =
+                    self.parse(&format!("{}/cert/{}", mount, serial), pem, "issued")?;
+                let current = certificate.expiration_date.is_some_and(|date| date > now);
+                let own = certificate
+                    .serial
+                    .as_ref()
+                    .is_some_and(|serial| issuer_serials.contains(serial));
+                Ok::<_, base::prelude::anyhow::Error>(
+                    (current && !own).then_some(certificate),
+                )
+            })
+            .buffer_unordered(READ_CONCURRENCY)
+            .try_collect()
+            .await?;
+        Ok(certificates.into_iter().flatten().collect())
+    }
+}
+
+#[async_trait]
+impl CertificateInventoryTrait for VaultPkiInventory {
+    async fn get_certificates(&self) -> Result<Vec<InventoryCertificate>> {
+        let mut certificates = Vec::new();
+        for mount in &self.mounts {
+            let issuers = self
+                .issuers(mount)
+                .await
+                .with_context(|| format!("PKI issuers in {}", mount))?;
+            let issued = if self.issued {
+                let serials: HashSet<String> =
+                    issuers.iter().filter_map(|issuer| issuer.serial.clone()).collect();
+                self.issued(mount, &serials)
+                    .await
+                    .with_context(|| format!("PKI certificates in {}", mount))?
+            } else {
+                Vec::new()
+            };
+            tracing::debug!(
+                dcl = self.dcl,
+                mount = mount,
+                issuers = issuers.len(),
+                issued = issued.len(),
+                "Загружены сертификаты PKI Vault"
+            );
+            certificates.extend(issuers);
+            certificates.extend(issued);
+        }
+        Ok(certificates)
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
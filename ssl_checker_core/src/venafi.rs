@@ -0,0 +1,409 @@
+//! Сертификаты, известные корпоративному УЦ Venafi: Trust Protection Platform
+//! (TPP) и TLS Protect Cloud. Так отслеживаются и сертификаты, которые выпущены,
+//! но ещё не развёрнуты ни на одном проверяемом хосте
+
+use crate::http::HttpSettings;
+use crate::inventory::{CertificateInventoryTrait, InventoryCertificate};
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow::{Context, Result, anyhow},
+    chrono::{DateTime, Utc},
+    serde_json::{Value, json},
+    tracing,
+};
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, de::DeserializeOwned};
+
+/// Адрес API TLS Protect Cloud
+pub const CLOUD_URL: &str = "https://api.venafi.cloud";
+/// Корневая папка политик TPP
+const TPP_ROOT_FOLDER: &str = "\\VED\\Policy";
+const PAGE_SIZE: usize = 1000;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TppPage {
+    #[serde(default)]
+    certificates: Vec<TppCertificate>,
+    #[serde(default)]
+    total_count: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TppCertificate {
+    /// `\VED\Policy\<папка>\<имя>`
+    #[serde(rename = "DN")]
+    dn: String,
+    /// Нет у объекта, для которого сертификат ещё не выпущен
+    #[serde(rename = "X509")]
+    x509: Option<TppX509>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TppX509 {
+    #[serde(rename = "CN")]
+    cn: Option<String>,
+    issuer: Option<String>,
+    serial: Option<String>,
+    valid_to: Option<DateTime<Utc>>,
+    #[serde(rename = "SANS")]
+    sans: Option<TppSans>,
+}
+
+#[derive(Deserialize)]
+struct TppSans {
+    #[serde(rename = "DNS", default)]
+    dns: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CloudPage {
+    #[serde(default)]
+    certificates: Vec<CloudCertificate>,
+    #[serde(default)]
+    count: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CloudCertificate {
+    id: String,
+    certificate_name: Option<String>,
+    #[serde(rename = "subjectCN", default)]
+    subject_cn: Vec<String>,
+    #[serde(default)]
+    subject_alternative_names_by_type: CloudSans,
+    validity_end: Option<DateTime<Utc>>,
+    serial_number: Option<String>,
+    #[serde(rename = "issuerCN", default)]
+    issuer_cn: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct CloudSans {
+    #[serde(rename = "dNSName", default)]
+    dns_name: Vec<String>,
+}
+
+enum Platform {
+    /// Trust Protection Platform: токен OAuth с областью `certificate`
+    /// и папка политик, сертификаты которой читаются рекурсивно
+    Tpp { folder: String },
+    /// TLS Protect Cloud: ключ API
+    Cloud,
+}
+
+/// Выпущенные сертификаты Venafi: срок, издатель и имена. Проверяемые хосты
+/// с этими именами, которые отдают более старый сертификат, попадают в ошибки
+pub struct VenafiInventory {
+    url: String,
+    token: String,
+    platform: Platform,
+    tags: Vec<String>,
+    client: Client,
+    http: HttpSettings,
+    dcl: &'static str,
+}
+
+impl VenafiInventory {
+    /// Trust Protection Platform по адресу `url` (`https://tpp.example.com`)
+    /// с токеном доступа, выданным `/vedauth`
+    pub fn tpp(url: &str, access_token: &str, http: HttpSettings) -> Self {
+        Self::new(
+            url,
+            access_token,
+            Platform::Tpp { folder: TPP_ROOT_FOLDER.into() },
+            http,
+        )
+    }
+
+    /// TLS Protect Cloud; `url` — адрес API региона, например [`CLOUD_URL`]
+    pub fn cloud(url: &str, api_key: &str, http: HttpSettings) -> Self {
+        Self::new(url, api_key, Platform::Cloud, http)
+    }
+
+    fn new(url: &str, token: &str, platform: Platform, http: HttpSettings) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            platform,
+            tags: Vec::new(),
+            client: http.client(),
+            http,
+            dcl: "VenafiInventory",
+        }
+    }
+
+    /// Папка политик TPP, например `\VED\Policy\Shop`; для Cloud не используется
+    pub fn with_folder(mut self, folder: &str) -> Self {
+        if let Platform::Tpp { folder: current } = &mut self.platform {
+            *current = folder.to_string();
+        }
+        self
+    }
+
+    /// Теги, добавляемые ко всем записям
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    async fn json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        request: impl Fn() -> RequestBuilder,
+    ) -> Result<T> {
+        let resp = self.http.send(request).await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            tracing::error!(
+                dcl = self.dcl,
+                url = url,
+                status = status.to_string(),
+                body = text,
+                "Ошибка запроса к Venafi"
+            );
+            return Err(anyhow!("Venafi returned {} for {}", status, url));
+        }
+        resp.json().await.with_context(|| format!("Unexpected Venafi response: {}", url))
+    }
+
+    async fn tpp_certificates(&self, folder: &str) -> Result<Vec<InventoryCertificate>> {
+        let url = format!("{}/vedsdk/certificates/", self.url);
+        let mut found = Vec::new();
+        loop {
+            let page_url = format!(
+                "{}?{}",
+                url,
+                form_urlencoded::Serializer::new(String::new())
+                    .append_pair("ParentDnRecursive", folder)
+                    .append_pair("Limit", &PAGE_SIZE.to_string())
+                    .append_pair("Offset", &found.len().to_string())
+                    .finish()
+            );
+            let page: TppPage = self
+                .json(&page_url, || self.client.get(&page_url).bearer_auth(&self.token))
+                .await?;
+            let done = page.certificates.is_empty()
+                || found.len() + page.certificates.len() >= page.total_count;
+            found.extend(page.certificates);
+            if done {
+                break;
+            }
+        }
+
+        Ok(found.into_iter().filter_map(|c| self.tpp_certificate(c)).collect())
+    }
+
+    fn tpp_certificate(
+        &self,
+        certificate: TppCertificate,
+    ) -> Option<InventoryCertificate> {
+        let x509 = certificate.x509?;
+        let expiration_date = x509.valid_to?;
+        let names = x509.sans.map(|sans| sans.dns).unwrap_or_default();
+        Some(self.certificate(
+            certificate.dn,
+            x509.cn,
+            names,
+            expiration_date,
+            x509.issuer,
+            x509.serial,
+        ))
+    }
+
+    async fn cloud_certificates(&self) -> Result<Vec<InventoryCertificate>> {
+        let url = format!("{}/outagedetection/v1/certificatesearch", self.url);
+        let mut found = Vec::new();
+        for page_number in 0.. {
+            let body: Value = json!({
+                "expression": { "operands": [
+                    { "field": "certificateStatus", "operator": "EQ", "value": "ACTIVE" }
+                ] },
+                "paging": { "pageNumber": page_number, "pageSize": PAGE_SIZE },
+            });
+            let page: CloudPage = self
+                .json(&url, || {
+                    self.client.post(&url).header("tppl-api-key", &self.token).json(&body)
+                })
+                .await?;
+            let done = page.certificates.is_empty()
+                || found.len() + page.certificates.len() >= page.count;
+            found.extend(page.certificates);
+            if done {
+                break;
+            }
+        }
+
+        Ok(found.into_iter().filter_map(|c| self.cloud_certificate(c)).collect())
+    }
+
+    fn cloud_certificate(
+        &self,
+        certificate: CloudCertificate,
+    ) -> Option<InventoryCertificate> {
+        let expiration_date = certificate.validity_end?;
+        Some(self.certificate(
+            certificate.certificate_name.unwrap_or(certificate.id),
+            certificate.subject_cn.into_iter().next(),
+            certificate.subject_alternative_names_by_type.dns_name,
+            expiration_date,
+            certificate.issuer_cn.into_iter().next(),
+            certificate.serial_number,
+        ))
+    }
+
+    fn certificate(
+        &self,
+        id: String,
+        common_name: Option<String>,
+        mut names: Vec<String>,
+        expiration_date: DateTime<Utc>,
+        issuer: Option<String>,
+        serial: Option<String>,
+    ) -> InventoryCertificate {
+        names.iter_mut().for_each(|name| *name = name.to_lowercase());
+        if names.is_empty()
+            && let Some(common_name) = &common_name
+        {
+            names.push(common_name.to_lowercase());
+        }
+        InventoryCertificate {
+            id,
+            domain: common_name.or_else(|| names.first().cloned()),
+            expiration_date: Some(expiration_date),
+            issuer,
+            serial: serial.map(|s| s.replace(':', "").to_uppercase()),
+            renewal_problem: None,
+            tags: self.tags.clone(),
+            names,
+            alarm_days: None,
+        }
+    }
+}
+
+#[async_trait]
+impl CertificateInventoryTrait for VenafiInventory {
+    async fn get_certificates(&self) -> Result<Vec<InventoryCertificate>> {
+        // Объекты без выпущенного сертификата пропускаются: срока у них ещё нет
+        let certificates = match &self.platform {
+            Platform::Tpp { folder } => self
+                .tpp_certificates(folder)
+                .await
+                .with_context(|| format!("Venafi TPP {}", self.url))?,
+            Platform::Cloud => self
+                .cloud_certificates()
+                .await
+                .with_context(|| format!("Venafi Cloud {}", self.url))?,
+        };
+        tracing::debug!(
+            dcl = self.dcl,
+            url = self.url,
+            count = certificates.len(),
+            "Загружены сертификаты Venafi"
+        );
+        Ok(certificates)
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        self.dcl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::prelude::serde_json;
+
+    fn inventory() -> VenafiInventory {
+        VenafiInventory::tpp("https://tpp.example.com", "token", HttpSettings::VENAFI)
+            .with_tags(vec!["venafi".to_string()])
+    }
+
+    #[test]
+    fn tpp_page_skips_objects_without_issued_certificate() {
+        let page: TppPage = serde_json::from_value(json!({
+            "Certificates": [
+                {
+                    "CreatedOn": "2025-01-01T00:00:00.0000000Z",
+                    "DN": "\\VED\\Policy\\Shop\\www.example.com",
+                    "Guid": "{5d2c1a8e-0000-4000-8000-000000000001}",
+                    "Name": "www.example.com",
+                    "X509": {
+                        "CN": "www.example.com",
+                        "Issuer": "CN=Corp Issuing CA, O=Example",
+                        "Serial": "6b:00:00:00:12",
+                        "ValidFrom": "2025-04-01T12:00:00.0000000Z",
+                        "ValidTo": "2026-04-01T12:00:00.0000000Z",
+                        "SANS": { "DNS": ["WWW.example.com", "example.com"] }
+                    }
+                },
+                {
+                    "DN": "\\VED\\Policy\\Shop\\new.example.com",
+                    "Name": "new.example.com"
+                }
+            ],
+            "TotalCount": 2
+        }))
+        .unwrap();
+        assert_eq!(page.total_count, 2);
+        let inventory = inventory();
+        let found: Vec<InventoryCertificate> = page
+            .certificates
+            .into_iter()
+            .filter_map(|c| inventory.tpp_certificate(c))
+            .collect();
+
+        assert_eq!(found.len(), 1);
+        let certificate = &found[0];
+        assert_eq!(certificate.id, "\\VED\\Policy\\Shop\\www.example.com");
+        assert_eq!(certificate.domain.as_deref(), Some("www.example.com"));
+        assert_eq!(certificate.names, ["www.example.com", "example.com"]);
+        assert_eq!(certificate.serial.as_deref(), Some("6B00000012"));
+        assert_eq!(
+            certificate.expiration_date.unwrap().to_rfc3339(),
+            "2026-04-01T12:00:00+00:00"
+        );
+        assert_eq!(certificate.tags, ["venafi"]);
+    }
+
+    #[test]
+    fn cloud_certificate_falls_back_to_common_name() {
+        let page: CloudPage = serde_json::from_value(json!({
+            "count": 2,
+            "certificates": [
+                {
+                    "id": "0e3f6a10-0000-4000-8000-000000000001",
+                    "certificateName": "api.example.com",
+                    "subjectCN": ["api.example.com"],
+                    "issuerCN": ["Corp Issuing CA"],
+                    "serialNumber": "0A1B",
+                    "validityStart": "2025-04-01T12:00:00.000+00:00",
+                    "validityEnd": "2026-04-01T12:00:00.000+00:00",
+                    "certificateStatus": "ACTIVE"
+                },
+                {
+                    "id": "0e3f6a10-0000-4000-8000-000000000002",
+                    "subjectCN": ["pending.example.com"]
+                }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(page.count, 2);
+        let inventory = inventory();
+        let found: Vec<InventoryCertificate> = page
+            .certificates
+            .into_iter()
+            .filter_map(|c| inventory.cloud_certificate(c))
+            .collect();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "api.example.com");
+        assert_eq!(found[0].names, ["api.example.com"]);
+        assert_eq!(found[0].issuer.as_deref(), Some("Corp Issuing CA"));
+    }
+}
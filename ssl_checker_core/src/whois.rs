@@ -0,0 +1,177 @@
+//! Разбор срока регистрации из ответа WHOIS по таблице правил. У реестров разные
+//! метки строки со сроком, форматы дат и часовые пояса, поэтому правила задаются
+//! по зонам; встроенную таблицу дополняет секция `whois_rules` конфигурации
+
+use crate::checks::WhoisResponseError;
+use base::prelude::{
+    anyhow::{Result, anyhow},
+    chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc},
+    once_cell::sync::Lazy,
+};
+use serde::Deserialize;
+
+/// Правило разбора срока регистрации (элемент списка `whois_rules`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WhoisParseRule {
+    /// Зоны, например `jp`, `com.br` или `рф`; пустой список — правило для всех зон
+    #[serde(default)]
+    pub zones: Vec<String>,
+    /// Метки строки со сроком без учёта регистра; дата берётся после метки
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Форматы даты в синтаксисе chrono; RFC 3339 пробуется всегда
+    #[serde(default)]
+    pub formats: Vec<String>,
+    /// Смещение от UTC в часах для дат без часового пояса
+    #[serde(default)]
+    pub utc_offset_hours: i32,
+    /// `false` — реестр не публикует срок регистрации (например, DENIC для `.de`):
+    /// ответ без срока не считается ошибкой разбора
+    #[serde(default = "WhoisParseRule::default_expiry_published")]
+    pub expiry_published: bool,
+}
+
+impl WhoisParseRule {
+    fn default_expiry_published() -> bool {
+        true
+    }
+
+    fn new(
+        zones: &[&str],
+        patterns: &[&str],
+        formats: &[&str],
+        utc_offset_hours: i32,
+    ) -> Self {
+        let strings =
+            |items: &[&str]| items.iter().map(|item| item.to_string()).collect();
+        Self {
+            zones: strings(zones),
+            patterns: strings(patterns),
+            formats: strings(formats),
+            utc_offset_hours,
+            expiry_published: true,
+        }
+    }
+
+    /// Применяется ли правило к домену в ASCII-записи
+    fn applies_to(&self, domain: &str) -> bool {
+        self.zones.is_empty()
+            || self.zones.iter().any(|zone| {
+                let zone = zone.trim_matches('.');
+                let zone =
+                    idna::domain_to_ascii(zone).unwrap_or_else(|_| zone.to_lowercase());
+                domain == zone || domain.ends_with(&format!(".{}", zone))
+            })
+    }
+
+    /// Первая строка ответа с меткой правила и разборчивой датой
+    fn find_expiry(&self, whois_text: &str) -> Option<DateTime<Utc>> {
+        whois_text.lines().find_map(|line| {
+            self.patterns
+                .iter()
+                .filter_map(|pattern| value_after(line, pattern))
+                .find_map(|value| self.parse_date(value))
+        })
+    }
+
+    /// Дата из значения строки целиком или из его первого слова, если за датой
+    /// следует пояснение, например `2025/03/31 (JST)`
+    fn parse_date(&self, value: &str) -> Option<DateTime<Utc>> {
+        let value = value.trim().trim_start_matches(':').trim();
+        let first_word = value.split_whitespace().next().unwrap_or_default();
+        [value, first_word].into_iter().find_map(|candidate| {
+            if let Ok(dt) = DateTime::parse_from_rfc3339(candidate) {
+                return Some(dt.with_timezone(&Utc));
+            }
+            self.formats.iter().find_map(|format| {
+                let naive = NaiveDateTime::parse_from_str(candidate, format)
+                    .ok()
+                    .or_else(|| {
+                        let date = NaiveDate::parse_from_str(candidate, format).ok()?;
+                        date.and_hms_opt(23, 59, 59)
+                    })?;
+                let offset = FixedOffset::east_opt(self.utc_offset_hours * 3600)?;
+                let dt = offset.from_local_datetime(&naive).single()?;
+                Some(dt.with_timezone(&Utc))
+            })
+        })
+    }
+}
+
+/// Значение строки после метки `pattern` (без учёта регистра)
+fn value_after<'a>(line: &'a str, pattern: &str) -> Option<&'a str> {
+    let pattern = pattern.to_lowercase();
+    line.char_indices().find_map(|(i, _)| {
+        let candidate = line.get(i..i + pattern.len())?;
+        (candidate.to_lowercase() == pattern).then(|| &line[i + pattern.len()..])
+    })
+}
+
+/// Встроенные правила: сначала правила зон, последним — общее для всех зон
+static BUILTIN_RULES: Lazy<Vec<WhoisParseRule>> = Lazy::new(|| {
+    vec![
+        // Координационный центр: .ru, .su, .рф
+        WhoisParseRule::new(&["ru", "su", "рф"], &["paid-till:"], &[], 0),
+        // JPRS: дата без часового пояса по японскому времени
+        WhoisParseRule::new(&["jp"], &["[有効期限]", "[expires on]"], &["%Y/%m/%d"], 9),
+        // Registro.br: `expires: 20250315`, время Бразилиа
+        WhoisParseRule::new(&["br"], &["expires:"], &["%Y%m%d"], -3),
+        // DENIC не публикует срок регистрации
+        WhoisParseRule {
+            expiry_published: false,
+            ..WhoisParseRule::new(&["de"], &[], &[], 1)
+        },
+        WhoisParseRule::new(
+            &[],
+            &[
+                "paid-till:",
+                "registry expiry date:",
+                "expiry date:",
+                "registrar registration expiration date:",
+                "expiration date:",
+                "expires:",
+                "expire:",
+                "expiration time:",
+            ],
+            &[
+                "%Y-%m-%d %H:%M:%S",
+                "%Y-%m-%d",
+                "%Y.%m.%d",
+                "%d-%b-%Y",
+                "%d.%m.%Y",
+                "%d/%m/%Y",
+            ],
+            0,
+        ),
+    ]
+});
+
+/// Ищет дату окончания регистрации домена в ответе WHOIS. Правила `rules`
+/// проверяются раньше встроенных. Ответ без срока с баннером ограничения частоты,
+/// скрытыми данными или из реестра, не публикующего срок, завершается
+/// [`WhoisResponseError`]
+pub fn parse_whois_expiry(
+    domain: &str,
+    whois_text: &str,
+    rules: &[WhoisParseRule],
+) -> Result<DateTime<Utc>> {
+    let domain = idna::domain_to_ascii(domain.trim_end_matches('.'))
+        .unwrap_or_else(|_| domain.to_lowercase());
+    let mut expiry_published = true;
+    for rule in rules.iter().chain(BUILTIN_RULES.iter()) {
+        if !rule.applies_to(&domain) {
+            continue;
+        }
+        if let Some(expiration_date) = rule.find_expiry(whois_text) {
+            return Ok(expiration_date);
+        }
+        expiry_published &= rule.expiry_published;
+    }
+
+    match WhoisResponseError::classify(whois_text) {
+        Some(error) => Err(error.into()),
+        None if !expiry_published => Err(WhoisResponseError::NotPublished.into()),
+        None => Err(anyhow!("Could not parse expiry date from WHOIS")),
+    }
+}
@@ -0,0 +1,1994 @@
+use async_trait::async_trait;
+use base::prelude::{
+    anyhow,
+    chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc},
+    tokio::{
+        self,
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    },
+};
+use futures::FutureExt;
+use ssl_checker_core::checks;
+use ssl_checker_core::notifiers::BaseNotifierTrait;
+use ssl_checker_core::testing::{
+    MockSource, RecordingNotifier, TestHttpServer, TestQuicServer, TestSshServer,
+    TestTlsServer, TestWhoisServer,
+};
+use ssl_checker_core::trust_store;
+use ssl_checker_core::whois::parse_whois_expiry;
+use ssl_checker_core::{
+    BusinessCalendar, CertificateFileConfig, CertificateInventoryTrait, CertificateUsage,
+    CheckBudgetConfig, CheckBudgets, CheckKind, CheckReport, ComplianceEvent,
+    ComplianceEventKind, CompliancePeriod, ComplianceReport, CredentialConfig, CtMonitor,
+    DockerSourceService, DomainCheckerService, DomainOverrideConfig, DomainOverrides,
+    DomainSourceTrait, EntryStatus, ErrorKind, ExpiryForecast, F5SourceService,
+    FileSourceService, ForecastPeriod, HaproxySourceService, HttpSettings,
+    InventoryCertificate, MailCheckOptions, MaintenanceNotifier, MaintenanceWindowConfig,
+    NetScalerSourceService, RenewalCheckConfig, RenewalIndex, ReportEntry, Severity,
+    SeverityThreshold, SslCheckOptions, SslCheckResult, TelegramNotifierService,
+    UrlListCache, UrlSourceService, VaultClient, VaultPkiInventory, WhoisCheckOptions,
+    WhoisParseRule, WhoisResponseError,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::pin::pin;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn ssl_expiring_certificate_is_notified() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(5)).await.unwrap();
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    let report = checker.run().await.unwrap();
+
+    let recording = notifier.recording();
+    assert_eq!(recording.ssl.len(), 1);
+    assert_eq!(recording.ssl[0].serial, server.certificate().serial);
+    assert_eq!(recording.ssl[0].issuer, TestTlsServer::ISSUER);
+    assert!(recording.ssl[0].expires_at.ends_with(" +00:00"));
+    assert!(recording.errors.is_empty());
+    assert_eq!(recording.commits, 1);
+    let stats = recording.stats.unwrap();
+    assert_eq!((stats.sources.len(), stats.sources[0].loaded), (1, 1));
+    assert_eq!((stats.ssl.ok, stats.ssl.failed, stats.domains.ok), (1, 0, 0));
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].check, CheckKind::Ssl);
+    assert_eq!(report.entries[0].status, EntryStatus::Warning);
+}
+
+#[tokio::test]
+async fn names_with_skipped_labels_are_not_checked() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(60)).await.unwrap();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([
+            TestTlsServer::HOST,
+            "_dmarc.example.com",
+            "autodiscover.example.com",
+            "_sipfederationtls._tcp.example.com",
+            // Метки по умолчанию заменены настроенными
+            "_acme-challenge.127.0.0.1",
+        ]))],
+        Vec::new(),
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_skipped_labels(
+        vec!["_dmarc".to_string()],
+        vec!["Autodiscover".to_string(), "_sip".to_string()],
+    )
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    let report = checker.run().await.unwrap();
+
+    let mut hosts: Vec<&str> =
+        report.entries.iter().map(|e| e.hostname.as_str()).collect();
+    hosts.sort();
+    assert_eq!(hosts, ["127.0.0.1", "_acme-challenge.127.0.0.1"]);
+}
+
+#[tokio::test]
+async fn wildcard_is_checked_by_sampled_or_configured_hosts() {
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([
+            "*.example.invalid",
+            "c.example.invalid",
+            "b.example.invalid",
+            "a.example.invalid",
+            "deep.a.example.invalid",
+            "*.other.invalid",
+        ]))],
+        Vec::new(),
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_wildcard_hosts(vec!["www".to_string(), "app".to_string()], 2);
+
+    let report = checker.run().await.unwrap();
+
+    let mut hosts: Vec<&str> =
+        report.entries.iter().map(|e| e.hostname.as_str()).collect();
+    hosts.sort();
+    // Поддомены example.invalid нашлись в источнике, у other.invalid их нет
+    assert_eq!(
+        hosts,
+        [
+            "a.example.invalid",
+            "app.other.invalid",
+            "b.example.invalid",
+            "c.example.invalid",
+            "deep.a.example.invalid",
+            "www.other.invalid",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn ssl_thresholds_assign_severity() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(5)).await.unwrap();
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_ssl_thresholds(vec![
+        SeverityThreshold { days: 30, severity: Severity::Info },
+        SeverityThreshold { days: 7, severity: Severity::High },
+        SeverityThreshold { days: 14, severity: Severity::Warning },
+    ])
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    let report = checker.run().await.unwrap();
+
+    let recording = notifier.recording();
+    assert_eq!(recording.ssl.len(), 1);
+    assert_eq!(recording.ssl[0].severity, Severity::High);
+    assert_eq!(report.entries[0].severity, Some(Severity::High));
+}
+
+#[tokio::test]
+async fn domain_registration_is_read_from_whois() {
+    let expiration_date = Utc::now() + Duration::days(10);
+    let whois =
+        TestWhoisServer::with_expiration_dates([("example.com", expiration_date)])
+            .await
+            .unwrap();
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(["www.example.com"]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(false, true)
+    .with_whois_options(WhoisCheckOptions {
+        server: Some(whois.address().to_string()),
+        ..Default::default()
+    });
+
+    checker.run().await.unwrap();
+
+    let recording = notifier.recording();
+    assert_eq!(recording.domains.len(), 1);
+    assert_eq!(recording.domains[0].hostname, "example.com");
+    assert_eq!(
+        recording.domains[0].expiration_date.timestamp(),
+        expiration_date.timestamp()
+    );
+}
+
+#[tokio::test]
+async fn idn_domain_is_looked_up_in_punycode() {
+    let whois = TestWhoisServer::start(HashMap::from([(
+        "xn--e1afmkfd.xn--p1ai".to_string(),
+        include_str!("fixtures/whois/xn--p1ai.txt").to_string(),
+    )]))
+    .await
+    .unwrap();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(["www.пример.рф", "xn--e1afmkfd.xn--p1ai"]))],
+        vec![Box::new(RecordingNotifier::new())],
+        14,
+        30,
+    )
+    .with_checks(false, true)
+    .with_whois_options(WhoisCheckOptions {
+        server: Some(whois.address().to_string()),
+        ..Default::default()
+    });
+
+    let report = checker.run().await.unwrap();
+
+    // Обе записи имени — один домен
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].hostname, "пример.рф");
+    assert_eq!(
+        report.entries[0].expiration_date,
+        Some(Utc.with_ymd_and_hms(2026, 11, 11, 9, 0, 0).unwrap())
+    );
+
+    let options = WhoisCheckOptions::default();
+    assert_eq!(
+        checks::whois_server("пример.рф", &options).as_deref(),
+        Some("whois.tcinet.ru")
+    );
+    assert_eq!(
+        checks::whois_server("пример.рус", &options).as_deref(),
+        Some("whois.nic.xn--p1acf")
+    );
+    assert_eq!(checks::root_domain("*.ПРИМЕР.РФ.").as_deref(), Some("пример.рф"));
+}
+
+#[tokio::test]
+async fn root_domain_follows_public_suffix_list() {
+    let cases = [
+        ("www.example.co.uk", Some("example.co.uk")),
+        ("a.b.example.com.br", Some("example.com.br")),
+        ("www.shop.msk.ru", Some("shop.msk.ru")),
+        ("*.example.com", Some("example.com")),
+        ("co.uk", None),
+    ];
+    for (hostname, root) in cases {
+        assert_eq!(checks::root_domain(hostname).as_deref(), root, "{hostname}");
+    }
+
+    let expiration_date = Utc::now() + Duration::days(200);
+    let whois =
+        TestWhoisServer::with_expiration_dates([("example.co.uk", expiration_date)])
+            .await
+            .unwrap();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(["www.example.co.uk", "api.example.co.uk"]))],
+        vec![Box::new(RecordingNotifier::new())],
+        14,
+        30,
+    )
+    .with_checks(false, true)
+    .with_whois_options(WhoisCheckOptions {
+        server: Some(whois.address().to_string()),
+        ..Default::default()
+    });
+
+    let report = checker.run().await.unwrap();
+
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].hostname, "example.co.uk");
+    assert_eq!(report.entries[0].status, EntryStatus::Ok);
+}
+
+#[tokio::test]
+async fn source_failure_is_reported() {
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::failing("source is down"))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    );
+
+    checker.run().await.unwrap();
+
+    let recording = notifier.recording();
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].kind, ErrorKind::Source);
+    assert!(recording.errors[0].message.contains("source is down"));
+    assert!(recording.ssl.is_empty() && recording.domains.is_empty());
+}
+
+#[tokio::test]
+async fn slow_source_times_out_without_delaying_others() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(5)).await.unwrap();
+    let notifier = RecordingNotifier::new();
+    let slow = MockSource::new(["slow.example.com"])
+        .with_delay(std::time::Duration::from_secs(30));
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(slow), Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_source_timeout(std::time::Duration::from_millis(200))
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    let started = std::time::Instant::now();
+    let report = checker.run().await.unwrap();
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    let recording = notifier.recording();
+    assert_eq!(recording.errors.len(), 1);
+    assert!(recording.errors[0].message.contains("превышено время загрузки"));
+    assert_eq!(recording.ssl.len(), 1);
+    assert_eq!(report.hostnames.len(), 1);
+}
+
+#[tokio::test]
+async fn run_deadline_reports_pending_checks() {
+    // Сервер принимает соединения, но не отвечает на TLS-рукопожатие
+    let listener = tokio::net::TcpListener::bind((TestTlsServer::HOST, 0)).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let _hold = tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            connections.push(stream);
+        }
+    });
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_max_run_duration(std::time::Duration::from_millis(300))
+    .with_ssl_options(SslCheckOptions {
+        port,
+        handshake_timeout: std::time::Duration::from_secs(30),
+        ..Default::default()
+    });
+
+    let started = std::time::Instant::now();
+    let report = checker.run().await.unwrap();
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].status, EntryStatus::Error);
+    assert_eq!(
+        report.entries[0].error.as_deref(),
+        Some(DomainCheckerService::NOT_CHECKED_IN_TIME)
+    );
+    assert!(!report.entries[0].was_checked());
+    let recording = notifier.recording();
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.commits, 1);
+}
+
+#[tokio::test]
+async fn renewed_certificate_is_reported_as_change() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(200)).await.unwrap();
+    let mut previous = CheckReport::new(30, 14);
+    previous.push(ReportEntry::ok(
+        CheckKind::Ssl,
+        TestTlsServer::HOST,
+        Utc::now() + Duration::days(5),
+        5,
+        true,
+    ));
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_changes_since(&previous)
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    checker.run().await.unwrap();
+
+    let changes = notifier.recording().changes.unwrap();
+    assert!(changes.new.is_empty() && changes.closer.is_empty());
+    assert_eq!(changes.renewed.len(), 1);
+    assert_eq!(changes.renewed[0].hostname, TestTlsServer::HOST);
+    assert_eq!(changes.renewed[0].previous_days, Some(5));
+}
+
+#[test]
+fn trust_store_audit_reports_expiring_ca_certificates() {
+    let bundle =
+        std::env::temp_dir().join(format!("ca-bundle-{}.pem", std::process::id()));
+    let mut pem = String::new();
+    for days in [400, 10] {
+        pem.push_str(
+            &TestTlsServer::renewed_certificate_pem(Utc::now() + Duration::days(days))
+                .unwrap(),
+        );
+    }
+    pem.push_str("-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n");
+    std::fs::write(&bundle, pem).unwrap();
+
+    let report = trust_store::audit_bundle(&bundle, 30).unwrap();
+    std::fs::remove_file(&bundle).unwrap();
+
+    let statuses: Vec<EntryStatus> = report.entries.iter().map(|e| e.status).collect();
+    assert_eq!(statuses, [EntryStatus::Warning, EntryStatus::Ok, EntryStatus::Error]);
+    assert!(report.entries.iter().all(|e| e.check == CheckKind::Ca));
+    assert_eq!(report.entries[0].tags, ["root"]);
+    assert_eq!(report.entries[0].issuer.as_deref(), Some(TestTlsServer::ISSUER));
+    assert_eq!(report.exit_code(), 1);
+}
+
+#[test]
+fn expiry_forecast_buckets_unique_certificates_by_month() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+    let entry = |check, host: &str, expires: DateTime<Utc>, issuer: Option<&str>| {
+        let mut entry = ReportEntry::ok(check, host, expires, 0, false);
+        entry.issuer = issuer.map(str::to_string);
+        entry.serial = Some(format!("{}-{}", host, expires.timestamp()));
+        entry
+    };
+    let april = Utc.with_ymd_and_hms(2025, 4, 20, 0, 0, 0).unwrap();
+    let shared = ReportEntry {
+        tags: vec!["shop".to_string()],
+        serial: Some("0A".to_string()),
+        ..entry(CheckKind::Ssl, "a.example.com", april, Some("Let's Encrypt"))
+    };
+    let mut report = CheckReport::new(7, 14);
+    report.entries = vec![
+        shared.clone(),
+        // Тот же сертификат на другом хосте
+        ReportEntry { hostname: "b.example.com".to_string(), ..shared },
+        entry(CheckKind::Inventory, "acm", now + Duration::days(5), None),
+        entry(CheckKind::Ssl, "old.example.com", now - Duration::days(1), Some("R3")),
+        entry(CheckKind::Ssl, "later.example.com", now + Duration::days(400), Some("R3")),
+        entry(CheckKind::Domain, "example.com", now + Duration::days(5), None),
+        entry(CheckKind::Ca, "root", now + Duration::days(5), Some("R3")),
+    ];
+
+    let forecast = ExpiryForecast::from_report(&report, now, 3, ForecastPeriod::Month);
+
+    let starts: Vec<String> =
+        forecast.buckets.iter().map(|b| b.start.to_string()).collect();
+    assert_eq!(starts, ["2025-03-01", "2025-04-01", "2025-05-01", "2025-06-01"]);
+    let totals: Vec<usize> = forecast.buckets.iter().map(|b| b.total).collect();
+    assert_eq!(totals, [1, 1, 0, 0]);
+    assert_eq!(forecast.buckets[0].issuers, BTreeMap::from([("unknown".into(), 1)]));
+    assert_eq!(
+        forecast.buckets[1].issuers,
+        BTreeMap::from([("Let's Encrypt".into(), 1)])
+    );
+    assert_eq!(forecast.buckets[1].tags, BTreeMap::from([("shop".into(), 1)]));
+    assert_eq!(forecast.expired, 1);
+    assert_eq!(forecast.total(), 2);
+
+    let weekly = ExpiryForecast::from_report(&report, now, 1, ForecastPeriod::Week);
+    assert_eq!(weekly.buckets[0].start.to_string(), "2025-03-10");
+    assert_eq!(weekly.buckets[0].total, 1);
+}
+
+#[test]
+fn compliance_report_counts_timely_renewals_and_expiries_once() {
+    let day = |month, day| Utc.with_ymd_and_hms(2025, month, day, 0, 0, 0).unwrap();
+    let report = |checked_at, entries: Vec<ReportEntry>| {
+        let mut report = CheckReport::new(7, 14);
+        report.checked_at = checked_at;
+        report.entries = entries;
+        report
+    };
+    let ssl =
+        |host: &str, expires| ReportEntry::ok(CheckKind::Ssl, host, expires, 0, false);
+    let first = report(
+        day(3, 1),
+        vec![
+            ssl("early.example.com", day(4, 15)),
+            ssl("late.example.com", day(3, 10)),
+            ssl("expired.example.com", day(3, 2)),
+            ReportEntry::ok(CheckKind::Domain, "example.com", day(3, 2), 0, false),
+        ],
+    );
+    let second = report(
+        day(3, 5),
+        vec![
+            ssl("early.example.com", day(7, 1)),
+            ssl("late.example.com", day(6, 1)),
+            ssl("expired.example.com", day(3, 2)),
+            ReportEntry::ok(CheckKind::Domain, "example.com", day(3, 2), 0, false),
+        ],
+    );
+    let third = report(day(3, 6), second.entries.clone());
+
+    let mut events = ComplianceEvent::between(&first, &second);
+    // Уже отмеченный истёкший сертификат не повторяется
+    assert!(ComplianceEvent::between(&second, &third).is_empty());
+    let kinds: Vec<(&str, ComplianceEventKind, Option<i64>)> = events
+        .iter()
+        .map(|e| (e.hostname.as_str(), e.kind, e.days_before_expiry))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ("early.example.com", ComplianceEventKind::Renewed, Some(41)),
+            ("late.example.com", ComplianceEventKind::Renewed, Some(5)),
+            ("expired.example.com", ComplianceEventKind::Expired, None),
+        ]
+    );
+
+    events.push(ComplianceEvent { at: day(4, 2), ..events[0].clone() });
+    let (from, until) = CompliancePeriod::Month.previous(day(4, 10));
+    assert_eq!((from, until), (day(3, 1), day(4, 1)));
+    assert_eq!(CompliancePeriod::Month.label(from), "2025-03");
+    assert_eq!(CompliancePeriod::Quarter.label(from), "2025-Q1");
+    assert_eq!(CompliancePeriod::Quarter.previous(day(4, 10)).0, day(1, 1));
+
+    let compliance = ComplianceReport::build(&events, from, until, 30);
+    assert_eq!(compliance.renewals, 2);
+    assert_eq!(compliance.renewed_in_time, 1);
+    assert_eq!(compliance.renewed_in_time_percent, Some(50.0));
+    assert_eq!(compliance.mean_days_before_expiry, Some(23.0));
+    assert_eq!(compliance.expired.len(), 1);
+    assert_eq!(compliance.late_renewals[0].hostname, "late.example.com");
+    assert_eq!(compliance.events.len(), 3);
+}
+
+#[tokio::test]
+async fn undeployed_renewal_is_reported() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(20)).await.unwrap();
+    let certbot_dir =
+        std::env::temp_dir().join(format!("certbot-{}", std::process::id()));
+    let cert_path = certbot_dir.join("live").join("cert.pem");
+    std::fs::create_dir_all(cert_path.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(certbot_dir.join("renewal")).unwrap();
+    let renewed = Utc::now() + Duration::days(80);
+    std::fs::write(&cert_path, TestTlsServer::renewed_certificate_pem(renewed).unwrap())
+        .unwrap();
+    std::fs::write(
+        certbot_dir.join("renewal").join("local.conf"),
+        format!("version = 2.0.0\ncert = {}\n", cert_path.display()),
+    )
+    .unwrap();
+    let renewals = RenewalIndex::load(&RenewalCheckConfig {
+        certbot_dir: Some(certbot_dir.to_string_lossy().into_owned()),
+        acme_sh_dir: None,
+    })
+    .unwrap();
+    std::fs::remove_dir_all(&certbot_dir).unwrap();
+
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_renewal_index(Arc::new(renewals))
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    let report = checker.run().await.unwrap();
+
+    assert!(report.entries[0].renewed_expiration_date.is_some());
+    let recording = notifier.recording();
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].kind, ErrorKind::Undeployed);
+    assert_eq!(recording.errors[0].hostname.as_deref(), Some(TestTlsServer::HOST));
+}
+
+#[tokio::test]
+async fn issuer_change_is_notified() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(200)).await.unwrap();
+    let known_issuers =
+        HashMap::from([(TestTlsServer::HOST.to_string(), "DigiCert Inc".to_string())]);
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_known_issuers(known_issuers)
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    let report = checker.run().await.unwrap();
+
+    assert_eq!(report.issuer_changes.len(), 1);
+    assert_eq!(report.issuer_changes[0].previous_issuer, "DigiCert Inc");
+    assert_eq!(report.issuer_changes[0].issuer, TestTlsServer::ISSUER);
+    let recording = notifier.recording();
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].kind, ErrorKind::IssuerChange);
+    assert!(recording.errors[0].message.ends_with(TestTlsServer::ISSUER));
+}
+
+#[tokio::test]
+async fn recently_checked_healthy_host_is_reused() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(200)).await.unwrap();
+    let options = SslCheckOptions { port: server.port(), ..Default::default() };
+    let checker = || {
+        DomainCheckerService::new(
+            vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+            vec![Box::new(RecordingNotifier::new())],
+            14,
+            30,
+        )
+        .with_checks(true, false)
+        .with_ssl_options(options.clone())
+    };
+    let previous = checker().run().await.unwrap();
+    drop(server);
+
+    let report = checker()
+        .with_previous_report(&previous, Duration::hours(6))
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].checked_at, previous.entries[0].checked_at);
+}
+
+#[tokio::test]
+async fn ct_monitor_reports_new_certificates_after_baseline() {
+    // crt.sh отвечает одним и тем же списком на оба запроса (домен и `%.домен`)
+    let body = r#"[
+        {"id": 1, "issuer_name": "C=US, O=Let's Encrypt, CN=R3",
+         "name_value": "example.com", "not_before": "2025-01-01T00:00:00",
+         "not_after": "2025-04-01T00:00:00", "serial_number": "01"},
+        {"id": 2, "issuer_name": "C=US, O=Unknown CA",
+         "name_value": "example.com\nwww.example.com", "not_before": "2025-02-01T00:00:00",
+         "not_after": "2025-05-01T00:00:00", "serial_number": "02"}
+    ]"#;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = [0; 4096];
+            let _ = stream.read(&mut request).await;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    let monitor = CtMonitor::new(
+        vec!["Example.com".to_string()],
+        HttpSettings::from_secs(5, 5, 0, 1),
+    )
+    .with_base_url(&format!("http://127.0.0.1:{}", port));
+
+    let mut seen = HashMap::new();
+    assert!(monitor.poll(&mut seen).await.is_empty());
+    assert_eq!(seen["example.com"], 2);
+
+    seen.insert("example.com".to_string(), 1);
+    let certificates = monitor.poll(&mut seen).await;
+
+    assert_eq!(certificates.len(), 1);
+    assert_eq!(certificates[0].id, 2);
+    assert_eq!(certificates[0].names, ["example.com", "www.example.com"]);
+    assert_eq!(certificates[0].hostnames(), ["example.com", "www.example.com"]);
+    assert!(certificates[0].message().contains("Unknown CA"));
+    assert!(certificates[0].url.ends_with("/?id=2"));
+    assert_eq!(seen["example.com"], 2);
+}
+
+#[test]
+fn business_days_skip_weekend_and_holidays() {
+    let holiday = NaiveDate::from_ymd_opt(2025, 1, 10).unwrap();
+    let calendar = BusinessCalendar::default();
+    // Среда → понедельник: четверг, пятница, понедельник
+    let wednesday = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    let monday = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+    assert_eq!(calendar.business_days_until(&wednesday, &monday), 3);
+    assert_eq!(calendar.business_days_until(&monday, &wednesday), -5);
+
+    let calendar = BusinessCalendar::new([], [holiday]);
+    let next_monday = Utc.with_ymd_and_hms(2025, 1, 13, 9, 0, 0).unwrap();
+    assert_eq!(calendar.business_days_until(&monday, &next_monday), 6);
+}
+
+struct PanickingSource;
+
+#[async_trait]
+impl DomainSourceTrait for PanickingSource {
+    async fn get_domains(&self) -> anyhow::Result<Vec<String>> {
+        panic!("источник сломан")
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        "PanickingSource"
+    }
+}
+
+#[tokio::test]
+async fn redacted_and_rate_limited_whois_are_classified() {
+    let whois = TestWhoisServer::start(HashMap::from([
+        (
+            "redacted.net".to_string(),
+            "Domain Name: REDACTED.NET\r\nRegistrant Name: REDACTED FOR PRIVACY\r\n"
+                .to_string(),
+        ),
+        (
+            "limited.com".to_string(),
+            "Query limit exceeded, try again later\r\n".to_string(),
+        ),
+    ]))
+    .await
+    .unwrap();
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(["redacted.net", "limited.com"]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(false, true)
+    .with_whois_options(WhoisCheckOptions {
+        server: Some(whois.address().to_string()),
+        rate_limit_retries: 1,
+        rate_limit_backoff: std::time::Duration::from_millis(10),
+        ..Default::default()
+    });
+
+    let report = checker.run().await.unwrap();
+
+    let entry =
+        |hostname| report.entries.iter().find(|e| e.hostname == hostname).unwrap();
+    assert_eq!(entry("redacted.net").status, EntryStatus::Skipped);
+    assert_eq!(entry("limited.com").status, EntryStatus::Error);
+    assert_eq!(
+        entry("limited.com").error.as_deref(),
+        Some(WhoisResponseError::RateLimited.to_string().as_str())
+    );
+    let recording = notifier.recording();
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].hostname.as_deref(), Some("limited.com"));
+}
+
+#[test]
+fn whois_fixtures_are_parsed_by_zone_rules() {
+    let utc = |s| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+    let fixtures = [
+        ("example.ru", include_str!("fixtures/whois/ru.txt"), "2025-09-30T21:00:00Z"),
+        (
+            "пример.рф",
+            include_str!("fixtures/whois/xn--p1ai.txt"),
+            "2026-11-11T09:00:00Z",
+        ),
+        // Дата без часового пояса — по японскому времени
+        ("example.jp", include_str!("fixtures/whois/jp.txt"), "2025-04-30T14:59:59Z"),
+        // Дата без часового пояса — по времени Бразилиа
+        ("example.com.br", include_str!("fixtures/whois/br.txt"), "2025-03-16T02:59:59Z"),
+    ];
+    for (domain, text, expected) in fixtures {
+        assert_eq!(
+            parse_whois_expiry(domain, text, &[]).unwrap(),
+            utc(expected),
+            "{domain}"
+        );
+    }
+
+    let error =
+        parse_whois_expiry("example.de", include_str!("fixtures/whois/de.txt"), &[])
+            .unwrap_err();
+    assert!(WhoisResponseError::matches(&error, WhoisResponseError::NotPublished));
+    assert!(WhoisResponseError::hides_expiry(&error));
+}
+
+#[test]
+fn configured_whois_rule_is_tried_before_builtin() {
+    let text = "Domain: example.test\nValid until   01 June 2025\n";
+    assert!(parse_whois_expiry("example.test", text, &[]).is_err());
+
+    let rules: Vec<WhoisParseRule> = base::prelude::serde_json::from_str(
+        r#"[{"zones": ["test"], "patterns": ["valid until"], "formats": ["%d %B %Y"],
+             "utc_offset_hours": 3}]"#,
+    )
+    .unwrap();
+    assert_eq!(
+        parse_whois_expiry("example.test", text, &rules).unwrap(),
+        Utc.with_ymd_and_hms(2025, 6, 1, 20, 59, 59).unwrap()
+    );
+    // Правило другой зоны не применяется
+    assert!(parse_whois_expiry("example.org", text, &rules).is_err());
+}
+
+#[tokio::test]
+async fn panicking_source_is_reported_and_run_continues() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(5)).await.unwrap();
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(PanickingSource), Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    let report = checker.run().await.unwrap();
+
+    let recording = notifier.recording();
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].source.as_deref(), Some("PanickingSource"));
+    assert!(recording.errors[0].message.contains("паника: источник сломан"));
+    assert_eq!(recording.ssl.len(), 1);
+    assert!(report.stats.sources.iter().any(|source| source.failed));
+}
+
+#[tokio::test]
+async fn unreachable_crl_is_noted_for_expiring_certificate() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let crl_port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = [0; 4096];
+            let _ = stream.read(&mut request).await;
+            let _ =
+                stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").await;
+        }
+    });
+    // Порт свободного слушателя после его закрытия: соединение отклоняется
+    let closed_port = {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let reachable = format!("http://127.0.0.1:{}/ca.crl", crl_port);
+    let unreachable = format!("http://127.0.0.1:{}/ca.crl", closed_port);
+    let mut results = Vec::new();
+    for days in [5, 60] {
+        let server = TestTlsServer::start_with_crl(
+            Utc::now() + Duration::days(days),
+            &[&reachable, &unreachable],
+        )
+        .await
+        .unwrap();
+        assert_eq!(server.certificate().revocation_urls, [&*reachable, &*unreachable]);
+        let notifier = RecordingNotifier::new();
+        let mut checker = DomainCheckerService::new(
+            vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+            vec![Box::new(notifier.clone())],
+            14,
+            30,
+        )
+        .with_checks(true, false)
+        .with_revocation_check(HttpSettings::from_secs(2, 1, 0, 0))
+        .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+        let report = checker.run().await.unwrap();
+
+        let entry = &report.entries[0];
+        results
+            .push(entry.revocation.iter().map(|e| e.is_reachable()).collect::<Vec<_>>());
+        if entry.is_alarm() {
+            assert_eq!(entry.unreachable_revocation(), [&*unreachable]);
+            assert_eq!(
+                notifier.recording().ssl[0].unreachable_revocation,
+                [&*unreachable]
+            );
+        }
+    }
+
+    // Сертификат вне окна оповещения не проверяется
+    assert_eq!(results, [vec![true, false], vec![]]);
+}
+
+#[tokio::test]
+async fn ssl_error_is_reported_after_consecutive_failures() {
+    // Сервер без TLS: рукопожатие завершается неожиданной ошибкой
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").await;
+        }
+    });
+
+    let mut errors = Vec::new();
+    for streak in [1, 2] {
+        let notifier = RecordingNotifier::new();
+        let streaks = HashMap::from([(TestTlsServer::HOST.to_string(), streak)]);
+        let mut checker = DomainCheckerService::new(
+            vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+            vec![Box::new(notifier.clone())],
+            14,
+            30,
+        )
+        .with_checks(true, false)
+        .with_ssl_failure_debounce(3, streaks)
+        .with_ssl_options(SslCheckOptions { port, ..Default::default() });
+
+        let report = checker.run().await.unwrap();
+        assert_eq!(report.entries[0].status, EntryStatus::Error);
+        errors.push(notifier.recording().errors.len());
+    }
+
+    assert_eq!(errors, [0, 1]);
+}
+
+#[tokio::test]
+async fn repeated_ssl_error_keeps_first_seen() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").await;
+        }
+    });
+    let checker = |notifier: &RecordingNotifier| {
+        DomainCheckerService::new(
+            vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+            vec![Box::new(notifier.clone())],
+            14,
+            30,
+        )
+        .with_checks(true, false)
+        .with_ssl_options(SslCheckOptions { port, ..Default::default() })
+    };
+
+    let notifier = RecordingNotifier::new();
+    let mut previous = checker(&notifier).run().await.unwrap();
+    assert_eq!(previous.errors.len(), 1);
+    assert_eq!(previous.errors[0].kind, ErrorKind::Ssl);
+    assert_eq!(previous.errors[0].first_seen, Some(previous.checked_at));
+    let first_seen = previous.checked_at - Duration::days(3);
+    previous.errors[0].first_seen = Some(first_seen);
+
+    let notifier = RecordingNotifier::new();
+    let report = checker(&notifier).with_error_history(&previous).run().await.unwrap();
+
+    assert_eq!(report.errors[0].first_seen, Some(first_seen));
+    let error = &notifier.recording().errors[0];
+    assert_eq!(error.hostname.as_deref(), Some(TestTlsServer::HOST));
+    assert!(error.line(Utc::now()).ends_with("(сбой длится 3 дн.)"));
+}
+
+#[tokio::test]
+async fn unreachable_whois_server_is_reported_once_per_zone() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let server = listener.local_addr().unwrap().to_string();
+    drop(listener);
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(["one.com", "www.two.com"]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(false, true)
+    .with_whois_options(WhoisCheckOptions {
+        timeout: std::time::Duration::from_secs(5),
+        follow: 0,
+        server: Some(server.clone()),
+        ..Default::default()
+    });
+
+    let report = checker.run().await.unwrap();
+
+    let recording = notifier.recording();
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].kind, ErrorKind::WhoisOutage);
+    assert_eq!(recording.errors[0].source.as_deref(), Some(server.as_str()));
+    assert_eq!(recording.errors[0].message, "пропущено доменов зоны .com: 2");
+    assert_eq!(report.entries.len(), 2);
+    assert!(report.entries.iter().all(|entry| entry.status == EntryStatus::Error));
+}
+
+#[tokio::test]
+async fn certificate_chain_is_dumped_for_checked_hosts() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(60)).await.unwrap();
+    let dir = std::env::temp_dir().join(format!("ssl-checker-dump-{}", server.port()));
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(RecordingNotifier::new())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_cert_dump_dir(dir.clone())
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    checker.run().await.unwrap();
+
+    let pem = std::fs::read_to_string(dir.join("127.0.0.1.pem")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(pem.starts_with("-----BEGIN CERTIFICATE-----"));
+    assert_eq!(pem, checker.certificate_chain(TestTlsServer::HOST).await.unwrap());
+}
+
+#[tokio::test]
+async fn mail_server_certificate_is_read_after_starttls() {
+    let server = TestTlsServer::start_smtp(Utc::now() + Duration::days(5)).await.unwrap();
+    let options = MailCheckOptions {
+        ssl: SslCheckOptions { port: server.port(), ..Default::default() },
+        ..Default::default()
+    };
+
+    let certificate =
+        ssl_checker_core::mail::check_starttls(TestTlsServer::HOST, &options)
+            .await
+            .unwrap();
+
+    assert_eq!(&certificate, server.certificate());
+}
+
+#[tokio::test]
+async fn negotiated_alpn_protocol_is_recorded() {
+    let server = TestTlsServer::start_with_alpn(Utc::now() + Duration::days(60), &["h2"])
+        .await
+        .unwrap();
+    let overrides = HashMap::from([(
+        TestTlsServer::HOST.to_string(),
+        DomainOverrideConfig {
+            alpn: Some(vec!["h2".to_string(), "http/1.1".to_string()]),
+            ..Default::default()
+        },
+    )]);
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(RecordingNotifier::new())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_overrides(Arc::new(DomainOverrides::new(&overrides)))
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    let report = checker.run().await.unwrap();
+
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].alpn.as_deref(), Some("h2"));
+}
+
+#[tokio::test]
+async fn owner_from_source_or_overrides_reaches_notifiers() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(5)).await.unwrap();
+    let overrides = Arc::new(DomainOverrides::new(&HashMap::from([(
+        TestTlsServer::HOST.to_string(),
+        DomainOverrideConfig {
+            owner: Some("platform".to_string()),
+            ..Default::default()
+        },
+    )])));
+    // Путь файла источника отсчитывается от рабочего каталога
+    let hosts_file = format!("owners-{}.txt", std::process::id());
+    std::fs::write(&hosts_file, format!("{}, payments\n", TestTlsServer::HOST)).unwrap();
+    let sources: [Box<dyn DomainSourceTrait>; 2] = [
+        Box::new(FileSourceService::new(&hosts_file)),
+        Box::new(MockSource::new([TestTlsServer::HOST])),
+    ];
+
+    let mut owners = Vec::new();
+    for source in sources {
+        let notifier = RecordingNotifier::new();
+        let mut checker = DomainCheckerService::new(
+            vec![source],
+            vec![Box::new(notifier.clone())],
+            14,
+            30,
+        )
+        .with_checks(true, false)
+        .with_overrides(overrides.clone())
+        .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+        let report = checker.run().await.unwrap();
+
+        let recording = notifier.recording();
+        assert_eq!(recording.ssl.len(), 1);
+        assert_eq!(recording.ssl[0].owner, report.entries[0].owner);
+        owners.push(recording.ssl[0].owner.clone());
+    }
+    std::fs::remove_file(&hosts_file).unwrap();
+
+    // Владелец из источника важнее секции `domains`
+    assert_eq!(owners, [Some("payments".to_string()), Some("platform".to_string())]);
+}
+
+#[tokio::test]
+async fn url_source_reuses_cached_list_when_not_modified() {
+    // Повтор хоста и строка-комментарий в списке пропускаются
+    let text = "# inventory\na.example.com\nb.example.com, payments\na.example.com\n";
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = [0; 4096];
+            let n = stream.read(&mut request).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request[..n]).to_lowercase();
+            let (status, headers, body) = if request.contains("if-none-match: \"v1\"") {
+                ("304 Not Modified", "", "")
+            } else if request.starts_with("get /json") {
+                (
+                    "200 OK",
+                    "Content-Type: application/json\r\n",
+                    r#"["a.example.com", {"host": "b.example.com", "owner": "web"}]"#,
+                )
+            } else {
+                ("200 OK", "ETag: \"v1\"\r\n", text)
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                headers,
+                body.len(),
+                body
+            );
+            recorded.lock().unwrap().push(request);
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    let url = format!("http://127.0.0.1:{}/hosts", port);
+    let http = HttpSettings::from_secs(5, 5, 0, 1);
+    let cache = Arc::new(UrlListCache::default());
+
+    // Источники создаются на каждый запуск, кэш между ними общий
+    let mut lists = Vec::new();
+    for _ in 0..2 {
+        let source = UrlSourceService::new(&url, http.clone())
+            .with_bearer_token("secret")
+            .with_cache(cache.clone());
+        let mut domains = source.get_domains().await.unwrap();
+        domains.sort();
+        lists.push((domains, source.owners()));
+    }
+    let json = UrlSourceService::new(&format!("http://127.0.0.1:{}/json", port), http);
+    let json_domains = json.get_domains().await.unwrap();
+
+    let requests = requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[..2].iter().all(|r| r.contains("authorization: bearer secret")));
+    assert!(!requests[0].contains("if-none-match"));
+    assert!(requests[1].contains("if-none-match: \"v1\""));
+    assert_eq!(lists[0], lists[1]);
+    assert_eq!(lists[0].0, ["a.example.com", "b.example.com"]);
+    assert_eq!(lists[0].1, HashMap::from([("b.example.com".into(), "payments".into())]));
+    assert_eq!(json_domains, ["a.example.com", "b.example.com"]);
+    assert_eq!(json.owners()["b.example.com"], "web");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn docker_source_reads_hosts_from_traefik_and_caddy_labels() {
+    let containers = r#"[
+        {"Names": ["/web"], "Labels": {
+            "traefik.http.routers.web.rule":
+                "Host(`a.example.com`) || (Host(`B.example.com`) && PathPrefix(`/api`))",
+            "team": "web"}},
+        {"Names": ["/db"], "Labels": {
+            "traefik.tcp.routers.db.rule": "HostSNI(`*`)",
+            "traefik.frontend.rule": "Host:legacy.example.com,old.example.com;Path:/"}},
+        {"Names": ["/shop"], "Labels": {
+            "caddy": "https://c.example.com:443, {{upstreams 80}}",
+            "caddy_1": ":8080",
+            "caddy_2": "d.example.com/path",
+            "team": "shop"}},
+        {"Names": ["/plain"], "Labels": null}
+    ]"#;
+    let socket = std::env::temp_dir().join(format!("docker-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&socket);
+    let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = [0; 4096];
+            let n = stream.read(&mut request).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request[..n]);
+            let (status, body) = if request.starts_with("GET /containers/json ") {
+                ("200 OK", containers)
+            } else {
+                ("404 Not Found", "")
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+
+    let source = DockerSourceService::new(
+        &format!("unix://{}", socket.display()),
+        HttpSettings::from_secs(5, 5, 0, 1),
+    )
+    .with_owner_label("team");
+    let domains = source.get_domains().await.unwrap();
+    let owners = source.owners();
+    let _ = std::fs::remove_file(&socket);
+
+    assert_eq!(
+        domains,
+        [
+            "a.example.com",
+            "B.example.com",
+            "legacy.example.com",
+            "old.example.com",
+            "c.example.com",
+            "d.example.com",
+        ]
+    );
+    assert_eq!(owners["a.example.com"], "web");
+    assert_eq!(owners["b.example.com"], "web");
+    assert_eq!(owners["c.example.com"], "shop");
+    assert!(!owners.contains_key("legacy.example.com"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn haproxy_source_reads_config_and_runtime_api() {
+    let dir = std::env::temp_dir().join(format!("haproxy-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("certs")).unwrap();
+    let pem = TestTlsServer::renewed_certificate_pem(Utc::now() + Duration::days(30));
+    std::fs::write(dir.join("certs/site.pem"), pem.unwrap()).unwrap();
+    std::fs::write(
+        dir.join("certs/list.txt"),
+        "other.pem [alpn h2] a.example.com !b.example.com\nsite.pem\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("haproxy.cfg"),
+        "global\n    crt-base certs\n\
+         frontend https\n    bind :443 ssl crt site.pem crt-list list.txt # main\n\
+         \x20   use_backend api if { req.ssl_sni -i api.example.com }\n\
+         \x20   use_backend shop if { ssl_fc_sni -m end .shop.example.com }\n",
+    )
+    .unwrap();
+    let from_config =
+        HaproxySourceService::from_config(&dir.join("haproxy.cfg").to_string_lossy());
+    let config_domains = from_config.get_domains().await.unwrap();
+
+    // Runtime API отвечает на одну команду и закрывает соединение
+    let socket = dir.join("admin.sock");
+    let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = [0; 1024];
+            let n = stream.read(&mut request).await.unwrap_or(0);
+            let response = match String::from_utf8_lossy(&request[..n]).trim() {
+                "show ssl cert" => {
+                    "# transaction\n*/etc/haproxy/new.pem\n\
+                     # filename\n/etc/haproxy/a.pem\n/etc/haproxy/b.pem\n"
+                }
+                "show ssl cert /etc/haproxy/a.pem" => {
+                    "Filename: /etc/haproxy/a.pem\nStatus: Used\n\
+                     Subject Alternative Name: DNS:www.example.com, DNS:example.com\n\
+                     Subject: /CN=www.example.com\n"
+                }
+                "show ssl cert /etc/haproxy/b.pem" => {
+                    "Filename: /etc/haproxy/b.pem\n\
+                     Subject: /O=Example/CN=legacy.example.com\n"
+                }
+                _ => "Unknown command\n",
+            };
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    let runtime =
+        HaproxySourceService::from_runtime_api(&format!("unix://{}", socket.display()));
+    let runtime_domains = runtime.get_domains().await.unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(config_domains, [TestTlsServer::HOST, "a.example.com", "api.example.com"]);
+    assert_eq!(runtime_domains, ["www.example.com", "example.com", "legacy.example.com"]);
+}
+
+#[tokio::test]
+async fn load_balancer_sources_read_certificate_bindings() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = [0; 4096];
+            let n = stream.read(&mut request).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request[..n]).to_lowercase();
+            let path = request.split_whitespace().nth(1).unwrap_or_default().to_string();
+            let body = match path.as_str() {
+                "/mgmt/tm/ltm/virtual?expandsubcollections=true" => {
+                    r#"{"items": [
+                        {"fullPath": "/Common/web_443", "profilesReference": {"items": [
+                            {"fullPath": "/Common/tcp"}, {"fullPath": "/Common/web_ssl"},
+                            {"fullPath": "/Common/api_ssl"}]}},
+                        {"fullPath": "/Common/old_443", "disabled": true,
+                         "profilesReference": {"items": [
+                            {"fullPath": "/Common/old_ssl"}]}},
+                        {"fullPath": "/Tenant/app_443", "profilesReference": {"items": [
+                            {"fullPath": "/Tenant/app_ssl"}]}}
+                    ]}"#
+                }
+                "/mgmt/tm/ltm/profile/client-ssl" => {
+                    r#"{"items": [
+                        {"fullPath": "/Common/web_ssl", "cert": "/Common/web.crt",
+                         "certKeyChain": [{"cert": "/Common/web.crt"}],
+                         "serverName": "none"},
+                        {"fullPath": "/Common/api_ssl", "cert": "/Common/web.crt",
+                         "serverName": "api.example.com"},
+                        {"fullPath": "/Common/old_ssl", "cert": "/Common/old.crt"},
+                        {"fullPath": "/Tenant/app_ssl", "cert": "/Tenant/app.crt"}
+                    ]}"#
+                }
+                "/mgmt/tm/sys/file/ssl-cert" => {
+                    r#"{"items": [
+                        {"fullPath": "/Common/web.crt",
+                         "subject": "CN=www.example.com,O=Ex",
+                         "subjectAlternativeName":
+                            "DNS:www.example.com, DNS:example.com"},
+                        {"fullPath": "/Common/old.crt", "subject": "CN=old.example.com"},
+                        {"fullPath": "/Tenant/app.crt",
+                         "subject": "O=Ex,CN=app.example.com"}
+                    ]}"#
+                }
+                "/nitro/v1/config/sslvserver_sslcertkey_binding?bulkbindings=yes" => {
+                    r#"{"errorcode": 0, "sslvserver_sslcertkey_binding": [
+                        {"vservername": "vs_web", "certkeyname": "web"},
+                        {"vservername": "vs_web", "certkeyname": "shop", "snicert": true},
+                        {"vservername": "vs_web", "certkeyname": "corp_ca", "ca": true},
+                        {"vservername": "vs_old", "certkeyname": "missing"}
+                    ]}"#
+                }
+                "/nitro/v1/config/sslcertkey" => {
+                    r#"{"errorcode": 0, "sslcertkey": [
+                        {"certkey": "web", "subject": "C=US, CN=www.example.com",
+                         "sandns": ["www.example.com", "example.com"]},
+                        {"certkey": "shop", "subject": "C=US, O=Ex, CN=shop.example.com"},
+                        {"certkey": "corp_ca", "subject": "CN=Corp CA"}
+                    ]}"#
+                }
+                _ => "{}",
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            recorded.lock().unwrap().push(request);
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    let address = format!("http://127.0.0.1:{}", port);
+    let http = HttpSettings::from_secs(5, 5, 0, 1);
+
+    let f5 = F5SourceService::new(&address, "admin", "secret", http.clone());
+    let f5_domains = f5.get_domains().await.unwrap();
+    let common = F5SourceService::new(&address, "admin", "secret", http.clone())
+        .with_partition("Common");
+    let common_domains = common.get_domains().await.unwrap();
+    let netscaler = NetScalerSourceService::new(&address, "nsroot", "secret", http);
+    let netscaler_domains = netscaler.get_domains().await.unwrap();
+
+    assert_eq!(
+        f5_domains,
+        ["www.example.com", "example.com", "api.example.com", "app.example.com"]
+    );
+    assert_eq!(common_domains, ["www.example.com", "example.com", "api.example.com"]);
+    assert_eq!(netscaler_domains, ["www.example.com", "example.com", "shop.example.com"]);
+    let requests = requests.lock().unwrap();
+    // Basic-авторизация admin:secret и заголовки NITRO
+    assert!(
+        requests
+            .iter()
+            .filter(|r| r.contains(" /mgmt/"))
+            .all(|r| { r.contains("authorization: basic ywrtaw46c2vjcmv0") })
+    );
+    assert!(requests.iter().filter(|r| r.contains(" /nitro/")).all(|r| {
+        r.contains("x-nitro-user: nsroot") && r.contains("x-nitro-pass: secret")
+    }));
+}
+
+#[tokio::test]
+async fn ssh_host_certificate_is_checked_for_configured_hosts() {
+    let server = TestSshServer::start(Utc::now() + Duration::days(5)).await.unwrap();
+    let plain = TestSshServer::start_without_certificate().await.unwrap();
+    // Проверка SSH не зависит от `skip_ssl`
+    let overrides = Arc::new(DomainOverrides::new(&HashMap::from([
+        (
+            TestSshServer::HOST.to_string(),
+            DomainOverrideConfig {
+                ssh: Some(true),
+                ssh_port: Some(server.port()),
+                ssh_alarm_days: Some(7),
+                skip_ssl: Some(true),
+                ..Default::default()
+            },
+        ),
+        (
+            "localhost".to_string(),
+            DomainOverrideConfig {
+                ssh: Some(true),
+                ssh_port: Some(plain.port()),
+                skip_ssl: Some(true),
+                ..Default::default()
+            },
+        ),
+    ])));
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestSshServer::HOST, "localhost"]))],
+        vec![Box::new(notifier.clone())],
+        3,
+        30,
+    )
+    .with_checks(true, false)
+    .with_overrides(overrides);
+
+    let report = checker.run().await.unwrap();
+
+    let ssh_entries: Vec<_> =
+        report.entries.iter().filter(|e| e.check == CheckKind::Ssh).collect();
+    assert_eq!(ssh_entries.len(), 2);
+    assert!(report.entries.iter().all(|e| e.check == CheckKind::Ssh));
+    let recording = notifier.recording();
+    assert_eq!(recording.ssl.len(), 1);
+    assert_eq!(recording.ssl[0].hostname, "127.0.0.1 (SSH)");
+    assert_eq!(recording.ssl[0].issuer, server.ca_fingerprint());
+    assert_eq!(recording.ssl[0].days, 4);
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].kind, ErrorKind::Ssh);
+    assert_eq!(recording.errors[0].hostname.as_deref(), Some("localhost"));
+    assert!(recording.errors[0].message.contains("no SSH certificate"));
+    assert_eq!(recording.stats.unwrap().ssh.failed, 1);
+}
+
+#[tokio::test]
+async fn certificate_files_are_classified_by_key_usage() {
+    let dir =
+        std::env::temp_dir().join(format!("certificate-files-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let bundle = dir.join("release.p12");
+    let pkcs12 = TestTlsServer::signing_certificate_pkcs12(
+        Utc::now() + Duration::days(5),
+        "secret",
+    )
+    .unwrap();
+    std::fs::write(&bundle, pkcs12).unwrap();
+    let server_pem = dir.join("server.pem");
+    let pem = TestTlsServer::renewed_certificate_pem(Utc::now() + Duration::days(90));
+    std::fs::write(&server_pem, pem.unwrap()).unwrap();
+    let file = |path: &std::path::Path, password: &str| CertificateFileConfig {
+        path: path.display().to_string(),
+        password: password.to_string(),
+        tags: vec!["release".to_string()],
+        ..Default::default()
+    };
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(Vec::<String>::new()))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_certificate_files(vec![
+        file(&bundle, "secret"),
+        file(&server_pem, ""),
+        file(&bundle, "wrong"),
+    ]);
+
+    let report = checker.run().await.unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(report.entries.len(), 3);
+    assert!(report.entries.iter().all(|e| e.check == CheckKind::File));
+    let signing =
+        report.entries.iter().find(|e| e.status == EntryStatus::Warning).unwrap();
+    assert!(signing.hostname.starts_with("Release Signing ("));
+    assert_eq!(signing.usage, [CertificateUsage::CodeSigning, CertificateUsage::Smime]);
+    assert_eq!(signing.tags, ["release"]);
+    let server = report.entries.iter().find(|e| e.status == EntryStatus::Ok).unwrap();
+    assert!(server.usage.is_empty());
+    let recording = notifier.recording();
+    assert_eq!(recording.ssl.len(), 1);
+    assert_eq!(recording.ssl[0].hostname, signing.hostname);
+    assert_eq!(recording.ssl[0].issuer, TestTlsServer::ISSUER);
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].kind, ErrorKind::CertificateFile);
+    assert!(recording.errors[0].message.contains("PKCS#12"));
+    let stats = recording.stats.unwrap();
+    assert_eq!((stats.files.ok, stats.files.failed), (2, 1));
+}
+
+#[tokio::test]
+async fn credential_expiry_is_read_from_config_and_vault_metadata() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let expires_at = (Utc::now() + Duration::days(3)).to_rfc3339();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = [0; 4096];
+            let n = stream.read(&mut request).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request[..n]).to_lowercase();
+            let body = if !request.contains("x-vault-token: root") {
+                r#"{"errors": ["permission denied"]}"#.to_string()
+            } else if request.starts_with("get /v1/secret/metadata/ci/deploy ") {
+                format!(
+                    r#"{{"data": {{"custom_metadata": {{"rotate_by": "{}"}}}}}}"#,
+                    expires_at
+                )
+            } else {
+                r#"{"data": {"custom_metadata": null}}"#.to_string()
+            };
+            let status = if body.contains("errors") { "403 Forbidden" } else { "200 OK" };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    let vault = VaultClient::new(
+        &format!("http://127.0.0.1:{}", port),
+        "root",
+        HttpSettings::from_secs(5, 5, 0, 1),
+    );
+    let credential = |name: &str| CredentialConfig {
+        name: name.to_string(),
+        owner: Some("platform".to_string()),
+        ..Default::default()
+    };
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(Vec::<String>::new()))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_credentials(vec![
+        CredentialConfig {
+            expires_at: Some((Utc::now() + Duration::days(200)).date_naive().to_string()),
+            ..credential("Payment API key")
+        },
+        CredentialConfig {
+            vault: Some("secret/ci/deploy".to_string()),
+            vault_field: Some("rotate_by".to_string()),
+            ..credential("Deploy token")
+        },
+        CredentialConfig {
+            vault: Some("secret/ci/other".to_string()),
+            ..credential("Other token")
+        },
+    ])
+    .with_vault(vault);
+
+    let report = checker.run().await.unwrap();
+
+    assert_eq!(report.entries.len(), 3);
+    assert!(report.entries.iter().all(|e| e.check == CheckKind::Credential));
+    let recording = notifier.recording();
+    assert_eq!(recording.credentials.len(), 1);
+    assert_eq!(recording.credentials[0].hostname, "Deploy token");
+    assert_eq!(recording.credentials[0].owner.as_deref(), Some("platform"));
+    assert!((2..=3).contains(&recording.credentials[0].days));
+    assert!(recording.domains.is_empty());
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].kind, ErrorKind::Credential);
+    assert_eq!(recording.errors[0].hostname.as_deref(), Some("Other token"));
+    assert!(recording.errors[0].message.contains("custom_metadata.expires_at"));
+    let stats = recording.stats.unwrap();
+    assert_eq!((stats.credentials.ok, stats.credentials.failed), (2, 1));
+}
+
+struct StaticInventory(Option<Vec<InventoryCertificate>>);
+
+#[async_trait]
+impl CertificateInventoryTrait for StaticInventory {
+    async fn get_certificates(&self) -> anyhow::Result<Vec<InventoryCertificate>> {
+        self.0.clone().ok_or_else(|| anyhow::anyhow!("access denied"))
+    }
+
+    fn get_source_name(&self) -> &'static str {
+        if self.0.is_some() { "StaticInventory" } else { "FailingInventory" }
+    }
+}
+
+#[tokio::test]
+async fn inventory_certificates_report_expiry_and_renewal_problems() {
+    let certificate = |id: &str, days: i64, problem: Option<&str>| InventoryCertificate {
+        id: id.to_string(),
+        domain: Some(format!("{}.example.com", id)),
+        expiration_date: Some(Utc::now() + Duration::days(days) + Duration::hours(1)),
+        issuer: Some("Amazon".to_string()),
+        serial: Some("0A1B".to_string()),
+        renewal_problem: problem.map(str::to_string),
+        tags: vec!["eu-central-1".to_string()],
+        names: Vec::new(),
+        alarm_days: None,
+    };
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(Vec::<String>::new()))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_inventories(vec![
+        Box::new(StaticInventory(Some(vec![
+            certificate("healthy", 200, None),
+            certificate("expiring", 5, None),
+            certificate("stuck", 20, Some("Managed renewal PENDING_VALIDATION")),
+            InventoryCertificate {
+                expiration_date: None,
+                ..certificate("new", 0, Some("Managed certificate FAILED"))
+            },
+        ]))),
+        Box::new(StaticInventory(None)),
+    ]);
+
+    let report = checker.run().await.unwrap();
+
+    assert_eq!(report.entries.len(), 4);
+    assert!(report.entries.iter().all(|e| e.check == CheckKind::Inventory));
+    // Невыпущенный сертификат попадает в отчёт только с ошибкой, без срока
+    let new =
+        report.entries.iter().find(|e| e.hostname == "new.example.com (new)").unwrap();
+    assert_eq!(new.status, EntryStatus::Error);
+    assert!(new.expiration_date.is_none());
+    let stuck = report
+        .entries
+        .iter()
+        .find(|e| e.hostname == "stuck.example.com (stuck)")
+        .unwrap();
+    assert_eq!(stuck.status, EntryStatus::Error);
+    assert!(stuck.tags.contains(&"eu-central-1".to_string()));
+    let recording = notifier.recording();
+    // Проблема продления вне порога оповещения — только ошибка, без оповещения о сроке
+    let expiring: Vec<&str> = recording.ssl.iter().map(|r| r.hostname.as_str()).collect();
+    assert_eq!(expiring, ["expiring.example.com (expiring)"]);
+    let kinds: Vec<ErrorKind> = recording.errors.iter().map(|e| e.kind).collect();
+    assert_eq!(kinds.len(), 3);
+    assert_eq!(kinds.iter().filter(|kind| **kind == ErrorKind::Renewal).count(), 2);
+    assert!(kinds.contains(&ErrorKind::Source));
+    let stats = recording.stats.unwrap();
+    assert_eq!((stats.inventory.ok, stats.inventory.failed), (2, 2));
+}
+
+#[tokio::test]
+async fn newer_inventory_certificate_than_served_is_reported() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(20)).await.unwrap();
+    let pem =
+        TestTlsServer::renewed_certificate_pem(Utc::now() + Duration::days(80)).unwrap();
+    let (_, pem) = x509_parser::pem::parse_x509_pem(pem.as_bytes()).unwrap();
+    let stored =
+        InventoryCertificate::from_der("kv-prod/certificates/local", &pem.contents)
+            .unwrap();
+    assert_eq!(stored.names, [TestTlsServer::HOST]);
+    assert_eq!(stored.domain.as_deref(), Some(TestTlsServer::HOST));
+
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_inventories(vec![Box::new(StaticInventory(Some(vec![stored])))])
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+    let report = checker.run().await.unwrap();
+
+    let served = report.entries.iter().find(|e| e.check == CheckKind::Ssl).unwrap();
+    assert!(served.renewed_expiration_date.is_some());
+    let recording = notifier.recording();
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].kind, ErrorKind::Undeployed);
+    assert_eq!(recording.errors[0].hostname.as_deref(), Some(TestTlsServer::HOST));
+}
+
+#[tokio::test]
+async fn vault_pki_issuers_and_current_issued_certificates_are_reported() {
+    let pem = |days: i64| {
+        TestTlsServer::renewed_certificate_pem(Utc::now() + Duration::days(days))
+            .unwrap()
+            .replace('\n', "\\n")
+    };
+    let issuer = pem(20);
+    let responses: HashMap<&str, String> = HashMap::from([
+        ("list /v1/pki/issuers ", r#"{"data": {"keys": ["7f3a"]}}"#.to_string()),
+        (
+            "get /v1/pki/issuer/7f3a ",
+            format!(
+                r#"{{"data": {{"certificate": "{}", "issuer_name": "root-2024"}}}}"#,
+                issuer
+            ),
+        ),
+        (
+            "list /v1/pki/certs ",
+            r#"{"data": {"keys": ["issuer", "current", "revoked", "expired"]}}"#
+                .to_string(),
+        ),
+        (
+            "get /v1/pki/cert/issuer ",
+            format!(
+                r#"{{"data": {{"certificate": "{}", "revocation_time": 0}}}}"#,
+                issuer
+            ),
+        ),
+        (
+            "get /v1/pki/cert/current ",
+            format!(
+                r#"{{"data": {{"certificate": "{}", "revocation_time": 0}}}}"#,
+                pem(200)
+            ),
+        ),
+        (
+            "get /v1/pki/cert/revoked ",
+            format!(
+                r#"{{"data": {{"certificate": "{}", "revocation_time": 1700000000}}}}"#,
+                pem(5)
+            ),
+        ),
+        (
+            "get /v1/pki/cert/expired ",
+            format!(
+                r#"{{"data": {{"certificate": "{}", "revocation_time": 0}}}}"#,
+                pem(-3)
+            ),
+        ),
+    ]);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = [0; 4096];
+            let n = stream.read(&mut request).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request[..n]).to_lowercase();
+            let body = responses
+                .iter()
+                .find(|(prefix, _)| request.starts_with(*prefix))
+                .map(|(_, body)| body.as_str());
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{}",
+                if body.is_some() { "200 OK" } else { "404 Not Found" },
+                body.map_or(2, str::len),
+                body.unwrap_or("{}")
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    let vault = VaultClient::new(
+        &format!("http://127.0.0.1:{}", port),
+        "root",
+        HttpSettings::from_secs(5, 5, 0, 1),
+    );
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(Vec::<String>::new()))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_inventories(vec![Box::new(
+        VaultPkiInventory::new(vault, &["/pki/".to_string()]).with_issuer_alarm_days(30),
+    )]);
+
+    let report = checker.run().await.unwrap();
+
+    // Отозванный, истёкший и сертификат самого издателя в отчёт не попадают
+    assert_eq!(report.entries.len(), 2);
+    let issuer = report
+        .entries
+        .iter()
+        .find(|e| e.hostname.ends_with("(pki/issuer/root-2024)"))
+        .unwrap();
+    assert_eq!(issuer.tags, ["issuer"]);
+    let issued = report
+        .entries
+        .iter()
+        .find(|e| e.hostname.ends_with("(pki/cert/current)"))
+        .unwrap();
+    assert_eq!(issued.tags, ["issued"]);
+    // Издатель в пределах своего порога, хотя общий порог SSL — 14 дней
+    let recording = notifier.recording();
+    assert_eq!(recording.ssl.len(), 1);
+    assert_eq!(recording.ssl[0].hostname, issuer.hostname);
+    assert!(recording.errors.is_empty());
+}
+
+#[tokio::test]
+async fn maintenance_window_mutes_covered_hosts_only() {
+    let server = TestTlsServer::start(Utc::now() + Duration::days(5)).await.unwrap();
+    let window = |hosts: &[&str], from: DateTime<Utc>| MaintenanceWindowConfig {
+        from,
+        until: from + Duration::hours(2),
+        hosts: hosts.iter().map(|host| host.to_string()).collect(),
+        notifiers: Vec::new(),
+        reason: Some("переезд".to_string()),
+    };
+    let started = Utc::now() - Duration::hours(1);
+    let cases = [
+        (window(&["127.0.0.*"], started), 0),
+        (window(&["*.example.com"], started), 1),
+        // Окно ещё не началось
+        (window(&[], Utc::now() + Duration::hours(1)), 1),
+    ];
+
+    for (window, delivered) in cases {
+        let notifier = RecordingNotifier::new();
+        let muted = MaintenanceNotifier::new(Box::new(notifier.clone()), vec![window]);
+        let mut checker = DomainCheckerService::new(
+            vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+            vec![Box::new(muted)],
+            14,
+            30,
+        )
+        .with_checks(true, false)
+        .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() });
+
+        let report = checker.run().await.unwrap();
+
+        // Находка остаётся в отчёте, даже если не отправлена
+        assert!(report.entries[0].is_alarm());
+        assert_eq!(notifier.recording().ssl.len(), delivered);
+    }
+}
+
+#[tokio::test]
+async fn different_certificate_over_quic_is_reported() {
+    let tcp = TestTlsServer::start(Utc::now() + Duration::days(60)).await.unwrap();
+    let quic = TestQuicServer::start_on_port(tcp.port(), Utc::now() + Duration::days(5))
+        .await
+        .unwrap();
+    let overrides = HashMap::from([(
+        TestTlsServer::HOST.to_string(),
+        DomainOverrideConfig { quic: Some(true), ..Default::default() },
+    )]);
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new([TestTlsServer::HOST]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_overrides(Arc::new(DomainOverrides::new(&overrides)))
+    .with_ssl_options(SslCheckOptions { port: tcp.port(), ..Default::default() });
+
+    let report = checker.run().await.unwrap();
+
+    let quic_entry = report.entries.iter().find(|e| e.check == CheckKind::Quic).unwrap();
+    assert_eq!(quic_entry.serial.as_ref(), Some(&quic.certificate().serial));
+    assert_eq!(quic_entry.alpn.as_deref(), Some("h3"));
+    let recording = notifier.recording();
+    assert_eq!(recording.ssl.len(), 1);
+    assert_eq!(recording.ssl[0].hostname, "127.0.0.1 (QUIC)");
+    assert_eq!(recording.errors.len(), 1);
+    assert_eq!(recording.errors[0].kind, ErrorKind::QuicMismatch);
+}
+
+#[tokio::test]
+async fn run_id_is_shared_by_report_and_notifiers() {
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(["example.invalid"]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(false, false);
+
+    let first = checker.run().await.unwrap();
+    assert_eq!(notifier.recording().run_id.as_deref(), Some(first.run_id.as_str()));
+    let second = checker.run().await.unwrap();
+    assert_ne!(first.run_id, second.run_id);
+
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(MockSource::new(["example.invalid"]))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(false, false)
+    .with_run_id("scheduled-1");
+    let report = checker.run().await.unwrap();
+    assert_eq!(report.run_id, "scheduled-1");
+    assert_eq!(notifier.recording().run_id.as_deref(), Some("scheduled-1"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn check_budgets_interleave_groups_and_limit_checks() {
+    let budgets = CheckBudgets::new(&HashMap::from([
+        (
+            "client_a".to_string(),
+            CheckBudgetConfig {
+                tags: vec!["client_a".to_string()],
+                whois_concurrency: Some(1),
+                ssl_per_minute: Some(600),
+                ..Default::default()
+            },
+        ),
+        (
+            "unused".to_string(),
+            CheckBudgetConfig {
+                tags: vec!["other".to_string()],
+                whois_per_minute: Some(1),
+                ..Default::default()
+            },
+        ),
+    ]));
+    let tagged = vec!["client_a".to_string()];
+    let hosts = vec![
+        ("a1", tagged.clone()),
+        ("a2", tagged.clone()),
+        ("a3", tagged.clone()),
+        ("b1", Vec::new()),
+        ("b2", Vec::new()),
+    ];
+    let order: Vec<&str> = budgets
+        .schedule(CheckKind::Domain, hosts)
+        .into_iter()
+        .map(|(host, _)| host)
+        .collect();
+    assert_eq!(order, ["b1", "a1", "b2", "a2", "a3"]);
+
+    // Второй запрос WHOIS группы ждёт завершения первого, хосты вне группы — нет
+    let permit = budgets.acquire(CheckKind::Domain, &tagged).await;
+    let mut blocked = pin!(budgets.acquire(CheckKind::Domain, &tagged));
+    assert!(blocked.as_mut().now_or_never().is_none());
+    assert!(budgets.acquire(CheckKind::Domain, &[]).now_or_never().is_some());
+    drop(permit);
+    assert!(blocked.now_or_never().is_some());
+
+    // 600 проверок в минуту — не чаще одной в 100 мс
+    assert!(budgets.acquire(CheckKind::Ssl, &tagged).now_or_never().is_some());
+    let mut next = pin!(budgets.acquire(CheckKind::Ssl, &tagged));
+    assert!(next.as_mut().now_or_never().is_none());
+    tokio::time::advance(std::time::Duration::from_millis(99)).await;
+    assert!(next.as_mut().now_or_never().is_none());
+    tokio::time::advance(std::time::Duration::from_millis(1)).await;
+    assert!(next.now_or_never().is_some());
+}
+
+#[tokio::test]
+async fn large_telegram_group_is_split_into_messages() {
+    let server = TestHttpServer::start().await.unwrap();
+    let mut notifier = TelegramNotifierService::new(
+        "token",
+        "-100",
+        HttpSettings::from_secs(5, 5, 0, 1),
+    )
+    .with_group_by_root(true)
+    .with_api_url(server.url());
+    for i in 0..300 {
+        notifier
+            .ssl_expiration(&SslCheckResult {
+                hostname: format!("service-{:03}.k8s.prod.example.com", i),
+                serial: i.to_string(),
+                issuer: "R11".to_string(),
+                expiration_date: Utc::now() + Duration::days(5),
+                expires_at: String::new(),
+                days: 5,
+                more: 0,
+                acme: true,
+                severity: Severity::Warning,
+                owner: None,
+                unreachable_revocation: Vec::new(),
+            })
+            .await;
+    }
+    notifier.commit().await.unwrap();
+
+    let texts: Vec<String> = server
+        .bodies()
+        .iter()
+        .map(|body| {
+            let body: base::prelude::serde_json::Value =
+                base::prelude::serde_json::from_str(body).unwrap();
+            body["text"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert!(texts.len() > 1);
+    for text in &texts {
+        assert!(text.chars().count() <= 4096, "{} символов", text.chars().count());
+    }
+    let all = texts.concat();
+    for i in 0..300 {
+        assert!(all.contains(&format!("service-{:03}.k8s.prod.example.com", i)));
+    }
+    assert!(all.contains("<b>example.com</b> (1/"));
+}
@@ -0,0 +1,57 @@
+//! Нагрузочный тест полного запуска на большом списке хостов. Долгий, поэтому
+//! запускается явно:
+//!
+//! ```bash
+//! cargo test --release -p ssl_checker_core --test load -- --ignored --nocapture
+//! HOSTS=200000 cargo test --release -p ssl_checker_core --test load -- --ignored
+//! ```
+
+use base::prelude::{
+    chrono::{Duration, Utc},
+    tokio,
+};
+use ssl_checker_core::testing::{LoopbackSource, RecordingNotifier, TestTlsServer};
+use ssl_checker_core::{DomainCheckerService, EntryStatus, SslCheckOptions};
+use std::net::Ipv4Addr;
+use std::time::Instant;
+
+const DEFAULT_HOSTS: usize = 100_000;
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "нагрузочный тест, запускается с --ignored"]
+async fn large_inventory_end_to_end() {
+    let count =
+        std::env::var("HOSTS").ok().and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_HOSTS);
+    let server = TestTlsServer::start_on(
+        Ipv4Addr::UNSPECIFIED.into(),
+        Utc::now() + Duration::days(5),
+    )
+    .await
+    .unwrap();
+    let notifier = RecordingNotifier::new();
+    let mut checker = DomainCheckerService::new(
+        vec![Box::new(LoopbackSource::new(count))],
+        vec![Box::new(notifier.clone())],
+        14,
+        30,
+    )
+    .with_checks(true, false)
+    .with_ssl_options(SslCheckOptions { port: server.port(), ..Default::default() })
+    .with_concurrency(200)
+    .with_notify_chunk_size(100)
+    .with_progress_log_every(count / 10);
+
+    let started = Instant::now();
+    let report = checker.run().await.unwrap();
+    println!("{} hosts checked in {:.2?}", count, started.elapsed());
+
+    assert_eq!(report.hostnames.len(), count);
+    assert_eq!(report.entries.len(), count);
+    assert_eq!(report.count(EntryStatus::Warning), count);
+
+    // Все хосты отдают один сертификат — одно уведомление
+    let recording = notifier.recording();
+    assert_eq!(recording.ssl.len(), 1);
+    assert_eq!(recording.ssl[0].more, count - 1);
+    assert!(recording.errors.is_empty());
+}
@@ -0,0 +1,27 @@
+/* C API ssl_checker_ffi (libssl_checker.so).
+ * Функции блокируют поток до получения результата и возвращают JSON:
+ *   {"expiration_date": "...", "serial": "...", "issuer": "...", "days": 42}
+ *   {"expiration_date": "...", "days": 300}
+ * или {"error": "..."}. Результат освобождается через ssl_checker_free_string. */
+#ifndef SSL_CHECKER_H
+#define SSL_CHECKER_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Срок действия сертификата; port 0 — 443, timeout_ms 0 — по умолчанию */
+char *ssl_checker_check_ssl(const char *hostname, uint16_t port, uint32_t timeout_ms);
+
+/* Срок регистрации домена по WHOIS; timeout_ms 0 — по умолчанию */
+char *ssl_checker_check_domain(const char *domain, uint32_t timeout_ms);
+
+void ssl_checker_free_string(char *value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SSL_CHECKER_H */
@@ -0,0 +1,90 @@
+//! C ABI. Функции принимают строки UTF-8 с завершающим нулём и возвращают JSON,
+//! выделенный библиотекой: при успехе — поля результата, при ошибке — `{"error": "..."}`.
+//! Возвращённую строку нужно освободить через [`ssl_checker_free_string`].
+//! Объявления для C — в `include/ssl_checker.h`.
+
+use super::{check_domain, check_ssl};
+use base::prelude::{
+    anyhow::{Context, Result, bail},
+    serde_json::{self, json},
+};
+use serde::Serialize;
+use ssl_checker_core::checks::{SslCheckOptions, WhoisCheckOptions};
+use std::ffi::{CStr, CString, c_char};
+use std::time::Duration;
+
+/// Проверка сертификата. `port` 0 — порт 443, `timeout_ms` 0 — таймауты по умолчанию
+///
+/// # Safety
+/// `hostname` — указатель на строку с завершающим нулём
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ssl_checker_check_ssl(
+    hostname: *const c_char,
+    port: u16,
+    timeout_ms: u32,
+) -> *mut c_char {
+    // SAFETY: гарантирует вызывающая сторона
+    let result = unsafe { read_str(hostname) }.and_then(|hostname| {
+        let mut options = SslCheckOptions::default();
+        if port != 0 {
+            options.port = port;
+        }
+        if timeout_ms != 0 {
+            options.connect_timeout = Duration::from_millis(timeout_ms.into());
+            options.handshake_timeout = Duration::from_millis(timeout_ms.into());
+        }
+        check_ssl(hostname, &options)
+    });
+    to_json(result)
+}
+
+/// Проверка срока регистрации домена. `timeout_ms` 0 — таймаут по умолчанию
+///
+/// # Safety
+/// `domain` — указатель на строку с завершающим нулём
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ssl_checker_check_domain(
+    domain: *const c_char,
+    timeout_ms: u32,
+) -> *mut c_char {
+    // SAFETY: гарантирует вызывающая сторона
+    let result = unsafe { read_str(domain) }.and_then(|domain| {
+        let mut options = WhoisCheckOptions::default();
+        if timeout_ms != 0 {
+            options.timeout = Duration::from_millis(timeout_ms.into());
+        }
+        check_domain(domain, &options)
+    });
+    to_json(result)
+}
+
+/// Освобождает строку, возвращённую функциями библиотеки
+///
+/// # Safety
+/// `value` — результат `ssl_checker_check_*` или NULL; освобождается только один раз
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ssl_checker_free_string(value: *mut c_char) {
+    if !value.is_null() {
+        // SAFETY: строка создана CString::into_raw в to_json
+        drop(unsafe { CString::from_raw(value) });
+    }
+}
+
+/// # Safety
+/// `value` — NULL или указатель на строку с завершающим нулём
+unsafe fn read_str<'a>(value: *const c_char) -> Result<&'a str> {
+    if value.is_null() {
+        bail!("Передан NULL вместо строки");
+    }
+    // SAFETY: гарантирует вызывающая сторона
+    unsafe { CStr::from_ptr(value) }.to_str().context("Строка не в UTF-8")
+}
+
+fn to_json<T: Serialize>(result: Result<T>) -> *mut c_char {
+    let value = match result.and_then(|value| Ok(serde_json::to_value(value)?)) {
+        Ok(value) => value,
+        Err(e) => json!({ "error": format!("{:#}", e) }),
+    };
+    // JSON не содержит нулевых байтов: они экранируются как \u0000
+    CString::new(value.to_string()).map_or(std::ptr::null_mut(), CString::into_raw)
+}
@@ -0,0 +1,61 @@
+//! Привязки `ssl_checker_core` для других языков: C ABI ([`ffi`]) и модуль Python
+//! `ssl_checker` (PyO3, фича `python`). Обе обёртки вызывают одни и те же проверки,
+//! что и `checker`, и блокируют вызывающий поток до получения результата.
+
+pub mod ffi;
+#[cfg(feature = "python")]
+mod python;
+
+use base::prelude::{
+    anyhow::{Context, Result},
+    chrono::Utc,
+    once_cell::sync::Lazy,
+    tokio::runtime::{Builder, Runtime},
+};
+use serde::Serialize;
+use ssl_checker_core::checks::{
+    self, CertificateInfo, DomainRegistration, SslCheckOptions, WhoisCheckOptions,
+};
+
+/// Рантайм для асинхронных проверок; создаётся при первом вызове
+static RUNTIME: Lazy<Runtime> = Lazy::new(|| {
+    Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_all()
+        .build()
+        .expect("Не удалось создать рантайм tokio")
+});
+
+/// Сертификат и число дней до окончания его действия
+#[derive(Debug, Clone, Serialize)]
+pub struct SslExpiry {
+    #[serde(flatten)]
+    pub certificate: CertificateInfo,
+    pub days: i64,
+}
+
+/// Срок регистрации домена и число оставшихся дней
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainExpiry {
+    #[serde(flatten)]
+    pub registration: DomainRegistration,
+    pub days: i64,
+}
+
+/// Проверка сертификата хоста; блокирует поток до завершения
+pub fn check_ssl(hostname: &str, options: &SslCheckOptions) -> Result<SslExpiry> {
+    let certificate = RUNTIME
+        .block_on(checks::check_ssl_expiry(hostname, options))
+        .with_context(|| format!("{}:{}", hostname, options.port))?;
+    let days = certificate.expiration_date.signed_duration_since(Utc::now()).num_days();
+    Ok(SslExpiry { certificate, days })
+}
+
+/// Проверка срока регистрации домена по WHOIS; блокирует поток до завершения
+pub fn check_domain(domain: &str, options: &WhoisCheckOptions) -> Result<DomainExpiry> {
+    let registration = RUNTIME
+        .block_on(checks::check_domain_expiration(domain, options))
+        .with_context(|| domain.to_string())?;
+    let days = registration.expiration_date.signed_duration_since(Utc::now()).num_days();
+    Ok(DomainExpiry { registration, days })
+}
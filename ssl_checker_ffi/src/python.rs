@@ -0,0 +1,84 @@
+//! Модуль Python `ssl_checker`:
+//!
+//! ```python
+//! import ssl_checker
+//!
+//! cert = ssl_checker.check_ssl("example.com", port=443, timeout=5.0)
+//! print(cert["serial"], cert["expiration_date"], cert["days"])
+//!
+//! domain = ssl_checker.check_domain("example.com")
+//! print(domain["expiration_date"], domain["days"])
+//! ```
+//!
+//! Ошибки проверки поднимают `ssl_checker.CheckError`.
+
+use super::{check_domain as check_domain_blocking, check_ssl as check_ssl_blocking};
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use ssl_checker_core::checks::{SslCheckOptions, WhoisCheckOptions};
+use std::time::Duration;
+
+create_exception!(ssl_checker, CheckError, PyException, "Ошибка проверки хоста");
+
+/// Срок действия сертификата хоста: dict с ключами
+/// `expiration_date` (datetime, UTC), `serial`, `issuer` и `days`
+#[pyfunction]
+#[pyo3(signature = (hostname, port = 443, sni = None, timeout = 5.0))]
+fn check_ssl<'py>(
+    py: Python<'py>,
+    hostname: &str,
+    port: u16,
+    sni: Option<String>,
+    timeout: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let timeout = Duration::try_from_secs_f64(timeout)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    let options = SslCheckOptions {
+        port,
+        sni,
+        connect_timeout: timeout,
+        handshake_timeout: timeout,
+        ..Default::default()
+    };
+    let expiry = py
+        .allow_threads(|| check_ssl_blocking(hostname, &options))
+        .map_err(|e| CheckError::new_err(format!("{:#}", e)))?;
+
+    let result = PyDict::new(py);
+    result.set_item("expiration_date", expiry.certificate.expiration_date)?;
+    result.set_item("serial", expiry.certificate.serial)?;
+    result.set_item("issuer", expiry.certificate.issuer)?;
+    result.set_item("days", expiry.days)?;
+    Ok(result)
+}
+
+/// Срок регистрации домена по WHOIS: dict с ключами `expiration_date` и `days`
+#[pyfunction]
+#[pyo3(signature = (domain, timeout = 60.0))]
+fn check_domain<'py>(
+    py: Python<'py>,
+    domain: &str,
+    timeout: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let timeout = Duration::try_from_secs_f64(timeout)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    let options = WhoisCheckOptions { timeout, ..Default::default() };
+    let expiry = py
+        .allow_threads(|| check_domain_blocking(domain, &options))
+        .map_err(|e| CheckError::new_err(format!("{:#}", e)))?;
+
+    let result = PyDict::new(py);
+    result.set_item("expiration_date", expiry.registration.expiration_date)?;
+    result.set_item("days", expiry.days)?;
+    Ok(result)
+}
+
+#[pymodule]
+fn ssl_checker(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(check_ssl, module)?)?;
+    module.add_function(wrap_pyfunction!(check_domain, module)?)?;
+    module.add("CheckError", module.py().get_type::<CheckError>())?;
+    Ok(())
+}